@@ -0,0 +1,38 @@
+// Seven-card evaluations per second: `cargo bench --bench hand_value`.
+use divan::{Bencher, black_box, counter::ItemsCount};
+use pokerbot::gameplay::{FullBoard, Hole, headsup::Deck};
+
+const HANDS: usize = 10_000;
+
+fn main() {
+    divan::main();
+}
+
+fn deals() -> Vec<(Hole, FullBoard)> {
+    let mut deck = Deck::default();
+    (0..HANDS)
+        .map(|_| {
+            let mut dealer = deck.shuffle_and_deal();
+            let hole = dealer.deal_hole();
+            let flop = dealer.deal_flop();
+            let cards = [
+                flop[0],
+                flop[1],
+                flop[2],
+                dealer.deal_card(),
+                dealer.deal_card(),
+            ];
+            (hole, FullBoard::new(cards).unwrap())
+        })
+        .collect()
+}
+
+#[divan::bench]
+fn seven_cards(bencher: Bencher) {
+    let deals = deals();
+    bencher.counter(ItemsCount::new(HANDS)).bench(|| {
+        for (hole, board) in &deals {
+            black_box(board.hand_value(*hole));
+        }
+    });
+}
@@ -0,0 +1,177 @@
+// The crate's own canonical workloads, for comparing evaluator backends, builds or machines on
+// equal terms. Each returns structured timings instead of printing, so callers can tabulate or
+// track them however they like.
+use crate::{
+    bot::{ExploitBot, SelfPlay},
+    equity::{equity, equity_exact},
+    gameplay::{Board, FullBoard, Hole, headsup::Deck},
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub items: u64,
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    fn time(name: &'static str, items: u64, run: impl FnOnce()) -> Self {
+        let start = Instant::now();
+        run();
+        Self {
+            name,
+            items,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    // 0 if nothing was measured.
+    pub fn per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            secs => self.items as f64 / secs,
+        }
+    }
+}
+
+impl Display for BenchResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.0}/s ({} in {:.3}s)",
+            self.name,
+            self.per_second(),
+            self.items,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+// Monte Carlo equity after `iterations` against the exact answer.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ConvergencePoint {
+    pub iterations: usize,
+    pub error: f64,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BenchReport {
+    pub eval: BenchResult,
+    pub convergence: Vec<ConvergencePoint>,
+    pub simulation: BenchResult,
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.eval)?;
+        for point in &self.convergence {
+            writeln!(
+                f,
+                "equity: {} iterations, error {:.4} in {:.3}s",
+                point.iterations,
+                point.error,
+                point.elapsed.as_secs_f64()
+            )?;
+        }
+        write!(f, "{}", self.simulation)
+    }
+}
+
+// Seven-card evaluations of `hands` random deals; dealing isn't timed.
+pub fn eval_throughput(hands: usize) -> BenchResult {
+    let mut deck = Deck::default();
+    let deals: Vec<(Hole, FullBoard)> = (0..hands)
+        .map(|_| {
+            let mut dealer = deck.shuffle_and_deal();
+            let hole = dealer.deal_hole();
+            let flop = dealer.deal_flop();
+            let cards = [
+                flop[0],
+                flop[1],
+                flop[2],
+                dealer.deal_card(),
+                dealer.deal_card(),
+            ];
+            (
+                hole,
+                FullBoard::new(cards).expect("Dealt cards never collide"),
+            )
+        })
+        .collect();
+
+    BenchResult::time("eval", hands as u64, || {
+        for (hole, board) in &deals {
+            black_box(board.hand_value(*hole));
+        }
+    })
+}
+
+// Monte Carlo equity of a flopped set against an overpair, at each of `iterations`.
+pub fn equity_convergence(iterations: &[usize]) -> Vec<ConvergencePoint> {
+    let (hole, villain): (Hole, Hole) = ("7s7h".parse().unwrap(), "AsAh".parse().unwrap());
+    let board: Board = "Kd7c2h".parse().unwrap();
+    let exact = equity_exact(hole, villain, board)
+        .expect("The canonical spot is valid")
+        .share();
+
+    iterations
+        .iter()
+        .filter_map(|&iterations| {
+            let start = Instant::now();
+            let sampled = equity(hole, villain, board, iterations)?;
+            Some(ConvergencePoint {
+                iterations,
+                error: (sampled.share() - exact).abs(),
+                elapsed: start.elapsed(),
+            })
+        })
+        .collect()
+}
+
+// Complete hands of self-play between two exploit bots, 100bb deep.
+pub fn hand_simulation(hands: u32) -> BenchResult {
+    let self_play = SelfPlay::default();
+    let (mut first, mut second) = (ExploitBot::new(20), ExploitBot::new(20));
+    BenchResult::time("hands", hands as u64, || {
+        black_box(self_play.play(&mut first, &mut second, hands));
+    })
+}
+
+// Every workload at its standard size.
+pub fn standard() -> BenchReport {
+    BenchReport {
+        eval: eval_throughput(1_000_000),
+        convergence: equity_convergence(&[1_000, 10_000, 100_000, 1_000_000]),
+        simulation: hand_simulation(1_000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_workloads() {
+        let eval = eval_throughput(1000);
+        assert_eq!((eval.name, eval.items), ("eval", 1000));
+        assert!(eval.per_second() > 0.0);
+
+        let convergence = equity_convergence(&[0, 100, 20_000]);
+        assert_eq!(convergence.len(), 2); // no equity from zero iterations
+        assert!(convergence[1].error < 0.03);
+
+        let report = BenchReport {
+            eval,
+            convergence,
+            simulation: hand_simulation(20),
+        };
+        assert!(report.to_string().starts_with("eval: "));
+        assert_eq!(report.to_string().lines().count(), 4);
+    }
+}
@@ -0,0 +1,8 @@
+// Plays heads-up matches on Discord; the bot token is read from `DISCORD_TOKEN`.
+#[tokio::main]
+async fn main() {
+    let token = std::env::var("DISCORD_TOKEN").expect("DISCORD_TOKEN is not set");
+    pokerbot::discord::run(&token)
+        .await
+        .expect("Discord connection failed");
+}
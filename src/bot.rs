@@ -0,0 +1,392 @@
+use crate::{
+    gameplay::{
+        Board, CardsCombined, HandValue, Hole, SortedHandValue, Street,
+        chips::Chips,
+        headsup::{
+            Action, ActionSendError, ActionValue, BetBound, BetContext, GameOver, HandState,
+            ObservableEvent, Player, PlayerEvent, ShowCards,
+        },
+    },
+    solver::ActionAbstraction,
+    stats::{ActionRecord, PlayerStats},
+};
+use itertools::Itertools;
+
+pub mod arena;
+pub mod exploit;
+pub mod features;
+pub mod mcts;
+pub mod nash;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod ratings;
+pub mod runner;
+pub mod selfplay;
+pub mod session;
+pub mod simulate;
+pub mod swap;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod timing;
+pub mod tournament;
+
+pub use arena::{Arena, DuplicateMatch, Glicko, play_duplicate};
+pub use exploit::ExploitBot;
+pub use features::FeatureEncoder;
+pub use mcts::MctsBot;
+pub use nash::{PushFoldBot, nash_call_range, nash_push_range};
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxBot;
+pub use ratings::{FileStore, RatingStore};
+pub use runner::{MatchConfig, MatchOutcome, MatchResult, MatchesReport, run_matches};
+pub use selfplay::{Deal, HandRecord, SelfPlay, Trajectories, Transition};
+pub use session::{GameResult, Session, SessionReport};
+pub use simulate::{SimReport, StreetStats};
+pub use swap::SwapReplay;
+pub use timing::{ActionKind, Delay, Timing};
+pub use tournament::{Match, Tournament, TournamentReport};
+
+pub trait Bot {
+    fn act(&mut self, decision: &Decision) -> Action;
+
+    // Every action at the table, including the bot's own ones (`seat` is the bot's).
+    fn observe(&mut self, _seat: bool, _record: &ActionRecord) {}
+
+    fn new_hand(&mut self) {}
+
+    // What adaptive bots already know about the opponent, given before the first hand.
+    fn load_opponent(&mut self, _stats: &PlayerStats) {}
+
+    // What the bot learned about the opponent, to keep for their next match.
+    fn opponent_stats(&self) -> Option<PlayerStats> {
+        None
+    }
+}
+
+impl<B: Bot + ?Sized> Bot for Box<B> {
+    fn act(&mut self, decision: &Decision) -> Action {
+        (**self).act(decision)
+    }
+
+    fn observe(&mut self, seat: bool, record: &ActionRecord) {
+        (**self).observe(seat, record)
+    }
+
+    fn new_hand(&mut self) {
+        (**self).new_hand()
+    }
+
+    fn load_opponent(&mut self, stats: &PlayerStats) {
+        (**self).load_opponent(stats)
+    }
+
+    fn opponent_stats(&self) -> Option<PlayerStats> {
+        (**self).opponent_stats()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Decision {
+    seat: bool,
+    button: bool,
+    hole: Option<Hole>,
+    board: Board,
+    street: Street,
+    big_blind: Chips,
+    pot: Chips,
+    bets: [Chips; 2],   // this round, [hero, villain]
+    stacks: [Chips; 2], // behind, [hero, villain]
+    bet_bound: BetBound,
+    state: HandState,
+    history: Vec<ActionRecord>, // this hand so far
+}
+
+impl Decision {
+    pub(crate) fn from_state(seat: bool, state: &HandState, bet_bound: BetBound) -> Self {
+        let (hero, villain) = if seat { (0, 1) } else { (1, 0) };
+        let behinds = state.behinds();
+        let cur_round = state.cur_round();
+
+        Self {
+            seat,
+            button: state.button() == seat,
+            hole: state.holes()[hero],
+            board: state.board(),
+            street: state.street(),
+            big_blind: state.big_blind(),
+            pot: state.total_pot(),
+            bets: [cur_round[hero], cur_round[villain]],
+            stacks: [
+                behinds[hero] - cur_round[hero],
+                behinds[villain] - cur_round[villain],
+            ],
+            bet_bound,
+            state: *state,
+            history: vec![],
+        }
+    }
+
+    pub(crate) fn with_history(mut self, history: Vec<ActionRecord>) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn seat(&self) -> bool {
+        self.seat
+    }
+
+    pub fn is_button(&self) -> bool {
+        self.button
+    }
+
+    pub fn hole(&self) -> Option<Hole> {
+        self.hole
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn big_blind(&self) -> Chips {
+        self.big_blind
+    }
+
+    pub fn pot(&self) -> Chips {
+        self.pot
+    }
+
+    pub fn bets(&self) -> [Chips; 2] {
+        self.bets
+    }
+
+    pub fn stacks(&self) -> [Chips; 2] {
+        self.stacks
+    }
+
+    pub fn bet_bound(&self) -> &BetBound {
+        &self.bet_bound
+    }
+
+    pub fn history(&self) -> &[ActionRecord] {
+        &self.history
+    }
+
+    pub(crate) fn state(&self) -> &HandState {
+        &self.state
+    }
+
+    // For reading and writing sizes in big blinds or of the pot.
+    pub fn bet_context(&self) -> BetContext {
+        BetContext::new(self.pot, self.big_blind, self.bets).with_unit(self.bet_unit())
+    }
+
+    // Bets and raises go in multiples of it.
+    pub fn bet_unit(&self) -> Chips {
+        self.state.bet_unit()
+    }
+
+    pub fn to_call(&self) -> Chips {
+        self.bets[1].saturating_sub(self.bets[0])
+    }
+
+    pub fn facing_bet(&self) -> bool {
+        !self.to_call().is_zero()
+    }
+
+    // Raise-to amount for a pot fraction, snapped into the legal range.
+    pub fn sized(&self, fraction: f64) -> Action {
+        let (range, all_in) = match &self.bet_bound {
+            BetBound::FoldCheckBetAllIn(range)
+            | BetBound::FoldCallRaiseAllIn(range)
+            | BetBound::FoldBetAllIn(range)
+            | BetBound::FoldRaiseAllIn(range) => (range.clone(), true),
+            BetBound::FoldCheckBet(range) | BetBound::FoldCallRaise(range) => {
+                (range.clone(), false)
+            }
+            BetBound::FoldCall => return Action::check_or_call(),
+            _ => return Action::all_in(),
+        };
+        let raw = self.bets[1].get() as f64 + fraction * (self.pot + self.to_call()).get() as f64;
+        let unit = self.bet_unit().get();
+        let amount = Chips::new((raw / unit as f64).round() as u32 * unit).max(*range.start());
+
+        if amount >= *range.end() && all_in {
+            Action::all_in()
+        } else {
+            // pot limit caps the size at the pot instead
+            Action::bet_or_raise(amount.min(*range.end())).unwrap_or(Action::all_in())
+        }
+    }
+
+    // The pot fraction for `sized` that, bet and called on this street and each one after, gets
+    // the shorter stack in by the river.
+    pub fn geometric(&self) -> f64 {
+        let behind = self.stacks[0]
+            .saturating_sub(self.to_call())
+            .min(self.stacks[1]);
+        let streets = Street::River as u32 - self.street as u32 + 1;
+        ActionAbstraction::geometric_fraction(self.pot + self.to_call(), behind, streets)
+    }
+
+    // The bot's choice if legal, otherwise the cheapest legal action.
+    pub fn legalize(&self, action: Action) -> Action {
+        if let Some(action) = self.bet_bound.alter_eq(action, self.bet_unit()) {
+            action
+        } else if self.bet_bound.validate_action(Action::check_or_call()) {
+            Action::check_or_call()
+        } else {
+            Action::fold()
+        }
+    }
+}
+
+// Rough preflop strength in `0.0..=1.0`, pocket aces on top.
+pub fn preflop_strength(hole: Hole) -> f64 {
+    let (hi, lo) = {
+        let a = hole[0].value() as u8 as f64;
+        let b = hole[1].value() as u8 as f64;
+        (a.max(b), a.min(b))
+    };
+
+    if hole.is_pocket_pair() {
+        return 0.5 + hi / 24.0;
+    }
+
+    let mut strength = (hi * 2.0 + lo) / 35.0 * 0.75;
+    if hole.is_suited() {
+        strength += 0.06;
+    }
+    if hi - lo <= 2.0 {
+        strength += 0.04 * (3.0 - (hi - lo)) / 2.0;
+    }
+    strength.min(0.95)
+}
+
+// Rough made-hand strength in `0.0..=1.0` on the current board, falling back to preflop strength.
+pub fn hand_strength(hole: Hole, board: &Board) -> f64 {
+    let board_cards = board.to_vec();
+    if board_cards.is_empty() {
+        return preflop_strength(hole);
+    }
+
+    let top_board = board_cards.iter().map(|card| card.value()).max();
+    let best = hole
+        .iter()
+        .chain(board_cards.iter())
+        .copied()
+        .array_combinations::<5>()
+        .filter_map(CardsCombined::new)
+        .map(HandValue::from)
+        .max()
+        .expect("Board should have at least three cards");
+
+    match *best {
+        SortedHandValue::HighCard(_) => 0.15,
+        SortedHandValue::OnePair(values) => {
+            if hole.contains_value(values[0]) && Some(values[0]) >= top_board {
+                0.6 // top pair or overpair
+            } else if hole.contains_value(values[0]) {
+                0.45
+            } else {
+                0.25 // paired board
+            }
+        }
+        SortedHandValue::TwoPair(_) => 0.72,
+        SortedHandValue::Trips(_) => 0.8,
+        SortedHandValue::Straight(_) => 0.86,
+        SortedHandValue::Flush(_) => 0.9,
+        _ => 0.97,
+    }
+}
+
+// Drives a `Player` with a bot until the game is over.
+pub async fn play(player: &mut Player, bot: &mut impl Bot) -> Option<GameOver> {
+    drive(player, bot, None).await
+}
+
+// Like `play`, thinking for a delay drawn from `timing` before every action.
+pub async fn play_timed(
+    player: &mut Player,
+    bot: &mut impl Bot,
+    timing: &Timing,
+) -> Option<GameOver> {
+    drive(player, bot, Some(timing)).await
+}
+
+async fn drive(
+    player: &mut Player,
+    bot: &mut impl Bot,
+    timing: Option<&Timing>,
+) -> Option<GameOver> {
+    let seat = player.seat()?;
+    let mut raises = 0;
+    let mut street = Street::Preflop;
+
+    loop {
+        let before = player.hand_state();
+        let event = player.tick_event().await?;
+
+        if before.street() != street {
+            street = before.street();
+            raises = 0;
+        }
+
+        match event {
+            PlayerEvent::HeroTurn(bet_bound, _) => {
+                let history = player.view().map(|view| view.history().to_vec());
+                let decision = Decision::from_state(seat, &player.hand_state(), bet_bound)
+                    .with_history(history.unwrap_or_default());
+                let action = decision.legalize(bot.act(&decision));
+                if let Some(timing) = timing {
+                    let delay = timing.sample(decision.street(), action, &mut rand::rng());
+                    tokio::time::sleep(delay).await;
+                }
+
+                if let Err(ActionSendError::GameAbort(game_over)) = player.send_action(action) {
+                    return Some(game_over);
+                }
+            }
+            PlayerEvent::InsuranceOffer(_) => {
+                if let Err(ActionSendError::GameAbort(game_over)) = player.send_insurance(false) {
+                    return Some(game_over);
+                }
+            }
+            PlayerEvent::ShowOffer => {
+                if let Err(ActionSendError::GameAbort(game_over)) =
+                    player.send_show(ShowCards::Neither)
+                {
+                    return Some(game_over);
+                }
+            }
+            PlayerEvent::Observable(ObservableEvent::DealHoles(_)) => {
+                street = Street::Preflop;
+                raises = 0;
+                bot.new_hand();
+            }
+            PlayerEvent::Observable(ObservableEvent::PlayerAction(action, _)) => {
+                let record = ActionRecord::new(
+                    before.cur_turn(),
+                    before.street(),
+                    action,
+                    !before.can_check(),
+                    raises,
+                );
+                if matches!(
+                    action.value(),
+                    ActionValue::BetOrRaise(_) | ActionValue::AllIn
+                ) {
+                    raises += 1;
+                }
+                bot.observe(seat, &record);
+            }
+            PlayerEvent::Observable(ObservableEvent::GameOver(game_over)) => {
+                return Some(game_over);
+            }
+            PlayerEvent::Observable(_) => {}
+        }
+    }
+}
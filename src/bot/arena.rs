@@ -0,0 +1,489 @@
+// Rates a pool of bots against each other with Glicko-2. Each match is one rating period of a
+// single game, scored by which bot won chips over the match's self-play hands.
+use super::{Bot, Deal, HandRecord, RatingStore, SelfPlay};
+use std::{
+    f64::consts::PI,
+    io::{self, BufRead, Write},
+};
+
+const SCALE: f64 = 173.7178;
+const TAU: f64 = 0.5; // how fast volatility may change
+const EPSILON: f64 = 1e-6;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Glicko {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Glicko {
+    fn default() -> Self {
+        Self::new(1500.0, 350.0, 0.06)
+    }
+}
+
+impl Glicko {
+    pub fn new(rating: f64, deviation: f64, volatility: f64) -> Self {
+        Self {
+            rating,
+            deviation,
+            volatility,
+        }
+    }
+
+    fn mu(&self) -> f64 {
+        (self.rating - 1500.0) / SCALE
+    }
+
+    fn phi(&self) -> f64 {
+        self.deviation / SCALE
+    }
+
+    // 95% interval of the true rating.
+    pub fn interval(&self) -> (f64, f64) {
+        (
+            self.rating - 1.96 * self.deviation,
+            self.rating + 1.96 * self.deviation,
+        )
+    }
+
+    pub fn expected_score(&self, opponent: &Glicko) -> f64 {
+        expected(self.mu(), opponent.mu(), opponent.phi())
+    }
+
+    // Rating after a period of `(opponent, score)` results, scores being 1 for a win, 0.5 for a
+    // draw and 0 for a loss. A period without games only widens the deviation.
+    pub fn update(&self, results: &[(Glicko, f64)]) -> Self {
+        let (mu, phi, sigma) = (self.mu(), self.phi(), self.volatility);
+
+        if results.is_empty() {
+            let deviation = (phi * phi + sigma * sigma).sqrt() * SCALE;
+            return Self::new(self.rating, deviation, sigma);
+        }
+
+        let mut inv_variance = 0.0;
+        let mut improvement = 0.0;
+        for (opponent, score) in results {
+            let g = g(opponent.phi());
+            let e = expected(mu, opponent.mu(), opponent.phi());
+            inv_variance += g * g * e * (1.0 - e);
+            improvement += g * (score - e);
+        }
+        let variance = 1.0 / inv_variance;
+        let delta = variance * improvement;
+
+        let sigma = new_volatility(phi, sigma, variance, delta);
+        let phi_star = (phi * phi + sigma * sigma).sqrt();
+        let phi = 1.0 / (1.0 / (phi_star * phi_star) + inv_variance).sqrt();
+        let mu = mu + phi * phi * improvement;
+
+        Self::new(mu * SCALE + 1500.0, phi * SCALE, sigma)
+    }
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+fn expected(mu: f64, opponent_mu: f64, opponent_phi: f64) -> f64 {
+    1.0 / (1.0 + (-g(opponent_phi) * (mu - opponent_mu)).exp())
+}
+
+// Step 5 of Glickman's paper, the Illinois variant of regula falsi.
+fn new_volatility(phi: f64, sigma: f64, variance: f64, delta: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denom = phi * phi + variance + ex;
+        ex * (delta * delta - denom) / (2.0 * denom * denom) - (x - a) / (TAU * TAU)
+    };
+
+    let mut lo = a;
+    let mut hi = if delta * delta > phi * phi + variance {
+        (delta * delta - phi * phi - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+
+    let (mut f_lo, mut f_hi) = (f(lo), f(hi));
+    while (hi - lo).abs() > EPSILON {
+        let next = lo + (lo - hi) * f_lo / (f_hi - f_lo);
+        let f_next = f(next);
+        if f_next * f_hi <= 0.0 {
+            lo = hi;
+            f_lo = f_hi;
+        } else {
+            f_lo /= 2.0;
+        }
+        hi = next;
+        f_hi = f_next;
+    }
+    (lo / 2.0).exp()
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Standing {
+    pub name: String,
+    pub rating: Glicko,
+    pub matches: u32,
+    pub big_blinds: f64, // won over all matches
+}
+
+struct Entry {
+    bot: Box<dyn Bot>,
+    standing: Standing,
+}
+
+pub struct Arena {
+    entries: Vec<Entry>,
+    self_play: SelfPlay,
+    hands: u32,
+    store: Option<Box<dyn RatingStore>>,
+    store_error: Option<io::Error>, // the first one since last taken
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new(200)
+    }
+}
+
+impl Arena {
+    pub fn new(hands: u32) -> Self {
+        Self {
+            entries: vec![],
+            self_play: SelfPlay::default(),
+            hands,
+            store: None,
+            store_error: None,
+        }
+    }
+
+    pub fn with_self_play(mut self, self_play: SelfPlay) -> Self {
+        self.self_play = self_play;
+        self
+    }
+
+    // Registered bots start from their saved standings and every match is saved as it ends.
+    pub fn with_store(mut self, store: impl RatingStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    // Store failures don't stop the arena; they are kept here instead.
+    pub fn take_store_error(&mut self) -> Option<io::Error> {
+        self.store_error.take()
+    }
+
+    fn store_result<T>(&mut self, result: io::Result<T>) -> Option<T> {
+        result
+            .map_err(|err| {
+                self.store_error.get_or_insert(err);
+            })
+            .ok()
+    }
+
+    // False if the name is taken or can't be persisted (tabs, line breaks).
+    pub fn register(&mut self, name: impl Into<String>, bot: impl Bot + 'static) -> bool {
+        let name = name.into();
+        if name.contains(['\t', '\n', '\r']) || self.position(&name).is_some() {
+            return false;
+        }
+
+        let saved = match self.store.as_mut().map(|store| store.load(&name)) {
+            Some(result) => self.store_result(result).flatten(),
+            None => None,
+        };
+        self.entries.push(Entry {
+            bot: Box::new(bot),
+            standing: saved.unwrap_or(Standing {
+                name,
+                rating: Default::default(),
+                matches: 0,
+                big_blinds: 0.0,
+            }),
+        });
+        true
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.standing.name == name)
+    }
+
+    // Plays one match and updates both ratings. Gives the big blinds `a` won.
+    pub fn play_match(&mut self, a: &str, b: &str) -> Option<f64> {
+        let indices = [self.position(a)?, self.position(b)?];
+        let [a, b] = self.entries.get_disjoint_mut(indices).ok()?;
+
+        let trajectories = self.self_play.play(&mut a.bot, &mut b.bot, self.hands);
+        let won: f64 = trajectories
+            .hands()
+            .iter()
+            .map(|hand| hand.results()[0])
+            .sum();
+        let score = match won.partial_cmp(&0.0) {
+            Some(std::cmp::Ordering::Greater) => 1.0,
+            Some(std::cmp::Ordering::Less) => 0.0,
+            _ => 0.5,
+        };
+
+        let (rating_a, rating_b) = (a.standing.rating, b.standing.rating);
+        a.standing.rating = rating_a.update(&[(rating_b, score)]);
+        b.standing.rating = rating_b.update(&[(rating_a, 1.0 - score)]);
+        for (standing, won) in [(&mut a.standing, won), (&mut b.standing, -won)] {
+            standing.matches += 1;
+            standing.big_blinds += won;
+        }
+
+        if let Some(store) = self.store.as_mut() {
+            let result = store
+                .save(&a.standing)
+                .and_then(|()| store.save(&b.standing));
+            self.store_result(result);
+        }
+        Some(won)
+    }
+
+    // A duplicate match of `deals` deals from `seed` between two registered bots, which leaves
+    // the ratings alone.
+    pub fn play_duplicate(
+        &mut self,
+        a: &str,
+        b: &str,
+        deals: u32,
+        seed: u64,
+    ) -> Option<DuplicateMatch> {
+        let indices = [self.position(a)?, self.position(b)?];
+        let [a, b] = self.entries.get_disjoint_mut(indices).ok()?;
+        Some(play_duplicate(
+            &self.self_play,
+            &mut a.bot,
+            &mut b.bot,
+            deals,
+            seed,
+        ))
+    }
+
+    // The pairing whose result is expected to tell the most about the ratings: uncertain ratings
+    // and close matchups first.
+    pub fn best_pairing(&self) -> Option<(&str, &str)> {
+        let n = self.entries.len();
+        (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let (a, b) = (&self.entries[i].standing, &self.entries[j].standing);
+                (a, b, information(&a.rating, &b.rating))
+            })
+            .max_by(|x, y| x.2.total_cmp(&y.2))
+            .map(|(a, b, _)| (a.name.as_str(), b.name.as_str()))
+    }
+
+    // Plays `matches` matches on the best pairings.
+    pub fn run(&mut self, matches: usize) {
+        for _ in 0..matches {
+            let Some((a, b)) = self
+                .best_pairing()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+            else {
+                return;
+            };
+            self.play_match(&a, &b);
+        }
+    }
+
+    // Highest rating first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> =
+            self.entries.iter().map(|e| e.standing.clone()).collect();
+        standings.sort_by(|a, b| b.rating.rating.total_cmp(&a.rating.rating));
+        standings
+    }
+
+    // One tab separated line per bot: name, rating, deviation, volatility, matches, big blinds.
+    pub fn write_standings(&self, mut writer: impl Write) -> io::Result<()> {
+        for standing in self.standings() {
+            write_standing(&mut writer, &standing)?;
+        }
+        writer.flush()
+    }
+
+    // Restores saved standings of the registered bots; gives how many were restored.
+    pub fn read_standings(&mut self, reader: impl BufRead) -> io::Result<usize> {
+        let mut restored = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+
+            let standing = parse_standing(&line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("Bad standing: {line}"))
+            })?;
+            if let Some(i) = self.position(&standing.name) {
+                self.entries[i].standing = standing;
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+}
+
+// Duplicate poker: every deal is played twice, the second time with the bots in each other's
+// seats, so each gets the cards and position the other had and the luck of the deal cancels out
+// of the difference between them.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DuplicateMatch {
+    pairs: Vec<[HandRecord; 2]>, // `a` in seat `true`, then in seat `false`
+}
+
+impl DuplicateMatch {
+    pub fn pairs(&self) -> &[[HandRecord; 2]] {
+        &self.pairs
+    }
+
+    // Big blinds `a` won from `b` on each deal over both plays of it.
+    pub fn differences(&self) -> impl Iterator<Item = f64> + '_ {
+        self.pairs
+            .iter()
+            .map(|[first, second]| first.results()[0] + second.results()[1])
+    }
+
+    pub fn total(&self) -> f64 {
+        self.differences().sum()
+    }
+
+    // Big blinds `a` won a deal.
+    pub fn mean(&self) -> f64 {
+        if self.pairs.is_empty() {
+            return 0.0;
+        }
+        self.total() / self.pairs.len() as f64
+    }
+
+    // Of `mean`, from the spread of the differences.
+    pub fn std_error(&self) -> f64 {
+        let n = self.pairs.len() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let variance = self.differences().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (variance / n).sqrt()
+    }
+}
+
+// Deal `i` comes from `seed` and `i`, with `a` on the button of even deals the first time.
+pub fn play_duplicate(
+    self_play: &SelfPlay,
+    a: &mut impl Bot,
+    b: &mut impl Bot,
+    deals: u32,
+    seed: u64,
+) -> DuplicateMatch {
+    let pairs = (0..deals)
+        .map(|i| {
+            let deal = Deal::seeded(seed, i as u64, i.is_multiple_of(2));
+            [
+                self_play.play_deal(i, deal, a, b, &mut vec![]),
+                self_play.play_deal(i, deal, b, a, &mut vec![]),
+            ]
+        })
+        .collect();
+    DuplicateMatch { pairs }
+}
+
+pub(crate) fn write_standing(mut writer: impl Write, s: &Standing) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        s.name, s.rating.rating, s.rating.deviation, s.rating.volatility, s.matches, s.big_blinds
+    )
+}
+
+pub(crate) fn parse_standing(line: &str) -> Option<Standing> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [name, rating, deviation, volatility, matches, big_blinds] = fields[..] else {
+        return None;
+    };
+
+    Some(Standing {
+        name: name.to_string(),
+        rating: Glicko::new(
+            rating.parse().ok()?,
+            deviation.parse().ok()?,
+            volatility.parse().ok()?,
+        ),
+        matches: matches.parse().ok()?,
+        big_blinds: big_blinds.parse().ok()?,
+    })
+}
+
+// Expected information of a game between `a` and `b`, weighted by how uncertain they are.
+fn information(a: &Glicko, b: &Glicko) -> f64 {
+    let spread = a.phi().hypot(b.phi());
+    let e = expected(a.mu(), b.mu(), spread);
+    g(spread).powi(2) * e * (1.0 - e) * spread * spread
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{ExploitBot, testing::Caller};
+
+    #[test]
+    fn glickman_example() {
+        // the worked example of Glickman's Glicko-2 paper
+        let player = Glicko::new(1500.0, 200.0, 0.06);
+        let updated = player.update(&[
+            (Glicko::new(1400.0, 30.0, 0.06), 1.0),
+            (Glicko::new(1550.0, 100.0, 0.06), 0.0),
+            (Glicko::new(1700.0, 300.0, 0.06), 0.0),
+        ]);
+        assert!((updated.rating - 1464.06).abs() < 0.01);
+        assert!((updated.deviation - 151.52).abs() < 0.01);
+        assert!((updated.volatility - 0.05999).abs() < 1e-5);
+
+        let mut arena = Arena::new(20);
+        assert!(arena.register("a", ExploitBot::default()));
+        assert!(arena.register("b", ExploitBot::default()));
+        assert!(!arena.register("a", ExploitBot::default()));
+        arena.run(2);
+
+        let mut saved = vec![];
+        arena.write_standings(&mut saved).unwrap();
+        let standings = arena.standings();
+        assert!(
+            standings
+                .iter()
+                .all(|s| s.matches == 2 && s.rating.deviation < 350.0)
+        );
+
+        let mut restored = Arena::new(20);
+        restored.register("b", ExploitBot::default());
+        assert_eq!(restored.read_standings(saved.as_slice()).unwrap(), 1);
+        assert!(restored.standings()[0].matches == 2);
+    }
+
+    #[test]
+    fn duplicate_deals_cancel_out() {
+        // both calling down, each deal wins one bot back what it lost the other
+        let self_play = SelfPlay::default();
+        let duplicate = play_duplicate(&self_play, &mut Caller, &mut Caller, 30, 9);
+        assert_eq!(duplicate.pairs().len(), 30);
+        assert!(duplicate.pairs()[0][0].results()[0] != 0.0);
+        assert!(duplicate.differences().all(|d| d.abs() < 1e-9));
+        assert_eq!((duplicate.mean(), duplicate.std_error()), (0.0, 0.0));
+
+        let [first, second] = &duplicate.pairs()[1];
+        assert_eq!(first.deal(), second.deal());
+        assert_eq!(
+            duplicate,
+            play_duplicate(&self_play, &mut Caller, &mut Caller, 30, 9)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Bot, Decision, hand_strength};
+use crate::{
+    gameplay::{Street, headsup::Action},
+    stats::{ActionRecord, PlayerStats},
+};
+use rand::prelude::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Adjustments {
+    pub open: f64,       // preflop strength needed to raise first in
+    pub value: f64,      // postflop strength needed to bet for value
+    pub call: f64,       // strength needed to continue against a bet
+    pub raise: f64,      // strength needed to raise a bet
+    pub bluff_freq: f64, // how often to bet air when checked to
+}
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Self {
+            open: 0.45,
+            value: 0.6,
+            call: 0.45,
+            raise: 0.8,
+            bluff_freq: 0.2,
+        }
+    }
+}
+
+// Starts from a straightforward baseline and leans against what the opponent over- or under-does.
+#[derive(Debug, Clone)]
+pub struct ExploitBot {
+    opponent: PlayerStats,
+    min_hands: u32,
+}
+
+impl Default for ExploitBot {
+    fn default() -> Self {
+        Self::new(30)
+    }
+}
+
+impl ExploitBot {
+    // `min_hands` observed before trusting the statistics.
+    pub fn new(min_hands: u32) -> Self {
+        Self {
+            opponent: Default::default(),
+            min_hands,
+        }
+    }
+
+    pub fn with_opponent(mut self, opponent: PlayerStats) -> Self {
+        self.opponent = opponent;
+        self
+    }
+
+    pub fn opponent(&self) -> &PlayerStats {
+        &self.opponent
+    }
+
+    pub fn adjustments(&self) -> Adjustments {
+        let mut adj = Adjustments::default();
+        let stats = &self.opponent;
+
+        if stats.hands() < self.min_hands {
+            return adj;
+        }
+
+        // over-folding: bluff and steal more
+        if stats.fold_to_bet() > 0.6 {
+            adj.bluff_freq = 0.5;
+            adj.open = 0.3;
+        }
+
+        // calling station: stop bluffing, bet thinner for value
+        if stats.fold_to_bet() < 0.3 && stats.aggression_factor() < 1.0 {
+            adj.bluff_freq = 0.03;
+            adj.value = 0.45;
+            adj.call = 0.5;
+        }
+
+        // hyper aggressive: call down lighter, trap more
+        if stats.aggression_factor() > 2.5 {
+            adj.call = 0.35;
+            adj.raise = 0.86;
+        }
+
+        // frequent limper: isolate wider
+        if stats.limp() > 0.3 {
+            adj.open = adj.open.min(0.35);
+        }
+
+        adj
+    }
+}
+
+impl Bot for ExploitBot {
+    fn act(&mut self, decision: &Decision) -> Action {
+        let Some(hole) = decision.hole() else {
+            return Action::check_or_call();
+        };
+        let adj = self.adjustments();
+        let strength = hand_strength(hole, &decision.board());
+        let preflop = decision.street() == Street::Preflop;
+
+        if decision.facing_bet() {
+            let unopened = preflop && decision.bets()[1] <= decision.big_blind();
+
+            if unopened {
+                if strength >= adj.open {
+                    decision.sized(0.75)
+                } else if strength >= adj.open - 0.1 {
+                    Action::check_or_call()
+                } else {
+                    Action::fold()
+                }
+            } else if strength >= adj.raise {
+                decision.sized(1.0)
+            } else if strength >= adj.call {
+                Action::check_or_call()
+            } else {
+                Action::fold()
+            }
+        } else if preflop {
+            // option after a limp
+            if strength >= adj.open {
+                decision.sized(1.0)
+            } else {
+                Action::check_or_call()
+            }
+        } else if strength >= adj.value {
+            decision.sized(0.66)
+        } else if rand::rng().random_bool(adj.bluff_freq) {
+            decision.sized(0.5)
+        } else {
+            Action::check_or_call()
+        }
+    }
+
+    fn observe(&mut self, seat: bool, record: &ActionRecord) {
+        if record.actor() != seat {
+            self.opponent.record(record);
+        }
+    }
+
+    fn new_hand(&mut self) {
+        self.opponent.new_hand();
+    }
+
+    fn load_opponent(&mut self, stats: &PlayerStats) {
+        self.opponent = *stats;
+    }
+
+    fn opponent_stats(&self) -> Option<PlayerStats> {
+        Some(self.opponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bluffs_more_against_overfolder() {
+        let mut bot = ExploitBot::new(5);
+        let fold = ActionRecord::new(false, Street::Flop, Action::fold(), true, 1);
+
+        for _ in 0..10 {
+            bot.new_hand();
+            bot.observe(true, &fold);
+        }
+
+        assert_eq!(bot.opponent().fold_to_bet(), 1.0);
+        assert!(bot.adjustments().bluff_freq > Adjustments::default().bluff_freq);
+    }
+}
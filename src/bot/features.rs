@@ -0,0 +1,206 @@
+// Numeric encodings of a `Decision` for machine learning. A version's layout never changes once
+// released; new layouts get a new variant, so models trained on one keep working in play.
+//
+// V1, 260 floats:
+//   0..52     hero hole, one-hot by card index (value * 4 + suit, suits in `Suit::ALL` order)
+//   52..104   board, same indexing
+//   104..108  street, one-hot
+//   108       hero has the button
+//   109..116  pot, to call, hero bet, villain bet, hero stack, villain stack, each as a share of
+//             all chips in play, then pot odds
+//   116..260  action history, 4 streets x 6 slots x 6 floats: [by hero, fold, check/call,
+//             bet/raise, all in, raise-to as a share of all chips]; later actions of a street
+//             beyond 6 are dropped
+//
+// Card planes are 4 x 13 grids (suit rows, value columns), one per hole, flop, turn and river.
+use super::Decision;
+use crate::{
+    gameplay::{Card, headsup::ActionValue},
+    stats::ActionRecord,
+};
+
+const CARDS: usize = 52;
+const STREETS: usize = 4;
+const SLOTS: usize = 6;
+const SLOT_LEN: usize = 6;
+
+#[non_exhaustive]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FeatureEncoder {
+    #[default]
+    V1,
+}
+
+impl FeatureEncoder {
+    pub const LATEST: Self = Self::V1;
+
+    pub fn version(self) -> u32 {
+        match self {
+            Self::V1 => 1,
+        }
+    }
+
+    pub fn len(self) -> usize {
+        match self {
+            Self::V1 => CARDS * 2 + STREETS + 1 + 7 + STREETS * SLOTS * SLOT_LEN,
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn encode(self, decision: &Decision) -> Vec<f32> {
+        match self {
+            Self::V1 => encode_v1(decision),
+        }
+    }
+
+    pub fn planes(self, decision: &Decision) -> [[[f32; 13]; 4]; 4] {
+        let mut planes = [[[0.0; 13]; 4]; 4];
+        let mut set = |plane: usize, card: Card| {
+            planes[plane][card.suit() as usize][card.value() as usize] = 1.0;
+        };
+
+        if let Some(hole) = decision.hole() {
+            hole.iter().for_each(|&card| set(0, card));
+        }
+        for (i, card) in decision.board().to_vec().into_iter().enumerate() {
+            let plane = match i {
+                0..=2 => 1,
+                3 => 2,
+                _ => 3,
+            };
+            set(plane, card);
+        }
+        planes
+    }
+}
+
+// Usable as an `OnnxBot` encoder.
+pub fn encode_v1(decision: &Decision) -> Vec<f32> {
+    let mut features = vec![0.0; FeatureEncoder::V1.len()];
+
+    if let Some(hole) = decision.hole() {
+        hole.iter()
+            .for_each(|&card| features[card_index(card)] = 1.0);
+    }
+    for card in decision.board().to_vec() {
+        features[CARDS + card_index(card)] = 1.0;
+    }
+
+    let street = decision.street() as usize;
+    features[CARDS * 2 + street] = 1.0;
+    features[CARDS * 2 + STREETS] = decision.is_button() as u8 as f32;
+
+    let [hero_bet, villain_bet] = decision.bets();
+    let [hero_stack, villain_stack] = decision.stacks();
+    let pot = decision.pot().get() as f32;
+    let to_call = decision.to_call().get() as f32;
+    let in_play = (pot + (hero_stack + villain_stack).get() as f32).max(1.0);
+    let chips = [
+        pot,
+        to_call,
+        hero_bet.get() as f32,
+        villain_bet.get() as f32,
+        hero_stack.get() as f32,
+        villain_stack.get() as f32,
+    ];
+
+    let scalars = CARDS * 2 + STREETS + 1;
+    for (i, value) in chips.into_iter().enumerate() {
+        features[scalars + i] = value / in_play;
+    }
+    features[scalars + 6] = if to_call > 0.0 {
+        to_call / (pot + to_call)
+    } else {
+        0.0
+    };
+
+    let history = scalars + 7;
+    let mut slots = [0; STREETS];
+    for record in decision.history() {
+        let street = record.street() as usize;
+        if slots[street] == SLOTS {
+            continue;
+        }
+
+        let offset = history + (street * SLOTS + slots[street]) * SLOT_LEN;
+        features[offset..offset + SLOT_LEN].copy_from_slice(&slot(
+            record,
+            decision.seat(),
+            in_play,
+        ));
+        slots[street] += 1;
+    }
+
+    features
+}
+
+fn card_index(card: Card) -> usize {
+    card.value() as usize * 4 + card.suit() as usize
+}
+
+fn slot(record: &ActionRecord, hero: bool, in_play: f32) -> [f32; SLOT_LEN] {
+    let mut slot = [0.0; SLOT_LEN];
+    slot[0] = (record.actor() == hero) as u8 as f32;
+
+    match record.action().value() {
+        ActionValue::Fold | ActionValue::Exit => slot[1] = 1.0,
+        ActionValue::CheckOrCall => slot[2] = 1.0,
+        ActionValue::BetOrRaise(amount) => {
+            slot[3] = 1.0;
+            slot[5] = amount.get() as f32 / in_play;
+        }
+        ActionValue::AllIn => slot[4] = 1.0,
+    }
+    slot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        Board, Hole, Street,
+        chips::Chips,
+        headsup::{Action, HandState},
+    };
+
+    #[test]
+    fn v1_layout() {
+        let hole: Hole = "AsKh".parse().unwrap();
+        let board: Board = "2c7d9h".parse().unwrap();
+        let mut state = HandState::postflop(
+            100,
+            true,
+            Street::Flop,
+            Chips::new(1000),
+            [Chips::new(10000), Chips::new(10000)],
+        )
+        .with_cards([Some(hole), None], board);
+        state.action(Action::bet_or_raise(Chips::new(500)).unwrap());
+
+        let bet = ActionRecord::new(
+            false,
+            Street::Flop,
+            Action::bet_or_raise(Chips::new(500)).unwrap(),
+            false,
+            0,
+        );
+        let decision =
+            Decision::from_state(true, &state, state.bet_bound()).with_history(vec![bet]);
+        let features = FeatureEncoder::LATEST.encode(&decision);
+
+        assert_eq!(features.len(), 260);
+        assert_eq!(features[..CARDS].iter().sum::<f32>(), 2.0);
+        assert_eq!(
+            features[CARDS * 2..CARDS * 2 + STREETS],
+            [0.0, 1.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            features[152..158], // first flop slot
+            [0.0, 0.0, 0.0, 1.0, 0.0, 500.0 / 21000.0]
+        );
+        assert_eq!(FeatureEncoder::V1.planes(&decision)[1][3][0], 1.0); // 2c on the flop
+    }
+}
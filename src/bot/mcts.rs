@@ -0,0 +1,354 @@
+use super::{Bot, Decision};
+use crate::{
+    gameplay::{
+        Card, FullBoard, Hole, Suit, Value,
+        chips::Chips,
+        headsup::{Action, ActionOver, ActionValue, HandState},
+    },
+    range::Range,
+    solver::ActionAbstraction,
+};
+use itertools::Itertools;
+use rand::prelude::*;
+use std::cmp::Ordering;
+
+// Information-set MCTS from the hero's seat: every iteration samples the villain's hole and the
+// runout, then walks one shared tree of abstract actions. The tree ignores cards, so villain
+// nodes pool statistics over every hole the villain might hold.
+#[derive(Debug, Clone)]
+pub struct MctsBot {
+    iterations: usize,
+    exploration: f64,
+    abstraction: ActionAbstraction,
+    rollouts: bool,
+    villain_range: Option<Range>,
+}
+
+impl Default for MctsBot {
+    fn default() -> Self {
+        Self::new(2000)
+    }
+}
+
+impl MctsBot {
+    pub fn new(iterations: usize) -> Self {
+        Self {
+            iterations,
+            exploration: 1.0,
+            abstraction: Default::default(),
+            rollouts: true,
+            villain_range: None,
+        }
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    pub fn with_abstraction(mut self, abstraction: ActionAbstraction) -> Self {
+        self.abstraction = abstraction;
+        self
+    }
+
+    // Without rollouts a new leaf is valued by splitting the matched pot at showdown.
+    pub fn with_rollouts(mut self, rollouts: bool) -> Self {
+        self.rollouts = rollouts;
+        self
+    }
+
+    // Where villain holes are sampled from; uniform over the live combos otherwise.
+    pub fn with_villain_range(mut self, range: Range) -> Self {
+        self.villain_range = Some(range);
+        self
+    }
+
+    // Root actions with their visit counts and mean hero result in chips, most visited first.
+    pub fn search(&self, decision: &Decision) -> Vec<(Action, u32, f64)> {
+        let Some(hole) = decision.hole() else {
+            return vec![];
+        };
+
+        let hero = decision.seat();
+        let board = decision.board().to_vec();
+        let dead = hole.iter().chain(board.iter()).copied().collect_vec();
+        let villain_range = self
+            .villain_range
+            .clone()
+            .unwrap_or_else(Range::full)
+            .without(&dead);
+        let deck = Value::ALL
+            .into_iter()
+            .cartesian_product(Suit::ALL)
+            .map(|(value, suit)| Card::new(value, suit))
+            .filter(|card| !dead.contains(card))
+            .collect_vec();
+
+        let root = *decision.state();
+        let hero_index = if hero { 0 } else { 1 };
+        let scale = (root.behinds()[0] + root.behinds()[1] + root.pot())
+            .get()
+            .max(1) as f64;
+        let mut search = Search {
+            bot: self,
+            hero,
+            hole,
+            base: root.behinds()[hero_index] - root.cur_round()[hero_index],
+            scale,
+            nodes: vec![],
+        };
+        search.push(root, 0, None, !root.cur_turn());
+
+        let mut rng = rand::rng();
+        for _ in 0..self.iterations {
+            let Some(villain) = villain_range.sample(&mut rng) else {
+                break;
+            };
+            let live = deck
+                .iter()
+                .filter(|card| !villain.contains_card(**card))
+                .copied()
+                .collect_vec();
+            let runout = board
+                .iter()
+                .copied()
+                .chain(live.choose_multiple(&mut rng, 5 - board.len()).copied())
+                .collect_vec();
+            let full = FullBoard::new(runout.try_into().expect("Runouts have five cards"))
+                .expect("Runout cards are distinct");
+
+            search.iterate(villain, &full, &mut rng);
+        }
+
+        let mut stats = search.nodes[0]
+            .children
+            .iter()
+            .map(|&(action, child)| {
+                let node = &search.nodes[child];
+                let mean = node.reward / node.visits.max(1) as f64 * scale;
+                (action, node.visits, mean)
+            })
+            .collect_vec();
+        stats.sort_by_key(|&(_, visits, _)| std::cmp::Reverse(visits));
+        stats
+    }
+}
+
+impl Bot for MctsBot {
+    fn act(&mut self, decision: &Decision) -> Action {
+        self.search(decision)
+            .first()
+            .map_or(Action::check_or_call(), |&(action, _, _)| action)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SearchNode {
+    state: HandState,
+    raises: u8,
+    outcome: Option<ActionOver>,
+    mover: bool, // who took the action leading here
+    untried: Vec<Action>,
+    children: Vec<(Action, usize)>,
+    visits: u32,
+    reward: f64, // for `mover`, normalized by the chips in play
+}
+
+struct Search<'a> {
+    bot: &'a MctsBot,
+    hero: bool,
+    hole: Hole,
+    base: Chips, // hero's chips behind at the root
+    scale: f64,
+    nodes: Vec<SearchNode>,
+}
+
+impl Search<'_> {
+    fn push(
+        &mut self,
+        state: HandState,
+        raises: u8,
+        outcome: Option<ActionOver>,
+        mover: bool,
+    ) -> usize {
+        let mut untried = match outcome {
+            None => self.bot.abstraction.actions_at(&state, raises),
+            Some(_) => vec![],
+        };
+        untried.shuffle(&mut rand::rng());
+
+        self.nodes.push(SearchNode {
+            state,
+            raises,
+            outcome,
+            mover,
+            untried,
+            children: vec![],
+            visits: 0,
+            reward: 0.0,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn iterate(&mut self, villain: Hole, board: &FullBoard, rng: &mut impl Rng) {
+        let mut path = vec![0];
+        let mut cur = 0;
+
+        while self.nodes[cur].outcome.is_none() {
+            if let Some(action) = self.nodes[cur].untried.pop() {
+                let node = &self.nodes[cur];
+                let (state, raises, outcome) = step(node.state, node.raises, action);
+                let mover = self.nodes[cur].state.cur_turn();
+                let child = self.push(state, raises, outcome, mover);
+                self.nodes[cur].children.push((action, child));
+                path.push(child);
+                cur = child;
+                break;
+            }
+
+            cur = self.select(cur);
+            path.push(cur);
+        }
+
+        let node = &self.nodes[cur];
+        let reward = if node.outcome.is_some() || self.bot.rollouts {
+            self.rollout(node.state, node.raises, node.outcome, villain, board, rng)
+        } else {
+            self.settle(&node.state, None, villain, board)
+        };
+
+        for id in path {
+            let node = &mut self.nodes[id];
+            node.visits += 1;
+            node.reward += if node.mover == self.hero {
+                reward
+            } else {
+                -reward
+            };
+        }
+    }
+
+    fn select(&self, id: usize) -> usize {
+        let parent = &self.nodes[id];
+        let log_visits = (parent.visits.max(1) as f64).ln();
+
+        parent
+            .children
+            .iter()
+            .map(|&(_, child)| {
+                let node = &self.nodes[child];
+                let visits = node.visits.max(1) as f64;
+                let score =
+                    node.reward / visits + self.bot.exploration * (log_visits / visits).sqrt();
+                (child, score)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(child, _)| child)
+            .expect("Expanded nodes should have children")
+    }
+
+    // Random abstract actions until the hand ends.
+    fn rollout(
+        &self,
+        mut state: HandState,
+        mut raises: u8,
+        mut outcome: Option<ActionOver>,
+        villain: Hole,
+        board: &FullBoard,
+        rng: &mut impl Rng,
+    ) -> f64 {
+        while outcome.is_none() {
+            let actions = self.bot.abstraction.actions_at(&state, raises);
+            let Some(&action) = actions.choose(rng) else {
+                break;
+            };
+            (state, raises, outcome) = step(state, raises, action);
+        }
+
+        self.settle(&state, outcome, villain, board)
+    }
+
+    // Hero's result against the root in chips, normalized.
+    fn settle(
+        &self,
+        state: &HandState,
+        outcome: Option<ActionOver>,
+        villain: Hole,
+        board: &FullBoard,
+    ) -> f64 {
+        let hero = if self.hero { 0 } else { 1 };
+        let behinds = state.behinds();
+        let share = || match board.hand_value(self.hole).cmp(&board.hand_value(villain)) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        };
+
+        let end = match outcome {
+            Some(ActionOver::HandOver) => behinds[hero].get() as f64,
+            Some(_) => behinds[hero].get() as f64 + share() * state.pot().get() as f64,
+            None => {
+                // check it down with the matched chips
+                let cur_round = state.cur_round();
+                let matched = cur_round[0].min(cur_round[1]);
+                (behinds[hero] - matched).get() as f64
+                    + share() * (state.pot() + matched * 2).get() as f64
+            }
+        };
+
+        (end - self.base.get() as f64) / self.scale
+    }
+}
+
+fn step(
+    mut state: HandState,
+    mut raises: u8,
+    action: Action,
+) -> (HandState, u8, Option<ActionOver>) {
+    let aggressive = matches!(
+        action.value(),
+        ActionValue::BetOrRaise(_) | ActionValue::AllIn
+    );
+
+    let outcome = match state.action(action) {
+        ActionOver::TurnOver => {
+            raises += aggressive as u8;
+            None
+        }
+        ActionOver::RoundOver => {
+            state.next_street();
+            raises = 0;
+            None
+        }
+        over => Some(over),
+    };
+
+    (state, raises, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{Board, Street};
+
+    #[test]
+    fn calls_with_the_nuts() {
+        let hole: Hole = "Ts3c".parse().unwrap();
+        let board: Board = "AsKsQsJs2d".parse().unwrap();
+        let mut state = HandState::postflop(
+            100,
+            true,
+            Street::River,
+            Chips::new(1000),
+            [Chips::new(10000), Chips::new(10000)],
+        )
+        .with_cards([Some(hole), None], board);
+        state.action(Action::bet_or_raise(Chips::new(1000)).unwrap());
+
+        let decision = Decision::from_state(true, &state, state.bet_bound());
+        let stats = MctsBot::new(300).search(&decision);
+
+        assert_ne!(stats[0].0, Action::fold());
+        assert!(stats.iter().all(|&(_, _, mean)| mean >= 0.0));
+    }
+}
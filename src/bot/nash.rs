@@ -0,0 +1,164 @@
+use super::{Bot, Decision};
+use crate::{
+    gameplay::{HoleClass, Street, Value, headsup::Action},
+    range::Range,
+    stats::{ActionRecord, PlayerStats},
+};
+use itertools::Itertools;
+
+// Deeper than this, in big blinds of effective stack, the charts don't apply.
+pub const MAX_BB: f64 = 20.0;
+
+// Heads-up push/fold at the Nash equilibrium, blinds of half a big blind and one, no antes: the
+// largest effective stack in big blinds each hand shoves from the button (PUSH) or calls that
+// shove in the big blind (CALL) at, 20.0 meaning up to `MAX_BB`. Rows and columns run from aces
+// down to deuces, suited hands above the diagonal of pairs and offsuit ones below.
+#[rustfmt::skip]
+const PUSH: [[f32; 13]; 13] = [
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 19.6, 19.1],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 17.4, 14.2, 11.9],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 19.0, 14.7, 13.6, 10.8,  8.8],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 13.2, 10.8,  7.7,  6.5],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 14.4,  5.7,  4.9,  3.4],
+    [20.0, 18.7, 13.0, 12.0, 14.7, 20.0, 20.0, 20.0, 20.0, 18.9,  8.8,  2.7,  2.5],
+    [20.0, 17.7, 10.3,  8.5, 10.0, 10.4, 15.6, 20.0, 20.0, 20.0, 13.5,  2.4,  2.1],
+    [20.0, 14.9,  9.8,  6.5,  5.9,  5.2,  6.8, 10.7, 20.0, 20.0, 16.5,  7.0,  2.0],
+    [20.0, 14.0,  8.9,  6.1,  4.1,  3.5,  2.9,  2.6,  6.9, 20.0, 20.0, 13.5,  2.0],
+    [20.0, 13.1,  7.8,  5.4,  3.8,  2.7,  2.3,  2.1,  2.0,  2.1, 20.0,  8.8,  1.9],
+    [20.0, 12.1,  7.5,  5.0,  3.4,  2.5,  1.9,  1.8,  1.7,  1.8,  1.6, 20.0,  1.7],
+    [20.0, 11.7,  7.1,  4.6,  3.0,  2.3,  1.8,  1.6,  1.5,  1.5,  1.4,  1.4, 20.0],
+];
+
+#[rustfmt::skip]
+const CALL: [[f32; 13]; 13] = [
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 20.0],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 20.0, 17.6, 15.5, 14.1, 13.3, 12.1, 11.5, 10.7],
+    [20.0, 20.0, 20.0, 20.0, 20.0, 16.0, 13.0, 10.4,  9.7,  8.9,  8.5,  7.8,  7.1],
+    [20.0, 20.0, 19.3, 20.0, 18.0, 13.5, 10.6,  8.8,  7.0,  6.9,  6.3,  5.9,  5.6],
+    [20.0, 20.0, 15.1, 12.7, 20.0, 11.5,  9.6,  7.3,  6.1,  5.2,  5.1,  4.8,  4.5],
+    [20.0, 17.1, 11.7,  9.4,  8.4, 20.0,  8.2,  7.0,  5.7,  4.9,  4.2,  4.1,  3.8],
+    [20.0, 13.8,  9.9,  7.6,  6.6,  6.0, 20.0,  6.5,  5.7,  4.8,  4.1,  3.6,  3.5],
+    [20.0, 12.3,  7.9,  6.4,  5.5,  5.0,  4.7, 20.0,  5.5,  4.6,  4.2,  3.6,  3.3],
+    [20.0, 10.9,  7.4,  5.4,  4.7,  4.2,  4.1,  4.0, 20.0,  4.9,  4.3,  3.7,  3.3],
+    [20.0, 10.2,  6.8,  5.1,  4.0,  3.7,  3.6,  3.6,  3.7, 20.0,  4.6,  4.1,  3.5],
+    [18.4,  9.2,  6.2,  4.8,  3.8,  3.3,  3.2,  3.2,  3.3,  3.5, 20.0,  3.8,  3.4],
+    [16.5,  8.7,  5.8,  4.5,  3.6,  3.1,  2.9,  2.9,  3.0,  3.1,  3.0, 20.0,  3.3],
+    [15.8,  8.1,  5.6,  4.2,  3.5,  3.1,  2.8,  2.6,  2.7,  2.8,  2.7,  2.6, 15.3],
+];
+
+// Hands the button shoves with at an effective stack of `bb` big blinds, capped at `MAX_BB`.
+pub fn nash_push_range(bb: f64) -> Range {
+    chart_range(&PUSH, bb)
+}
+
+// Hands the big blind calls a shove with at an effective stack of `bb` big blinds.
+pub fn nash_call_range(bb: f64) -> Range {
+    chart_range(&CALL, bb)
+}
+
+fn chart_range(chart: &[[f32; 13]; 13], bb: f64) -> Range {
+    let bb = bb.min(MAX_BB);
+    let classes = Value::ALL
+        .into_iter()
+        .cartesian_product(Value::ALL)
+        .map(|(a, b)| {
+            HoleClass::suited(a, b)
+                .filter(|_| a > b)
+                .or(HoleClass::offsuit(a, b))
+                .unwrap_or(HoleClass::Pair(a))
+        });
+    Range::from_classes(classes.filter(|&class| chart_stack(chart, class) as f64 >= bb))
+}
+
+fn chart_stack(chart: &[[f32; 13]; 13], class: HoleClass) -> f32 {
+    let (row, col) = class.grid_cell();
+    chart[row][col]
+}
+
+// Short stacked preflop play straight from the charts; deeper, or after the flop, `deep` plays.
+#[derive(Debug, Clone)]
+pub struct PushFoldBot<B> {
+    deep: B,
+}
+
+impl<B: Bot> PushFoldBot<B> {
+    pub fn new(deep: B) -> Self {
+        Self { deep }
+    }
+}
+
+impl<B: Bot> Bot for PushFoldBot<B> {
+    fn act(&mut self, decision: &Decision) -> Action {
+        let [hero, villain] = [0, 1].map(|i| decision.stacks()[i] + decision.bets()[i]);
+        let effective = hero.min(villain).in_bb(decision.big_blind());
+        let Some(hole) = decision.hole() else {
+            return self.deep.act(decision);
+        };
+        if decision.street() != Street::Preflop || effective > MAX_BB {
+            return self.deep.act(decision);
+        }
+
+        // first in on the button shoves, anything after it is played as a shove to call
+        let first_in = decision.is_button() && decision.history().is_empty();
+        let chart = if first_in || !decision.facing_bet() {
+            &PUSH
+        } else {
+            &CALL
+        };
+        let action = if chart_stack(chart, HoleClass::from(hole)) as f64 >= effective {
+            Action::all_in()
+        } else if decision.facing_bet() {
+            Action::fold()
+        } else {
+            Action::check_or_call()
+        };
+        decision.legalize(action)
+    }
+
+    fn observe(&mut self, seat: bool, record: &ActionRecord) {
+        self.deep.observe(seat, record)
+    }
+
+    fn new_hand(&mut self) {
+        self.deep.new_hand()
+    }
+
+    fn load_opponent(&mut self, stats: &PlayerStats) {
+        self.deep.load_opponent(stats)
+    }
+
+    fn opponent_stats(&self) -> Option<PlayerStats> {
+        self.deep.opponent_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::ExploitBot,
+        gameplay::{Board, chips::Chips, headsup::HandState},
+    };
+
+    #[test]
+    fn shoves_the_chart() {
+        let ten = nash_push_range(10.0);
+        assert!((ten.len() as f64 / 1326.0 - 0.59).abs() < 0.02);
+        assert!(ten.contains("Kc2d".parse().unwrap()));
+        assert!(!ten.contains("7c2d".parse().unwrap()));
+        assert!(nash_push_range(15.0).len() < ten.len());
+        assert!(nash_call_range(10.0).len() < ten.len());
+
+        let mut bot = PushFoldBot::new(ExploitBot::default());
+        let mut decide = |hole: &str, stacks| {
+            let mut state = HandState::new(100, true, [Chips::new(stacks); 2])
+                .with_cards([hole.parse().ok(), None], Board::default());
+            state.deal_holes_int();
+            bot.act(&Decision::from_state(true, &state, state.bet_bound()))
+        };
+        assert_eq!(decide("AsKd", 1000), Action::all_in());
+        assert_eq!(decide("7c2d", 1000), Action::fold());
+        assert_eq!(decide("7c2d", 150), Action::all_in());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Bot, Decision};
+use crate::gameplay::headsup::Action;
+use rand::prelude::*;
+use std::{io::Read, path::Path};
+use tract_onnx::prelude::*;
+
+type Model = TypedRunnableModel<TypedModel>;
+
+// Plays a policy network exported to ONNX. The model takes one `[1, inputs]` f32 row built by
+// the encoder (e.g. `features::encode_v1`) and returns one score per action slot:
+// `[fold, check/call, bet sizes.., all in]`, bet sizes being pot fractions.
+// Illegal slots are masked and the rest renormalized.
+#[derive(Debug, Clone)]
+pub struct OnnxBot {
+    model: Model,
+    encoder: fn(&Decision) -> Vec<f32>,
+    sizes: Vec<f64>,
+    softmax: bool,
+    greedy: bool,
+}
+
+impl OnnxBot {
+    pub fn load(
+        path: impl AsRef<Path>,
+        inputs: usize,
+        encoder: fn(&Decision) -> Vec<f32>,
+    ) -> TractResult<Self> {
+        Self::from_model(tract_onnx::onnx().model_for_path(path)?, inputs, encoder)
+    }
+
+    pub fn from_reader(
+        reader: &mut impl Read,
+        inputs: usize,
+        encoder: fn(&Decision) -> Vec<f32>,
+    ) -> TractResult<Self> {
+        Self::from_model(tract_onnx::onnx().model_for_read(reader)?, inputs, encoder)
+    }
+
+    fn from_model(
+        model: InferenceModel,
+        inputs: usize,
+        encoder: fn(&Decision) -> Vec<f32>,
+    ) -> TractResult<Self> {
+        let model = model
+            .with_input_fact(0, f32::fact([1, inputs]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        Ok(Self {
+            model,
+            encoder,
+            sizes: vec![0.5, 1.0],
+            softmax: true,
+            greedy: false,
+        })
+    }
+
+    pub fn with_sizes(mut self, sizes: Vec<f64>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    // Whether the outputs are logits (the default) or already probabilities.
+    pub fn with_softmax(mut self, softmax: bool) -> Self {
+        self.softmax = softmax;
+        self
+    }
+
+    // Always take the most likely action instead of sampling.
+    pub fn with_greedy(mut self, greedy: bool) -> Self {
+        self.greedy = greedy;
+        self
+    }
+
+    pub fn policy(&self, decision: &Decision) -> TractResult<Vec<(Action, f64)>> {
+        let features = (self.encoder)(decision);
+        let input = Tensor::from_shape(&[1, features.len()], &features)?;
+        let outputs = self.model.run(tvec!(input.into()))?;
+        let scores: Vec<f32> = outputs[0].to_array_view::<f32>()?.iter().copied().collect();
+
+        if scores.len() != self.sizes.len() + 3 {
+            return Err(TractError::msg(format!(
+                "Model gives {} scores for {} action slots",
+                scores.len(),
+                self.sizes.len() + 3
+            )));
+        }
+        Ok(legal_policy(decision, &self.sizes, &scores, self.softmax))
+    }
+}
+
+impl Bot for OnnxBot {
+    fn act(&mut self, decision: &Decision) -> Action {
+        let Ok(policy) = self.policy(decision) else {
+            return Action::check_or_call();
+        };
+
+        let choice = if self.greedy {
+            policy.iter().max_by(|a, b| a.1.total_cmp(&b.1))
+        } else {
+            policy.choose_weighted(&mut rand::rng(), |(_, p)| *p).ok()
+        };
+        choice.map_or(Action::check_or_call(), |&(action, _)| action)
+    }
+}
+
+// Legal actions of the slots with their renormalized probabilities; slots mapping onto the same
+// action (e.g. a size clamped to all in) are merged.
+fn legal_policy(
+    decision: &Decision,
+    sizes: &[f64],
+    scores: &[f32],
+    softmax: bool,
+) -> Vec<(Action, f64)> {
+    let slots = [Action::fold(), Action::check_or_call()]
+        .into_iter()
+        .chain(sizes.iter().map(|&size| decision.sized(size)))
+        .chain([Action::all_in()]);
+    let weights = scores.iter().map(|&score| {
+        if softmax {
+            (score as f64).exp()
+        } else {
+            (score as f64).max(0.0)
+        }
+    });
+
+    let mut policy: Vec<(Action, f64)> = vec![];
+    for (action, weight) in slots.zip(weights) {
+        let pointless_fold = action == Action::fold() && !decision.facing_bet();
+        if pointless_fold || !decision.bet_bound().validate_action(action) {
+            continue;
+        }
+
+        match policy.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, total)) => *total += weight,
+            None => policy.push((action, weight)),
+        }
+    }
+
+    let total: f64 = policy.iter().map(|(_, w)| w).sum();
+    if total > 0.0 {
+        policy.iter_mut().for_each(|(_, w)| *w /= total);
+    }
+    policy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{Street, chips::Chips, headsup::HandState};
+
+    #[test]
+    fn masks_fold_when_checking() {
+        let state = HandState::postflop(
+            100,
+            true,
+            Street::Flop,
+            Chips::new(1000),
+            [Chips::new(10000), Chips::new(10000)],
+        );
+        let decision = Decision::from_state(false, &state, state.bet_bound());
+        let policy = legal_policy(&decision, &[1.0], &[5.0, 0.0, 0.0, 0.0], true);
+
+        assert!(policy.iter().all(|(action, _)| *action != Action::fold()));
+        assert_eq!(policy.len(), 3);
+        assert!((policy.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,109 @@
+// Where ratings and lifetime results outlive the process, keyed by player or bot name.
+use super::arena::{Standing, parse_standing, write_standing};
+use indexmap::IndexMap;
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter},
+    path::PathBuf,
+};
+
+pub trait RatingStore {
+    fn load(&mut self, name: &str) -> io::Result<Option<Standing>>;
+
+    fn save(&mut self, standing: &Standing) -> io::Result<()>;
+
+    fn all(&mut self) -> io::Result<Vec<Standing>>;
+}
+
+// The arena's standings file, kept in memory and rewritten on every save.
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    standings: IndexMap<String, Standing>,
+}
+
+impl FileStore {
+    // A missing file is an empty store.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut standings = IndexMap::new();
+
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    let standing = parse_standing(&line).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("Bad standing: {line}"))
+                    })?;
+                    standings.insert(standing.name.clone(), standing);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        Ok(Self { path, standings })
+    }
+
+    // Writes a temporary file next to the store and renames it over, so a crash never leaves
+    // half a file behind.
+    fn flush(&self) -> io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for standing in self.standings.values() {
+            write_standing(&mut writer, standing)?;
+        }
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+impl RatingStore for FileStore {
+    fn load(&mut self, name: &str) -> io::Result<Option<Standing>> {
+        Ok(self.standings.get(name).cloned())
+    }
+
+    fn save(&mut self, standing: &Standing) -> io::Result<()> {
+        self.standings
+            .insert(standing.name.clone(), standing.clone());
+        self.flush()
+    }
+
+    fn all(&mut self) -> io::Result<Vec<Standing>> {
+        Ok(self.standings.values().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{Arena, ExploitBot};
+
+    #[test]
+    fn ratings_survive_arenas() {
+        let path = std::env::temp_dir().join(format!("ratings-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut arena = Arena::new(20).with_store(FileStore::open(&path).unwrap());
+        arena.register("a", ExploitBot::default());
+        arena.register("b", ExploitBot::default());
+        arena.run(2);
+        assert!(arena.take_store_error().is_none());
+
+        let mut store = FileStore::open(&path).unwrap();
+        let saved = store.load("a").unwrap().unwrap();
+        assert_eq!(saved.matches, 2);
+        assert_eq!(store.all().unwrap().len(), 2);
+
+        let mut restored = Arena::new(20).with_store(store);
+        restored.register("a", ExploitBot::default());
+        assert_eq!(restored.standings()[0], saved);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,255 @@
+// Many games between bots at once on the tokio runtime, each in a task of its own so one that
+// panics or runs away only costs its own result.
+use super::Bot;
+use crate::gameplay::headsup::{
+    Game, GameOver, GameType, Shutdown, Visibility, history::HandHistory,
+};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{task::JoinSet, time::Instant};
+use tokio_util::sync::CancellationToken;
+
+// `bots` makes the pair seated in each game, given its index; the first one is player 0.
+#[derive(Debug, Clone)]
+pub struct MatchConfig<F> {
+    game_type: GameType,
+    bots: F,
+    time_limit: Option<Duration>,
+    // Events a game may record before it's stopped, which is what its memory grows with.
+    event_limit: Option<usize>,
+}
+
+impl<F> MatchConfig<F>
+where
+    F: Fn(usize) -> [Box<dyn Bot + Send>; 2] + Send + Sync + 'static,
+{
+    pub fn new(game_type: GameType, bots: F) -> Self {
+        Self {
+            game_type,
+            bots,
+            time_limit: None,
+            event_limit: None,
+        }
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    pub fn with_event_limit(mut self, event_limit: usize) -> Self {
+        self.event_limit = Some(event_limit);
+        self
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum MatchOutcome {
+    Finished(GameOver),
+    // Stopped at a limit; the hand running then was void.
+    TimedOut(GameOver),
+    EventLimit(GameOver),
+    Panicked(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct MatchResult {
+    pub index: usize,
+    pub outcome: MatchOutcome,
+    pub hands: Vec<HandHistory>, // every hand the game finished, both holes shown
+    pub elapsed: Duration,
+}
+
+// Results in the order the games were started.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct MatchesReport {
+    pub results: Vec<MatchResult>,
+}
+
+impl MatchesReport {
+    pub fn count(&self, outcome: impl Fn(&MatchOutcome) -> bool) -> usize {
+        self.results
+            .iter()
+            .filter(|result| outcome(&result.outcome))
+            .count()
+    }
+
+    pub fn hands(&self) -> impl Iterator<Item = &HandHistory> {
+        self.results.iter().flat_map(|result| &result.hands)
+    }
+}
+
+impl Display for MatchesReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} games, {} hands: {} finished, {} timed out, {} over the event limit, {} panicked",
+            self.results.len(),
+            self.hands().count(),
+            self.count(|outcome| matches!(outcome, MatchOutcome::Finished(_))),
+            self.count(|outcome| matches!(outcome, MatchOutcome::TimedOut(_))),
+            self.count(|outcome| matches!(outcome, MatchOutcome::EventLimit(_))),
+            self.count(|outcome| matches!(outcome, MatchOutcome::Panicked(_))),
+        )
+    }
+}
+
+// Plays `n` games, at most `concurrency` of them at a time (at least one).
+pub async fn run_matches<F>(config: MatchConfig<F>, n: usize, concurrency: usize) -> MatchesReport
+where
+    F: Fn(usize) -> [Box<dyn Bot + Send>; 2] + Send + Sync + 'static,
+{
+    let config = Arc::new(config);
+    let mut running = JoinSet::new();
+    let mut indices = HashMap::new();
+    let mut results = Vec::with_capacity(n);
+    let mut next = 0;
+
+    while next < n || !running.is_empty() {
+        while next < n && running.len() < concurrency.max(1) {
+            let config = config.clone();
+            let index = next;
+            let task = running.spawn(async move { run_match(&config, index).await });
+            indices.insert(task.id(), index);
+            next += 1;
+        }
+
+        let result = match running.join_next_with_id().await {
+            Some(Ok((id, (outcome, hands, elapsed)))) => MatchResult {
+                index: indices[&id],
+                outcome,
+                hands,
+                elapsed,
+            },
+            // the game and its bots panicking is caught in `run_match`, this is anything else
+            Some(Err(error)) => MatchResult {
+                index: indices[&error.id()],
+                outcome: MatchOutcome::Panicked(error.to_string()),
+                hands: vec![],
+                elapsed: Duration::ZERO,
+            },
+            None => continue,
+        };
+        results.push(result);
+    }
+
+    results.sort_by_key(|result| result.index);
+    MatchesReport { results }
+}
+
+async fn run_match<F>(
+    config: &MatchConfig<F>,
+    index: usize,
+) -> (MatchOutcome, Vec<HandHistory>, Duration)
+where
+    F: Fn(usize) -> [Box<dyn Bot + Send>; 2] + Send + Sync + 'static,
+{
+    let start = Instant::now();
+    let (mut game, players) = Game::new(config.game_type);
+    let mut observer = game
+        .observer(Visibility::God)
+        .expect("A new game has no observer");
+    let token = CancellationToken::new();
+    let run = tokio::spawn(game.run_until(token.clone(), Shutdown::AbortHand));
+
+    let bots = (config.bots)(index);
+    let drivers: Vec<_> = players
+        .into_iter()
+        .zip(bots)
+        .map(|(mut player, mut bot)| {
+            tokio::spawn(async move { super::play(&mut player, &mut bot).await })
+        })
+        .collect();
+
+    let deadline = config.time_limit.map(|limit| start + limit);
+    let mut events = 0;
+    let mut stopped: Option<fn(GameOver) -> MatchOutcome> = None;
+    loop {
+        let tick = observer.tick_event();
+        let event = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, tick).await {
+                Ok(event) => event,
+                Err(_) => {
+                    stopped = stopped.or(Some(MatchOutcome::TimedOut));
+                    token.cancel();
+                    break;
+                }
+            },
+            None => tick.await,
+        };
+        if event.is_none() {
+            break;
+        }
+
+        events += 1;
+        if config.event_limit.is_some_and(|limit| events >= limit) && stopped.is_none() {
+            stopped = Some(MatchOutcome::EventLimit);
+            token.cancel();
+        }
+    }
+
+    let mut outcome = match run.await {
+        Ok(result) => stopped.unwrap_or(MatchOutcome::Finished)(result.reason),
+        Err(error) => MatchOutcome::Panicked(error.to_string()),
+    };
+    for driver in drivers {
+        if let Err(error) = driver.await
+            && !matches!(outcome, MatchOutcome::Panicked(_))
+        {
+            outcome = MatchOutcome::Panicked(error.to_string());
+        }
+    }
+
+    (outcome, observer.hands_history().to_vec(), start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{Decision, testing::Caller},
+        gameplay::headsup::{Action, CashBuyin},
+    };
+
+    struct Panics;
+
+    impl Bot for Panics {
+        fn act(&mut self, _decision: &Decision) -> Action {
+            panic!("bad bot")
+        }
+    }
+
+    #[tokio::test]
+    async fn isolates_panicking_games() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB30,
+            hands: 5,
+            rake: None,
+        };
+        let config = MatchConfig::new(game_type, |index| -> [Box<dyn Bot + Send>; 2] {
+            if index == 1 {
+                [Box::new(Caller), Box::new(Panics)]
+            } else {
+                [Box::new(Caller), Box::new(Caller)]
+            }
+        })
+        .with_event_limit(10_000);
+
+        let report = run_matches(config, 4, 2).await;
+        let indices: Vec<_> = report.results.iter().map(|result| result.index).collect();
+        assert_eq!(indices, [0, 1, 2, 3]);
+        assert!(matches!(
+            report.results[1].outcome,
+            MatchOutcome::Panicked(_)
+        ));
+        assert_eq!(
+            report.count(|outcome| matches!(outcome, MatchOutcome::Finished(_))),
+            3
+        );
+        assert_eq!(report.results[0].hands.len(), 5);
+    }
+}
@@ -0,0 +1,480 @@
+// Bot-vs-bot hands on a bare `HandState`, recording every decision as a training sample.
+// Samples export as one flat f32 table to CSV, NumPy `.npy` or (with the `parquet` feature)
+// Parquet; `Trajectories::columns` names the columns:
+//   features..  the encoder's output, `f0` onwards
+//   legal_*     fold, check/call, bet/raise, all in
+//   min_raise, max_raise (bet/raise-to bounds in big blinds, 0 when no bet/raise is legal)
+//   action      0 fold, 1 check/call, 2 bet/raise, 3 all in; `amount` is the raise-to in bbs
+//   reward      the actor's result of the whole hand in big blinds
+//   hand, seat (1 for seat `true`), street
+use super::{Bot, Decision, FeatureEncoder};
+use crate::{
+    gameplay::{
+        Board, Card, Hole, Street,
+        chips::Chips,
+        headsup::{Action, ActionOver, ActionValue, BetBound, Dealer, Deck, HandState},
+    },
+    stats::ActionRecord,
+};
+use itertools::Itertools;
+use std::{
+    cmp::Ordering,
+    io::{self, Write},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transition {
+    pub hand: u32,
+    pub seat: bool,
+    pub street: Street,
+    pub features: Vec<f32>,
+    pub legal: [bool; 4], // fold, check/call, bet/raise, all in
+    pub raise_range: Option<(Chips, Chips)>,
+    pub action: Action,
+    pub reward: f64, // in big blinds
+}
+
+// The cards of one hand, fixed up front so the same hand can be played again.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Deal {
+    holes: [Hole; 2], // [seat `true`, seat `false`]
+    runout: [Card; 5],
+    button: bool,
+}
+
+impl Deal {
+    pub fn new(holes: [Hole; 2], runout: [Card; 5], button: bool) -> Option<Self> {
+        let mut cards = holes
+            .iter()
+            .flat_map(|hole| hole.iter())
+            .chain(runout.iter());
+        if !cards.all_unique() {
+            return None;
+        }
+
+        Some(Self {
+            holes,
+            runout,
+            button,
+        })
+    }
+
+    pub fn random(button: bool) -> Self {
+        Self::dealt(Deck::default().shuffle_and_deal(), button)
+    }
+
+    // Deal `hand` of `seed`, the same every time, see `Deck::shuffle_and_deal_seeded`.
+    pub fn seeded(seed: u64, hand: u64, button: bool) -> Self {
+        Self::dealt(Deck::default().shuffle_and_deal_seeded(seed, hand), button)
+    }
+
+    fn dealt(mut dealer: Dealer, button: bool) -> Self {
+        Self {
+            holes: [dealer.deal_hole(), dealer.deal_hole()],
+            runout: std::array::from_fn(|_| dealer.deal_card()),
+            button,
+        }
+    }
+
+    pub fn holes(&self) -> [Hole; 2] {
+        self.holes
+    }
+
+    pub fn runout(&self) -> [Card; 5] {
+        self.runout
+    }
+
+    pub fn button(&self) -> bool {
+        self.button
+    }
+
+    // The same deck from the other side of the table: seats trade cards and the button, as in
+    // duplicate poker.
+    pub fn mirrored(&self) -> Self {
+        Self {
+            holes: [self.holes[1], self.holes[0]],
+            runout: self.runout,
+            button: !self.button,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HandRecord {
+    deal: Deal,
+    actions: Vec<ActionRecord>,
+    results: [f64; 2], // [seat `true`, seat `false`] in big blinds
+}
+
+impl HandRecord {
+    pub(crate) fn new(deal: Deal, actions: Vec<ActionRecord>, results: [f64; 2]) -> Self {
+        Self {
+            deal,
+            actions,
+            results,
+        }
+    }
+
+    pub fn deal(&self) -> Deal {
+        self.deal
+    }
+
+    pub fn actions(&self) -> &[ActionRecord] {
+        &self.actions
+    }
+
+    pub fn results(&self) -> [f64; 2] {
+        self.results
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfPlay {
+    big_blind: u16,
+    stacks: u32,
+    encoder: FeatureEncoder,
+}
+
+impl Default for SelfPlay {
+    fn default() -> Self {
+        Self::new(100, 10000)
+    }
+}
+
+impl SelfPlay {
+    // Stacks are reset every hand and the button alternates.
+    pub fn new(big_blind: u16, stacks: u32) -> Self {
+        Self {
+            big_blind,
+            stacks,
+            encoder: FeatureEncoder::LATEST,
+        }
+    }
+
+    pub fn with_encoder(mut self, encoder: FeatureEncoder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    // `first` sits in seat `true`, `second` in seat `false`.
+    pub fn play(&self, first: &mut impl Bot, second: &mut impl Bot, hands: u32) -> Trajectories {
+        let mut trajectories = Trajectories {
+            encoder: self.encoder,
+            big_blind: Chips::new(self.big_blind as u32),
+            transitions: vec![],
+            hands: vec![],
+        };
+
+        for hand in 0..hands {
+            let deal = Deal::random(hand.is_multiple_of(2));
+            let record = self.play_deal(hand, deal, first, second, &mut trajectories.transitions);
+            trajectories.hands.push(record);
+        }
+        trajectories
+    }
+
+    pub(super) fn play_deal(
+        &self,
+        hand: u32,
+        deal: Deal,
+        first: &mut impl Bot,
+        second: &mut impl Bot,
+        transitions: &mut Vec<Transition>,
+    ) -> HandRecord {
+        first.new_hand();
+        second.new_hand();
+
+        let mut state = HandState::new(self.big_blind, deal.button, [Chips::new(self.stacks); 2])
+            .with_cards(deal.holes.map(Some), Board::default());
+        let mut outcome = state
+            .deal_holes_int()
+            .map_or(Some(ActionOver::ShowdownAll), |_| None);
+
+        let start = transitions.len();
+        let mut history = vec![];
+        let mut raises = 0;
+
+        while outcome.is_none() {
+            let seat = state.cur_turn();
+            let decision =
+                Decision::from_state(seat, &state, state.bet_bound()).with_history(history.clone());
+            let action = decision.legalize(if seat {
+                first.act(&decision)
+            } else {
+                second.act(&decision)
+            });
+
+            transitions.push(self.transition(hand, &decision, action));
+
+            let record =
+                ActionRecord::new(seat, state.street(), action, !state.can_check(), raises);
+            first.observe(true, &record);
+            second.observe(false, &record);
+            history.push(record);
+
+            match state.action(action) {
+                ActionOver::TurnOver => raises += record.is_aggressive() as u8,
+                ActionOver::RoundOver => {
+                    state.next_street();
+                    let board = Board::from_slice(&deal.runout[..state.street().board_len()])
+                        .expect("Runout cards are distinct");
+                    state = state.with_cards(state.holes(), board);
+                    raises = 0;
+                }
+                over => outcome = Some(over),
+            }
+        }
+
+        let results = self.results(&state, outcome, &deal);
+        for transition in &mut transitions[start..] {
+            transition.reward = results[if transition.seat { 0 } else { 1 }];
+        }
+        HandRecord::new(deal, history, results)
+    }
+
+    fn transition(&self, hand: u32, decision: &Decision, action: Action) -> Transition {
+        let bound = decision.bet_bound();
+        let raise_range = match bound {
+            BetBound::FoldCheckBetAllIn(range)
+            | BetBound::FoldCallRaiseAllIn(range)
+            | BetBound::FoldBetAllIn(range)
+            | BetBound::FoldRaiseAllIn(range)
+            | BetBound::FoldCheckBet(range)
+            | BetBound::FoldCallRaise(range) => Some((*range.start(), *range.end())),
+            _ => None,
+        };
+
+        Transition {
+            hand,
+            seat: decision.seat(),
+            street: decision.street(),
+            features: self.encoder.encode(decision),
+            legal: [
+                decision.facing_bet(),
+                bound.validate_action(Action::check_or_call()),
+                raise_range.is_some(),
+                bound.validate_action(Action::all_in()),
+            ],
+            raise_range,
+            action,
+            reward: 0.0,
+        }
+    }
+
+    // Chips won or lost by each seat in big blinds.
+    fn results(&self, state: &HandState, outcome: Option<ActionOver>, deal: &Deal) -> [f64; 2] {
+        let mut ends = state.behinds();
+
+        if outcome != Some(ActionOver::HandOver) {
+            let board = Board::from_slice(&deal.runout)
+                .and_then(|board| board.as_full_board())
+                .expect("Runouts have five distinct cards");
+            let pot = state.pot();
+            match board
+                .hand_value(deal.holes[0])
+                .cmp(&board.hand_value(deal.holes[1]))
+            {
+                Ordering::Greater => ends[0] += pot,
+                Ordering::Less => ends[1] += pot,
+                // the odd chip of a chop goes out of position, as at the table
+                Ordering::Equal => {
+                    let share = state.chop_share();
+                    ends[0] += share;
+                    ends[1] += pot - share;
+                }
+            }
+        }
+
+        ends.map(|end| (end.get() as f64 - self.stacks as f64) / self.big_blind as f64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trajectories {
+    encoder: FeatureEncoder,
+    big_blind: Chips,
+    transitions: Vec<Transition>,
+    hands: Vec<HandRecord>,
+}
+
+impl Trajectories {
+    pub fn encoder(&self) -> FeatureEncoder {
+        self.encoder
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    pub fn hands(&self) -> &[HandRecord] {
+        &self.hands
+    }
+
+    pub fn len(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    pub fn columns(&self) -> Vec<String> {
+        (0..self.encoder.len())
+            .map(|i| format!("f{i}"))
+            .chain(
+                [
+                    "legal_fold",
+                    "legal_check_call",
+                    "legal_bet_raise",
+                    "legal_all_in",
+                    "min_raise",
+                    "max_raise",
+                    "action",
+                    "amount",
+                    "reward",
+                    "hand",
+                    "seat",
+                    "street",
+                ]
+                .map(String::from),
+            )
+            .collect()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<f32>> + '_ {
+        self.transitions.iter().map(|t| {
+            let bbs = |chips: Chips| chips.in_bb(self.big_blind) as f32;
+            let (min_raise, max_raise) = t.raise_range.unwrap_or_default();
+            let (action, amount) = match t.action.value() {
+                ActionValue::Exit | ActionValue::Fold => (0.0, Chips::ZERO),
+                ActionValue::CheckOrCall => (1.0, Chips::ZERO),
+                ActionValue::BetOrRaise(amount) => (2.0, amount),
+                ActionValue::AllIn => (3.0, Chips::ZERO),
+            };
+
+            let mut row = t.features.clone();
+            row.extend(t.legal.map(|legal| legal as u8 as f32));
+            row.extend([
+                bbs(min_raise),
+                bbs(max_raise),
+                action,
+                bbs(amount),
+                t.reward as f32,
+                t.hand as f32,
+                t.seat as u8 as f32,
+                t.street as u8 as f32,
+            ]);
+            row
+        })
+    }
+
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", self.columns().join(","))?;
+        for row in self.rows() {
+            let line: Vec<String> = row.iter().map(f32::to_string).collect();
+            writeln!(writer, "{}", line.join(","))?;
+        }
+        writer.flush()
+    }
+
+    // A `[rows, columns]` little-endian f32 array in NumPy's `.npy` format (version 1.0).
+    pub fn write_npy(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+            self.len(),
+            self.columns().len()
+        );
+        // magic, version and length take 10 bytes; the data starts 64-byte aligned
+        let padding = 63 - (10 + header.len()) % 64;
+        header.extend(std::iter::repeat_n(' ', padding));
+        header.push('\n');
+
+        writer.write_all(b"\x93NUMPY\x01\x00")?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+        for row in self.rows() {
+            for value in row {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    }
+
+    // One row group of required FLOAT columns.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet(&self, writer: impl Write + Send) -> parquet::errors::Result<()> {
+        use parquet::{
+            data_type::FloatType,
+            file::{properties::WriterProperties, writer::SerializedFileWriter},
+            schema::parser::parse_message_type,
+        };
+        use std::sync::Arc;
+
+        let columns = self.columns();
+        let fields: String = columns
+            .iter()
+            .map(|column| format!("REQUIRED FLOAT {column}; "))
+            .collect();
+        let schema = parse_message_type(&format!("message trajectories {{ {fields}}}"))?;
+        let mut writer = SerializedFileWriter::new(
+            writer,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+
+        let rows: Vec<Vec<f32>> = self.rows().collect();
+        let mut row_group = writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column()? {
+            let values: Vec<f32> = rows.iter().map(|row| row[index]).collect();
+            column
+                .typed::<FloatType>()
+                .write_batch(&values, None, None)?;
+            column.close()?;
+            index += 1;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::ExploitBot;
+
+    #[test]
+    fn zero_sum_exports() {
+        let trajectories =
+            SelfPlay::default().play(&mut ExploitBot::default(), &mut ExploitBot::default(), 40);
+        assert!(!trajectories.is_empty());
+
+        for hand in 0..40 {
+            let rewards = |seat: bool| {
+                trajectories
+                    .transitions()
+                    .iter()
+                    .find(|t| t.hand == hand && t.seat == seat)
+                    .map(|t| t.reward)
+            };
+            if let (Some(a), Some(b)) = (rewards(true), rewards(false)) {
+                assert_eq!(a + b, 0.0);
+            }
+        }
+
+        let mut csv = vec![];
+        trajectories.write_csv(&mut csv).unwrap();
+        let lines = String::from_utf8(csv).unwrap();
+        assert_eq!(lines.lines().count(), trajectories.len() + 1);
+
+        let mut npy = vec![];
+        trajectories.write_npy(&mut npy).unwrap();
+        let header = u16::from_le_bytes([npy[8], npy[9]]) as usize + 10;
+        assert_eq!(header % 64, 0);
+        assert_eq!(
+            npy.len() - header,
+            trajectories.len() * trajectories.columns().len() * 4
+        );
+    }
+}
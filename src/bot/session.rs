@@ -0,0 +1,284 @@
+// A series of games between two bots with money carried from one to the next: every game is
+// bought into from the players' bankrolls and cashed out back to them, the first button
+// alternates so neither bot gets the better start more often, and the results add up to bb/100,
+// ROI and how much they swing from game to game.
+use super::Bot;
+use crate::gameplay::{
+    chips::Chips,
+    headsup::{Game, GameOver, GameType},
+};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Session {
+    game_type: GameType,
+    games: u32,
+    bankroll: Chips,     // each player's to start with
+    rebuys: Option<u32>, // games a player may buy into again after busting, None for any number
+    sng_fee: Chips,      // on top of a sit and go's buy-in, to the house
+    seed: Option<u64>,
+}
+
+impl Session {
+    // Bankrolls of 100 buy-ins each.
+    pub fn new(game_type: GameType, games: u32) -> Self {
+        Self {
+            game_type,
+            games,
+            bankroll: buyin(game_type) * 100,
+            rebuys: None,
+            sng_fee: Chips::ZERO,
+            seed: None,
+        }
+    }
+
+    pub fn with_bankroll(mut self, bankroll: Chips) -> Self {
+        self.bankroll = bankroll;
+        self
+    }
+
+    // A player who busted more than `rebuys` times sits out the rest of the session.
+    pub fn with_rebuys(mut self, rebuys: u32) -> Self {
+        self.rebuys = Some(rebuys);
+        self
+    }
+
+    pub fn with_sng_fee(mut self, fee: Chips) -> Self {
+        self.sng_fee = fee;
+        self
+    }
+
+    // Deals game `i` from `seed + i`, see `Game::with_seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // What a game costs each player: the buy-in, and the fee for a sit and go.
+    pub fn cost(&self) -> Chips {
+        buyin(self.game_type) + self.fee()
+    }
+
+    fn fee(&self) -> Chips {
+        match self.game_type {
+            GameType::Cash { .. } => Chips::ZERO,
+            GameType::SNG { .. } => self.sng_fee,
+        }
+    }
+
+    // `first` plays player 0 and `second` player 1 in every game; player 0 has the first button
+    // of even games. Ends early once a player can't pay for the next game or has no rebuys left.
+    pub async fn run(&self, first: &mut impl Bot, second: &mut impl Bot) -> SessionReport {
+        let mut report = SessionReport {
+            big_blind: self.game_type.blind_structure().level(0).big_blind(),
+            cost: self.cost(),
+            bankrolls: [self.bankroll; 2],
+            games: vec![],
+            out: None,
+        };
+        let mut busts = [0; 2];
+
+        for index in 0..self.games {
+            let broke = [true, false].into_iter().find(|&seat| {
+                let i = if seat { 0 } else { 1 };
+                report.bankrolls[i] < self.cost() || self.rebuys.is_some_and(|n| busts[i] > n)
+            });
+            if broke.is_some() {
+                report.out = broke;
+                break;
+            }
+
+            let result = self.play(index, first, second).await;
+            for ((bankroll, busts), cashout) in report
+                .bankrolls
+                .iter_mut()
+                .zip(&mut busts)
+                .zip(result.cashout)
+            {
+                *bankroll = *bankroll + cashout - self.cost();
+                *busts += cashout.is_zero() as u32;
+            }
+            report.games.push(result);
+        }
+        report
+    }
+
+    async fn play(&self, index: u32, first: &mut impl Bot, second: &mut impl Bot) -> GameResult {
+        let button = index.is_multiple_of(2);
+        let (mut game, [mut player0, mut player1]) =
+            Game::with_button(self.game_type, Default::default(), button)
+                .expect("Unbounded channels are valid");
+        if let Some(seed) = self.seed {
+            game = game.with_seed(seed.wrapping_add(index as u64));
+        }
+
+        let (result, _, _) = tokio::join!(
+            game.run(),
+            super::play(&mut player0, first),
+            super::play(&mut player1, second),
+        );
+
+        let cashout = match self.game_type {
+            GameType::Cash { .. } => result.stacks,
+            // winner takes both buy-ins, a game that ended without one gives them back
+            GameType::SNG { .. } => match result.reason {
+                GameOver::Defeated(loser) => {
+                    let mut cashout = [buyin(self.game_type) * 2; 2];
+                    cashout[if loser { 0 } else { 1 }] = Chips::ZERO;
+                    cashout
+                }
+                _ => [buyin(self.game_type); 2],
+            },
+        };
+        GameResult {
+            index,
+            button,
+            game_over: result.reason,
+            hands: result.hands,
+            cashout,
+        }
+    }
+}
+
+// Chips are money: a game buys in for the starting stack, cash or sit and go.
+fn buyin(game_type: GameType) -> Chips {
+    game_type.init_stack()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct GameResult {
+    pub index: u32,
+    pub button: bool, // who had the first button
+    pub game_over: GameOver,
+    pub hands: u32,
+    pub cashout: [Chips; 2], // what each player took back to their bankroll
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SessionReport {
+    big_blind: Chips, // of the first level
+    cost: Chips,
+    pub bankrolls: [Chips; 2], // at the end
+    pub games: Vec<GameResult>,
+    pub out: Option<bool>, // who was out of money or rebuys, if that ended it
+}
+
+impl SessionReport {
+    pub fn hands(&self) -> u32 {
+        self.games.iter().map(|game| game.hands).sum()
+    }
+
+    // Chips won each game, negative for a loss.
+    pub fn results(&self, seat: bool) -> impl Iterator<Item = i64> + '_ {
+        let i = if seat { 0 } else { 1 };
+        self.games
+            .iter()
+            .map(move |game| game.cashout[i].get() as i64 - self.cost.get() as i64)
+    }
+
+    pub fn net(&self, seat: bool) -> i64 {
+        self.results(seat).sum()
+    }
+
+    pub fn bb_per_100(&self, seat: bool) -> f64 {
+        let hands = self.hands();
+        if hands == 0 {
+            return 0.0;
+        }
+        self.net(seat) as f64 / self.big_blind.get() as f64 / hands as f64 * 100.0
+    }
+
+    // Won over what the games cost.
+    pub fn roi(&self, seat: bool) -> f64 {
+        let paid = self.cost.get() as f64 * self.games.len() as f64;
+        if paid == 0.0 {
+            return 0.0;
+        }
+        self.net(seat) as f64 / paid
+    }
+
+    // Sample variance of the chips won a game.
+    pub fn variance(&self, seat: bool) -> f64 {
+        let n = self.games.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean = self.net(seat) as f64 / n as f64;
+        let squares: f64 = self
+            .results(seat)
+            .map(|result| (result as f64 - mean).powi(2))
+            .sum();
+        squares / (n - 1) as f64
+    }
+}
+
+impl Display for SessionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} games, {} hands", self.games.len(), self.hands())?;
+        if let Some(out) = self.out {
+            write!(f, ", player {} out of money", if out { 0 } else { 1 })?;
+        }
+        for (seat, name) in [(true, 0), (false, 1)] {
+            write!(
+                f,
+                "\nPlayer {name}: {:+} ({:+.1} bb/100), ROI {:+.1}%, std dev {:.0} a game, bankroll {}",
+                self.net(seat),
+                self.bb_per_100(seat),
+                self.roi(seat) * 100.0,
+                self.variance(seat).sqrt(),
+                self.bankrolls[if seat { 0 } else { 1 }],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Decision,
+        gameplay::headsup::{Action, CashBuyin, SNGSpeed},
+    };
+
+    struct Shover;
+
+    impl Bot for Shover {
+        fn act(&mut self, _decision: &Decision) -> Action {
+            Action::all_in()
+        }
+    }
+
+    #[tokio::test]
+    async fn bankrolls_carry_over() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB30,
+            hands: 3,
+            rake: None,
+        };
+        let session = Session::new(game_type, 4).with_seed(11);
+        let report = session.run(&mut Shover, &mut Shover).await;
+        assert_eq!(report.games.len(), 4);
+        let buttons: Vec<_> = report.games.iter().map(|game| game.button).collect();
+        assert_eq!(buttons, [true, false, true, false]);
+        assert_eq!(report.net(true), -report.net(false));
+        for (i, seat) in [(0, true), (1, false)] {
+            let bankroll = session.bankroll.get() as i64 + report.net(seat);
+            assert_eq!(report.bankrolls[i].get() as i64, bankroll);
+        }
+        assert_eq!(report, session.run(&mut Shover, &mut Shover).await);
+
+        // two sit and go buy-ins and their fees are all either player can pay for
+        let game_type = GameType::sng(SNGSpeed::Turbo);
+        let stack = game_type.init_stack();
+        let session = Session::new(game_type, 10)
+            .with_bankroll((stack + Chips::new(10)) * 2)
+            .with_sng_fee(Chips::new(10))
+            .with_seed(5);
+        let report = session.run(&mut Shover, &mut Shover).await;
+        assert!(report.out.is_some() && report.games.len() <= 2, "{report}");
+        let out = report.out.unwrap();
+        assert_eq!(report.roi(out), -1.0);
+        assert!(report.to_string().contains("out of money"));
+    }
+}
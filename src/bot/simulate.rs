@@ -0,0 +1,190 @@
+// Many bot-vs-bot hands at once on rayon's pool, for how one strategy does against another. Hands
+// are played on a bare `HandState` like `SelfPlay`, at the game type's first blind level with
+// stacks reset every hand, so no game or channel is set up per hand.
+use super::{Bot, Deal, SelfPlay};
+use crate::gameplay::{
+    Street,
+    headsup::{ActionValue, GameType},
+};
+use crate::parallel::prelude::*;
+use itertools::Itertools;
+use std::fmt::{self, Display, Formatter};
+
+// Hands a worker plays with one pair of bots; fixed, so a seed gives the same report on any pool.
+const CHUNK: u32 = 256;
+
+// Of the hands with a betting action on a street.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct StreetStats {
+    pub seen: u32,  // with an action on the street
+    pub ended: u32, // whose last action was on the street
+    pub won: f64,   // big blinds `a` won in the hands that ended there
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SimReport {
+    results: Vec<f64>, // big blinds `a` won each hand, in deal order
+    streets: [StreetStats; 4],
+    showdowns: u32,
+}
+
+impl SimReport {
+    pub fn hands(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn results(&self) -> &[f64] {
+        &self.results
+    }
+
+    pub fn street(&self, street: Street) -> StreetStats {
+        self.streets[street as usize]
+    }
+
+    // Hands not ended by a fold.
+    pub fn showdowns(&self) -> u32 {
+        self.showdowns
+    }
+
+    pub fn total(&self) -> f64 {
+        self.results.iter().sum()
+    }
+
+    pub fn bb_per_100(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.total() / self.results.len() as f64 * 100.0
+    }
+
+    // Of `bb_per_100`, from the spread of the hands.
+    pub fn std_error(&self) -> f64 {
+        let n = self.results.len() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let mean = self.total() / n;
+        let variance = self.results.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (variance / n).sqrt() * 100.0
+    }
+
+    // The 95% confidence interval of `bb_per_100`.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let (mean, error) = (self.bb_per_100(), self.std_error());
+        (mean - 1.96 * error, mean + 1.96 * error)
+    }
+
+    // What `a` won a hand at quantile `q` of the distribution, None without hands or for `q`
+    // outside 0 to 1.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.results.is_empty() || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let mut sorted = self.results.clone();
+        sorted.sort_by(f64::total_cmp);
+        Some(sorted[((sorted.len() - 1) as f64 * q).round() as usize])
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.results.extend(other.results);
+        for (street, other) in self.streets.iter_mut().zip(other.streets) {
+            street.seen += other.seen;
+            street.ended += other.ended;
+            street.won += other.won;
+        }
+        self.showdowns += other.showdowns;
+        self
+    }
+}
+
+impl Display for SimReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.confidence_interval();
+        write!(
+            f,
+            "{} hands: {:.2} bb/100 ({low:.2} to {high:.2}), {} showdowns",
+            self.hands(),
+            self.bb_per_100(),
+            self.showdowns
+        )
+    }
+}
+
+// Plays `hands` hands of `a` in seat `true` against `b`, each worker with bots of its own from
+// the factories. Hand `i` is dealt from `seed` and `i`, with `a` on the button of even hands.
+pub fn batch<A, B>(
+    game_type: GameType,
+    a: impl Fn() -> A + Sync,
+    b: impl Fn() -> B + Sync,
+    hands: u32,
+    seed: u64,
+) -> SimReport
+where
+    A: Bot,
+    B: Bot,
+{
+    let big_blind = game_type.blind_structure().level(0).big_blind();
+    let big_blind = u16::try_from(big_blind.get()).expect("Blinds are u16 chips");
+    let self_play = SelfPlay::new(big_blind, game_type.init_stack().get());
+
+    (0..hands.div_ceil(CHUNK))
+        .into_par_iter()
+        .map(|chunk| {
+            let (mut a, mut b) = (a(), b());
+            let mut report = SimReport::default();
+            for i in chunk * CHUNK..((chunk + 1) * CHUNK).min(hands) {
+                let deal = Deal::seeded(seed, i as u64, i.is_multiple_of(2));
+                let record = self_play.play_deal(i, deal, &mut a, &mut b, &mut vec![]);
+                let won = record.results()[0];
+                report.results.push(won);
+
+                let actions = record.actions();
+                for street in actions.iter().map(|action| action.street()).dedup() {
+                    report.streets[street as usize].seen += 1;
+                }
+                if let Some(last) = actions.last() {
+                    let ended = &mut report.streets[last.street() as usize];
+                    ended.ended += 1;
+                    ended.won += won;
+                }
+                report.showdowns += actions
+                    .last()
+                    .is_none_or(|last| last.action().value() != ActionValue::Fold)
+                    as u32;
+            }
+            report
+        })
+        .reduce(SimReport::default, SimReport::merge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::testing::{Caller, Folder},
+        gameplay::headsup::CashBuyin,
+    };
+
+    #[test]
+    fn batch_reports() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB100,
+            hands: 0,
+            rake: None,
+        };
+        // the folder gives up the small blind on its button and checks down the rest
+        let report = batch(game_type, || Folder, || Caller, 1000, 7);
+        assert_eq!(report.hands(), 1000);
+        assert_eq!(report, batch(game_type, || Folder, || Caller, 1000, 7));
+        assert_eq!(report.street(Street::Preflop).seen, 1000);
+        assert_eq!(report.street(Street::Preflop).ended, 500);
+        assert_eq!(report.street(Street::Preflop).won, -250.0);
+        assert_eq!(report.street(Street::River).ended, 500);
+        // but for the 8 where the caller has the river nuts on the button, which can't be checked
+        // behind, and gives them up
+        assert_eq!(report.showdowns(), 492);
+        assert_eq!(report.quantile(0.0), Some(-1.0));
+        let (low, high) = report.confidence_interval();
+        assert!(low < report.bb_per_100() && report.bb_per_100() < high);
+    }
+}
@@ -0,0 +1,89 @@
+// "What would the other bot have done here": a recorded hand played again on the same deck with
+// the bots trading seats, so each plays the cards and position the other one had.
+use super::{
+    Bot,
+    selfplay::{HandRecord, SelfPlay},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwapReplay {
+    original: HandRecord,
+    swapped: HandRecord,
+}
+
+impl SwapReplay {
+    // `first` and `second` are the bots that sat in seats `true` and `false` of `recorded`.
+    pub fn new(
+        self_play: &SelfPlay,
+        recorded: &HandRecord,
+        first: &mut impl Bot,
+        second: &mut impl Bot,
+    ) -> Self {
+        let swapped = self_play.play_deal(0, recorded.deal(), second, first, &mut vec![]);
+        Self {
+            original: recorded.clone(),
+            swapped,
+        }
+    }
+
+    pub fn original(&self) -> &HandRecord {
+        &self.original
+    }
+
+    pub fn swapped(&self) -> &HandRecord {
+        &self.swapped
+    }
+
+    // Big blinds a seat won more with the other bot playing it.
+    pub fn gain(&self, seat: bool) -> f64 {
+        let i = if seat { 0 } else { 1 };
+        self.swapped.results()[i] - self.original.results()[i]
+    }
+
+    // Where the two lines part, as an index into their actions.
+    pub fn divergence(&self) -> Option<usize> {
+        let (original, swapped) = (self.original.actions(), self.swapped.actions());
+        original
+            .iter()
+            .zip(swapped)
+            .position(|(a, b)| a.action() != b.action())
+            .or((original.len() != swapped.len()).then(|| original.len().min(swapped.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{
+            selfplay::Deal,
+            testing::{Caller, Folder},
+        },
+        gameplay::Board,
+    };
+
+    #[test]
+    fn folder_takes_the_button() {
+        let self_play = SelfPlay::default();
+        let deal = Deal::new(
+            ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()],
+            "KsQd9h4c3s"
+                .parse::<Board>()
+                .unwrap()
+                .to_vec()
+                .try_into()
+                .unwrap(),
+            true,
+        )
+        .unwrap();
+
+        let recorded = self_play.play_deal(0, deal, &mut Caller, &mut Folder, &mut vec![]);
+        assert_eq!(recorded.results(), [1.0, -1.0]); // the limp gets checked down
+
+        let replay = SwapReplay::new(&self_play, &recorded, &mut Caller, &mut Folder);
+        assert_eq!(replay.divergence(), Some(0));
+        assert_eq!(replay.swapped().results(), [-0.5, 0.5]);
+        assert_eq!(replay.gain(true), -1.5);
+        assert_eq!(replay.gain(false), 1.5);
+    }
+}
@@ -0,0 +1,26 @@
+// Bots with fixed answers, for tests to play games with.
+use crate::{
+    bot::{Bot, Decision},
+    gameplay::headsup::Action,
+};
+
+pub(crate) struct Caller;
+
+impl Bot for Caller {
+    fn act(&mut self, _decision: &Decision) -> Action {
+        Action::check_or_call()
+    }
+}
+
+// Checks when it can and folds to any bet.
+pub(crate) struct Folder;
+
+impl Bot for Folder {
+    fn act(&mut self, decision: &Decision) -> Action {
+        if decision.facing_bet() {
+            Action::fold()
+        } else {
+            Action::check_or_call()
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Human-like think times for bots at tables people watch: every action waits a delay drawn from
+// the distribution configured for its street and kind, instead of going out instantly.
+use crate::gameplay::{
+    Street,
+    headsup::{Action, ActionValue},
+};
+use rand::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ActionKind {
+    Fold,
+    CheckOrCall,
+    BetOrRaise,
+    AllIn,
+}
+
+impl From<Action> for ActionKind {
+    fn from(action: Action) -> Self {
+        match action.value() {
+            ActionValue::Exit | ActionValue::Fold => Self::Fold,
+            ActionValue::CheckOrCall => Self::CheckOrCall,
+            ActionValue::BetOrRaise(_) => Self::BetOrRaise,
+            ActionValue::AllIn => Self::AllIn,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Delay {
+    Fixed(Duration),
+    Uniform(Duration, Duration),
+    // Right-skewed like real think times: mostly near the median, now and then much longer.
+    LogNormal { median: Duration, sigma: f64 },
+}
+
+impl Delay {
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Uniform(min, max) if min < max => rng.random_range(min..=max),
+            Self::Uniform(min, _) => min,
+            Self::LogNormal { median, sigma } => {
+                // Box-Muller for a standard normal
+                let u: f64 = 1.0 - rng.random::<f64>();
+                let v: f64 = rng.random();
+                let z = (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos();
+                median.mul_f64((sigma * z).exp())
+            }
+        }
+    }
+}
+
+// A default delay and overrides by street, action kind or both; the last matching override wins.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Timing {
+    default: Delay,
+    overrides: Vec<(Option<Street>, Option<ActionKind>, Delay)>,
+}
+
+impl Default for Timing {
+    // Snap folds and checks, longer thoughts for bets, all ins and later streets.
+    fn default() -> Self {
+        let secs = Duration::from_secs_f64;
+        Self::new(Delay::LogNormal {
+            median: secs(1.5),
+            sigma: 0.5,
+        })
+        .with_delay(
+            None,
+            Some(ActionKind::Fold),
+            Delay::Uniform(secs(0.5), secs(2.0)),
+        )
+        .with_delay(
+            None,
+            Some(ActionKind::BetOrRaise),
+            Delay::LogNormal {
+                median: secs(3.0),
+                sigma: 0.6,
+            },
+        )
+        .with_delay(
+            Some(Street::River),
+            None,
+            Delay::LogNormal {
+                median: secs(4.0),
+                sigma: 0.6,
+            },
+        )
+        .with_delay(
+            None,
+            Some(ActionKind::AllIn),
+            Delay::LogNormal {
+                median: secs(6.0),
+                sigma: 0.5,
+            },
+        )
+    }
+}
+
+impl Timing {
+    pub fn new(default: Delay) -> Self {
+        Self {
+            default,
+            overrides: vec![],
+        }
+    }
+
+    // `None` matches any street or kind.
+    pub fn with_delay(
+        mut self,
+        street: Option<Street>,
+        kind: Option<ActionKind>,
+        delay: Delay,
+    ) -> Self {
+        self.overrides.push((street, kind, delay));
+        self
+    }
+
+    pub fn delay(&self, street: Street, action: Action) -> Delay {
+        let kind = ActionKind::from(action);
+        self.overrides
+            .iter()
+            .rev()
+            .find(|(s, k, _)| s.is_none_or(|s| s == street) && k.is_none_or(|k| k == kind))
+            .map_or(self.default, |&(_, _, delay)| delay)
+    }
+
+    pub fn sample(&self, street: Street, action: Action, rng: &mut impl Rng) -> Duration {
+        self.delay(street, action).sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_by_street_and_kind() {
+        let ms = Duration::from_millis;
+        let timing = Timing::new(Delay::Fixed(ms(100)))
+            .with_delay(Some(Street::River), None, Delay::Fixed(ms(300)))
+            .with_delay(None, Some(ActionKind::Fold), Delay::Uniform(ms(10), ms(20)));
+        let mut rng = rand::rng();
+
+        let call = Action::check_or_call();
+        assert_eq!(timing.sample(Street::Flop, call, &mut rng), ms(100));
+        assert_eq!(timing.sample(Street::River, call, &mut rng), ms(300));
+        let fold = timing.sample(Street::River, Action::fold(), &mut rng);
+        assert!((ms(10)..=ms(20)).contains(&fold));
+
+        let lognormal = Delay::LogNormal {
+            median: ms(1000),
+            sigma: 0.5,
+        };
+        let mut samples: Vec<_> = (0..1001).map(|_| lognormal.sample(&mut rng)).collect();
+        samples.sort();
+        assert!((ms(800)..ms(1250)).contains(&samples[500]));
+    }
+}
@@ -0,0 +1,230 @@
+// Single-elimination brackets of heads-up sit and gos between bots: every round pairs up who is
+// left in entry order, the last one sitting out with a bye when they don't pair up, and a match
+// is one sit and go played as a `Session` of a game. Losers of a round share the places it
+// decides and split their payouts. Multi-table tournaments wait on a game driving `ring` tables.
+use super::{Bot, Session};
+use crate::gameplay::{
+    chips::Chips,
+    headsup::{GameOver, GameType},
+};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tournament {
+    game_type: GameType,
+    buyin: Chips,
+    payouts: Vec<f64>, // the prize pool's share by place, from first
+    seed: Option<u64>,
+}
+
+impl Tournament {
+    // A bracket of `game_type` sit and gos, the prize pool going 50/30/20 to the top three. None
+    // for a cash game.
+    pub fn new(game_type: GameType) -> Option<Self> {
+        matches!(game_type, GameType::SNG { .. }).then(|| Self {
+            game_type,
+            buyin: game_type.init_stack(),
+            payouts: vec![0.5, 0.3, 0.2],
+            seed: None,
+        })
+    }
+
+    // What each entrant puts in the prize pool, the sit and go's starting stack by default.
+    pub fn with_buyin(mut self, buyin: Chips) -> Self {
+        self.buyin = buyin;
+        self
+    }
+
+    // None for negative shares or shares over the whole pool.
+    pub fn with_payouts(mut self, payouts: Vec<f64>) -> Option<Self> {
+        if payouts.iter().any(|share| *share < 0.0) || payouts.iter().sum::<f64>() > 1.0 + 1e-9 {
+            return None;
+        }
+        self.payouts = payouts;
+        Some(self)
+    }
+
+    // Deals match `i`, counting through the rounds, from `seed + i`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Runs the bracket between `bots`, the entrants by index. A match ending without a winner,
+    // which an aborted game does, goes to the entrant listed first.
+    pub async fn run(&self, bots: &mut [impl Bot]) -> TournamentReport {
+        let entrants = bots.len();
+        let mut left: Vec<usize> = (0..entrants).collect();
+        let mut places = vec![1; entrants];
+        let mut matches = vec![];
+
+        for round in 1.. {
+            if left.len() < 2 {
+                break;
+            }
+
+            let mut winners = vec![];
+            for pair in left.chunks(2) {
+                let &[a, b] = pair else {
+                    winners.extend(pair); // a bye
+                    continue;
+                };
+                let (first, second) = pair_mut(bots, a, b);
+                let mut session = Session::new(self.game_type, 1);
+                if let Some(seed) = self.seed {
+                    session = session.with_seed(seed.wrapping_add(matches.len() as u64));
+                }
+                let game = session.run(first, second).await.games[0];
+                let a_won = !matches!(game.game_over, GameOver::Defeated(true));
+                let (winner, loser) = if a_won { (a, b) } else { (b, a) };
+
+                winners.push(winner);
+                matches.push(Match {
+                    round,
+                    winner,
+                    loser,
+                    hands: game.hands,
+                });
+            }
+
+            // the losers share the places below the winners
+            for game in matches.iter().filter(|game| game.round == round) {
+                places[game.loser] = winners.len() + 1;
+            }
+            left = winners;
+        }
+
+        let pool = self.buyin * entrants as u32;
+        let payouts = places
+            .iter()
+            .map(|&place| {
+                let tied = places.iter().filter(|&&other| other == place).count();
+                let shares = self.payouts.iter().skip(place - 1).take(tied).sum::<f64>();
+                Chips::new((pool.get() as f64 * shares / tied as f64) as u32)
+            })
+            .collect();
+        TournamentReport {
+            places,
+            payouts,
+            matches,
+        }
+    }
+}
+
+// Two different bots of the slice at once.
+fn pair_mut<B>(bots: &mut [B], a: usize, b: usize) -> (&mut B, &mut B) {
+    if a < b {
+        let (low, high) = bots.split_at_mut(b);
+        (&mut low[a], &mut high[0])
+    } else {
+        let (low, high) = bots.split_at_mut(a);
+        (&mut high[0], &mut low[b])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Match {
+    pub round: u32, // from 1
+    pub winner: usize,
+    pub loser: usize,
+    pub hands: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TournamentReport {
+    places: Vec<usize>, // by entrant, the best place of a tie
+    payouts: Vec<Chips>,
+    matches: Vec<Match>, // in the order they were played
+}
+
+impl TournamentReport {
+    pub fn place(&self, entrant: usize) -> usize {
+        self.places[entrant]
+    }
+
+    pub fn payout(&self, entrant: usize) -> Chips {
+        self.payouts[entrant]
+    }
+
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.places.iter().position(|&place| place == 1)
+    }
+
+    // Entrants from first place down, ties in entry order.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut standings: Vec<usize> = (0..self.places.len()).collect();
+        standings.sort_by_key(|&entrant| self.places[entrant]);
+        standings
+    }
+
+    // The match that knocked `entrant` out, None for the winner.
+    pub fn elimination(&self, entrant: usize) -> Option<Match> {
+        self.matches
+            .iter()
+            .find(|game| game.loser == entrant)
+            .copied()
+    }
+}
+
+impl Display for TournamentReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, entrant) in self.standings().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}. entrant {entrant}: {}",
+                self.place(entrant),
+                self.payout(entrant)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Decision,
+        gameplay::headsup::{Action, SNGSpeed},
+    };
+
+    // Shoves everything, or folds everything.
+    struct Shover(bool);
+
+    impl Bot for Shover {
+        fn act(&mut self, decision: &Decision) -> Action {
+            match self.0 {
+                true => Action::all_in(),
+                false if decision.facing_bet() => Action::fold(),
+                false => Action::check_or_call(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn brackets() {
+        let game_type = GameType::sng(SNGSpeed::Turbo);
+        assert!(Tournament::new(GameType::cash_default()).is_none());
+        let tournament = Tournament::new(game_type).unwrap().with_seed(7);
+        assert!(tournament.clone().with_payouts(vec![0.8, 0.3]).is_none());
+
+        // the shover beats the folders, the one with the bye comes second
+        let mut bots = [Shover(false), Shover(true), Shover(false)];
+        let report = tournament.run(&mut bots).await;
+        assert_eq!(report.standings(), [1, 2, 0]);
+        assert_eq!(report.matches().len(), 2);
+        assert_eq!(report.elimination(0).map(|game| game.round), Some(1));
+        assert_eq!(report.elimination(1), None);
+
+        // 9000 in the pool
+        let payouts = [0, 1, 2].map(|entrant| report.payout(entrant).get());
+        assert_eq!(payouts, [1800, 4500, 2700]);
+    }
+}
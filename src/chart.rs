@@ -0,0 +1,239 @@
+// Preflop charts: how often each starting hand folds, calls and raises, on the button opening and
+// in the big blind facing an open. Text charts are one `position,class,fold,call,raise` line per
+// class with percentages, e.g. "button,AKs,0,0,100"; blank lines and `#` comments are skipped.
+use crate::{
+    bot::preflop_strength,
+    gameplay::{Hole, HoleClass, parse::HoleClassParseError},
+};
+use indexmap::IndexMap;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+// Fractions of the time, summing to 1.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Frequencies {
+    pub fold: f64,
+    pub call: f64,
+    pub raise: f64,
+}
+
+impl Default for Frequencies {
+    fn default() -> Self {
+        Self::FOLD
+    }
+}
+
+impl Frequencies {
+    pub const FOLD: Self = Self {
+        fold: 1.0,
+        call: 0.0,
+        raise: 0.0,
+    };
+    pub const CALL: Self = Self {
+        fold: 0.0,
+        call: 1.0,
+        raise: 0.0,
+    };
+    pub const RAISE: Self = Self {
+        fold: 0.0,
+        call: 0.0,
+        raise: 1.0,
+    };
+
+    // Normalized, so `(0.0, 40.0, 60.0)` works as well as `(0.0, 0.4, 0.6)`. None if one is
+    // negative or they're all 0.
+    pub fn new(fold: f64, call: f64, raise: f64) -> Option<Self> {
+        let total = fold + call + raise;
+        if [fold, call, raise].iter().any(|&f| f.is_nan() || f < 0.0) || total <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            fold: fold / total,
+            call: call / total,
+            raise: raise / total,
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Chart {
+    positions: [IndexMap<HoleClass, Frequencies>; 2], // [button, big blind]
+}
+
+impl Chart {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // Heads-up defaults 100bb deep: the button opens about three hands in four, the big blind
+    // defends against an open with about two in three, three-betting the strongest.
+    pub fn heads_up() -> Self {
+        let mut classes: Vec<HoleClass> = HoleClass::all().collect();
+        classes.sort_by(|a, b| strength(*b).total_cmp(&strength(*a)));
+
+        let mut chart = Self::new();
+        let mut combos = 0;
+        for class in classes {
+            let share = combos as f64 / 1326.0; // of hands stronger than this one
+            combos += class.combos().len();
+
+            let open = if share < 0.75 {
+                Frequencies::RAISE
+            } else {
+                Frequencies::FOLD
+            };
+            let defend = if share < 0.12 {
+                Frequencies::RAISE
+            } else if share < 0.65 {
+                Frequencies::CALL
+            } else {
+                Frequencies::FOLD
+            };
+            chart.set(true, class, open);
+            chart.set(false, class, defend);
+        }
+        chart
+    }
+
+    pub fn set(&mut self, button: bool, class: HoleClass, frequencies: Frequencies) {
+        self.positions[position(button)].insert(class, frequencies);
+    }
+
+    // Classes the chart leaves out fold.
+    pub fn get(&self, button: bool, class: HoleClass) -> Frequencies {
+        self.positions[position(button)]
+            .get(&class)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn lookup(&self, button: bool, hole: Hole) -> Frequencies {
+        self.get(button, hole.class())
+    }
+}
+
+fn position(button: bool) -> usize {
+    if button { 0 } else { 1 }
+}
+
+fn strength(class: HoleClass) -> f64 {
+    preflop_strength(class.combos()[0])
+}
+
+impl Display for Chart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (button, name) in [(true, "button"), (false, "bb")] {
+            for (class, freq) in &self.positions[position(button)] {
+                let [fold, call, raise] = [freq.fold, freq.call, freq.raise].map(|f| f * 100.0);
+                writeln!(f, "{name},{class},{fold},{call},{raise}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChartParseError {
+    Fields(usize),           // line number, counting from 1, without five fields
+    Position(usize, String), // neither "button" nor "bb"
+    Class(usize, HoleClassParseError),
+    Frequencies(usize), // not numbers, negative or all 0
+}
+
+impl Display for ChartParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fields(line) => write!(f, "line {line}: expected position,class,fold,call,raise"),
+            Self::Position(line, position) => {
+                write!(f, "line {line}: \"{position}\" is not button or bb")
+            }
+            Self::Class(line, err) => write!(f, "line {line}: {err}"),
+            Self::Frequencies(line) => write!(f, "line {line}: invalid frequencies"),
+        }
+    }
+}
+
+impl Error for ChartParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Class(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Chart {
+    type Err = ChartParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chart = Self::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [position, class, fold, call, raise] = fields[..] else {
+                return Err(ChartParseError::Fields(line_no));
+            };
+            let button = match position.to_ascii_lowercase().as_str() {
+                "button" | "btn" => true,
+                "bb" | "big blind" => false,
+                _ => return Err(ChartParseError::Position(line_no, position.to_string())),
+            };
+            let class = class
+                .parse()
+                .map_err(|err| ChartParseError::Class(line_no, err))?;
+            let [fold, call, raise] = [fold, call, raise].map(|f| f.parse::<f64>().ok());
+            let frequencies = match (fold, call, raise) {
+                (Some(fold), Some(call), Some(raise)) => Frequencies::new(fold, call, raise),
+                _ => None,
+            };
+            chart.set(
+                button,
+                class,
+                frequencies.ok_or(ChartParseError::Frequencies(line_no))?,
+            );
+        }
+
+        Ok(chart)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::Value;
+
+    #[test]
+    fn heads_up_defaults_and_text() {
+        let chart = Chart::heads_up();
+        let aces = HoleClass::Pair(Value::Ace);
+        assert_eq!(chart.get(true, aces), Frequencies::RAISE);
+        assert_eq!(chart.get(false, aces), Frequencies::RAISE);
+        let seven_deuce = "7c2d".parse().unwrap();
+        assert_eq!(chart.lookup(true, seven_deuce), Frequencies::FOLD);
+        assert_eq!(chart.lookup(false, seven_deuce), Frequencies::FOLD);
+        assert_eq!(chart.to_string().parse(), Ok(chart));
+
+        let text = "# limp or raise\nbutton, AKs, 0, 40, 60\n\nbb,T9o,50,50,0\n";
+        let chart: Chart = text.parse().unwrap();
+        let ak = chart.get(true, "AKs".parse().unwrap());
+        assert_eq!((ak.call, ak.raise), (0.4, 0.6));
+        assert_eq!(chart.get(true, "T9o".parse().unwrap()), Frequencies::FOLD);
+        assert_eq!(
+            "button,AKs,0,0".parse::<Chart>(),
+            Err(ChartParseError::Fields(1))
+        );
+        assert_eq!(
+            "sb,AKs,0,0,1".parse::<Chart>(),
+            Err(ChartParseError::Position(1, "sb".to_string()))
+        );
+    }
+}
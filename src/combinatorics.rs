@@ -0,0 +1,127 @@
+// Counting helpers for study and bots: combos, boards and made hands.
+use crate::{
+    gameplay::{Board, Card, CardsCombined, HandCategory, HandValue, Hole, HoleClass, Suit, Value},
+    range::Range,
+};
+use indexmap::IndexMap;
+use itertools::Itertools;
+
+// Binomial coefficient, 0 when `k > n`.
+pub fn choose(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+
+    let k = k.min(n - k);
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+// Combos of `class` left when `dead` cards are out, e.g. 3 AKs on an ace-high board.
+pub fn class_combos(class: HoleClass, dead: &[Card]) -> usize {
+    class
+        .combos()
+        .into_iter()
+        .filter(|hole| !dead.iter().any(|&card| hole.contains_card(card)))
+        .count()
+}
+
+// Chance that the next `cards.len()` cards dealt are exactly `cards`, in any order.
+// None if one of them is dead or they repeat.
+pub fn runout_probability(cards: &[Card], dead: &[Card]) -> Option<f64> {
+    if !cards.iter().chain(dead).all_unique() {
+        return None;
+    }
+
+    let live = 52 - dead.iter().unique().count() as u64;
+    Some(1.0 / choose(live, cards.len() as u64) as f64)
+}
+
+// Chance that `cards` cards dealt from the live deck satisfy `hits`, by enumeration.
+pub fn board_probability(dead: &[Card], cards: usize, hits: impl Fn(&[Card]) -> bool) -> f64 {
+    let deck = live_deck(dead);
+    let (hit, total) = deck
+        .into_iter()
+        .combinations(cards)
+        .fold((0u64, 0u64), |(hit, total), board| {
+            (hit + hits(&board) as u64, total + 1)
+        });
+
+    if total == 0 {
+        0.0
+    } else {
+        hit as f64 / total as f64
+    }
+}
+
+// Best made hand of `hole` with at least three board cards.
+pub fn made_hand(hole: Hole, board: &Board) -> Option<HandCategory> {
+    let board_cards = board.to_vec();
+    if board_cards.len() < 3 || board_cards.iter().any(|&card| hole.contains_card(card)) {
+        return None;
+    }
+
+    hole.iter()
+        .chain(board_cards.iter())
+        .copied()
+        .array_combinations::<5>()
+        .filter_map(CardsCombined::new)
+        .map(HandValue::from)
+        .max()
+        .map(|value| value.category())
+}
+
+// Weighted combos of `range` making each hand on `board`, strongest first. Combos colliding with
+// the board are left out.
+pub fn range_hits(range: &Range, board: &Board) -> IndexMap<HandCategory, f64> {
+    let mut hits: IndexMap<HandCategory, f64> = IndexMap::new();
+    for (hole, weight) in range.iter() {
+        if let Some(hand) = made_hand(hole, board) {
+            *hits.entry(hand).or_default() += weight;
+        }
+    }
+
+    hits.sort_by(|a, _, b, _| b.cmp(a));
+    hits
+}
+
+fn live_deck(dead: &[Card]) -> Vec<Card> {
+    Value::ALL
+        .into_iter()
+        .cartesian_product(Suit::ALL)
+        .map(|(value, suit)| Card::new(value, suit))
+        .filter(|card| !dead.contains(card))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        assert_eq!(choose(52, 2), 1326);
+        assert_eq!(choose(50, 3), 19600);
+        assert_eq!(choose(3, 5), 0);
+
+        let ace: Card = "As".parse().unwrap();
+        assert_eq!(class_combos("AKs".parse().unwrap(), &[ace]), 3);
+        assert_eq!(class_combos("AA".parse().unwrap(), &[ace]), 3);
+        assert_eq!(class_combos("AKo".parse().unwrap(), &[ace]), 9);
+
+        let hole: Hole = "7h7d".parse().unwrap();
+        let flop: Vec<Card> = "2c8sKh".parse::<Board>().unwrap().to_vec();
+        assert_eq!(runout_probability(&flop, &hole[..]), Some(1.0 / 19600.0));
+
+        // a pocket pair flops a set or better
+        let set = board_probability(&hole[..], 3, |flop| {
+            flop.iter().any(|card| card.value() == Value::Seven)
+        });
+        assert!((set - (1.0 - 17296.0 / 19600.0)).abs() < 1e-12);
+
+        let board: Board = "7c8sKh".parse().unwrap();
+        let hits = range_hits(&"77, KQs, 96s".parse().unwrap(), &board);
+        assert_eq!(hits.get_index(0), Some((&HandCategory::Trips, &3.0)));
+        assert_eq!(hits[&HandCategory::OnePair], 3.0);
+        assert_eq!(hits[&HandCategory::HighCard], 4.0);
+    }
+}
@@ -0,0 +1,532 @@
+// Heads-up matches between two Discord users, played with `!` commands in a channel. The table's
+// public events are posted to the channel with emoji cards; hole cards and decisions go to each
+// player in direct messages. One match per channel:
+//   !challenge @user [cash|sng]   !accept   !decline
+//   !f  !c  !a  !<chips>  !x      fold, check/call, all in, bet/raise to, leave
+//   !show n|1|2|b                 after winning uncontested
+//   !insure yes|no                when offered to cash out an all in
+use crate::gameplay::{
+    Card, DisplayMode,
+    chips::Chips,
+    headsup::{
+        Action, ActionValue, BetBound, Game, GameType, ObservableEvent, Observer, Player,
+        PlayerEvent, ShowCards, Visibility, identity::Identity,
+    },
+};
+use serenity::{
+    all::{
+        ChannelId, Client, Context, CreateMessage, EventHandler, GatewayIntents, Http, Message,
+        UserId,
+    },
+    async_trait,
+};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{
+    Mutex,
+    mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+
+const MODE: DisplayMode = DisplayMode::ColoredEmoji;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChatCommand {
+    Challenge(GameType), // the opponent is the message's mention
+    Accept,
+    Decline,
+    Act(Action),
+    Show(ShowCards),
+    Insure(bool),
+}
+
+// None for messages that aren't commands.
+pub fn parse_command(content: &str) -> Option<ChatCommand> {
+    let mut words = content.trim().strip_prefix('!')?.split_whitespace();
+    let command = words.next()?.to_ascii_lowercase();
+    let arg = words.find(|word| !word.starts_with("<@"));
+
+    match command.as_str() {
+        "challenge" => match arg.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("sng") => Some(ChatCommand::Challenge(GameType::default())),
+            Some("cash") => Some(ChatCommand::Challenge(GameType::cash_default())),
+            _ => None,
+        },
+        "accept" => Some(ChatCommand::Accept),
+        "decline" => Some(ChatCommand::Decline),
+        "show" => arg?.parse().ok().map(ChatCommand::Show),
+        "insure" => match arg?.to_ascii_lowercase().as_str() {
+            "y" | "yes" => Some(ChatCommand::Insure(true)),
+            "n" | "no" => Some(ChatCommand::Insure(false)),
+            _ => None,
+        },
+        action => action.parse().ok().map(ChatCommand::Act),
+    }
+}
+
+// What everyone in the channel sees; `names` are in seat order, player 0 first.
+pub fn render_public(event: &ObservableEvent, names: [&str; 2]) -> Option<String> {
+    let name = |seat: bool| names[if seat { 0 } else { 1 }];
+    let text = match event {
+        ObservableEvent::BombPot(ante) => format!("💣 Bomb pot! Both players put in {ante}."),
+        ObservableEvent::DealHoles(_) => "🃏 New hand.".to_string(),
+        ObservableEvent::BlindLevel {
+            level,
+            blinds,
+            hands,
+        } => {
+            let ante = match blinds.ante() {
+                ante if ante.is_zero() => String::new(),
+                ante => format!(", ante {ante}"),
+            };
+            let blinds = format!(
+                "📈 Level {level}: blinds {}/{}{ante}",
+                blinds.small_blind(),
+                blinds.big_blind()
+            );
+            match hands {
+                Some(hands) => format!("{blinds} for {hands} hands."),
+                None => format!("{blinds} from now on."),
+            }
+        }
+        ObservableEvent::ShowdownAll(holes) | ObservableEvent::ShowdownAuto(holes) => format!(
+            "Showdown: {} {} · {} {}",
+            names[0],
+            holes[0].display(MODE),
+            names[1],
+            holes[1].display(MODE)
+        ),
+        ObservableEvent::ShowdownShow(seat, hole) => {
+            format!("{} shows {}.", name(*seat), hole.display(MODE))
+        }
+        ObservableEvent::ShowdownMuck(seat) => format!("{} mucks.", name(*seat)),
+        ObservableEvent::ShowCards(seat, cards) => {
+            format!("{} shows {}.", name(*seat), cards_text(cards))
+        }
+        ObservableEvent::EarlyAward(winner) => match winner {
+            Some(seat) => format!("{} wins, no card to come changes it.", name(*seat)),
+            None => "Chopped, no card to come changes it.".to_string(),
+        },
+        ObservableEvent::AllInEquity(shares) => format!(
+            "📊 {} {:.1}% · {} {:.1}%",
+            names[0],
+            shares[0].0 * 100.0,
+            names[1],
+            shares[1].0 * 100.0
+        ),
+        ObservableEvent::BoardVariant(run, board) => {
+            format!("🔁 Run {}: {}", run + 1, board.display(MODE))
+        }
+        ObservableEvent::TimedOut(seat) => format!("⏰ {} ran out of time.", name(*seat)),
+        ObservableEvent::DealBoard(board) => {
+            format!("{:?}: {}", board.street(), board.display(MODE))
+        }
+        ObservableEvent::PotAwarded { amounts, hand } => {
+            let hand = hand.display(MODE);
+            match amounts {
+                [won, Chips::ZERO] => format!("🏆 {} wins {won} with {hand}.", names[0]),
+                [Chips::ZERO, won] => format!("🏆 {} wins {won} with {hand}.", names[1]),
+                _ => format!("🤝 Chopped with {hand}."),
+            }
+        }
+        ObservableEvent::Insured { seat, payout } => {
+            format!("🛡️ {} cashes out for {payout}.", name(*seat))
+        }
+        ObservableEvent::SevenDeuce { seat, bonus } => {
+            format!(
+                "🎉 {} wins with seven-deuce and collects {bonus}!",
+                name(*seat)
+            )
+        }
+        ObservableEvent::Rake(rake) => format!("🏠 Rake {}.", rake[0] + rake[1]),
+        ObservableEvent::HouseRule {
+            name: rule,
+            seat,
+            chips,
+        } => format!("{} gets {chips} ({rule}).", name(*seat)),
+        // announced by the acting seat, the event doesn't say who acted
+        ObservableEvent::PlayerAction(..) | ObservableEvent::Checksum(_) => return None,
+        ObservableEvent::Stacks { behinds, bets, pot } => format!(
+            "💰 {} {} (bet {}) · {} {} (bet {}) · pot {pot}",
+            names[0], behinds[0], bets[0], names[1], behinds[1], bets[1]
+        ),
+        ObservableEvent::CatchUp {
+            hands,
+            big_blind,
+            stacks,
+            ..
+        } => format!(
+            "👀 Joined after {hands} hands, big blind {big_blind} · {} {} · {} {}",
+            names[0], stacks[0], names[1], stacks[1]
+        ),
+        ObservableEvent::GameOver(game_over) => format!("🏁 Game over: {game_over:?}"),
+    };
+    Some(text)
+}
+
+// What only the player sees, in their direct messages.
+pub fn render_private(event: &PlayerEvent) -> Option<String> {
+    let text = match event {
+        PlayerEvent::Observable(ObservableEvent::DealHoles(holes)) => {
+            format!(
+                "Your cards: {}",
+                holes.iter().find_map(|&h| h)?.display(MODE)
+            )
+        }
+        PlayerEvent::HeroTurn(bound, _) => format!("Your turn: {}", options(bound)),
+        PlayerEvent::ShowOffer => {
+            "You won uncontested. Show your cards? `!show n|1|2|b`".to_string()
+        }
+        PlayerEvent::InsuranceOffer(chips) => {
+            format!("Cash out your all in for {chips}? `!insure yes|no`")
+        }
+        PlayerEvent::Observable(_) => return None,
+    };
+    Some(text)
+}
+
+fn options(bound: &BetBound) -> String {
+    let sized = |verb: &str, range: &std::ops::RangeInclusive<Chips>| {
+        format!("`!<chips>` to {verb} {}-{}", range.start(), range.end())
+    };
+    let options = match bound {
+        BetBound::FoldCheckAllIn => vec!["`!f`".to_string(), "`!c` check".into()],
+        BetBound::FoldCheckBetAllIn(range) => {
+            vec!["`!f`".to_string(), "`!c` check".into(), sized("bet", range)]
+        }
+        BetBound::FoldAllIn => vec!["`!f`".to_string()],
+        BetBound::FoldCall => return "`!f`, `!c` call".to_string(),
+        BetBound::FoldCallAllIn => vec!["`!f`".to_string(), "`!c` call".into()],
+        BetBound::FoldCallRaiseAllIn(range) => {
+            vec![
+                "`!f`".to_string(),
+                "`!c` call".into(),
+                sized("raise", range),
+            ]
+        }
+        BetBound::FoldBetAllIn(range) => vec!["`!f`".to_string(), sized("bet", range)],
+        BetBound::FoldRaiseAllIn(range) => vec!["`!f`".to_string(), sized("raise", range)],
+        BetBound::FoldCheckBet(range) => {
+            let options = ["`!f`".to_string(), "`!c` check".into(), sized("bet", range)];
+            return options.join(", ");
+        }
+        BetBound::FoldCallRaise(range) => {
+            let options = [
+                "`!f`".to_string(),
+                "`!c` call".into(),
+                sized("raise", range),
+            ];
+            return options.join(", ");
+        }
+    };
+    options.join(", ") + ", `!a` all in"
+}
+
+fn cards_text(cards: &[Option<Card>; 2]) -> String {
+    cards
+        .iter()
+        .flatten()
+        .map(|card| card.display(MODE).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug)]
+enum SeatCommand {
+    Act(Action),
+    Show(ShowCards),
+    Insure(bool),
+}
+
+#[derive(Debug)]
+enum Table {
+    Challenge {
+        from: UserId,
+        to: UserId,
+        game_type: GameType,
+    },
+    Match {
+        users: [UserId; 2],
+        seats: [UnboundedSender<SeatCommand>; 2],
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct Handler {
+    tables: Arc<Mutex<HashMap<ChannelId, Table>>>,
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot {
+            return;
+        }
+        let Some(command) = parse_command(&msg.content) else {
+            return;
+        };
+
+        if let Some(reply) = self.handle(&ctx, &msg, command).await {
+            let _ = msg.channel_id.say(&ctx.http, reply).await;
+        }
+    }
+}
+
+impl Handler {
+    // Gives a reply for the channel, if any.
+    async fn handle(&self, ctx: &Context, msg: &Message, command: ChatCommand) -> Option<String> {
+        let channel = msg.channel_id;
+        let author = msg.author.id;
+        let mut tables = self.tables.lock().await;
+
+        match (command, tables.get(&channel)) {
+            (ChatCommand::Challenge(game_type), None) => {
+                let opponent = msg.mentions.iter().find(|user| user.id != author)?;
+                tables.insert(
+                    channel,
+                    Table::Challenge {
+                        from: author,
+                        to: opponent.id,
+                        game_type,
+                    },
+                );
+                Some(format!(
+                    "<@{}>, <@{author}> challenges you to heads-up. `!accept` or `!decline`",
+                    opponent.id
+                ))
+            }
+            (ChatCommand::Challenge(_), Some(_)) => {
+                Some("This channel already has a table.".to_string())
+            }
+            (ChatCommand::Decline, Some(&Table::Challenge { from, to, .. }))
+                if author == from || author == to =>
+            {
+                tables.remove(&channel);
+                Some("Challenge withdrawn.".to_string())
+            }
+            (
+                ChatCommand::Accept,
+                Some(&Table::Challenge {
+                    from,
+                    to,
+                    game_type,
+                }),
+            ) if author == to => {
+                let names = [user_name(ctx, from).await, msg.author.name.clone()];
+                let seats = self.start(ctx, channel, [from, to], names, game_type);
+                tables.insert(
+                    channel,
+                    Table::Match {
+                        users: [from, to],
+                        seats,
+                    },
+                );
+                Some("Shuffle up and deal! Check your direct messages for your cards.".to_string())
+            }
+            (
+                ChatCommand::Act(_) | ChatCommand::Show(_) | ChatCommand::Insure(_),
+                Some(Table::Match { users, seats }),
+            ) => {
+                let seat = users.iter().position(|&user| user == author)?;
+                let command = match command {
+                    ChatCommand::Act(action) => SeatCommand::Act(action),
+                    ChatCommand::Show(show) => SeatCommand::Show(show),
+                    ChatCommand::Insure(take) => SeatCommand::Insure(take),
+                    _ => unreachable!(),
+                };
+                let _ = seats[seat].send(command);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn start(
+        &self,
+        ctx: &Context,
+        channel: ChannelId,
+        users: [UserId; 2],
+        names: [String; 2],
+        game_type: GameType,
+    ) -> [UnboundedSender<SeatCommand>; 2] {
+        let identities = [0, 1].map(|i| Identity::new(&names[i]).with_id(&users[i].to_string()));
+        let (game, players) = Game::new(game_type);
+        let mut game = game.with_identities(identities);
+        let observer = game
+            .observer(Visibility::None)
+            .expect("A new game has no observer");
+        tokio::spawn(game.run());
+
+        tokio::spawn(post_public(
+            ctx.http.clone(),
+            channel,
+            observer,
+            self.tables.clone(),
+        ));
+
+        let mut players = players.into_iter();
+        [0, 1].map(|i| {
+            let (send, recv) = unbounded_channel();
+            tokio::spawn(drive_seat(
+                ctx.http.clone(),
+                channel,
+                users[i],
+                names[i].clone(),
+                players.next().expect("Two players"),
+                recv,
+            ));
+            send
+        })
+    }
+}
+
+async fn user_name(ctx: &Context, user: UserId) -> String {
+    match user.to_user(ctx).await {
+        Ok(user) => user.name,
+        Err(_) => format!("<@{user}>"),
+    }
+}
+
+async fn post_public(
+    http: Arc<Http>,
+    channel: ChannelId,
+    mut observer: Observer,
+    tables: Arc<Mutex<HashMap<ChannelId, Table>>>,
+) {
+    while let Some(event) = observer.tick_event().await {
+        if let Some(text) = render_public(&event, observer.identities().names()) {
+            let _ = channel.say(&http, text).await;
+        }
+    }
+    tables.lock().await.remove(&channel);
+}
+
+async fn drive_seat(
+    http: Arc<Http>,
+    channel: ChannelId,
+    user: UserId,
+    name: String,
+    mut player: Player,
+    mut commands: UnboundedReceiver<SeatCommand>,
+) {
+    loop {
+        tokio::select! {
+            event = player.tick_event() => {
+                let Some(event) = event else {
+                    return;
+                };
+                if let Some(text) = render_private(&event) {
+                    let _ = user
+                        .direct_message(&http, CreateMessage::new().content(text))
+                        .await;
+                }
+            }
+            Some(command) = commands.recv() => {
+                let result = match command {
+                    SeatCommand::Act(action) => player.send_action(action).map(|()| {
+                        Some(format!("{name}: {}", action_text(action)))
+                    }),
+                    SeatCommand::Show(show) => player.send_show(show).map(|()| None),
+                    SeatCommand::Insure(take) => player.send_insurance(take).map(|()| None),
+                };
+                let reply = match result {
+                    Ok(announce) => announce,
+                    Err(err) => Some(format!("<@{user}> can't do that now ({err:?}).")),
+                };
+                if let Some(reply) = reply {
+                    let _ = channel.say(&http, reply).await;
+                }
+            }
+        }
+    }
+}
+
+fn action_text(action: Action) -> String {
+    match action.value() {
+        ActionValue::Exit => "leaves the table".to_string(),
+        ActionValue::Fold => "folds".to_string(),
+        ActionValue::CheckOrCall => "checks/calls".to_string(),
+        ActionValue::BetOrRaise(chips) => format!("bets/raises to {chips}"),
+        ActionValue::AllIn => "goes all in".to_string(),
+    }
+}
+
+// Runs the bot until the connection ends. The bot needs the message content intent.
+pub async fn run(token: &str) -> serenity::Result<()> {
+    let intents = GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT;
+    Client::builder(token, intents)
+        .event_handler(Handler::default())
+        .await?
+        .start()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{FullBoard, headsup::TurnId};
+
+    #[test]
+    fn commands_and_rendering() {
+        assert_eq!(
+            parse_command("!challenge <@42> cash"),
+            Some(ChatCommand::Challenge(GameType::cash_default()))
+        );
+        assert_eq!(
+            parse_command("!Challenge <@42>"),
+            Some(ChatCommand::Challenge(GameType::default()))
+        );
+        assert_eq!(
+            parse_command("!400"),
+            Some(ChatCommand::Act(
+                Action::bet_or_raise(Chips::new(400)).unwrap()
+            ))
+        );
+        assert_eq!(
+            parse_command("!show b"),
+            Some(ChatCommand::Show(ShowCards::Both))
+        );
+        assert_eq!(
+            parse_command("!insure no"),
+            Some(ChatCommand::Insure(false))
+        );
+        assert_eq!(parse_command("gg"), None);
+        assert_eq!(parse_command("!0"), None);
+
+        let holes = ["AsKd".parse().unwrap(), "7c2h".parse().unwrap()];
+        let private = PlayerEvent::Observable(ObservableEvent::DealHoles([None, Some(holes[1])]));
+        assert_eq!(
+            render_private(&private).unwrap(),
+            format!("Your cards: {}", holes[1].display(MODE))
+        );
+        assert_eq!(
+            render_private(&PlayerEvent::HeroTurn(
+                BetBound::FoldCallRaiseAllIn(Chips::new(200)..=Chips::new(9900)),
+                TurnId::default()
+            ))
+            .unwrap(),
+            "Your turn: `!f`, `!c` call, `!<chips>` to raise 200-9900, `!a` all in"
+        );
+
+        let names = ["alice", "bob"];
+        let shows = ObservableEvent::ShowdownShow(true, holes[0]);
+        assert_eq!(
+            render_public(&shows, names).unwrap(),
+            format!("alice shows {}.", holes[0].display(MODE))
+        );
+        let mucks = ObservableEvent::ShowdownMuck(false);
+        assert_eq!(render_public(&mucks, names).unwrap(), "bob mucks.");
+        let board: FullBoard = "Kc9d8h4s3h".parse().unwrap();
+        let awarded = ObservableEvent::PotAwarded {
+            amounts: [Chips::new(1000), Chips::ZERO],
+            hand: board.hand_value(holes[0]),
+        };
+        assert!(
+            render_public(&awarded, names)
+                .unwrap()
+                .starts_with("🏆 alice wins 1000 with ")
+        );
+        let action = ObservableEvent::PlayerAction(Action::fold(), TurnId::default());
+        assert_eq!(render_public(&action, names), None);
+    }
+}
@@ -0,0 +1,924 @@
+use crate::{
+    gameplay::{
+        Board, Card, FullBoard, Hole,
+        cardset::CardSet,
+        runout::{RunoutBucket, Runouts},
+    },
+    parallel::{self, Threads, prelude::*},
+    range::Range,
+};
+use itertools::Itertools;
+use rand::prelude::*;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    io::{self, Write},
+};
+
+pub mod cache;
+pub mod icm;
+pub mod odds;
+pub mod sim;
+
+pub use cache::{CacheStats, StrengthCache};
+pub use icm::{bubble_factor, icm, risk_premium};
+pub use odds::{OutsReport, outs, pot_odds};
+pub use sim::SimContext;
+
+// Random runouts drawn preflop, where enumerating every board is too slow.
+const PREFLOP_RUNOUTS: usize = 2000;
+
+// Histogram of hero's equity over `0.0..=1.0` in equal-width bins, one sample per runout.
+// Compares by first-order stochastic dominance: `a < b` when b's equity is at least as high at
+// every percentile, and incomparable when the CDFs cross.
+#[derive(Debug, Clone)]
+pub struct EquityDistribution {
+    bins: Vec<f64>,
+    total: f64,
+    sum: f64, // weighted equity, for an exact mean
+}
+
+impl EquityDistribution {
+    pub fn new(bins: usize) -> Option<Self> {
+        if bins == 0 {
+            return None;
+        }
+
+        Some(Self {
+            bins: vec![0.0; bins],
+            total: 0.0,
+            sum: 0.0,
+        })
+    }
+
+    pub fn from_samples(
+        bins: usize,
+        samples: impl IntoIterator<Item = (f64, f64)>,
+    ) -> Option<Self> {
+        let mut dist = Self::new(bins)?;
+        for (equity, weight) in samples {
+            dist.add(equity, weight);
+        }
+        Some(dist)
+    }
+
+    pub fn add(&mut self, equity: f64, weight: f64) {
+        let equity = equity.clamp(0.0, 1.0);
+        let bin = ((equity * self.bins.len() as f64) as usize).min(self.bins.len() - 1);
+
+        self.bins[bin] += weight;
+        self.total += weight;
+        self.sum += equity * weight;
+    }
+
+    pub fn bins(&self) -> usize {
+        self.bins.len()
+    }
+
+    // Share of runouts in each bin.
+    pub fn histogram(&self) -> Vec<f64> {
+        self.bins.iter().map(|&weight| self.share(weight)).collect()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total <= 0.0
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.share(self.sum)
+    }
+
+    // Share of runouts with equity below `equity`, linear inside a bin.
+    pub fn cdf(&self, equity: f64) -> f64 {
+        let scaled = equity.clamp(0.0, 1.0) * self.bins.len() as f64;
+        let full = (scaled as usize).min(self.bins.len());
+        let below: f64 = self.bins[..full].iter().sum();
+        let partial = self
+            .bins
+            .get(full)
+            .map_or(0.0, |&weight| weight * (scaled - full as f64));
+
+        self.share(below + partial)
+    }
+
+    // Equity at the `p` quantile, `p` in `0.0..=1.0`, interpolated inside the bin.
+    pub fn percentile(&self, p: f64) -> f64 {
+        let target = p.clamp(0.0, 1.0) * self.total;
+        let width = 1.0 / self.bins.len() as f64;
+        let mut below = 0.0;
+
+        for (i, &weight) in self.bins.iter().enumerate() {
+            if weight > 0.0 && below + weight >= target {
+                return (i as f64 + (target - below) / weight) * width;
+            }
+            below += weight;
+        }
+
+        1.0
+    }
+
+    // Earth mover's distance, the usual metric for clustering distributions. None if the bins differ.
+    pub fn emd(&self, other: &Self) -> Option<f64> {
+        if self.bins.len() != other.bins.len() {
+            return None;
+        }
+
+        let width = 1.0 / self.bins.len() as f64;
+        let distance = self
+            .cumulative()
+            .zip(other.cumulative())
+            .map(|(a, b)| (a - b).abs() * width)
+            .sum();
+        Some(distance)
+    }
+
+    fn cumulative(&self) -> impl Iterator<Item = f64> + '_ {
+        self.histogram().into_iter().scan(0.0, |acc, share| {
+            *acc += share;
+            Some(*acc)
+        })
+    }
+
+    fn share(&self, weight: f64) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            weight / self.total
+        }
+    }
+}
+
+impl PartialEq for EquityDistribution {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for EquityDistribution {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.bins.len() != other.bins.len() {
+            return None;
+        }
+
+        // more mass below means weaker
+        let mut ordering = Ordering::Equal;
+        for (a, b) in self.cumulative().zip(other.cumulative()) {
+            let step = if (a - b).abs() < 1e-12 {
+                Ordering::Equal
+            } else if a > b {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+
+            match (ordering, step) {
+                (_, Ordering::Equal) => {}
+                (Ordering::Equal, _) => ordering = step,
+                (current, step) if current != step => return None,
+                _ => {}
+            }
+        }
+
+        Some(ordering)
+    }
+}
+
+// Hero's equity against the weighted `villain` range on every runout of `board`.
+// None if hero's hole collides with the board or the range is fully blocked.
+pub fn equity_distribution(
+    hole: Hole,
+    villain: &Range,
+    board: &Board,
+    bins: usize,
+) -> Option<EquityDistribution> {
+    let board_cards = board.to_vec();
+    if board_cards.iter().any(|card| hole.contains_card(*card)) {
+        return None;
+    }
+
+    let dead = hole.iter().chain(board_cards.iter()).copied().collect_vec();
+    let villain = villain.without(&dead);
+    if villain.is_empty() {
+        return None;
+    }
+
+    let samples: Vec<_> = runouts(&dead, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            let full = FullBoard::new(cards.try_into().ok()?)?;
+            runout_equity(hole, &villain, &full).map(|equity| (equity, 1.0))
+        })
+        .collect();
+
+    let dist = EquityDistribution::from_samples(bins, samples)?;
+    (!dist.is_empty()).then_some(dist)
+}
+
+// Player 0's share of the pot with both holes face up, averaged over the runouts of `board`
+// (a sample of them preflop). None if any cards collide.
+pub fn hole_equity(holes: [Hole; 2], board: &Board) -> Option<f64> {
+    let board_cards = board.to_vec();
+    let dead = holes
+        .iter()
+        .flat_map(|hole| hole.iter())
+        .chain(board_cards.iter())
+        .copied()
+        .collect_vec();
+    if !dead.iter().all_unique() {
+        return None;
+    }
+
+    let scores: Vec<f64> = runouts(&dead, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            let full = FullBoard::new(cards.try_into().ok()?)?;
+            Some(showdown_score(
+                full.hand_value(holes[0]).cmp(&full.hand_value(holes[1])),
+            ))
+        })
+        .collect();
+
+    (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+}
+
+// How often hero wins, ties and loses, as fractions of the sampled runouts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+impl Equity {
+    fn from_counts(counts: [usize; 3]) -> Self {
+        Self::from_weights(counts.map(|count| count as f64))
+    }
+
+    fn from_weights([win, tie, lose]: [f64; 3]) -> Self {
+        let total = win + tie + lose;
+        Self {
+            win: win / total,
+            tie: tie / total,
+            lose: lose / total,
+        }
+    }
+
+    // Hero's share of the pot, ties split.
+    pub fn share(&self) -> f64 {
+        self.win + self.tie / 2.0
+    }
+}
+
+impl Display for Equity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "win {:.1}% · tie {:.1}% · lose {:.1}%",
+            self.win * 100.0,
+            self.tie * 100.0,
+            self.lose * 100.0
+        )
+    }
+}
+
+// Monte Carlo equity of `hole` against `villain` over `iterations` random completions of
+// `board`, split evenly over the rayon threads. None if any cards collide or there are no
+// iterations.
+pub fn equity(hole: Hole, villain: Hole, board: Board, iterations: usize) -> Option<Equity> {
+    let chunks = parallel::current_num_threads().clamp(1, iterations.max(1));
+    let counts = (0..chunks)
+        .into_par_iter()
+        .map(|chunk| {
+            let iterations = iterations / chunks + usize::from(chunk < iterations % chunks);
+            SimContext::new().counts(hole, villain, &board, iterations, &mut rand::rng())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Equity::from_counts(
+        counts.into_iter().fold([0; 3], add_counts),
+    ))
+}
+
+// Like `equity`, enumerating every completion of a flop, turn or river board instead, so the
+// result is exact and reproducible. None preflop or if any cards collide.
+pub fn equity_exact(hole: Hole, villain: Hole, board: Board) -> Option<Equity> {
+    let board_cards = board.to_vec();
+    let dead = hole
+        .iter()
+        .chain(villain.iter())
+        .chain(board_cards.iter())
+        .copied()
+        .collect_vec();
+    if board.is_preflop() || !dead.iter().all_unique() {
+        return None;
+    }
+
+    let dead = hole.iter().chain(villain.iter()).copied().collect();
+    let counts = board
+        .full_runouts(&dead)
+        .par_bridge()
+        .map(|full| showdown_counts(hole, villain, full.to_vec()))
+        .reduce(|| [0; 3], add_counts);
+    Some(Equity::from_counts(counts))
+}
+
+// Like `equity_exact`, preflop boards included: all 1.7 million of them there, so it is for
+// looking back at a hand rather than for play. None if any cards collide.
+pub fn equity_every_runout(hole: Hole, villain: Hole, board: Board) -> Option<Equity> {
+    if !board.is_preflop() {
+        return equity_exact(hole, villain, board);
+    }
+    let dead = hole.iter().chain(villain.iter()).copied().collect_vec();
+    if !dead.iter().all_unique() {
+        return None;
+    }
+
+    let deck = dead.into_iter().collect::<CardSet>().complement().to_vec();
+    let counts = (0..deck.len())
+        .into_par_iter()
+        .map(|first| {
+            deck[first + 1..]
+                .iter()
+                .copied()
+                .combinations(4)
+                .map(|rest| showdown_counts(hole, villain, [&[deck[first]], &rest[..]].concat()))
+                .fold([0; 3], add_counts)
+        })
+        .reduce(|| [0; 3], add_counts);
+    Some(Equity::from_counts(counts))
+}
+
+// Wins, ties and losses of `hole` on one full board, as counts.
+fn showdown_counts(hole: Hole, villain: Hole, cards: Vec<Card>) -> [usize; 3] {
+    let full = cards
+        .try_into()
+        .ok()
+        .and_then(FullBoard::new)
+        .expect("Runout cards should never collide");
+    match full.hand_value(hole).cmp(&full.hand_value(villain)) {
+        Ordering::Greater => [1, 0, 0],
+        Ordering::Equal => [0, 1, 0],
+        Ordering::Less => [0, 0, 1],
+    }
+}
+
+fn add_counts(a: [usize; 3], b: [usize; 3]) -> [usize; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+// Like `equity`, on `threads`; the iterations are split over that pool's threads.
+pub fn equity_in(
+    hole: Hole,
+    villain: Hole,
+    board: Board,
+    iterations: usize,
+    threads: &Threads,
+) -> Option<Equity> {
+    threads.install(|| equity(hole, villain, board, iterations))
+}
+
+// Like `equity_exact`, on `threads`.
+pub fn equity_exact_in(
+    hole: Hole,
+    villain: Hole,
+    board: Board,
+    threads: &Threads,
+) -> Option<Equity> {
+    threads.install(|| equity_exact(hole, villain, board))
+}
+
+// Weighted equity of `hero` against `villain`, averaged over the runouts of `board`.
+// Colliding combo pairs are skipped. None if no pair of combos can meet.
+pub fn range_equity(hero: &Range, villain: &Range, board: &Board) -> Option<f64> {
+    let board_cards = board.to_vec();
+    let hero = hero.without(&board_cards);
+    let villain = villain.without(&board_cards);
+
+    let (won, total) = runouts(&board_cards, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            let full = FullBoard::new(cards.try_into().ok()?)?;
+            let live = |range: &Range| {
+                range
+                    .iter()
+                    .filter(|(hole, _)| !hole.iter().any(|card| full.contains_card(*card)))
+                    .map(|(hole, weight)| (hole, weight, full.hand_value(hole)))
+                    .collect_vec()
+            };
+            let (hero, villain) = (live(&hero), live(&villain));
+
+            let mut won = 0.0;
+            let mut total = 0.0;
+            for (h, hw, hv) in &hero {
+                for (v, vw, vv) in &villain {
+                    if h.iter().any(|card| v.contains_card(*card)) {
+                        continue;
+                    }
+                    won += hw * vw * showdown_score(hv.cmp(vv));
+                    total += hw * vw;
+                }
+            }
+            Some((won, total))
+        })
+        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    (total > 0.0).then(|| won / total)
+}
+
+// Like `range_equity`, on `threads`.
+pub fn range_equity_in(
+    hero: &Range,
+    villain: &Range,
+    board: &Board,
+    threads: &Threads,
+) -> Option<f64> {
+    threads.install(|| range_equity(hero, villain, board))
+}
+
+// Hero's equity against one combo of the villain's range.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ComboEquity {
+    pub combo: Hole,
+    pub weight: f64, // in the range
+    pub equity: Equity,
+}
+
+// Hero's equity against a range combo by combo, in the range's order. Combos hero's cards or the
+// board block are left out.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RangeEquity(Vec<ComboEquity>);
+
+impl RangeEquity {
+    pub fn combos(&self) -> &[ComboEquity] {
+        &self.0
+    }
+
+    // Against the whole range, the combos weighted.
+    pub fn total(&self) -> Equity {
+        let mut weights = [0.0; 3];
+        for ComboEquity { weight, equity, .. } in &self.0 {
+            weights[0] += equity.win * weight;
+            weights[1] += equity.tie * weight;
+            weights[2] += equity.lose * weight;
+        }
+        Equity::from_weights(weights)
+    }
+
+    // The combos hero gets more than half the pot against.
+    pub fn ahead(&self) -> impl Iterator<Item = &ComboEquity> {
+        self.0.iter().filter(|combo| combo.equity.share() > 0.5)
+    }
+}
+
+// `hole` against each combo of `villain` over the runouts of `board` (a sample of them preflop).
+// None if the hole collides with the board or the range is fully blocked.
+pub fn equity_vs_range(hole: Hole, villain: &Range, board: &Board) -> Option<RangeEquity> {
+    let mut hero = Range::new();
+    hero.set(hole, 1.0);
+    range_vs_range(&hero, villain, board)
+}
+
+// The weighted `hero` range against each combo of `villain`, like `range_equity` pair by pair:
+// colliding combo pairs are skipped. None if no pair of combos can meet.
+pub fn range_vs_range(hero: &Range, villain: &Range, board: &Board) -> Option<RangeEquity> {
+    let board_cards = board.to_vec();
+    let hero = hero.without(&board_cards);
+    let villain = villain.without(&board_cards).iter().collect_vec();
+
+    let weights = runouts(&board_cards, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            FullBoard::new(cards.try_into().ok()?)
+        })
+        .fold(
+            || vec![[0.0; 3]; villain.len()],
+            |mut weights, full| {
+                let live = |hole: &Hole| !hole.iter().any(|card| full.contains_card(*card));
+                let hero = hero
+                    .iter()
+                    .filter(|(hole, _)| live(hole))
+                    .map(|(hole, weight)| (hole, weight, full.hand_value(hole)))
+                    .collect_vec();
+                for ((v, _), weights) in villain.iter().zip(&mut weights) {
+                    if !live(v) {
+                        continue;
+                    }
+                    let vv = full.hand_value(*v);
+                    for (h, hw, hv) in &hero {
+                        if !h.iter().any(|card| v.contains_card(*card)) {
+                            weights[outcome(hv.cmp(&vv))] += hw;
+                        }
+                    }
+                }
+                weights
+            },
+        )
+        .reduce(
+            || vec![[0.0; 3]; villain.len()],
+            |mut a, b| {
+                for (a, b) in a.iter_mut().zip(b) {
+                    *a = [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+                }
+                a
+            },
+        );
+
+    let combos = villain
+        .into_iter()
+        .zip(weights)
+        .filter(|(_, weights)| weights.iter().sum::<f64>() > 0.0)
+        .map(|((combo, weight), weights)| ComboEquity {
+            combo,
+            weight,
+            equity: Equity::from_weights(weights),
+        })
+        .collect_vec();
+    (!combos.is_empty()).then_some(RangeEquity(combos))
+}
+
+// Where a showdown goes in win, tie and lose counts.
+fn outcome(ordering: Ordering) -> usize {
+    match ordering {
+        Ordering::Greater => 0,
+        Ordering::Equal => 1,
+        Ordering::Less => 2,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ShowdownClass {
+    Value,        // ahead of 60% of the continue range or more
+    ThinValue,    // ahead of half of it
+    BluffCatcher, // behind, but still ahead of a fifth
+    Air,
+}
+
+impl Display for ShowdownClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Value => "value",
+            Self::ThinValue => "thin value",
+            Self::BluffCatcher => "bluff-catcher",
+            Self::Air => "air",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Hero's standing against the range that continues, averaged over the runouts; `win + tie / 2`
+// is the equity.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ShowdownValue {
+    pub class: ShowdownClass,
+    pub equity: f64,
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+    pub combos: f64, // live weight of the continue range
+}
+
+// Whether betting `hole` gets called by worse (value), by about as much worse as better (thin
+// value), or whether it only beats what bluffs (bluff-catcher) or nothing (air).
+// None if the hole collides with the board or no combo of the range is live.
+pub fn classify_showdown(hole: Hole, board: &Board, continues: &Range) -> Option<ShowdownValue> {
+    let board_cards = board.to_vec();
+    if board_cards.iter().any(|card| hole.contains_card(*card)) {
+        return None;
+    }
+
+    let dead = hole.iter().chain(board_cards.iter()).copied().collect_vec();
+    let continues = continues.without(&dead);
+    let combos = continues.total_weight();
+
+    let [win, tie, lose] = runouts(&dead, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            let full = FullBoard::new(cards.try_into().ok()?)?;
+            let hero = full.hand_value(hole);
+
+            let mut weights = [0.0; 3];
+            for (combo, weight) in continues.iter() {
+                if combo.iter().any(|card| full.contains_card(*card)) {
+                    continue;
+                }
+                let i = match hero.cmp(&full.hand_value(combo)) {
+                    Ordering::Greater => 0,
+                    Ordering::Equal => 1,
+                    Ordering::Less => 2,
+                };
+                weights[i] += weight;
+            }
+            Some(weights)
+        })
+        .reduce(|| [0.0; 3], |a, b| [a[0] + b[0], a[1] + b[1], a[2] + b[2]]);
+
+    let total = win + tie + lose;
+    if total <= 0.0 {
+        return None;
+    }
+
+    let [win, tie, lose] = [win, tie, lose].map(|weight| weight / total);
+    let equity = win + tie / 2.0;
+    let class = match equity {
+        e if e >= 0.6 => ShowdownClass::Value,
+        e if e >= 0.5 => ShowdownClass::ThinValue,
+        e if e >= 0.2 => ShowdownClass::BluffCatcher,
+        _ => ShowdownClass::Air,
+    };
+
+    Some(ShowdownValue {
+        class,
+        equity,
+        win,
+        tie,
+        lose,
+        combos,
+    })
+}
+
+// How one next card of the runout plays for two ranges: hero's equity on the board it makes and
+// the weight share of each range holding the nuts there.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RunoutRow {
+    pub card: Card,
+    pub bucket: RunoutBucket,
+    pub equity: f64,
+    pub hero_nuts: f64,
+    pub villain_nuts: f64,
+}
+
+// The aggregate runout report of a flop (one row per turn) or a turn (one row per river), rows
+// grouped by bucket like `Runouts`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RunoutReport {
+    board: Board,
+    rows: Vec<RunoutRow>,
+}
+
+impl RunoutReport {
+    // None before the flop, on the river, or if the ranges can't meet on any runout.
+    pub fn new(hero: &Range, villain: &Range, board: Board) -> Option<Self> {
+        let runouts = Runouts::new(board, &[])?;
+        let rows = runouts
+            .iter()
+            .flat_map(|(bucket, cards)| cards.iter().map(move |&card| (bucket, card)))
+            .filter_map(|(bucket, card)| {
+                let next = board.turn(card).or_else(|| board.river(card))?;
+                Some(RunoutRow {
+                    card,
+                    bucket,
+                    equity: range_equity(hero, villain, &next)?,
+                    hero_nuts: nut_share(hero, &next),
+                    villain_nuts: nut_share(villain, &next),
+                })
+            })
+            .collect_vec();
+
+        (!rows.is_empty()).then_some(Self { board, rows })
+    }
+
+    // For a flop, the river report of every turn card.
+    pub fn by_turn(hero: &Range, villain: &Range, flop: Board) -> Option<Vec<Self>> {
+        if !flop.is_flop() {
+            return None;
+        }
+
+        let reports = Runouts::new(flop, &[])?
+            .iter()
+            .flat_map(|(_, cards)| cards.iter())
+            .filter_map(|&card| Self::new(hero, villain, flop.turn(card)?))
+            .collect_vec();
+        (!reports.is_empty()).then_some(reports)
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn rows(&self) -> &[RunoutRow] {
+        &self.rows
+    }
+
+    pub fn row(&self, card: Card) -> Option<&RunoutRow> {
+        self.rows.iter().find(|row| row.card == card)
+    }
+
+    // Mean equity of each non-empty bucket and the bucket's share of the rows, like
+    // `Runouts::summarize`.
+    pub fn buckets(&self) -> Vec<(RunoutBucket, f64, f64)> {
+        let total = self.rows.len() as f64;
+
+        RunoutBucket::ALL
+            .into_iter()
+            .filter_map(|bucket| {
+                let equities = self
+                    .rows
+                    .iter()
+                    .filter(|row| row.bucket == bucket)
+                    .map(|row| row.equity)
+                    .collect_vec();
+                let len = equities.len() as f64;
+                (len > 0.0).then(|| (bucket, equities.iter().sum::<f64>() / len, len / total))
+            })
+            .collect()
+    }
+
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "card,bucket,equity,hero_nuts,villain_nuts")?;
+        for row in &self.rows {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                row.card, row.bucket, row.equity, row.hero_nuts, row.villain_nuts
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+// Weight share of the live combos of `range` holding the nuts on `board`.
+fn nut_share(range: &Range, board: &Board) -> f64 {
+    let nuts = board.find_nuts();
+    let live = range.without(&board.to_vec());
+    let total = live.total_weight();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let nut_weight: f64 = live
+        .iter()
+        .filter(|&(hole, _)| nuts == hole)
+        .map(|(_, weight)| weight)
+        .sum();
+    nut_weight / total
+}
+
+// Every completion of the board, or a random sample of them preflop.
+pub(crate) fn runouts(dead: &[Card], board_cards: &[Card]) -> Vec<Vec<Card>> {
+    let deck = dead
+        .iter()
+        .copied()
+        .collect::<CardSet>()
+        .complement()
+        .to_vec();
+    let missing = 5 - board_cards.len();
+
+    if missing == 5 {
+        let mut rng = rand::rng();
+        (0..PREFLOP_RUNOUTS)
+            .map(|_| deck.choose_multiple(&mut rng, 5).copied().collect())
+            .collect()
+    } else {
+        deck.iter().copied().combinations(missing).collect()
+    }
+}
+
+fn showdown_score(ordering: Ordering) -> f64 {
+    match ordering {
+        Ordering::Greater => 1.0,
+        Ordering::Equal => 0.5,
+        Ordering::Less => 0.0,
+    }
+}
+
+fn runout_equity(hole: Hole, villain: &Range, board: &FullBoard) -> Option<f64> {
+    let hero = board.hand_value(hole);
+    let mut won = 0.0;
+    let mut total = 0.0;
+
+    for (combo, weight) in villain.iter() {
+        if combo.iter().any(|card| board.contains_card(*card)) {
+            continue;
+        }
+
+        won += weight * showdown_score(hero.cmp(&board.hand_value(combo)));
+        total += weight;
+    }
+
+    (total > 0.0).then(|| won / total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flop_runout_report() {
+        let hero: Range = "AA, KK".parse().unwrap();
+        let villain: Range = "AsKs, QQ".parse().unwrap();
+        let board: Board = "Ks7s2d".parse().unwrap();
+        let report = RunoutReport::new(&hero, &villain, board).unwrap();
+        assert_eq!(report.rows().len(), 49);
+
+        // only KhKc makes quads, one of the seven live hero combos
+        let quads = report.row("Kd".parse().unwrap()).unwrap();
+        assert!((quads.hero_nuts - 1.0 / 7.0).abs() < 1e-9);
+        assert_eq!(quads.villain_nuts, 0.0);
+        let flush = report.row("4s".parse().unwrap()).unwrap();
+        assert_eq!(flush.bucket, RunoutBucket::FlushCompleting);
+        assert!(flush.equity < quads.equity);
+
+        let shares: f64 = report.buckets().iter().map(|(_, _, share)| share).sum();
+        assert!((shares - 1.0).abs() < 1e-9);
+
+        let mut csv = vec![];
+        report.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 50);
+        assert!(
+            csv.lines()
+                .any(|line| line.starts_with("Kd,board-pairing,"))
+        );
+
+        let turn = report.row("3h".parse().unwrap()).unwrap();
+        let rivers = RunoutReport::new(&hero, &villain, board.turn(turn.card).unwrap()).unwrap();
+        assert_eq!(rivers.rows().len(), 48);
+        let mean = rivers.rows().iter().map(|row| row.equity).sum::<f64>() / 48.0;
+        assert!((mean - turn.equity).abs() < 0.05); // combo pairs weigh differently by river
+    }
+
+    #[test]
+    fn equity_by_combo() {
+        let aces: Hole = "AsAh".parse().unwrap();
+        let villain: Range = "KK, AKs".parse().unwrap();
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        // three sets of kings and the one suited AK the aces and the board leave
+        let report = equity_vs_range(aces, &villain, &flop).unwrap();
+        assert_eq!(report.combos().len(), 4);
+        let ahead = report.ahead().map(|combo| combo.combo).collect_vec();
+        assert_eq!(ahead, ["AcKc".parse::<Hole>().unwrap()]);
+
+        let mut hero = Range::new();
+        hero.set(aces, 1.0);
+        let total = range_equity(&hero, &villain, &flop).unwrap();
+        assert!((report.total().share() - total).abs() < 1e-9);
+        assert_eq!(equity_vs_range(aces, &"AsAd".parse().unwrap(), &flop), None);
+
+        let kings: Range = "KK".parse().unwrap();
+        let both = range_vs_range(&"AA".parse().unwrap(), &kings, &flop).unwrap();
+        assert!(both.ahead().next().is_none());
+    }
+
+    #[test]
+    fn monte_carlo_equity() {
+        let aces: Hole = "AsAh".parse().unwrap();
+        let kings: Hole = "KsKh".parse().unwrap();
+        let preflop = equity(aces, kings, Board::default(), 5000).unwrap();
+        assert!((preflop.win - 0.82).abs() < 0.03);
+        assert!((preflop.win + preflop.tie + preflop.lose - 1.0).abs() < 1e-9);
+
+        // the aces need one of the last two aces
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        let set = equity(kings, aces, flop, 5000).unwrap();
+        assert!((set.share() - 0.91).abs() < 0.03);
+        assert_eq!(equity(aces, "AsKd".parse().unwrap(), flop, 100), None);
+        assert_eq!(equity(aces, kings, flop, 0), None);
+
+        let exact = equity_exact(kings, aces, flop).unwrap();
+        assert_eq!(equity_exact(kings, aces, flop), Some(exact));
+        assert!((exact.share() - set.share()).abs() < 0.03);
+        // 87 of the 990 runouts bring an ace, 2 of those the last king too
+        assert!((exact.lose - 85.0 / 990.0).abs() < 1e-9);
+        let river = flop.turn("3s".parse().unwrap()).unwrap();
+        let river = river.river("Ac".parse().unwrap()).unwrap();
+        assert_eq!(equity_exact(kings, aces, river).unwrap().lose, 1.0);
+        assert_eq!(equity_exact(kings, aces, Board::default()), None);
+    }
+
+    #[test]
+    fn draw_is_polarized() {
+        let board: Board = "Ks7s2d5c".parse().unwrap();
+        let villain: Range = "KK, 77, AK".parse().unwrap();
+        let draw = equity_distribution("AsQs".parse().unwrap(), &villain, &board, 10).unwrap();
+
+        assert_eq!(draw.total_weight(), 46.0);
+        assert!(draw.percentile(0.5) < 0.2);
+        assert!(draw.percentile(0.9) > 0.9);
+        assert!((draw.cdf(0.5) - 37.0 / 46.0).abs() < 1e-9);
+
+        let weak = EquityDistribution::from_samples(10, [(0.1, 1.0), (0.5, 1.0)]).unwrap();
+        let strong = EquityDistribution::from_samples(10, [(0.3, 1.0), (0.9, 1.0)]).unwrap();
+        assert!(weak < strong);
+        let made = EquityDistribution::from_samples(10, [(0.7, 1.0)]).unwrap();
+        assert_eq!(draw.partial_cmp(&made), None);
+        assert!((weak.emd(&strong).unwrap() - 0.3).abs() < 1e-9);
+
+        let river: Board = "Ks9s5d3c2h".parse().unwrap();
+        let calls: Range = "AK, KQ, K9, QQ, 99, 55".parse().unwrap();
+        let set = classify_showdown("9h9d".parse().unwrap(), &river, &calls).unwrap();
+        let top_pair = classify_showdown("KhQc".parse().unwrap(), &river, &calls).unwrap();
+        let air = classify_showdown("JhTh".parse().unwrap(), &river, &calls).unwrap();
+        assert_eq!(set.class, ShowdownClass::Value);
+        assert_eq!(top_pair.class, ShowdownClass::BluffCatcher);
+        assert_eq!(air.class, ShowdownClass::Air);
+        assert_eq!(air.win, 0.0);
+    }
+}
@@ -0,0 +1,152 @@
+// Memoized hand values and exact equities shared across simulation threads, for solvers and
+// analysis that keep revisiting the same spots. Hand values are keyed by suit-isomorphic index,
+// so spots equal up to relabelling suits share one entry.
+use super::{Equity, equity_exact};
+use crate::gameplay::{Board, HandValue, Hole, indexer::HandIndexer};
+use lru::LruCache;
+use std::{
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    // 0 before the first lookup.
+    pub fn hit_rate(&self) -> f64 {
+        match self.lookups() {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Memo<K: Hash + Eq, V> {
+    entries: Mutex<LruCache<K, V>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq, V: Copy> Memo<K, V> {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    // Computes outside the lock, so a slow miss never holds up the other threads.
+    fn get_or_insert(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.entries.lock().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return *value;
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = compute();
+        self.entries.lock().unwrap().put(key, value);
+        value
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+#[derive(Debug)]
+pub struct StrengthCache {
+    indexer: HandIndexer,
+    values: Memo<u64, HandValue>, // river indices
+    equities: Memo<(Hole, Hole, Board), Option<Equity>>,
+}
+
+impl StrengthCache {
+    // Keeps the `capacity` most recently used entries of each kind.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            indexer: HandIndexer::new(),
+            values: Memo::new(capacity),
+            equities: Memo::new(capacity),
+        }
+    }
+
+    // None unless `board` is a river sharing no card with `hole`.
+    pub fn hand_value(&self, hole: Hole, board: &Board) -> Option<HandValue> {
+        let full = board.as_full_board()?;
+        let index = self.indexer.index(hole, board)?;
+        Some(self.values.get_or_insert(index, || full.hand_value(hole)))
+    }
+
+    // `equity_exact`, remembered.
+    pub fn equity_exact(&self, hole: Hole, villain: Hole, board: Board) -> Option<Equity> {
+        self.equities.get_or_insert((hole, villain, board), || {
+            equity_exact(hole, villain, board)
+        })
+    }
+
+    pub fn value_stats(&self) -> CacheStats {
+        self.values.stats()
+    }
+
+    pub fn equity_stats(&self) -> CacheStats {
+        self.equities.stats()
+    }
+
+    // Entries held of each kind, as (hand values, equities).
+    pub fn entries(&self) -> (usize, usize) {
+        (self.values.len(), self.equities.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parallel::prelude::*;
+
+    #[test]
+    fn isomorphic_spots_hit() {
+        let cache = StrengthCache::new(NonZeroUsize::new(2).unwrap());
+        let board: Board = "Ks7s2d5c9h".parse().unwrap();
+        let hole: Hole = "AsQs".parse().unwrap();
+        let value = cache.hand_value(hole, &board).unwrap();
+        assert_eq!(value, board.as_full_board().unwrap().hand_value(hole));
+
+        // the same spot with spades and hearts swapped
+        let swapped: Board = "Kh7h2d5c9s".parse().unwrap();
+        let hits: Vec<_> = (0..8)
+            .into_par_iter()
+            .map(|_| cache.hand_value("AhQh".parse().unwrap(), &swapped))
+            .collect();
+        assert!(hits.iter().all(|hit| *hit == Some(value)));
+        assert_eq!(cache.value_stats(), CacheStats { hits: 8, misses: 1 });
+        assert_eq!(cache.hand_value(hole, &"Ks7s2d".parse().unwrap()), None);
+
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        let (aces, kings) = ("AsAh".parse().unwrap(), "KsKh".parse().unwrap());
+        let exact = cache.equity_exact(kings, aces, flop);
+        assert_eq!(cache.equity_exact(kings, aces, flop), exact);
+        assert_eq!(cache.equity_stats().hit_rate(), 0.5);
+        assert_eq!(cache.entries(), (1, 1));
+    }
+}
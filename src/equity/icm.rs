@@ -0,0 +1,97 @@
+// Tournament equity by the Malmuth-Harville model: a player finishes first as often as their
+// share of the chips, and each next place goes the same way among those left. Stacks are chips,
+// payouts the prize pool's share by place, first place first.
+
+// Each player's share of the prize pool, in the order of `stacks`. Busted players (0 chips) get
+// nothing. None without chips in play.
+pub fn icm(stacks: &[u32], payouts: &[f64]) -> Option<Vec<f64>> {
+    let total: u64 = stacks.iter().map(|&stack| stack as u64).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut equities = vec![0.0; stacks.len()];
+    let live: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i] > 0).collect();
+    finish(stacks, payouts, &live, 1.0, &mut equities);
+    Some(equities)
+}
+
+fn finish(stacks: &[u32], payouts: &[f64], live: &[usize], chance: f64, equities: &mut [f64]) {
+    let Some((&payout, payouts)) = payouts.split_first() else {
+        return;
+    };
+    let total: f64 = live.iter().map(|&i| stacks[i] as f64).sum();
+
+    for (place, &i) in live.iter().enumerate() {
+        let first = chance * stacks[i] as f64 / total;
+        equities[i] += first * payout;
+
+        let mut rest = live.to_vec();
+        rest.remove(place);
+        if !rest.is_empty() {
+            finish(stacks, payouts, &rest, first, equities);
+        }
+    }
+}
+
+// How much more `hero` loses in prize equity busting to `villain` all in than they win doubling
+// through, 1 in a winner-take-all game or without payouts. None unless both have chips.
+pub fn bubble_factor(stacks: &[u32], payouts: &[f64], hero: usize, villain: usize) -> Option<f64> {
+    let now = icm(stacks, payouts)?;
+    let at_risk = stacks[hero].min(stacks[villain]);
+    if at_risk == 0 || hero == villain {
+        return None;
+    }
+
+    let moved = |winner: usize, loser: usize| {
+        let mut stacks = stacks.to_vec();
+        stacks[winner] += at_risk;
+        stacks[loser] -= at_risk;
+        icm(&stacks, payouts)
+    };
+    let gain = moved(hero, villain)?[hero] - now[hero];
+    let loss = now[hero] - moved(villain, hero)?[hero];
+    if gain <= 0.0 {
+        return Some(1.0);
+    }
+    Some(loss / gain)
+}
+
+// Equity `hero` needs on top of chip-EV's 50% to call an even all in against `villain`.
+pub fn risk_premium(stacks: &[u32], payouts: &[f64], hero: usize, villain: usize) -> Option<f64> {
+    let factor = bubble_factor(stacks, payouts, hero, villain)?;
+    Some(factor / (1.0 + factor) - 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::headsup::GameType;
+
+    #[test]
+    fn three_handed_bubble() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        let payouts = [0.5, 0.3, 0.2];
+        let equities = icm(&[5000, 3000, 2000], &payouts).unwrap();
+        assert!(close(equities.iter().sum(), 1.0));
+        // first half the time, second after either other player wins
+        let second = 0.3 * 5.0 / 7.0 + 0.2 * 5.0 / 8.0;
+        let expected = 0.5 * 0.5 + 0.3 * second + 0.2 * (0.5 - second);
+        assert!(close(equities[0], expected));
+
+        // winner takes all is chip EV
+        let winner_takes_all = GameType::sng(Default::default()).payouts();
+        let heads_up = icm(&[7000, 3000], winner_takes_all).unwrap();
+        assert!(close(heads_up[0], 0.7));
+        assert!(close(
+            bubble_factor(&[7000, 3000], &[1.0], 1, 0).unwrap(),
+            1.0
+        ));
+
+        let factor = bubble_factor(&[5000, 3000, 2000], &[0.65, 0.35], 1, 0).unwrap();
+        assert!(factor > 1.0);
+        assert!(risk_premium(&[5000, 3000, 2000], &[0.65, 0.35], 1, 0).unwrap() > 0.0);
+        assert_eq!(icm(&[0, 0], &payouts), None);
+        assert_eq!(bubble_factor(&[5000, 0, 2000], &payouts, 1, 0), None);
+    }
+}
@@ -0,0 +1,106 @@
+// Call or fold arithmetic for the bot side: which next cards put hero ahead of a range, and what
+// share of the pot a call has to win to pay for itself.
+use super::classify_showdown;
+use crate::{
+    gameplay::{Board, Card, Hole, cardset::CardSet},
+    range::Range,
+};
+
+// Outs are the next cards taking hero from behind the range to ahead of it, equity at least a
+// half on the board they make. A hero already ahead has none to count.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutsReport {
+    pub equity: f64, // now, against the live range
+    pub outs: Vec<Card>,
+    pub unseen: usize, // next cards that can come
+}
+
+impl OutsReport {
+    // The chance the next card is an out.
+    pub fn hit_chance(&self) -> f64 {
+        if self.unseen == 0 {
+            return 0.0;
+        }
+        self.outs.len() as f64 / self.unseen as f64
+    }
+
+    // Whether hitting on the next card pays for calling `to_call` into `pot`, counting `implied`
+    // more chips won after hitting.
+    pub fn is_priced_in(&self, pot: u32, to_call: u32, implied: u32) -> bool {
+        self.hit_chance() >= pot_odds(pot.saturating_add(implied), to_call)
+    }
+}
+
+// None preflop, on the river, if the hole collides with the board or no combo of the range is
+// live.
+pub fn outs(hole: Hole, board: &Board, villain: &Range) -> Option<OutsReport> {
+    if board.is_preflop() || board.is_river() {
+        return None;
+    }
+    let equity = classify_showdown(hole, board, villain)?.equity;
+
+    let cards: Vec<_> = board.runouts(&CardSet::from(hole)).collect();
+    let outs = if equity < 0.5 {
+        cards
+            .iter()
+            .copied()
+            .filter(|&card| {
+                let next = board.turn(card).or_else(|| board.river(card));
+                next.and_then(|next| classify_showdown(hole, &next, villain))
+                    .is_some_and(|showdown| showdown.equity >= 0.5)
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Some(OutsReport {
+        equity,
+        outs,
+        unseen: cards.len(),
+    })
+}
+
+// The share of the final pot a call of `to_call` into `pot`, the bet being called included, has
+// to win to break even. Free calls need nothing.
+pub fn pot_odds(pot: u32, to_call: u32) -> f64 {
+    if to_call == 0 {
+        return 0.0;
+    }
+    to_call as f64 / (pot as f64 + to_call as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_draw_against_an_overpair() {
+        assert_eq!(pot_odds(100, 50), 1.0 / 3.0);
+        assert_eq!(pot_odds(100, 0), 0.0);
+
+        let mut villain = Range::new();
+        villain.set("KcKd".parse().unwrap(), 1.0);
+        let board = "Qh7h2c3s".parse().unwrap();
+        let report = outs("Ah9h".parse().unwrap(), &board, &villain).unwrap();
+
+        // nine hearts and the three aces left
+        assert_eq!(report.outs.len(), 12);
+        assert_eq!(report.unseen, 46);
+        assert!(report.equity < 0.5);
+        assert!(report.is_priced_in(100, 25, 0));
+        assert!(!report.is_priced_in(100, 100, 0));
+        assert!(report.is_priced_in(100, 100, 300));
+
+        let ahead = outs("KcKd".parse().unwrap(), &board, &Range::full()).unwrap();
+        assert!(ahead.outs.is_empty());
+        assert_eq!(
+            outs(
+                "Ah9h".parse().unwrap(),
+                &"Qh7h2c3s4d".parse().unwrap(),
+                &villain
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// Scratch space for bulk simulations. Keep one per thread and reuse it across calls: the deck
+// buffer is filled in place and runouts are dealt into arrays, so the sampling and enumeration
+// loops allocate nothing once the context has warmed up.
+use super::Equity;
+use crate::gameplay::{Board, Card, FullBoard, Hole, cardset::CardSet};
+use itertools::Itertools;
+use rand::prelude::*;
+use std::{array, cmp::Ordering};
+
+#[derive(Debug, Default, Clone)]
+pub struct SimContext {
+    deck: Vec<Card>,
+    board: Vec<Card>,
+}
+
+impl SimContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Like `equity::equity` on the calling thread.
+    pub fn equity(
+        &mut self,
+        hole: Hole,
+        villain: Hole,
+        board: &Board,
+        iterations: usize,
+        rng: &mut impl Rng,
+    ) -> Option<Equity> {
+        self.counts(hole, villain, board, iterations, rng)
+            .map(Equity::from_counts)
+    }
+
+    // Like `equity::equity_exact` on the calling thread.
+    pub fn equity_exact(&mut self, hole: Hole, villain: Hole, board: &Board) -> Option<Equity> {
+        if board.is_preflop() {
+            return None;
+        }
+        let dealt = self.prepare(hole, villain, board)?;
+
+        let mut counts = [0; 3];
+        let deck = &self.deck;
+        let mut count = |runout: &[Card]| {
+            let full = deal(&self.board, runout);
+            counts[showdown(hole, villain, &full)] += 1;
+        };
+        match 5 - dealt {
+            0 => count(&[]),
+            1 => deck.iter().for_each(|&card| count(&[card])),
+            _ => deck
+                .iter()
+                .tuple_combinations()
+                .for_each(|(&first, &second)| count(&[first, second])),
+        }
+        Some(Equity::from_counts(counts))
+    }
+
+    // Wins, ties and losses over `iterations` random runouts. None if any cards collide or there
+    // are no iterations.
+    pub(super) fn counts(
+        &mut self,
+        hole: Hole,
+        villain: Hole,
+        board: &Board,
+        iterations: usize,
+        rng: &mut impl Rng,
+    ) -> Option<[usize; 3]> {
+        if iterations == 0 {
+            return None;
+        }
+        let dealt = self.prepare(hole, villain, board)?;
+        let missing = 5 - dealt;
+
+        let mut counts = [0; 3];
+        for _ in 0..iterations {
+            // partial Fisher-Yates: the first `missing` cards of the deck are the runout
+            for i in 0..missing {
+                let j = rng.random_range(i..self.deck.len());
+                self.deck.swap(i, j);
+            }
+            let full = deal(&self.board, &self.deck[..missing]);
+            counts[showdown(hole, villain, &full)] += 1;
+        }
+        Some(counts)
+    }
+
+    // Fills the buffers for a spot, giving the number of board cards dealt. None on collisions.
+    fn prepare(&mut self, hole: Hole, villain: Hole, board: &Board) -> Option<usize> {
+        let board_cards = board.to_vec();
+        let dead: CardSet = hole
+            .iter()
+            .chain(villain.iter())
+            .chain(&board_cards)
+            .copied()
+            .collect();
+        if dead.len() != board_cards.len() + 4 {
+            return None;
+        }
+
+        self.board.clear();
+        self.board.extend(&board_cards);
+        self.deck.clear();
+        self.deck.extend(dead.complement());
+        Some(board_cards.len())
+    }
+}
+
+// `board` completed with `runout`.
+fn deal(board: &[Card], runout: &[Card]) -> FullBoard {
+    let dealt = board.len();
+    let cards = array::from_fn(|i| {
+        if i < dealt {
+            board[i]
+        } else {
+            runout[i - dealt]
+        }
+    });
+    FullBoard::new(cards).expect("Runout cards should never collide")
+}
+
+// 0 for a win of `hole`, 1 for a tie and 2 for a loss.
+fn showdown(hole: Hole, villain: Hole, board: &FullBoard) -> usize {
+    match board.hand_value(hole).cmp(&board.hand_value(villain)) {
+        Ordering::Greater => 0,
+        Ordering::Equal => 1,
+        Ordering::Less => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::equity_exact;
+
+    #[test]
+    fn reused_context() {
+        let mut sim = SimContext::new();
+        let (aces, kings) = ("AsAh".parse().unwrap(), "KsKh".parse().unwrap());
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        let turn = flop.turn("3s".parse().unwrap()).unwrap();
+
+        for board in [flop, turn] {
+            let exact = sim.equity_exact(kings, aces, &board);
+            assert_eq!(exact, equity_exact(kings, aces, board));
+        }
+        let capacity = sim.deck.capacity();
+
+        let mut rng = rand::rng();
+        let sampled = sim.equity(kings, aces, &flop, 2000, &mut rng).unwrap();
+        assert!((sampled.share() - 0.91).abs() < 0.03);
+        assert_eq!(sim.deck.capacity(), capacity); // the buffer was reused
+        assert_eq!(sim.equity(aces, aces, &flop, 10, &mut rng), None);
+    }
+}
@@ -1,9 +1,15 @@
-use indexmap::IndexSet;
+use crate::{
+    equity::{Equity, equity_exact},
+    parallel::{self, Threads, prelude::*},
+};
+use cardset::CardSet;
+use chips::Chips;
+use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
-use rayon::prelude::*;
+use parse::{BoardParseError, CardParseError, CardsParseError, HoleClassParseError};
 use std::{
-    cmp::Ordering,
-    collections::{BTreeMap, BTreeSet},
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, BTreeSet, HashSet},
     fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     ops::Deref,
@@ -49,24 +55,12 @@ impl Display for Value {
 }
 
 impl FromStr for Value {
-    type Err = ();
+    type Err = CardParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "2" => Ok(Self::Deuce),
-            "3" => Ok(Self::Trey),
-            "4" => Ok(Self::Four),
-            "5" => Ok(Self::Five),
-            "6" => Ok(Self::Six),
-            "7" => Ok(Self::Seven),
-            "8" => Ok(Self::Eight),
-            "9" => Ok(Self::Nine),
-            "T" => Ok(Self::Ten),
-            "J" => Ok(Self::Jack),
-            "Q" => Ok(Self::Queen),
-            "K" => Ok(Self::King),
-            "A" => Ok(Self::Ace),
-            _ => Err(()),
+        match *s.chars().collect::<Vec<_>>() {
+            [c] => Self::from_char(c),
+            ref chars => Err(CardParseError::WrongLength(chars.len())),
         }
     }
 }
@@ -74,6 +68,119 @@ impl FromStr for Value {
 impl Value {
     const ACE_HIGH: u8 = 13;
 
+    fn from_char(c: char) -> Result<Self, CardParseError> {
+        match c {
+            '2' => Ok(Self::Deuce),
+            '3' => Ok(Self::Trey),
+            '4' => Ok(Self::Four),
+            '5' => Ok(Self::Five),
+            '6' => Ok(Self::Six),
+            '7' => Ok(Self::Seven),
+            '8' => Ok(Self::Eight),
+            '9' => Ok(Self::Nine),
+            'T' => Ok(Self::Ten),
+            'J' => Ok(Self::Jack),
+            'Q' => Ok(Self::Queen),
+            'K' => Ok(Self::King),
+            'A' => Ok(Self::Ace),
+            c => Err(CardParseError::InvalidRank(c)),
+        }
+    }
+
+    pub(crate) const ALL: [Self; 13] = [
+        Self::Deuce,
+        Self::Trey,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+        Self::Nine,
+        Self::Ten,
+        Self::Jack,
+        Self::Queen,
+        Self::King,
+        Self::Ace,
+    ];
+
+    // Deuce up to ace.
+    pub fn all() -> impl DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        Self::ALL.into_iter()
+    }
+
+    // 2 for a deuce up to 14 for an ace.
+    pub fn rank(self) -> u8 {
+        self.as_u8() + 2
+    }
+
+    // The word for the value, e.g. "Three" for a trey.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Deuce => "Deuce",
+            Self::Trey => "Three",
+            Self::Four => "Four",
+            Self::Five => "Five",
+            Self::Six => "Six",
+            Self::Seven => "Seven",
+            Self::Eight => "Eight",
+            Self::Nine => "Nine",
+            Self::Ten => "Ten",
+            Self::Jack => "Jack",
+            Self::Queen => "Queen",
+            Self::King => "King",
+            Self::Ace => "Ace",
+        }
+    }
+
+    pub fn plural(self) -> String {
+        match self {
+            Self::Six => "Sixes".to_string(),
+            value => format!("{}s", value.name()),
+        }
+    }
+
+    // Both 1 and 14 are an ace.
+    pub fn from_rank(rank: u8) -> Option<Self> {
+        match rank {
+            1 => Some(Self::Ace),
+            2..=14 => Some(Self::ALL[rank as usize - 2]),
+            _ => None,
+        }
+    }
+
+    // With `wrap`, an ace is followed by a deuce as in a wheel.
+    pub fn next_higher(self, wrap: bool) -> Option<Self> {
+        match self {
+            Self::Ace if wrap => Some(Self::Deuce),
+            _ => Self::ALL.get(self.as_u8() as usize + 1).copied(),
+        }
+    }
+
+    // With `wrap`, a deuce is preceded by an ace as in a wheel.
+    pub fn next_lower(self, wrap: bool) -> Option<Self> {
+        match self {
+            Self::Deuce if wrap => Some(Self::Ace),
+            Self::Deuce => None,
+            _ => Some(Self::ALL[self.as_u8() as usize - 1]),
+        }
+    }
+
+    // Rank steps between two values, e.g. 2 for jack and nine.
+    pub fn distance(self, other: Self) -> u8 {
+        self.as_u8().abs_diff(other.as_u8())
+    }
+
+    // Like `distance` with the ace counting low when that is closer, e.g. 1 for ace and deuce.
+    pub fn distance_wrapping(self, other: Self) -> u8 {
+        let distance = self.distance(other);
+        distance.min(13 - distance)
+    }
+
+    // Ranks missing between two values for them to connect, e.g. 1 for jack and nine.
+    pub fn gap(self, other: Self) -> u8 {
+        self.distance(other).saturating_sub(1)
+    }
+
     fn as_u8(self) -> u8 {
         match self {
             Self::Deuce => 0,
@@ -117,6 +224,7 @@ impl Value {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplayMode {
     Ascii,
     Unicode,
@@ -141,6 +249,23 @@ pub enum Suit {
 use display::*;
 
 impl Suit {
+    pub(crate) const ALL: [Self; 4] = [Self::Spades, Self::Hearts, Self::Diamonds, Self::Clubs];
+
+    // Spades, hearts, diamonds, then clubs.
+    pub fn all() -> impl DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+        Self::ALL.into_iter()
+    }
+
+    fn from_char(c: char) -> Result<Self, CardParseError> {
+        match c {
+            's' => Ok(Self::Spades),
+            'h' => Ok(Self::Hearts),
+            'd' => Ok(Self::Diamonds),
+            'c' => Ok(Self::Clubs),
+            c => Err(CardParseError::InvalidSuit(c)),
+        }
+    }
+
     pub fn display(self, mode: DisplayMode) -> SuitDisplay {
         SuitDisplay { suit: self, mode }
     }
@@ -155,16 +280,75 @@ impl Suit {
     }
 }
 
+// The ASCII letter, for logs and generic code; `display` picks another mode.
+impl Display for Suit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display(DisplayMode::Ascii))
+    }
+}
+
 impl FromStr for Suit {
-    type Err = ();
+    type Err = CardParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "s" => Ok(Self::Spades),
-            "h" => Ok(Self::Hearts),
-            "d" => Ok(Self::Diamonds),
-            "c" => Ok(Self::Clubs),
-            _ => Err(()),
+        match *s.chars().collect::<Vec<_>>() {
+            [c] => Self::from_char(c),
+            ref chars => Err(CardParseError::WrongLength(chars.len())),
+        }
+    }
+}
+
+// The ruleset cards are dealt and hands ranked by. Short Deck (6+) plays without the deuces to
+// fives, where a flush beats a full house and the ace plays low in A-6-7-8-9.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    #[default]
+    Holdem,
+    ShortDeck,
+}
+
+impl Variant {
+    pub fn lowest(self) -> Value {
+        match self {
+            Self::Holdem => Value::Deuce,
+            Self::ShortDeck => Value::Six,
+        }
+    }
+
+    // The ranks in the deck, lowest first.
+    pub fn values(self) -> impl DoubleEndedIterator<Item = Value> {
+        Value::all().filter(move |&value| value >= self.lowest())
+    }
+
+    pub fn deck_size(self) -> usize {
+        self.values().count() * Suit::ALL.len()
+    }
+
+    // Where the ace plays in the lowest straight, just below the lowest rank.
+    fn ace_low(self) -> u8 {
+        self.lowest().as_u8_straight() - 1
+    }
+
+    pub fn compare(self, a: &HandValue, b: &HandValue) -> Ordering {
+        match (self, a.category(), b.category()) {
+            (Self::ShortDeck, HandCategory::Flush, HandCategory::FullHouse) => Ordering::Greater,
+            (Self::ShortDeck, HandCategory::FullHouse, HandCategory::Flush) => Ordering::Less,
+            _ => a.cmp(b),
+        }
+    }
+
+    // `FullBoard::who_wins` with hands ranked by this variant.
+    pub fn who_wins(self, board: &FullBoard, h1: Hole, h2: Hole) -> (HandValue, Option<bool>) {
+        if self == Self::Holdem {
+            return board.who_wins(h1, h2);
+        }
+        let [v1, v2] = [h1, h2].map(|hole| board.hand_value_in(hole, self));
+
+        match self.compare(&v1, &v2) {
+            Ordering::Greater => (v1, Some(true)),
+            Ordering::Less => (v2, Some(false)),
+            Ordering::Equal => (v1, None),
         }
     }
 }
@@ -172,6 +356,13 @@ impl FromStr for Suit {
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Card(Value, Suit);
 
+// Like `Suit`, ASCII such as "Ah".
+impl Display for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display(DisplayMode::Ascii))
+    }
+}
+
 impl Default for Card {
     fn default() -> Self {
         Self(Value::Ace, Suit::Spades)
@@ -195,6 +386,10 @@ impl Card {
         CardDisplay { card: self, mode }
     }
 
+    pub fn big(self) -> BigCardsDisplay {
+        BigCardsDisplay::new([Some(self)])
+    }
+
     fn as_u8(self) -> u8 {
         (self.value().as_u8() << 2) | self.suit().as_u8()
     }
@@ -205,30 +400,30 @@ impl Card {
 }
 
 impl FromStr for Card {
-    type Err = ();
+    type Err = CardParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 2 || !s.is_ascii() {
-            return Err(());
+        match *s.chars().collect::<Vec<_>>() {
+            [value, suit] => Ok(Self(Value::from_char(value)?, Suit::from_char(suit)?)),
+            ref chars => Err(CardParseError::WrongLength(chars.len())),
         }
-        let value = Value::from_str(&s[0..1])?;
-        let suit = Suit::from_str(&s[1..2])?;
-        Ok(Self(value, suit))
     }
 }
 
+// Cards keep the order they were dealt or parsed in, but compare and hash as a set: both go
+// through `canonical`, so "AsKc" and "KcAs" are one key of a `HashSet`.
 #[derive(Debug, Eq, Clone, Copy)]
 pub struct CardsCombined<const N: usize>([Card; N]);
 
 impl<const N: usize> PartialEq for CardsCombined<N> {
     fn eq(&self, other: &Self) -> bool {
-        self.sorted() == other.sorted()
+        self.canonical().0 == other.canonical().0
     }
 }
 
 impl<const N: usize> Hash for CardsCombined<N> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.sorted().hash(state);
+        self.canonical().0.hash(state);
     }
 }
 
@@ -253,10 +448,16 @@ impl<const N: usize> CardsCombined<N> {
         Self(cards)
     }
 
-    fn sorted(&self) -> [Card; N] {
-        let mut sorted = self.0;
-        sorted.sort_by(|a, b| a.as_u8().cmp(&b.as_u8()));
-        sorted
+    // The same cards highest value first, suits of a value in spades, hearts, diamonds, clubs
+    // order. Equal combinations have the same canonical form, card for card.
+    pub fn canonical(&self) -> Self {
+        let mut cards = self.0;
+        cards.sort_by_key(|card| (Reverse(card.value()), card.suit().as_u8()));
+        Self(cards)
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        self.0 == self.canonical().0
     }
 
     pub fn new(cards: [Card; N]) -> Option<Self> {
@@ -283,11 +484,15 @@ impl<const N: usize> CardsCombined<N> {
         CardsDisplay { cards: self, mode }
     }
 
+    pub fn big(self) -> BigCardsDisplay {
+        BigCardsDisplay::new(self.iter().copied().map(Some))
+    }
+
     fn is_flush(&self) -> bool {
         self.0.iter().map(Card::suit).all_equal()
     }
 
-    fn to_sorted_values(&self) -> [Value; N] {
+    fn to_sorted_values(self) -> [Value; N] {
         let mut values = self.0.map(|card| card.value());
         values.sort_unstable_by(|a, b| b.cmp(a));
         values
@@ -303,15 +508,15 @@ impl<const N: usize> CardsCombined<N> {
         }
     }
 
-    fn is_straight(&self) -> Option<Value> {
+    fn is_straight(&self, variant: Variant) -> Option<Value> {
         let mut u8s = self.0.map(|card| card.value().as_u8_straight());
         let check_straight = Self::check_straight(u8s);
 
         if check_straight.is_none() && u8s.contains(&Value::ACE_HIGH) {
-            // Check for wheel (A-2-3-4-5)
+            // Check for wheel (A-2-3-4-5, or A-6-7-8-9 in Short Deck)
             for u in &mut u8s {
                 if *u == Value::ACE_HIGH {
-                    *u = 0;
+                    *u = variant.ace_low();
                     break;
                 }
             }
@@ -325,101 +530,100 @@ impl<const N: usize> CardsCombined<N> {
 
 impl CardsCombined<7> {
     pub fn hand_value(&self) -> HandValue {
-        self.0
-            .into_iter()
-            .array_combinations::<5>()
-            .collect::<Vec<_>>()
-            .par_iter()
-            .map(|cards| *cards)
-            .map(|cards| CardsCombined(cards))
-            .map(From::from)
-            .max()
-            .expect("At least one combination should exist")
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum ParserResult<T> {
-    Err,
-    None,
-    OkSome(T),
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct CardsParser<'a>(&'a str);
-
-impl<'a> CardsParser<'a> {
-    fn card_eaten(&self) -> ParserResult<(Card, Self)> {
-        let s = self.0.trim();
-        if s.is_empty() {
-            return ParserResult::None;
-        }
-        if s.len() == 1 {
-            return ParserResult::Err;
-        }
-        match Card::from_str(&s[0..2]) {
-            Ok(card) => ParserResult::OkSome((card, Self(&s[2..]))),
-            Err(_) => ParserResult::Err,
-        }
-    }
-
-    fn eat_card(&mut self) -> ParserResult<Card> {
-        match self.card_eaten() {
-            ParserResult::OkSome((card, next)) => {
-                self.0 = next.0;
-                ParserResult::OkSome(card)
-            }
-            ParserResult::None => ParserResult::None,
-            ParserResult::Err => ParserResult::Err,
+        evaluator::evaluate(self)
+    }
+
+    // The five cards that make the hand, for highlighting the winning cards. When several fives
+    // make the same hand, the first is taken, keeping the cards in their order among the seven.
+    pub fn best_five(&self) -> (HandValue, CardsCombined<5>) {
+        let value = self.hand_value();
+        let five = self
+            .fives()
+            .find(|&five| HandValue::from(five) == value)
+            .expect("The value should be made by some five cards");
+        (value, five)
+    }
+
+    // Every five of the seven, leaving out each of the 21 pairs in turn.
+    fn fives(&self) -> impl Iterator<Item = CardsCombined<5>> + '_ {
+        (0..7)
+            .tuple_combinations()
+            .map(|(left_out, also_left_out)| {
+                let mut five = [self.0[0]; 5];
+                let kept = (0..7).filter(|&i| i != left_out && i != also_left_out);
+                for (slot, i) in five.iter_mut().zip(kept) {
+                    *slot = self.0[i];
+                }
+                CardsCombined(five)
+            })
+    }
+
+    // The table evaluator ranks Hold'em hands only, other variants take the best of the fives.
+    pub fn hand_value_in(&self, variant: Variant) -> HandValue {
+        match variant {
+            Variant::Holdem => self.hand_value(),
+            Variant::ShortDeck => self
+                .fives()
+                .map(|five| HandValue::new(five, variant))
+                .max_by(|a, b| variant.compare(a, b))
+                .expect("At least one combination should exist"),
         }
     }
 
-    fn eat_cards<const N: usize>(&mut self) -> Option<CardsCombined<N>> {
-        let mut cards = [Card::default(); _];
-        let mut parser = *self;
-        for i in 0..N {
-            match parser.card_eaten() {
-                ParserResult::OkSome((card, next)) => {
-                    cards[i] = card;
-                    parser = next;
-                }
-                _ => return None,
-            }
-        }
-        let cards = CardsCombined::new(cards);
-        if cards.is_some() {
-            self.0 = parser.0;
-        }
-        cards
+    // The best of the fives, the reference the table evaluator is checked against.
+    #[cfg(test)]
+    fn hand_value_by_combos(&self) -> HandValue {
+        self.fives()
+            .map(HandValue::from)
+            .max()
+            .expect("At least one combination should exist")
     }
 }
 
+// Cards back to back, with or without whitespace between them.
 impl<const N: usize> FromStr for CardsCombined<N> {
-    type Err = ();
+    type Err = CardsParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if !s.is_ascii() {
-            return Err(());
+            return Err(CardsParseError::NotAscii);
         }
-        let mut parser = CardsParser(s);
-        match parser.eat_cards::<N>() {
-            Some(cards) => {
-                if parser.0.is_empty() {
-                    Ok(cards)
-                } else {
-                    Err(())
-                }
-            }
-            None => Err(()),
+        let cards = parse::parse_cards(s)?;
+        if let Some(card) = parse::duplicate(&cards) {
+            return Err(CardsParseError::DuplicateCard(card));
         }
+        let found = cards.len();
+        cards
+            .try_into()
+            .map(Self)
+            .map_err(|_| CardsParseError::WrongCount { expected: N, found })
     }
 }
 
 pub type Hole = CardsCombined<2>;
 pub type Flop = CardsCombined<3>;
 pub type FullBoard = CardsCombined<5>;
+pub type OmahaHole = CardsCombined<4>;
+
+impl OmahaHole {
+    // Omaha plays exactly two cards from the hole with three from the board: the best of the 60
+    // such fives.
+    pub fn hand_value_omaha(&self, board: &FullBoard) -> HandValue {
+        let holes = self.0.iter().tuple_combinations();
+        holes
+            .cartesian_product(board.0.iter().tuple_combinations().collect_vec())
+            .map(|((&a, &b), (&c, &d, &e))| HandValue::from(CardsCombined([a, b, c, d, e])))
+            .max()
+            .expect("At least one combination should exist")
+    }
+}
 
 impl Hole {
+    // The flush and straight draws of the hole on a flop or turn.
+    pub fn draws(&self, board: &Board) -> draws::Draws {
+        draws::Draws::new(*self, board)
+    }
+
     pub fn is_pocket_pair(&self) -> bool {
         self.0.iter().map(Card::value).all_equal()
     }
@@ -436,11 +640,201 @@ impl Hole {
         self.contains_value(values[0]) && self.contains_value(values[1])
     }
 
+    pub fn is_seven_deuce(&self) -> bool {
+        self.contains_value(Value::Seven) && self.contains_value(Value::Deuce)
+    }
+
+    // Lenient parsing for user input: concrete cards ("AsKd") or rank-first shorthand ("AKs",
+    // "QJo", "77", and "AK" for both suited and offsuit), giving every matching combo.
+    pub fn parse_combos(s: &str) -> Option<Vec<Self>> {
+        let s = s.trim();
+        if let Ok(hole) = Self::from_str(s) {
+            return Some(vec![hole]);
+        }
+
+        match HoleClass::from_str(s) {
+            Ok(class) => Some(class.combos()),
+            Err(_) if s.len() == 2 => {
+                let suited = HoleClass::from_str(&format!("{s}s")).ok()?;
+                let offsuit = HoleClass::from_str(&format!("{s}o")).ok()?;
+                Some([suited.combos(), offsuit.combos()].concat())
+            }
+            Err(_) => None,
+        }
+    }
+
+    // The first combo `parse_combos` gives, for tools that need one concrete hole.
+    pub fn parse_lenient(s: &str) -> Option<Self> {
+        Self::parse_combos(s).map(|combos| combos[0])
+    }
+
+    // Which of the 169 starting hand classes this is, e.g. `AKs`, `T9o` or `55`.
+    pub fn class(&self) -> HoleClass {
+        HoleClass::from(*self)
+    }
+
     fn from_values_suited(values: [Value; 2], suit: Suit) -> Self {
         Self([Card(values[0], suit), Card(values[1], suit)])
     }
 }
 
+// One of the 169 strategically distinct starting hands, higher value first.
+// Ordered pairs, then suited, then offsuit hands, each from the lowest values up.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum HoleClass {
+    Pair(Value),
+    Suited(Value, Value),
+    Offsuit(Value, Value),
+}
+
+impl From<Hole> for HoleClass {
+    fn from(hole: Hole) -> Self {
+        let (a, b) = (hole[0].value(), hole[1].value());
+        let (high, low) = if a >= b { (a, b) } else { (b, a) };
+
+        if high == low {
+            Self::Pair(high)
+        } else if hole.is_suited() {
+            Self::Suited(high, low)
+        } else {
+            Self::Offsuit(high, low)
+        }
+    }
+}
+
+impl HoleClass {
+    // All 169 classes, pairs first then suited and offsuit from the top.
+    pub fn all() -> impl Iterator<Item = Self> {
+        let pairs = Value::ALL.into_iter().rev().map(Self::Pair);
+        let unpaired = || Value::ALL.into_iter().rev().tuple_combinations::<(_, _)>();
+        let suited = unpaired().map(|(high, low)| Self::Suited(high, low));
+        let offsuit = unpaired().map(|(high, low)| Self::Offsuit(high, low));
+
+        pairs.chain(suited).chain(offsuit)
+    }
+
+    pub fn suited(a: Value, b: Value) -> Option<Self> {
+        match a.cmp(&b) {
+            Ordering::Greater => Some(Self::Suited(a, b)),
+            Ordering::Less => Some(Self::Suited(b, a)),
+            Ordering::Equal => None,
+        }
+    }
+
+    pub fn offsuit(a: Value, b: Value) -> Option<Self> {
+        match a.cmp(&b) {
+            Ordering::Greater => Some(Self::Offsuit(a, b)),
+            Ordering::Less => Some(Self::Offsuit(b, a)),
+            Ordering::Equal => None,
+        }
+    }
+
+    pub fn values(self) -> [Value; 2] {
+        match self {
+            Self::Pair(value) => [value, value],
+            Self::Suited(high, low) | Self::Offsuit(high, low) => [high, low],
+        }
+    }
+
+    pub fn contains(self, hole: Hole) -> bool {
+        Self::from(hole) == self
+    }
+
+    // Row and column in the 13 x 13 chart of classes, aces first: pairs on the diagonal, suited
+    // hands above it and offsuit ones below.
+    pub fn grid_cell(self) -> (usize, usize) {
+        let [high, low] = self
+            .values()
+            .map(|value| (Value::Ace.rank() - value.rank()) as usize);
+        match self {
+            Self::Pair(_) | Self::Suited(..) => (high, low),
+            Self::Offsuit(..) => (low, high),
+        }
+    }
+
+    // 6 for pairs, 4 for suited and 12 for offsuit hands.
+    pub fn combos(self) -> Vec<Hole> {
+        let [high, low] = self.values();
+        let mut combos = Vec::with_capacity(12);
+
+        for (i, &s1) in Suit::ALL.iter().enumerate() {
+            for (j, &s2) in Suit::ALL.iter().enumerate() {
+                let keep = match self {
+                    Self::Pair(_) => i < j,
+                    Self::Suited(..) => i == j,
+                    Self::Offsuit(..) => i != j,
+                };
+                if keep {
+                    combos.push(Hole::unchecked([Card(high, s1), Card(low, s2)]));
+                }
+            }
+        }
+
+        combos
+    }
+}
+
+impl Display for HoleClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pair(value) => write!(f, "{}{}", value, value),
+            Self::Suited(high, low) => write!(f, "{}{}s", high, low),
+            Self::Offsuit(high, low) => write!(f, "{}{}o", high, low),
+        }
+    }
+}
+
+impl FromStr for HoleClass {
+    type Err = HoleClassParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.trim().chars().collect::<Vec<_>>();
+        if !(2..=3).contains(&chars.len()) {
+            return Err(HoleClassParseError::WrongLength(chars.len()));
+        }
+
+        let a = Value::from_char(chars[0])?;
+        let b = Value::from_char(chars[1])?;
+        let suffix = chars[2..].iter().collect::<String>();
+        let class = match (suffix.as_str(), a == b) {
+            ("", true) => Some(Self::Pair(a)),
+            ("s", false) => Self::suited(a, b),
+            ("o", false) => Self::offsuit(a, b),
+            _ => None,
+        };
+        class.ok_or(HoleClassParseError::Suffix(suffix))
+    }
+}
+
+// Both hands of a showdown, or of the cards out so far, with `winner` None for a tie.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ShowdownResult {
+    pub winner: Option<bool>,
+    pub hands: [HandValue; 2],
+    pub best_five: [CardsCombined<5>; 2],
+    pub equity: Option<Equity>, // of the first hole, before the river only
+}
+
+impl ShowdownResult {
+    fn new(
+        hands: [HandValue; 2],
+        best_five: [CardsCombined<5>; 2],
+        equity: Option<Equity>,
+    ) -> Self {
+        let winner = match hands[0].cmp(&hands[1]) {
+            Ordering::Greater => Some(true),
+            Ordering::Less => Some(false),
+            Ordering::Equal => None,
+        };
+        Self {
+            winner,
+            hands,
+            best_five,
+            equity,
+        }
+    }
+}
+
 impl FullBoard {
     pub fn to_seven(&self, hole: Hole) -> CardsCombined<7> {
         let hole = hole.0;
@@ -455,8 +849,12 @@ impl FullBoard {
         self.to_seven(hole).hand_value()
     }
 
+    pub fn hand_value_in(&self, hole: Hole, variant: Variant) -> HandValue {
+        self.to_seven(hole).hand_value_in(variant)
+    }
+
     pub fn who_wins(&self, h1: Hole, h2: Hole) -> (HandValue, Option<bool>) {
-        let (v1, v2) = rayon::join(|| self.hand_value(h1), || self.hand_value(h2));
+        let (v1, v2) = parallel::join(|| self.hand_value(h1), || self.hand_value(h2));
 
         match v1.cmp(&v2) {
             Ordering::Greater => (v1, Some(true)),
@@ -465,6 +863,27 @@ impl FullBoard {
         }
     }
 
+    // `who_wins` with both hands and the five cards making each, ties included.
+    pub fn showdown(&self, h1: Hole, h2: Hole) -> ShowdownResult {
+        let [(v1, five1), (v2, five2)] = [h1, h2].map(|hole| self.to_seven(hole).best_five());
+        ShowdownResult::new([v1, v2], [five1, five2], None)
+    }
+
+    // Like `who_wins`, on `threads`.
+    pub fn who_wins_in(&self, h1: Hole, h2: Hole, threads: &Threads) -> (HandValue, Option<bool>) {
+        threads.install(|| self.who_wins(h1, h2))
+    }
+
+    // Hand values of many holes on this board at once, in order.
+    pub fn hand_values(&self, holes: &[Hole], threads: &Threads) -> Vec<HandValue> {
+        threads.install(|| {
+            holes
+                .par_iter()
+                .map(|&hole| self.hand_value(hole))
+                .collect()
+        })
+    }
+
     pub fn is_nuts(&self) -> bool {
         match HandValue::from(*self).0 {
             SortedHandValue::RoyalFlush => true,
@@ -482,6 +901,27 @@ impl FullBoard {
     }
 }
 
+// Hand values of one hole on many boards, in order. The hole's ranks and suits are tallied once,
+// so each board costs only its own five cards.
+pub fn evaluate_many(hole: Hole, boards: &[FullBoard]) -> Vec<HandValue> {
+    let masks = evaluator::Masks::default().with(&hole.0);
+    boards
+        .iter()
+        .map(|board| masks.with(&board.0).value())
+        .collect()
+}
+
+// Like `evaluate_many`, with the boards split across `threads`.
+pub fn evaluate_many_in(hole: Hole, boards: &[FullBoard], threads: &Threads) -> Vec<HandValue> {
+    let masks = evaluator::Masks::default().with(&hole.0);
+    threads.install(|| {
+        boards
+            .par_iter()
+            .map(|board| masks.with(&board.0).value())
+            .collect()
+    })
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Board(BoardCards);
 
@@ -569,6 +1009,32 @@ impl Board {
         }
     }
 
+    // Who is ahead with the cards out so far, the best five of each hole and the board, and hero's
+    // exact equity while cards are to come. None preflop or if any cards collide.
+    pub fn who_wins_now(&self, h1: Hole, h2: Hole) -> Option<ShowdownResult> {
+        if let Some(board) = self.as_full_board() {
+            let mut cards = board.iter().chain(h1.iter()).chain(h2.iter());
+            return cards.all_unique().then(|| board.showdown(h1, h2));
+        }
+
+        let equity = equity_exact(h1, h2, *self)?;
+        let board = self.to_vec();
+        let [(v1, five1), (v2, five2)] = [h1, h2].map(|hole| {
+            let cards = hole.iter().chain(&board).copied();
+            cards
+                .combinations(5)
+                .map(|five| {
+                    let five = CardsCombined::from_slice(&five);
+                    (HandValue::from(five), five)
+                })
+                .fold(None, |best: Option<(HandValue, CardsCombined<5>)>, next| {
+                    Some(best.filter(|best| best.0 >= next.0).unwrap_or(next))
+                })
+                .expect("A flop and a hole make five cards")
+        });
+        Some(ShowdownResult::new([v1, v2], [five1, five2], Some(equity)))
+    }
+
     pub fn as_full_board(&self) -> Option<FullBoard> {
         if let BoardCards::River { flop, turn, river } = self.0 {
             Some(FullBoard::unchecked([
@@ -579,6 +1045,30 @@ impl Board {
         }
     }
 
+    // Every card that can come next on a flop or turn, leaving out the board and `dead`. Nothing
+    // preflop, where a whole flop comes next, or on the river.
+    pub fn runouts(&self, dead: &CardSet) -> impl Iterator<Item = Card> + use<> {
+        let live = if self.is_flop() || self.is_turn() {
+            CardSet::from(*self).union(*dead).complement()
+        } else {
+            CardSet::EMPTY
+        };
+        live.into_iter()
+    }
+
+    // Every full board this one can end up as, each once, leaving out `dead`. A river board is
+    // its only runout; preflop yields all 1.7 million boards.
+    pub fn full_runouts(&self, dead: &CardSet) -> impl Iterator<Item = FullBoard> + use<> {
+        let cards = self.to_vec();
+        let live = CardSet::from(*self).union(*dead).complement();
+        live.into_iter()
+            .combinations(5 - cards.len())
+            .map(move |rest| {
+                let full = cards.iter().chain(&rest).copied().collect_vec();
+                FullBoard::unchecked(full.try_into().expect("A runout should make five cards"))
+            })
+    }
+
     pub fn is_preflop(&self) -> bool {
         matches!(self.0, BoardCards::Preflop)
     }
@@ -595,21 +1085,243 @@ impl Board {
         matches!(self.0, BoardCards::River { .. })
     }
 
+    // Lenient parsing for user input: cards with or without any of " ,|-/[]" around them, "10"
+    // for a ten and ranks or suits in either case, e.g. "as kd 7c | 2h"; "x" or "X" for no board.
+    pub fn parse_lenient(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("x") {
+            return Some(Self::default());
+        }
+        Self::from_str(&strict_cards(s)?).ok()
+    }
+
+    // Parses like `parse_lenient`, also telling which street the board is of.
+    pub fn parse_street(s: &str) -> Option<(Self, Street)> {
+        let board = Self::parse_lenient(s)?;
+        Some((board, board.street()))
+    }
+
+    pub fn street(&self) -> Street {
+        match self.0 {
+            BoardCards::Preflop => Street::Preflop,
+            BoardCards::Flop(_) => Street::Flop,
+            BoardCards::Turn { .. } => Street::Turn,
+            BoardCards::River { .. } => Street::River,
+        }
+    }
+
     pub fn display(self, mode: DisplayMode) -> BoardDisplay {
         BoardDisplay { board: self, mode }
     }
 
+    // The flop, turn and river set apart like `display` does; nothing preflop.
+    pub fn big(self) -> BigCardsDisplay {
+        let mut big = BigCardsDisplay::new(self.to_vec().into_iter().map(Some));
+        big.breaks = vec![3, 4];
+        big
+    }
+
     pub fn is_nuts(&self, hole: Hole) -> bool {
         self.find_nuts() == hole
     }
 
+    // Opponent hole classes with their live combo counts that beat `hole` on the river, strongest
+    // first. Empty before the river or if `hole` collides with the board.
+    pub fn hands_beating(&self, hole: Hole) -> Vec<(HoleClass, usize)> {
+        self.hands_against(hole, Ordering::Less)
+    }
+
+    pub fn hands_tying(&self, hole: Hole) -> Vec<(HoleClass, usize)> {
+        self.hands_against(hole, Ordering::Equal)
+    }
+
+    pub fn hands_losing(&self, hole: Hole) -> Vec<(HoleClass, usize)> {
+        self.hands_against(hole, Ordering::Greater)
+    }
+
+    // Classes whose combos compare to `hole` as `ordering` from hero's side.
+    fn hands_against(&self, hole: Hole, ordering: Ordering) -> Vec<(HoleClass, usize)> {
+        let Some(board) = self.as_full_board() else {
+            return vec![];
+        };
+        if hole.iter().any(|&card| board.contains_card(card)) {
+            return vec![];
+        }
+
+        let hero = board.hand_value(hole);
+        let mut classes: Vec<(HoleClass, usize, HandValue)> = vec![];
+        let live = Value::ALL
+            .into_iter()
+            .cartesian_product(Suit::ALL)
+            .map(|(value, suit)| Card(value, suit))
+            .filter(|&card| !hole.contains_card(card) && !board.contains_card(card));
+
+        for cards in live.combinations(2) {
+            let combo = Hole::unchecked([cards[0], cards[1]]);
+            let value = board.hand_value(combo);
+            if hero.cmp(&value) != ordering {
+                continue;
+            }
+
+            let class = HoleClass::from(combo);
+            match classes.iter_mut().find(|(c, ..)| *c == class) {
+                Some((_, count, best)) => {
+                    *count += 1;
+                    *best = (*best).max(value);
+                }
+                None => classes.push((class, 1, value)),
+            }
+        }
+
+        classes.sort_by_key(|&(_, _, best)| std::cmp::Reverse(best));
+        classes
+            .into_iter()
+            .map(|(class, count, _)| (class, count))
+            .collect()
+    }
+
+    // The holdings of the `n` strongest distinct hands on this board, strongest first. A hole
+    // ranks with the first entry it matches, so an entry may also match holes of stronger ones.
+    // Fewer when the board makes fewer hands, or when one can't be told apart by any
+    // `FindNuts`, which ends the list there. Empty preflop.
+    pub fn find_nuts_n(&self, n: usize) -> Vec<FindNuts> {
+        let groups = self.strength_groups();
+        let mut weaker: Vec<Hole> = groups.iter().flatten().copied().collect();
+        let mut found = vec![];
+        for group in groups.iter().take(n) {
+            let Some(nuts) = Self::describe_holes(group, &weaker) else {
+                break;
+            };
+            found.push(nuts);
+            weaker.drain(..group.len());
+        }
+        found
+    }
+
+    // How many distinct hands on this board beat `hole`, 0 for the nuts. None preflop or if the
+    // hole collides with the board.
+    pub fn relative_strength(&self, hole: Hole) -> Option<usize> {
+        if hole.iter().any(|card| self.to_vec().contains(card)) {
+            return None;
+        }
+        let value = self.hole_value(hole)?;
+        let groups = self.strength_groups();
+        Some(
+            groups
+                .iter()
+                .take_while(|group| self.hole_value(group[0]) > Some(value))
+                .count(),
+        )
+    }
+
+    // Whether the card(s) dealt since `prev`, an earlier street of this board, changed which
+    // holdings make the nuts. Unchanged if `prev` is preflop or not an earlier street.
+    pub fn nut_changes(&self, prev: &Board) -> NutChange {
+        let cards = self.to_vec();
+        let earlier = prev.to_vec();
+        if earlier.len() >= cards.len() || !cards.starts_with(&earlier) {
+            return NutChange::Unchanged;
+        }
+        let (Some(before), Some(after)) = (
+            prev.strength_groups().into_iter().next(),
+            self.strength_groups().into_iter().next(),
+        ) else {
+            return NutChange::Unchanged;
+        };
+
+        let live: HashSet<Hole> = before
+            .iter()
+            .filter(|hole| !hole.iter().any(|card| cards.contains(card)))
+            .copied()
+            .collect();
+        if live == after.iter().copied().collect() {
+            return NutChange::Unchanged;
+        }
+
+        let kind = |board: &Board, group: &[Hole]| board.hole_value(group[0]).map(|v| v.category());
+        NutChange::Changed {
+            before: prev.find_nuts(),
+            after: self.find_nuts(),
+            upgraded: kind(prev, &before) != kind(self, &after),
+        }
+    }
+
+    // Every hole that fits the board, grouped by the hand it makes, strongest first.
+    fn strength_groups(&self) -> Vec<Vec<Hole>> {
+        let live = CardSet::from(*self).complement();
+        let mut holes: Vec<_> = live
+            .iter()
+            .tuple_combinations()
+            .filter_map(|(a, b)| {
+                let hole = Hole::unchecked([a, b]);
+                Some((self.hole_value(hole)?, hole))
+            })
+            .collect();
+        holes.sort_by_key(|&(value, _)| Reverse(value));
+        holes
+            .into_iter()
+            .chunk_by(|&(value, _)| value)
+            .into_iter()
+            .map(|(_, group)| group.map(|(_, hole)| hole).collect())
+            .collect()
+    }
+
+    // The best hand of `hole` with the cards out so far, None preflop.
+    fn hole_value(&self, hole: Hole) -> Option<HandValue> {
+        let mut cards = self.to_vec();
+        cards.extend(hole.0);
+        match cards.len() {
+            5 => Some(HandValue::from(CardsCombined::<5>::from_slice(&cards))),
+            6 => (0..6)
+                .map(|left_out| {
+                    let five = cards.iter().enumerate().filter(|&(i, _)| i != left_out);
+                    let five: Vec<_> = five.map(|(_, &card)| card).collect();
+                    HandValue::from(CardsCombined::<5>::from_slice(&five))
+                })
+                .max(),
+            7 => Some(CardsCombined::<7>::from_slice(&cards).hand_value()),
+            _ => None,
+        }
+    }
+
+    // A `FindNuts` matching exactly the holes of `group` among `holes`, which holds the group.
+    fn describe_holes(group: &[Hole], holes: &[Hole]) -> Option<FindNuts> {
+        let [a, b] = group[0].0;
+        let unpaired = |x: Value, y: Value| UnpairedValues::new([x.max(y), x.min(y)]);
+        let mut candidates = vec![FindNuts::AnyTwo];
+        if a.value() == b.value() {
+            candidates.push(FindNuts::PocketPair(a.value()));
+            if let Some(other) = group.iter().find(|hole| !hole.is_pocket_pair()) {
+                let values = unpaired(other[0].value(), other[1].value());
+                candidates.extend(values.map(|values| FindNuts::PocketOrTwo(a.value(), values)));
+            }
+        }
+        candidates.extend([a, b].map(|card| FindNuts::OneValue(card.value())));
+        candidates.extend(unpaired(a.value(), b.value()).map(FindNuts::TwoValues));
+        candidates.extend([a, b].map(FindNuts::CardPlusAny));
+        candidates.extend([a, b].map(FindNuts::CardPlusAnySuited));
+        candidates.push(match *group {
+            [hole] => FindNuts::OneHole(hole),
+            [first, second] => FindNuts::TwoHoles([first, second]),
+            [first, second, third] => FindNuts::ThreeHoles([first, second, third]),
+            _ => FindNuts::OneHole(group[0]),
+        });
+
+        let in_group: HashSet<Hole> = group.iter().copied().collect();
+        candidates.into_iter().find(|nuts| {
+            holes
+                .iter()
+                .all(|hole| (nuts == hole) == in_group.contains(hole))
+        })
+    }
+
     pub fn find_nuts(&self) -> FindNuts {
         let cards = self.to_vec();
         let board_paired = Self::paired(&cards);
 
-        if let Some((suit, cards)) = Self::flush_cards(&cards) {
-            let cards_len = cards.len();
-            let (nuts_high_value, sf_solves) = Self::straight_scan(&cards, false);
+        if let Some((suit, flush)) = Self::flush_cards(&cards) {
+            let cards_len = flush.len();
+            let (nuts_high_value, sf_solves) = Self::straight_scan(&flush, false, Variant::Holdem);
             let nuts_high_card = Card(nuts_high_value, suit);
             let mut sf_solves = sf_solves.into_iter();
 
@@ -702,7 +1414,7 @@ impl Board {
         } else if board_paired {
             Self::quads_full_house(&cards)
         } else {
-            let (_, straight) = Self::straight_scan(&cards, true);
+            let (_, straight) = Self::straight_scan(&cards, true, Variant::Holdem);
 
             match straight.first() {
                 Some(StraightSolve::None) => FindNuts::AnyTwo,
@@ -716,15 +1428,27 @@ impl Board {
     }
 
     fn flush_cards(cards: &[Card]) -> Option<(Suit, Vec<Card>)> {
-        cards
-            .iter()
-            .copied()
-            .into_group_map_by(Card::suit)
+        let set: CardSet = cards.iter().copied().collect();
+        let suit = Suit::ALL
             .into_iter()
-            .find(|(_, cards)| cards.len() >= 3)
-    }
-
-    fn straight_scan(cards: &[Card], only_first: bool) -> (Value, IndexSet<StraightSolve>) {
+            .find(|&suit| set.suit_count(suit) >= 3)?;
+        let flush = cards.iter().copied().filter(|card| card.suit() == suit);
+        Some((suit, flush.collect()))
+    }
+
+    fn straight_scan(
+        cards: &[Card],
+        only_first: bool,
+        variant: Variant,
+    ) -> (Value, IndexSet<StraightSolve>) {
+        let ace_low = variant.ace_low();
+        let value = |u: u8| {
+            if u == ace_low {
+                Value::Ace
+            } else {
+                Value::from_u8_straight(u)
+            }
+        };
         let mut values: BTreeSet<u8> = cards
             .iter()
             .map(Card::value)
@@ -732,17 +1456,17 @@ impl Board {
             .collect();
 
         if values.contains(&Value::ACE_HIGH) {
-            values.insert(0); // For wheel (A-2-3-4-5)
+            values.insert(ace_low); // For wheel (A-2-3-4-5, or A-6-7-8-9 in Short Deck)
         }
 
         let range_start = *values
             .first()
             .expect("Input cards should not be empty")
-            .max(&2)
+            .max(&(ace_low + 2))
             - 2;
         let range_end =
             Value::ACE_HIGH.min(values.last().expect("Input cards should not be empty") + 2) - 4;
-        let remain_set: BTreeSet<u8> = (0..=13)
+        let remain_set: BTreeSet<u8> = (ace_low..=Value::ACE_HIGH)
             .collect::<BTreeSet<u8>>()
             .difference(&values)
             .copied()
@@ -750,7 +1474,7 @@ impl Board {
         let remain_high = remain_set
             .last()
             .copied()
-            .map(Value::from_u8_straight)
+            .map(value)
             .expect("Input cards should at most contain 12 cards");
         let mut solves = IndexSet::new();
 
@@ -765,11 +1489,11 @@ impl Board {
                         return (remain_high, solves);
                     }
                     1 => {
-                        solves.insert(StraightSolve::One(Value::from_u8_straight(solve[0])));
+                        solves.insert(StraightSolve::One(value(solve[0])));
                     }
                     2 => {
-                        let low = Value::from_u8_straight(solve[0]);
-                        let high = Value::from_u8_straight(solve[1]);
+                        let low = value(solve[0]);
+                        let high = value(solve[1]);
 
                         solves.insert(StraightSolve::Two([high, low]));
                     }
@@ -862,12 +1586,14 @@ impl Board {
     }
 }
 
+// Three to five cards as `Card` parses them, spaces between them allowed, e.g. "Ks9s5d3c2h";
+// "x" for no board. User input goes through `Board::parse_lenient` instead.
 impl FromStr for Board {
-    type Err = ();
+    type Err = BoardParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if !s.is_ascii() {
-            return Err(());
+            return Err(BoardParseError::NotAscii);
         }
 
         let s = s.trim();
@@ -875,37 +1601,39 @@ impl FromStr for Board {
             return Ok(Self::default());
         }
 
-        let mut parser = CardsParser(s);
-        match parser.eat_cards::<3>() {
-            Some(flop) => {
-                let board = Self::flop(flop);
-                match parser.eat_card() {
-                    ParserResult::OkSome(turn) => match board.turn(turn) {
-                        Some(board) => match parser.eat_card() {
-                            ParserResult::OkSome(river) => match board.river(river) {
-                                Some(board) => {
-                                    if parser.0.is_empty() {
-                                        Ok(board) // River board
-                                    } else {
-                                        Err(())
-                                    }
-                                }
-                                None => Err(()),
-                            },
-                            ParserResult::None => Ok(board), // Turn board
-                            ParserResult::Err => Err(()),
-                        },
-                        None => Err(()),
-                    },
-                    ParserResult::None => Ok(board), // Flop board
-                    ParserResult::Err => Err(()),
-                }
-            }
-            None => Err(()),
+        let cards = parse::parse_cards(s)?;
+        if let Some(card) = parse::duplicate(&cards) {
+            return Err(BoardParseError::DuplicateCard(card));
+        }
+        if !(3..=5).contains(&cards.len()) {
+            return Err(BoardParseError::WrongCount(cards.len()));
         }
+        Ok(Self::from_slice(&cards).expect("Checked the count and duplicates above"))
     }
 }
 
+// Rewrites loosely written cards in the strict grammar: separators dropped, "10" as "T", ranks
+// upper and suits lower case.
+fn strict_cards(s: &str) -> Option<String> {
+    let chars = s
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !",|-/[]".contains(*c))
+        .collect::<String>()
+        .replace("10", "T")
+        .chars()
+        .collect::<Vec<_>>();
+    if chars.len() % 2 != 0 {
+        return None;
+    }
+
+    Some(
+        chars
+            .chunks(2)
+            .flat_map(|card| [card[0].to_ascii_uppercase(), card[1].to_ascii_lowercase()])
+            .collect(),
+    )
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum BoardCards {
     #[default]
@@ -922,6 +1650,36 @@ pub enum BoardCards {
     },
 }
 
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Street {
+    #[default]
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    pub fn next(self) -> Option<Self> {
+        match self {
+            Self::Preflop => Some(Self::Flop),
+            Self::Flop => Some(Self::Turn),
+            Self::Turn => Some(Self::River),
+            Self::River => None,
+        }
+    }
+
+    pub fn board_len(self) -> usize {
+        match self {
+            Self::Preflop => 0,
+            Self::Flop => 3,
+            Self::Turn => 4,
+            Self::River => 5,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct UnpairedValues([Value; 2]);
 
@@ -980,6 +1738,31 @@ impl PartialEq<Hole> for FindNuts {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NutChange {
+    Unchanged,
+    // `upgraded` when the nuts are now a stronger kind of hand, e.g. a flush over a straight.
+    Changed {
+        before: FindNuts,
+        after: FindNuts,
+        upgraded: bool,
+    },
+}
+
+impl NutChange {
+    pub fn is_changed(&self) -> bool {
+        matches!(self, Self::Changed { .. })
+    }
+
+    // Whether `hole` had the nuts on the earlier street but doesn't anymore.
+    pub fn lost_nuts(&self, hole: Hole) -> bool {
+        match *self {
+            Self::Unchanged => false,
+            Self::Changed { before, after, .. } => before == hole && after != hole,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 struct ValueMap(BTreeMap<usize, BTreeSet<Value>>);
 
@@ -988,10 +1771,7 @@ impl From<&[Card]> for ValueMap {
         let mut value_map: BTreeMap<usize, BTreeSet<Value>> = BTreeMap::new();
 
         for (value, count) in cards.iter().map(Card::value).counts() {
-            value_map
-                .entry(count)
-                .or_insert_with(BTreeSet::new)
-                .insert(value);
+            value_map.entry(count).or_default().insert(value);
         }
 
         Self(value_map)
@@ -1035,6 +1815,7 @@ impl StraightSolve {
 }
 
 #[derive(Debug, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandValue(SortedHandValue, Option<Suit>);
 
 impl Deref for HandValue {
@@ -1045,6 +1826,12 @@ impl Deref for HandValue {
     }
 }
 
+impl Display for HandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl PartialEq for HandValue {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0
@@ -1071,8 +1858,15 @@ impl Hash for HandValue {
 
 impl From<CardsCombined<5>> for HandValue {
     fn from(cards: CardsCombined<5>) -> Self {
+        Self::new(cards, Variant::Holdem)
+    }
+}
+
+impl HandValue {
+    // Under `variant`, to be compared with `Variant::compare`.
+    pub fn new(cards: CardsCombined<5>, variant: Variant) -> Self {
         let is_flush = cards.is_flush();
-        let is_straight = cards.is_straight();
+        let is_straight = cards.is_straight(variant);
         let flush_suit = if is_flush {
             Some(cards[0].suit())
         } else {
@@ -1108,15 +1902,105 @@ impl From<CardsCombined<5>> for HandValue {
 
         Self(hand_value, flush_suit)
     }
-}
-
-impl HandValue {
     pub fn get_flush_suit(&self) -> Option<Suit> {
         self.1
     }
+
+    // The description followed by the suit of any flush, e.g. "Flush, Ace high ♠".
+    pub fn display(self, mode: DisplayMode) -> HandValueDisplay {
+        HandValueDisplay { value: self, mode }
+    }
+
+    pub fn category(&self) -> HandCategory {
+        HandCategory::from(&self.0)
+    }
+
+    pub fn is_at_least(&self, category: HandCategory) -> bool {
+        self.category() >= category
+    }
+}
+
+// Made hand tiers, weakest first.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    Trips,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+    RoyalFlush,
+}
+
+impl HandCategory {
+    pub const ALL: [Self; 10] = [
+        Self::HighCard,
+        Self::OnePair,
+        Self::TwoPair,
+        Self::Trips,
+        Self::Straight,
+        Self::Flush,
+        Self::FullHouse,
+        Self::Quads,
+        Self::StraightFlush,
+        Self::RoyalFlush,
+    ];
+
+    // How many of `values` fall in each category, strongest first, categories missing left out.
+    pub fn counts(values: impl IntoIterator<Item = HandValue>) -> IndexMap<Self, usize> {
+        let mut counts: IndexMap<Self, usize> = IndexMap::new();
+        for value in values {
+            *counts.entry(value.category()).or_default() += 1;
+        }
+        counts.sort_by(|a, _, b, _| b.cmp(a));
+        counts
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HighCard => "High Card",
+            Self::OnePair => "One Pair",
+            Self::TwoPair => "Two Pair",
+            Self::Trips => "Three of a Kind",
+            Self::Straight => "Straight",
+            Self::Flush => "Flush",
+            Self::FullHouse => "Full House",
+            Self::Quads => "Four of a Kind",
+            Self::StraightFlush => "Straight Flush",
+            Self::RoyalFlush => "Royal Flush",
+        }
+    }
+}
+
+impl From<&SortedHandValue> for HandCategory {
+    fn from(value: &SortedHandValue) -> Self {
+        match value {
+            SortedHandValue::RoyalFlush => Self::RoyalFlush,
+            SortedHandValue::StraightFlush(_) => Self::StraightFlush,
+            SortedHandValue::Quads(_) => Self::Quads,
+            SortedHandValue::FullHouse(_) => Self::FullHouse,
+            SortedHandValue::Flush(_) => Self::Flush,
+            SortedHandValue::Straight(_) => Self::Straight,
+            SortedHandValue::Trips(_) => Self::Trips,
+            SortedHandValue::TwoPair(_) => Self::TwoPair,
+            SortedHandValue::OnePair(_) => Self::OnePair,
+            SortedHandValue::HighCard(_) => Self::HighCard,
+        }
+    }
+}
+
+impl Display for HandCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SortedHandValue {
     RoyalFlush,
     StraightFlush(Value),
@@ -1130,6 +2014,57 @@ pub enum SortedHandValue {
     HighCard([Value; 5]),
 }
 
+impl SortedHandValue {
+    // The hand in words, e.g. "Two Pair, Aces and Fours with Queen kicker".
+    pub fn describe(&self) -> String {
+        match *self {
+            Self::RoyalFlush => "Royal Flush".to_string(),
+            Self::StraightFlush(high) => format!("Straight Flush, {} high", high.name()),
+            Self::Quads([quads, kicker]) => {
+                format!("Four of a Kind, {}{}", quads.plural(), kickers(&[kicker]))
+            }
+            Self::FullHouse([trips, pair]) => {
+                format!("Full House, {} full of {}", trips.plural(), pair.plural())
+            }
+            Self::Flush([high, ref rest @ ..]) => {
+                format!("Flush, {} high{}", high.name(), kickers(rest))
+            }
+            Self::Straight(high) => format!("Straight, {} high", high.name()),
+            Self::Trips([trips, ref rest @ ..]) => {
+                format!("Three of a Kind, {}{}", trips.plural(), kickers(rest))
+            }
+            Self::TwoPair([high, low, kicker]) => format!(
+                "Two Pair, {} and {}{}",
+                high.plural(),
+                low.plural(),
+                kickers(&[kicker])
+            ),
+            Self::OnePair([pair, ref rest @ ..]) => {
+                format!("One Pair, {}{}", pair.plural(), kickers(rest))
+            }
+            Self::HighCard([high, ref rest @ ..]) => {
+                format!("High Card, {}{}", high.name(), kickers(rest))
+            }
+        }
+    }
+}
+
+// " with Queen kicker" or " with King, Nine and Four kickers".
+fn kickers(values: &[Value]) -> String {
+    let names: Vec<_> = values.iter().map(|value| value.name()).collect();
+    match names.as_slice() {
+        [] => String::new(),
+        [kicker] => format!(" with {kicker} kicker"),
+        [rest @ .., last] => format!(" with {} and {last} kickers", rest.join(", ")),
+    }
+}
+
+impl Display for SortedHandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe())
+    }
+}
+
 impl PartialOrd for SortedHandValue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -1143,31 +2078,31 @@ impl Ord for SortedHandValue {
             (RoyalFlush, RoyalFlush) => Ordering::Equal,
             (RoyalFlush, _) => Ordering::Greater,
             (_, RoyalFlush) => Ordering::Less,
-            (StraightFlush(v1), StraightFlush(v2)) => v1.cmp(&v2),
+            (StraightFlush(v1), StraightFlush(v2)) => v1.cmp(v2),
             (StraightFlush(_), _) => Ordering::Greater,
             (_, StraightFlush(_)) => Ordering::Less,
-            (Quads(v1), Quads(v2)) => v1.cmp(&v2),
+            (Quads(v1), Quads(v2)) => v1.cmp(v2),
             (Quads(_), _) => Ordering::Greater,
             (_, Quads(_)) => Ordering::Less,
-            (FullHouse(v1), FullHouse(v2)) => v1.cmp(&v2),
+            (FullHouse(v1), FullHouse(v2)) => v1.cmp(v2),
             (FullHouse(_), _) => Ordering::Greater,
             (_, FullHouse(_)) => Ordering::Less,
-            (Flush(v1), Flush(v2)) => v1.cmp(&v2),
+            (Flush(v1), Flush(v2)) => v1.cmp(v2),
             (Flush(_), _) => Ordering::Greater,
             (_, Flush(_)) => Ordering::Less,
-            (Straight(v1), Straight(v2)) => v1.cmp(&v2),
+            (Straight(v1), Straight(v2)) => v1.cmp(v2),
             (Straight(_), _) => Ordering::Greater,
             (_, Straight(_)) => Ordering::Less,
-            (Trips(v1), Trips(v2)) => v1.cmp(&v2),
+            (Trips(v1), Trips(v2)) => v1.cmp(v2),
             (Trips(_), _) => Ordering::Greater,
             (_, Trips(_)) => Ordering::Less,
-            (TwoPair(v1), TwoPair(v2)) => v1.cmp(&v2),
+            (TwoPair(v1), TwoPair(v2)) => v1.cmp(v2),
             (TwoPair(_), _) => Ordering::Greater,
             (_, TwoPair(_)) => Ordering::Less,
-            (OnePair(v1), OnePair(v2)) => v1.cmp(&v2),
+            (OnePair(v1), OnePair(v2)) => v1.cmp(v2),
             (OnePair(_), _) => Ordering::Greater,
             (_, OnePair(_)) => Ordering::Less,
-            (HighCard(v1), HighCard(v2)) => v1.cmp(&v2),
+            (HighCard(v1), HighCard(v2)) => v1.cmp(v2),
         }
     }
 }
@@ -1258,6 +2193,22 @@ pub mod display {
         }
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+    pub struct HandValueDisplay {
+        pub(super) value: HandValue,
+        pub(super) mode: DisplayMode,
+    }
+
+    impl Display for HandValueDisplay {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.value)?;
+            match self.value.get_flush_suit() {
+                Some(suit) => write!(f, " {}", suit.display(self.mode)),
+                None => Ok(()),
+            }
+        }
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
     pub struct BoardDisplay {
         pub(super) board: Board,
@@ -1297,6 +2248,464 @@ pub mod display {
             }
         }
     }
+
+    // Cards five lines tall, box-drawn with the rank in the corners and the suit in the middle,
+    // side by side. The same in every `DisplayMode`: no colors, Unicode suits. `None` is a card
+    // face down, as the opponent's hole before the showdown.
+    #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+    pub struct BigCardsDisplay {
+        cards: Vec<Option<Card>>,
+        pub(super) breaks: Vec<usize>, // wider gaps before these cards
+    }
+
+    impl BigCardsDisplay {
+        pub fn new(cards: impl IntoIterator<Item = Option<Card>>) -> Self {
+            Self {
+                cards: cards.into_iter().collect(),
+                breaks: vec![],
+            }
+        }
+
+        fn line(card: Option<Card>, line: usize) -> String {
+            let Some(card) = card else {
+                return match line {
+                    0 => "┌─────┐".into(),
+                    4 => "└─────┘".into(),
+                    _ => "│░░░░░│".into(),
+                };
+            };
+            let (value, suit) = (card.value(), card.suit().display(DisplayMode::Unicode));
+            match line {
+                0 => "┌─────┐".into(),
+                1 => format!("│{value}    │"),
+                2 => format!("│  {suit}  │"),
+                3 => format!("│    {value}│"),
+                _ => "└─────┘".into(),
+            }
+        }
+    }
+
+    impl Display for BigCardsDisplay {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            if self.cards.is_empty() {
+                return Ok(());
+            }
+            for line in 0..5 {
+                if line > 0 {
+                    writeln!(f)?;
+                }
+                for (i, &card) in self.cards.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", if self.breaks.contains(&i) { "   " } else { " " })?;
+                    }
+                    write!(f, "{}", Self::line(card, line))?;
+                }
+            }
+            Ok(())
+        }
+    }
 }
 
+pub mod cardset;
+pub mod chips;
+pub mod draws;
+mod evaluator;
 pub mod headsup;
+pub mod indexer;
+pub mod parse;
+pub mod ring;
+pub mod runout;
+#[cfg(feature = "serde")]
+pub mod wire;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn what_beats_top_pair() {
+        let board: Board = "Ks9s5d3c2h".parse().unwrap();
+        let hole: Hole = "KhQc".parse().unwrap();
+        let count = |classes: &[(HoleClass, usize)], class: &str| {
+            let class = class.parse().unwrap();
+            classes.iter().find(|(c, _)| *c == class).map(|(_, n)| *n)
+        };
+
+        let beating = board.hands_beating(hole);
+        assert_eq!(count(&beating, "AKs"), Some(2));
+        assert_eq!(count(&beating, "AKo"), Some(6));
+        assert_eq!(count(&beating, "KJo"), None);
+        assert_eq!(beating[0].0, "64s".parse().unwrap()); // the straight flush
+
+        let tying = board.hands_tying(hole);
+        assert_eq!(tying.len(), 2);
+        assert_eq!(count(&tying, "KQs"), Some(1));
+        assert_eq!(count(&tying, "KQo"), Some(5));
+
+        let total =
+            |classes: Vec<(HoleClass, usize)>| classes.iter().map(|(_, n)| n).sum::<usize>();
+        assert_eq!(
+            total(beating) + total(tying) + total(board.hands_losing(hole)),
+            990
+        );
+
+        // a paired board with three of a suit, quads beat the nut flush
+        let board: Board = "Ks9s5s5d2h".parse().unwrap();
+        assert!(board.is_nuts("5c5h".parse().unwrap()));
+        assert!(!board.is_nuts("AsQs".parse().unwrap()));
+    }
+
+    #[test]
+    fn rank_ordering() {
+        assert_eq!(Value::all().count(), 13);
+        assert_eq!(Value::all().next_back(), Some(Value::Ace));
+        assert_eq!(Value::Ten.rank(), 10);
+        assert_eq!(Value::from_rank(1), Some(Value::Ace));
+        assert_eq!(Value::from_rank(Value::Ace.rank()), Some(Value::Ace));
+        assert_eq!(Value::from_rank(15), None);
+
+        assert_eq!(Value::Ace.next_higher(false), None);
+        assert_eq!(Value::Ace.next_higher(true), Some(Value::Deuce));
+        assert_eq!(Value::Deuce.next_lower(false), None);
+        assert_eq!(Value::Deuce.next_lower(true), Some(Value::Ace));
+        assert_eq!(Value::Ten.next_lower(false), Some(Value::Nine));
+
+        assert_eq!(Value::Jack.gap(Value::Nine), 1);
+        assert_eq!(Value::Nine.distance(Value::Jack), 2);
+        assert_eq!(Value::Ace.distance(Value::Deuce), 12);
+        assert_eq!(Value::Ace.distance_wrapping(Value::Deuce), 1);
+    }
+
+    #[test]
+    fn short_deck_rankings() {
+        let five = |cards: &str| cards.parse::<FullBoard>().unwrap();
+        let short = |cards| HandValue::new(five(cards), Variant::ShortDeck);
+
+        // A-6-7-8-9 is the lowest straight, A-2-3-4-5 no longer one
+        assert_eq!(*short("Ah6c7d8s9h"), SortedHandValue::Straight(Value::Nine));
+        assert_eq!(short("Ah2c3d4s5h").category(), HandCategory::HighCard);
+        assert_eq!(
+            *HandValue::from(five("Ah6c7d8s9h")),
+            SortedHandValue::HighCard([
+                Value::Ace,
+                Value::Nine,
+                Value::Eight,
+                Value::Seven,
+                Value::Six
+            ])
+        );
+
+        // a flush beats a full house
+        let flush = short("Ks9s8s7sJs");
+        let boat = short("AhAcAdKcKd");
+        assert_eq!(Variant::ShortDeck.compare(&flush, &boat), Ordering::Greater);
+        assert_eq!(Variant::Holdem.compare(&flush, &boat), Ordering::Less);
+
+        let board: FullBoard = "KsJs9s7dKc".parse().unwrap();
+        let boat = board.hand_value_in("JhJd".parse().unwrap(), Variant::ShortDeck);
+        let flush = board.hand_value_in("As6s".parse().unwrap(), Variant::ShortDeck);
+        assert_eq!(boat.category(), HandCategory::FullHouse);
+        assert_eq!(Variant::ShortDeck.compare(&flush, &boat), Ordering::Greater);
+        let (jacks, suited) = ("JhJd".parse().unwrap(), "As6s".parse().unwrap());
+        assert_eq!(
+            Variant::ShortDeck.who_wins(&board, jacks, suited),
+            (flush, Some(false))
+        );
+        assert_eq!(
+            Variant::Holdem.who_wins(&board, jacks, suited).1,
+            Some(true)
+        );
+
+        // with a six on board, an ace and a seven still make the low straight
+        let cards: Vec<Card> = ["6h", "8c", "9d"].map(|c| c.parse().unwrap()).to_vec();
+        let (_, solves) = Board::straight_scan(&cards, false, Variant::ShortDeck);
+        assert!(solves.contains(&StraightSolve::Two([Value::Seven, Value::Ace])));
+    }
+
+    #[test]
+    fn omaha_plays_two_from_the_hole() {
+        let board: FullBoard = "2s5s8sJsKd".parse().unwrap();
+        let hole: OmahaHole = "AsKcQh7d".parse().unwrap();
+        // one spade in the hole makes no flush, the kings pair up
+        let value = hole.hand_value_omaha(&board);
+        assert_eq!(value.category(), HandCategory::OnePair);
+        assert_eq!(
+            value,
+            HandValue::from("KcKdAsJs8s".parse::<FullBoard>().unwrap())
+        );
+
+        // four of a kind on board plays only one of them
+        let board: FullBoard = "9c9d9h9sAd".parse().unwrap();
+        let hole: OmahaHole = "KcKdQhJh".parse().unwrap();
+        assert_eq!(
+            hole.hand_value_omaha(&board).category(),
+            HandCategory::FullHouse
+        );
+    }
+
+    #[test]
+    fn hand_descriptions() {
+        let value = |cards: &str| HandValue::from(cards.parse::<FullBoard>().unwrap());
+        assert_eq!(
+            value("KsKdKhTcTd").to_string(),
+            "Full House, Kings full of Tens"
+        );
+        assert_eq!(
+            value("AsAd4h4cQd").describe(),
+            "Two Pair, Aces and Fours with Queen kicker"
+        );
+        assert_eq!(
+            value("6s6d9h4cAd").describe(),
+            "One Pair, Sixes with Ace, Nine and Four kickers"
+        );
+        assert_eq!(value("5h4h3h2hAh").describe(), "Straight Flush, Five high");
+
+        let flush = value("As9s7s4s2s");
+        assert_eq!(
+            flush.display(DisplayMode::Unicode).to_string(),
+            "Flush, Ace high with Nine, Seven, Four and Deuce kickers ♠"
+        );
+        assert_eq!(
+            value("AsKd9h7c4d").display(DisplayMode::Ascii).to_string(),
+            "High Card, Ace with King, Nine, Seven and Four kickers"
+        );
+    }
+
+    #[test]
+    fn best_five_cards() {
+        let seven: CardsCombined<7> = "AhKh7h2c7d4h9h".parse().unwrap();
+        let (value, five) = seven.best_five();
+        assert_eq!(value, seven.hand_value());
+        assert_eq!(five, "AhKh7h4h9h".parse().unwrap());
+
+        let seven: CardsCombined<7> = "QsQd8c8h5s5dAc".parse().unwrap();
+        let (value, five) = seven.best_five();
+        assert_eq!(
+            value.describe(),
+            "Two Pair, Queens and Eights with Ace kicker"
+        );
+        assert_eq!(five, "QsQd8c8hAc".parse().unwrap());
+    }
+
+    #[test]
+    fn showdowns_now() {
+        let (aces, kings): (Hole, Hole) = ("AsAh".parse().unwrap(), "KsKh".parse().unwrap());
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        let now = flop.who_wins_now(aces, kings).unwrap();
+        assert_eq!(now.winner, Some(false));
+        assert_eq!(now.best_five[1], "KsKhKd7c2h".parse().unwrap());
+        assert!(now.equity.unwrap().win < 0.1);
+        assert_eq!(Board::default().who_wins_now(aces, kings), None);
+
+        // a straight on the board plays for both, the tie keeps both hands
+        let river: Board = "9c8d7s6h5c".parse().unwrap();
+        let now = river.who_wins_now(aces, kings).unwrap();
+        assert_eq!((now.winner, now.equity), (None, None));
+        assert_eq!(now.hands[0], now.hands[1]);
+        let full = river.as_full_board().unwrap();
+        assert_eq!(full.showdown(aces, kings), now);
+        assert_eq!(river.who_wins_now(aces, "9c2d".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn board_runouts() {
+        let board: Board = "AsKd7c".parse().unwrap();
+        let dead: CardSet = "QhQd".parse::<Hole>().unwrap().into();
+        assert_eq!(board.runouts(&dead).count(), 47);
+        assert!(
+            board
+                .runouts(&dead)
+                .all(|card| !dead.contains(card) && !board.to_vec().contains(&card))
+        );
+        assert_eq!(board.full_runouts(&dead).count(), 47 * 46 / 2);
+        assert!(
+            board
+                .full_runouts(&dead)
+                .all(|full| FullBoard::new(full.0).is_some() && full[..3] == board.to_vec()[..])
+        );
+
+        let river: Board = "AsKd7c2h3h".parse().unwrap();
+        assert_eq!(river.runouts(&dead).count(), 0);
+        assert_eq!(
+            river.full_runouts(&dead).collect_vec(),
+            [river.as_full_board().unwrap()]
+        );
+    }
+
+    #[test]
+    fn nuts_ranking() {
+        let board: Board = "Ks9s5d3c2h".parse().unwrap();
+        let ranked = board.find_nuts_n(3);
+        assert_eq!(ranked[0], board.find_nuts());
+        // the wheel, then sets of kings and nines
+        let values = |first, second| UnpairedValues::new([first, second]).unwrap();
+        assert_eq!(
+            ranked,
+            [
+                FindNuts::TwoValues(values(Value::Six, Value::Four)),
+                FindNuts::TwoValues(values(Value::Ace, Value::Four)),
+                FindNuts::PocketPair(Value::King),
+            ]
+        );
+
+        let hole = |hole: &str| board.relative_strength(hole.parse().unwrap());
+        assert_eq!(hole("6h4h"), Some(0));
+        assert_eq!(hole("KhKd"), Some(2));
+        assert!(hole("KhQd") > hole("KhKd"));
+        assert_eq!(hole("Ks8d"), None);
+        assert!(Board::default().find_nuts_n(3).is_empty());
+    }
+
+    #[test]
+    fn nuts_changing_by_street() {
+        let flop: Board = "9s8s2d".parse().unwrap();
+        let blank: Board = "9s8s2d3c".parse().unwrap();
+        assert_eq!(blank.nut_changes(&flop), NutChange::Unchanged);
+
+        let turn: Board = "9s8s2d7s".parse().unwrap();
+        let change = turn.nut_changes(&flop);
+        let hole = |hole: &str| hole.parse::<Hole>().unwrap();
+        assert_eq!(
+            change,
+            NutChange::Changed {
+                before: FindNuts::PocketPair(Value::Nine),
+                after: FindNuts::TwoHoles([hole("JsTs"), hole("Ts6s")]),
+                upgraded: true,
+            }
+        );
+        assert!(change.lost_nuts(hole("9h9d")));
+        assert!(!change.lost_nuts(hole("JsTs")));
+        assert_eq!(flop.nut_changes(&turn), NutChange::Unchanged);
+    }
+
+    #[test]
+    fn canonical_holes() {
+        let hole: Hole = "KcAs".parse().unwrap();
+        assert!(!hole.is_canonical());
+        assert_eq!(
+            hole.canonical().to_vec(),
+            "AsKc".parse::<Hole>().unwrap().to_vec()
+        );
+        assert!(hole.canonical().is_canonical());
+
+        let pair: Hole = "QdQh".parse().unwrap();
+        assert_eq!(
+            pair.canonical().to_vec(),
+            "QhQd".parse::<Hole>().unwrap().to_vec()
+        );
+
+        let holes: HashSet<Hole> = ["AsKc", "KcAs"].map(|s| s.parse().unwrap()).into();
+        assert_eq!(holes.len(), 1);
+    }
+
+    #[test]
+    fn plain_display() {
+        assert_eq!(Suit::all().map(|suit| suit.to_string()).join(""), "shdc");
+        assert_eq!(Suit::all().next_back(), Some(Suit::Clubs));
+        let card = Card::new(Value::Ten, Suit::Hearts);
+        assert_eq!(card.to_string(), "Th");
+        assert_eq!(
+            format!("{card}"),
+            card.display(DisplayMode::Ascii).to_string()
+        );
+    }
+
+    #[test]
+    fn big_cards() {
+        let card = Card::new(Value::Ten, Suit::Hearts);
+        let lines = ["┌─────┐", "│T    │", "│  ♥  │", "│    T│", "└─────┘"];
+        assert_eq!(card.big().to_string(), lines.join("\n"));
+
+        let board: Board = "AsKd7c2h".parse().unwrap();
+        let big = board.big().to_string();
+        assert_eq!(big.lines().count(), 5);
+        assert!(big.starts_with("┌─────┐ ┌─────┐ ┌─────┐   ┌─────┐"));
+        assert_eq!(Board::default().big().to_string(), "");
+        let hidden = BigCardsDisplay::new([None, None]).to_string();
+        assert_eq!(hidden.lines().nth(2), Some("│░░░░░│ │░░░░░│"));
+    }
+
+    #[test]
+    fn loose_boards() {
+        let board: Board = "AsKd7c2h".parse().unwrap();
+        assert_eq!(Board::parse_lenient("as kd 7c | 2h"), Some(board));
+        assert_eq!(Board::parse_lenient("AS,KD,7C,2H"), Some(board));
+        assert_eq!(Board::parse_lenient("[As Kd 7c] [2h]"), Some(board));
+        assert_eq!("as kd 7c | 2h".parse::<Board>().ok(), None);
+        assert_eq!("X".parse::<Board>().ok(), None);
+
+        let tens: Board = "TsTdTc".parse().unwrap();
+        for s in ["10s10d10c", "1 0s, 10d, 1,0c", "10s-1 0d-10c"] {
+            assert_eq!(Board::parse_lenient(s), Some(tens));
+        }
+        assert_eq!(
+            Board::parse_street("Ts-9s-5d | 10c"),
+            Some(("Ts9s5dTc".parse().unwrap(), Street::Turn))
+        );
+        assert_eq!(
+            Board::parse_street("X"),
+            Some((Board::default(), Street::Preflop))
+        );
+        assert_eq!(Board::parse_street("As Kd 7"), None);
+        assert_eq!(Board::parse_street("As As 7c"), None);
+    }
+
+    #[test]
+    fn shorthand_holes() {
+        let count = |s| Hole::parse_combos(s).map(|combos| combos.len());
+        assert_eq!(count("AsKd"), Some(1));
+        assert_eq!(count("AKs"), Some(4));
+        assert_eq!(count("QJo"), Some(12));
+        assert_eq!(count(" 77 "), Some(6));
+        assert_eq!(count("AK"), Some(16));
+        assert_eq!(count("AAs"), None);
+        assert_eq!(count("AsAs"), None);
+
+        let hole = Hole::parse_lenient("T9s").unwrap();
+        assert_eq!(
+            HoleClass::from(hole),
+            HoleClass::Suited(Value::Ten, Value::Nine)
+        );
+        assert_eq!(Hole::parse_lenient("KhQc"), "KhQc".parse().ok());
+    }
+
+    #[test]
+    fn hand_categories() {
+        let board: Board = "AsKsQsJs2d".parse().unwrap();
+        let board = board.as_full_board().unwrap();
+        let royal = board.hand_value("Ts3c".parse().unwrap());
+        assert_eq!(royal.category(), HandCategory::RoyalFlush);
+        let flush = board.hand_value("3s4c".parse().unwrap());
+        assert_eq!(flush.category(), HandCategory::Flush);
+        assert!(flush.is_at_least(HandCategory::Straight));
+        assert!(!flush.is_at_least(HandCategory::FullHouse));
+        assert_eq!(HandCategory::FullHouse.to_string(), "Full House");
+
+        let pair = board.hand_value("2c4c".parse().unwrap());
+        let counts = HandCategory::counts([flush, pair, royal, flush]);
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [
+                (HandCategory::RoyalFlush, 1),
+                (HandCategory::Flush, 2),
+                (HandCategory::OnePair, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn hole_classes() {
+        let classes: Vec<HoleClass> = HoleClass::all().collect();
+        assert_eq!(classes.len(), 169);
+        assert_eq!(classes.iter().collect::<HashSet<_>>().len(), 169);
+        let combos: usize = classes.iter().map(|class| class.combos().len()).sum();
+        assert_eq!(combos, 1326);
+
+        let class = |hole: &str| hole.parse::<Hole>().unwrap().class().to_string();
+        assert_eq!(class("KsAs"), "AKs");
+        assert_eq!(class("9dTc"), "T9o");
+        assert_eq!(class("5c5h"), "55");
+        assert_eq!("T9o".parse::<HoleClass>().unwrap().to_string(), "T9o");
+        assert!(HoleClass::Pair(Value::Ace) < HoleClass::Suited(Value::Trey, Value::Deuce));
+    }
+}
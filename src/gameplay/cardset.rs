@@ -0,0 +1,250 @@
+// A set of cards as one u64: suit `s` owns bits `16 * s` up to `16 * s + 12`, a bit per rank from
+// the deuce up. Set operations are single instructions and the rank mask of a suit is a shift, so
+// hot paths can skip juggling `Vec<Card>`s and `BTreeSet`s.
+use super::{Board, Card, CardsCombined, Suit, Value};
+use std::fmt::{self, Debug, Formatter};
+
+const RANKS: u64 = 0x1fff;
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CardSet(u64);
+
+impl CardSet {
+    pub const EMPTY: Self = Self(0);
+    pub const DECK: Self = Self(RANKS | RANKS << 16 | RANKS << 32 | RANKS << 48);
+
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    // None if any bit falls outside the 52 cards.
+    pub fn from_bits(bits: u64) -> Option<Self> {
+        (bits & !Self::DECK.0 == 0).then_some(Self(bits))
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    fn bit(card: Card) -> u64 {
+        1 << (card.suit().as_u8() * 16 + card.value().as_u8())
+    }
+
+    pub fn contains(self, card: Card) -> bool {
+        self.0 & Self::bit(card) != 0
+    }
+
+    // False if the card was already in the set.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let had = self.contains(card);
+        self.0 |= Self::bit(card);
+        !had
+    }
+
+    // False if the card wasn't in the set.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let had = self.contains(card);
+        self.0 &= !Self::bit(card);
+        had
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    // The cards of the deck not in the set.
+    pub fn complement(self) -> Self {
+        Self::DECK.difference(self)
+    }
+
+    pub fn is_disjoint(self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    pub fn is_subset(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    // Ranks held in `suit`, bit 0 for the deuce up to bit 12 for the ace.
+    pub fn suit_mask(self, suit: Suit) -> u16 {
+        (self.0 >> (suit.as_u8() * 16) & RANKS) as u16
+    }
+
+    // Ranks held in any suit.
+    pub fn rank_mask(self) -> u16 {
+        Suit::ALL
+            .into_iter()
+            .fold(0, |mask, suit| mask | self.suit_mask(suit))
+    }
+
+    // Suits holding `value`, bit `i` for the suit `i` of spades, hearts, diamonds and clubs.
+    pub fn value_mask(self, value: Value) -> u8 {
+        Suit::ALL
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, suit)| self.contains(Card::new(value, suit)))
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    pub fn suit_count(self, suit: Suit) -> usize {
+        self.suit_mask(suit).count_ones() as usize
+    }
+
+    // Suit by suit from spades to clubs, deuce up to ace within each.
+    pub fn iter(self) -> Cards {
+        Cards(self.0)
+    }
+
+    pub fn to_vec(self) -> Vec<Card> {
+        self.iter().collect()
+    }
+
+    // The cards in `iter` order, if there are exactly `N`.
+    pub fn to_combined<const N: usize>(self) -> Option<CardsCombined<N>> {
+        self.to_vec().try_into().ok().map(CardsCombined)
+    }
+
+    // A board of the cards in `iter` order; which card is the turn or river is lost in a set.
+    pub fn to_board(self) -> Option<Board> {
+        Board::from_slice(&self.to_vec())
+    }
+
+    fn card(index: u32) -> Card {
+        Card::new(
+            Value::ALL[(index % 16) as usize],
+            Suit::ALL[(index / 16) as usize],
+        )
+    }
+}
+
+impl Debug for CardSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl From<Card> for CardSet {
+    fn from(card: Card) -> Self {
+        Self(Self::bit(card))
+    }
+}
+
+impl<const N: usize> From<CardsCombined<N>> for CardSet {
+    fn from(cards: CardsCombined<N>) -> Self {
+        cards.0.into_iter().collect()
+    }
+}
+
+impl From<Board> for CardSet {
+    fn from(board: Board) -> Self {
+        board.to_vec().into_iter().collect()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut set = Self::EMPTY;
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        for card in iter {
+            self.0 |= Self::bit(card);
+        }
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Cards;
+
+    fn into_iter(self) -> Cards {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cards(u64);
+
+impl Iterator for Cards {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(CardSet::card(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Cards {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::Hole;
+
+    #[test]
+    fn set_operations() {
+        let board: Board = "Ks7s2d5c9s".parse().unwrap();
+        let hole: Hole = "AsKd".parse().unwrap();
+        let (board_set, hole_set) = (CardSet::from(board), CardSet::from(hole));
+        assert_eq!(board_set.len(), 5);
+        assert!(board_set.is_disjoint(hole_set));
+
+        let seven = board_set.union(hole_set);
+        assert_eq!(seven.suit_count(Suit::Spades), 4);
+        assert_eq!(
+            seven.suit_mask(Suit::Spades),
+            1 << 12 | 1 << 11 | 1 << 7 | 1 << 5
+        );
+        assert_eq!(seven.rank_mask().count_ones(), 6);
+        assert_eq!(seven.value_mask(Value::King), 0b0101);
+        assert_eq!(seven.difference(hole_set), board_set);
+        assert_eq!(seven.intersection(hole_set), hole_set);
+        assert!(hole_set.is_subset(seven));
+
+        let deck = CardSet::DECK;
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.iter().collect::<CardSet>(), deck);
+        assert_eq!(seven.complement().len(), 45);
+        assert_eq!(CardSet::from_bits(1 << 13), None);
+
+        let mut set = CardSet::new();
+        assert!(set.insert("Qh".parse().unwrap()));
+        assert!(!set.insert("Qh".parse().unwrap()));
+        assert!(set.remove("Qh".parse().unwrap()));
+        assert!(set.is_empty());
+
+        assert_eq!(hole_set.to_combined::<2>(), Some(hole));
+        assert_eq!(CardSet::from(hole).to_combined::<3>(), None);
+        let flop: Board = "Ks7s2d".parse().unwrap();
+        assert_eq!(CardSet::from(flop).to_board(), Some(flop));
+    }
+}
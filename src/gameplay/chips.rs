@@ -0,0 +1,162 @@
+// An amount of chips. Amounts in big blinds are plain `f64`s and only meet chips through
+// `in_bb` and `from_bb`, so the two can't be mixed up by accident.
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Rem, Sub, SubAssign},
+};
+
+#[derive(Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Chips(u32);
+
+impl Chips {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn new(chips: u32) -> Self {
+        Self(chips)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_multiple_of(self, chips: u32) -> bool {
+        self.0.is_multiple_of(chips)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    // This many big blinds of `big_blind`.
+    pub fn in_bb(self, big_blind: Self) -> f64 {
+        self.0 as f64 / big_blind.0 as f64
+    }
+
+    // `bbs` big blinds in chips, rounded to the nearest chip; negative amounts are no chips.
+    pub fn from_bb(bbs: f64, big_blind: Self) -> Self {
+        Self((bbs * big_blind.0 as f64).round().max(0.0) as u32)
+    }
+
+    // Thousands separated by commas for reading, e.g. "1,500"; `Display` stays bare.
+    pub fn grouped(self) -> String {
+        let digits = self.0.to_string();
+        let mut grouped = String::with_capacity(digits.len() * 4 / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+// Shown as the bare amount, like the u32 inside, so state diffs and logged events stay terse.
+impl Debug for Chips {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for Chips {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Chips {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Chips {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+// Scaling by a count, e.g. an ante from each of two players.
+impl Mul<u32> for Chips {
+    type Output = Self;
+
+    fn mul(self, count: u32) -> Self {
+        Self(self.0 * count)
+    }
+}
+
+impl Div<u32> for Chips {
+    type Output = Self;
+
+    fn div(self, count: u32) -> Self {
+        Self(self.0 / count)
+    }
+}
+
+impl Rem<u32> for Chips {
+    type Output = Self;
+
+    fn rem(self, count: u32) -> Self {
+        Self(self.0 % count)
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_blind_conversions() {
+        let big_blind = Chips::new(100);
+        let pot = Chips::new(250) + Chips::new(100) * 2;
+        assert_eq!(pot, Chips::new(450));
+        assert_eq!(pot.in_bb(big_blind), 4.5);
+        assert_eq!(Chips::from_bb(2.5, big_blind), Chips::new(250));
+        assert_eq!(Chips::from_bb(-1.0, big_blind), Chips::ZERO);
+        assert_eq!(pot.checked_sub(Chips::new(500)), None);
+        assert_eq!([pot, big_blind].into_iter().sum::<Chips>(), Chips::new(550));
+        assert_eq!(pot.to_string(), "450");
+        assert_eq!(Chips::new(1234567).grouped(), "1,234,567");
+        assert_eq!(Chips::new(100).grouped(), "100");
+    }
+}
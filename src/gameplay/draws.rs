@@ -0,0 +1,183 @@
+// What a hole is drawing to on the flop or turn: every unseen card is tried as the next one, and
+// those giving a flush or straight the hole plays in are its outs. Outs that pair the board, or
+// bring a third card of a suit to a straight, can just as well fill someone else, so they aren't
+// counted clean.
+use super::{Board, Card, Hole, Suit, cardset::CardSet, evaluator::straight};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct FlushDraw {
+    pub suit: Suit,
+    pub nut: bool, // no higher card of the suit is left to come
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum StraightDraw {
+    OpenEnded,
+    DoubleGutshot,
+    Gutshot,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Draws {
+    flush: Option<FlushDraw>,
+    straight: Option<StraightDraw>,
+    paired: bool,
+    outs: CardSet,
+    clean_outs: CardSet,
+}
+
+impl Draws {
+    // No draws preflop or on the river, where no single card comes next.
+    pub fn new(hole: Hole, board: &Board) -> Self {
+        let hole_cards = CardSet::from(hole);
+        let board_cards = CardSet::from(*board);
+        let paired = hole.is_pocket_pair()
+            || hole
+                .iter()
+                .any(|card| board_cards.rank_mask() & 1 << card.value().as_u8() != 0);
+        let mut draws = Self {
+            paired,
+            ..Default::default()
+        };
+        let all = hole_cards.union(board_cards);
+
+        let (mut flush_outs, mut straight_outs) = (CardSet::new(), CardSet::new());
+        for card in board.runouts(&hole_cards) {
+            let next = board_cards.union(card.into());
+            if makes_flush(hole_cards, next, card.suit()) {
+                flush_outs.insert(card);
+            }
+            if !made_straight(all, board_cards) && made_straight(all.union(card.into()), next) {
+                straight_outs.insert(card);
+            }
+        }
+
+        if let Some(card) = flush_outs.iter().next() {
+            let suit = card.suit();
+            // the highest card of the suit nobody can see on the board is ours
+            let unseen = !board_cards.suit_mask(suit) & 0x1fff;
+            let nut = hole_cards.suit_mask(suit) & 1 << unseen.ilog2() != 0;
+            draws.flush = Some(FlushDraw { suit, nut });
+        }
+
+        let ranks = straight_outs.rank_mask();
+        draws.straight = match ranks.count_ones() {
+            0 => None,
+            1 => Some(StraightDraw::Gutshot),
+            // both ends of four in a row are five ranks apart, the ace low at the wheel end
+            _ if ranks.ilog2() - ranks.trailing_zeros() == 5 || ranks == 1 << 12 | 1 << 4 => {
+                Some(StraightDraw::OpenEnded)
+            }
+            _ => Some(StraightDraw::DoubleGutshot),
+        };
+
+        draws.outs = flush_outs.union(straight_outs);
+        draws.clean_outs = draws
+            .outs
+            .iter()
+            .filter(|&card| is_clean(card, board_cards, flush_outs.contains(card)))
+            .collect();
+        draws
+    }
+
+    pub fn flush(&self) -> Option<FlushDraw> {
+        self.flush
+    }
+
+    pub fn straight(&self) -> Option<StraightDraw> {
+        self.straight
+    }
+
+    // Whether the hole already makes a pair or better with the board.
+    pub fn is_paired(&self) -> bool {
+        self.paired
+    }
+
+    // A flush draw with a straight draw, or either with a pair.
+    pub fn is_combo(&self) -> bool {
+        match (self.flush.is_some(), self.straight.is_some()) {
+            (true, true) => true,
+            (false, false) => false,
+            _ => self.paired,
+        }
+    }
+
+    // The cards giving a flush or straight, each counted once.
+    pub fn outs(&self) -> CardSet {
+        self.outs
+    }
+
+    pub fn clean_outs(&self) -> CardSet {
+        self.clean_outs
+    }
+}
+
+// A flush of `suit` with a hole card in it that `board` doesn't make on its own.
+fn makes_flush(hole: CardSet, board: CardSet, suit: Suit) -> bool {
+    let (held, on_board) = (hole.suit_count(suit), board.suit_count(suit));
+    held > 0 && held + on_board >= 5 && on_board < 5
+}
+
+// A straight of `cards` higher than any `board` makes on its own.
+fn made_straight(cards: CardSet, board: CardSet) -> bool {
+    straight(cards.rank_mask()) > straight(board.rank_mask())
+}
+
+fn is_clean(card: Card, board: CardSet, flush_out: bool) -> bool {
+    let pairs = board.rank_mask() & 1 << card.value().as_u8() != 0;
+    let flush_possible = !flush_out && board.suit_count(card.suit()) >= 2;
+    !pairs && !flush_possible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_and_straight_draws() {
+        let draws = |hole: &str, board: &str| {
+            let hole: Hole = hole.parse().unwrap();
+            hole.draws(&board.parse().unwrap())
+        };
+
+        let nut_flush = draws("AhTh", "Kh7h2c");
+        assert_eq!(
+            nut_flush.flush(),
+            Some(FlushDraw {
+                suit: Suit::Hearts,
+                nut: true
+            })
+        );
+        assert_eq!(nut_flush.straight(), None);
+        assert_eq!(nut_flush.outs().len(), 9);
+        assert!(!nut_flush.is_combo());
+
+        let open_ended = draws("9c8d", "Ts7c2c");
+        assert_eq!(open_ended.straight(), Some(StraightDraw::OpenEnded));
+        assert_eq!(open_ended.outs().len(), 8);
+        assert_eq!(open_ended.clean_outs().len(), 6); // the Jack and Six of clubs bring three clubs
+
+        assert_eq!(
+            draws("9c7d", "JsTh2c").straight(),
+            Some(StraightDraw::Gutshot)
+        );
+        assert_eq!(
+            draws("9c5d", "Js7h8c2d").straight(),
+            Some(StraightDraw::DoubleGutshot)
+        );
+        assert_eq!(
+            draws("Ac2d", "3s4h9c").straight(),
+            Some(StraightDraw::Gutshot)
+        );
+
+        // a non-nut flush draw with an open-ender, fifteen outs
+        let combo = draws("Jh9h", "Th8c2h");
+        assert!(!combo.is_paired() && combo.is_combo());
+        assert_eq!(combo.flush().map(|flush| flush.nut), Some(false));
+        assert_eq!(combo.outs().len(), 15);
+        assert!(draws("AhTh", "Kh7hTc").is_combo());
+
+        let river = draws("AhTh", "Kh7h2c9s4d");
+        assert!(river.flush().is_none() && river.outs().is_empty());
+    }
+}
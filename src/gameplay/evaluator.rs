@@ -0,0 +1,206 @@
+// Seven-card evaluation straight from rank bitmasks: one pass over the cards fills a mask per suit
+// and a count per rank, then compile-time tables give the highest straight and the top ranks of
+// any mask. No five-card combos are enumerated and nothing is allocated.
+use super::{Card, CardsCombined, HandValue, SortedHandValue, Suit, Value};
+
+const MASKS: usize = 1 << 13;
+
+// Highest straight of each rank mask, as the top rank plus one (0 for no straight).
+static STRAIGHTS: [u8; MASKS] = straights();
+
+// The five highest ranks of each rank mask.
+static TOP_FIVE: [u16; MASKS] = top_five();
+
+const fn straights() -> [u8; MASKS] {
+    let mut table = [0; MASKS];
+    let mut mask = 0;
+    while mask < MASKS {
+        // the ace plays low in the wheel, as a bit below the deuce
+        let ranks = ((mask << 1) | (mask >> 12 & 1)) as u16;
+        let mut top = 13;
+        while top >= 4 {
+            let run = 0b11111 << (top - 4);
+            if ranks & run == run {
+                table[mask] = top as u8; // rank `top - 1` with the low ace shifted in
+                break;
+            }
+            top -= 1;
+        }
+        mask += 1;
+    }
+    table
+}
+
+const fn top_five() -> [u16; MASKS] {
+    let mut table = [0; MASKS];
+    let mut mask = 0;
+    while mask < MASKS {
+        let mut rest = mask as u16;
+        while rest.count_ones() > 5 {
+            rest &= rest - 1; // drops the lowest rank
+        }
+        table[mask] = rest;
+        mask += 1;
+    }
+    table
+}
+
+fn rank(index: u32) -> Value {
+    Value::ALL[index as usize]
+}
+
+// Ranks of `mask` from the highest down.
+fn ranks_desc(mask: u16) -> impl Iterator<Item = Value> {
+    let mut rest = mask;
+    std::iter::from_fn(move || {
+        let top = rest.checked_ilog2()?;
+        rest &= !(1 << top);
+        Some(rank(top))
+    })
+}
+
+fn highest(mask: u16) -> Value {
+    rank(mask.ilog2())
+}
+
+pub(super) fn straight(mask: u16) -> Option<Value> {
+    match STRAIGHTS[mask as usize] {
+        0 => None,
+        top => Some(rank(top as u32 - 1)),
+    }
+}
+
+// The `N` highest ranks of `mask`, which must have at least `N`.
+fn top<const N: usize>(mask: u16) -> [Value; N] {
+    let mut ranks = ranks_desc(mask);
+    std::array::from_fn(|_| ranks.next().expect("mask should have enough ranks"))
+}
+
+pub(super) fn evaluate(cards: &CardsCombined<7>) -> HandValue {
+    Masks::default().with(&cards.0).value()
+}
+
+// The rank masks and counts of some cards. Those of a hole are filled once and copied for every
+// board it's evaluated on, leaving five cards to add per board.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct Masks {
+    suits: [u16; 4],
+    counts: [u8; 13],
+}
+
+impl Masks {
+    pub(super) fn with(mut self, cards: &[Card]) -> Self {
+        for card in cards {
+            self.suits[card.suit().as_u8() as usize] |= 1 << card.value().as_u8();
+            self.counts[card.value().as_u8() as usize] += 1;
+        }
+        self
+    }
+
+    // The value of seven cards.
+    pub(super) fn value(&self) -> HandValue {
+        let Self { suits, counts } = self;
+
+        // with five suited cards out of seven nothing but a straight flush beats the flush
+        if let Some((suit, &mask)) = suits
+            .iter()
+            .enumerate()
+            .find(|(_, mask)| mask.count_ones() >= 5)
+        {
+            let suit = Some(Suit::ALL[suit]);
+            let value = match straight(mask) {
+                Some(Value::Ace) => SortedHandValue::RoyalFlush,
+                Some(top) => SortedHandValue::StraightFlush(top),
+                None => SortedHandValue::Flush(top(TOP_FIVE[mask as usize])),
+            };
+            return HandValue(value, suit);
+        }
+
+        // rank masks by how many of the rank there are
+        let mut by_count = [0u16; 5];
+        for (rank, &count) in counts.iter().enumerate() {
+            by_count[count as usize] |= 1 << rank;
+        }
+        let all = by_count[1] | by_count[2] | by_count[3] | by_count[4];
+
+        let value = if by_count[4] != 0 {
+            let quads = highest(by_count[4]);
+            let kicker = highest(all & !(1 << quads.as_u8()));
+            SortedHandValue::Quads([quads, kicker])
+        } else if by_count[3] != 0 && (by_count[3].count_ones() > 1 || by_count[2] != 0) {
+            let trips = highest(by_count[3]);
+            let pair = highest((by_count[3] | by_count[2]) & !(1 << trips.as_u8()));
+            SortedHandValue::FullHouse([trips, pair])
+        } else if let Some(top) = straight(all) {
+            SortedHandValue::Straight(top)
+        } else if by_count[3] != 0 {
+            let trips = highest(by_count[3]);
+            let [first, second] = top(all & !(1 << trips.as_u8()));
+            SortedHandValue::Trips([trips, first, second])
+        } else if by_count[2].count_ones() >= 2 {
+            let [high, low] = top(by_count[2]);
+            let kicker = highest(all & !(1 << high.as_u8()) & !(1 << low.as_u8()));
+            SortedHandValue::TwoPair([high, low, kicker])
+        } else if by_count[2] != 0 {
+            let pair = highest(by_count[2]);
+            let [first, second, third] = top(all & !(1 << pair.as_u8()));
+            SortedHandValue::OnePair([pair, first, second, third])
+        } else {
+            SortedHandValue::HighCard(top(TOP_FIVE[all as usize]))
+        };
+        HandValue(value, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rand::prelude::*;
+
+    #[test]
+    fn matches_combos() {
+        let deck = Value::ALL
+            .into_iter()
+            .cartesian_product(Suit::ALL)
+            .map(|(value, suit)| Card::new(value, suit))
+            .collect_vec();
+        let mut rng = rand::rng();
+        let hole: CardsCombined<2> = "AhKh".parse().unwrap();
+        let boards = (0..500)
+            .map(|_| {
+                let rest = deck.iter().filter(|card| !hole.0.contains(card));
+                let cards: Vec<_> = rest.copied().choose_multiple(&mut rng, 5);
+                CardsCombined(cards.try_into().unwrap())
+            })
+            .collect_vec();
+        let values = crate::gameplay::evaluate_many(hole, &boards);
+        let threads = crate::parallel::Threads::default();
+        assert_eq!(
+            crate::gameplay::evaluate_many_in(hole, &boards, &threads),
+            values
+        );
+        for (board, value) in boards.iter().zip(values) {
+            assert_eq!(board.hand_value(hole), value);
+        }
+
+        let random = (0..5000).map(|_| {
+            let cards: Vec<_> = deck.choose_multiple(&mut rng, 7).copied().collect();
+            CardsCombined(cards.try_into().unwrap())
+        });
+        let edges = [
+            "Ah2c3d4s5h9cKd", // wheel
+            "Ah2h3h4h5h6cKd", // steel wheel
+            "AsKsQsJsTs9s8s", // royal over a straight flush
+            "KdKcKh7s7c7dAs", // two trips
+            "QdQc7h7s2c2dAs", // three pairs
+            "9h8h7h6h2h5cTc", // flush over a straight
+        ]
+        .map(|cards| cards.parse::<CardsCombined<7>>().unwrap());
+
+        for cards in random.chain(edges) {
+            let (table, combos) = (evaluate(&cards), cards.hand_value_by_combos());
+            assert_eq!((table.0, table.1), (combos.0, combos.1), "{cards:?}");
+        }
+    }
+}
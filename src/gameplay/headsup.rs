@@ -1,22 +1,52 @@
 #![allow(dead_code)]
 
 use super::*;
+use crate::{
+    equity::{equity_exact, hole_equity},
+    stats::{ActionRecord, LineWalker},
+};
+use clock::{Clock, SystemClock, Timed};
+use history::HandHistory;
+use identity::{Identities, Identity, SharedIdentities};
 use rand::prelude::*;
-use std::{array, ops::RangeInclusive, slice::Iter, vec};
-use tokio::sync::{
-    mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
-    oneshot::{Sender, channel},
+use rand_chacha::ChaCha20Rng;
+use sink::{EventRecord, EventSink};
+use std::{
+    array,
+    cmp::Reverse,
+    collections::VecDeque,
+    error::Error,
+    fmt::Debug,
+    future::poll_fn,
+    iter,
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, ready},
+    time::Duration,
+    vec,
+};
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{self, UnboundedReceiver, UnboundedSender, unbounded_channel},
+        oneshot::{Sender, channel},
+    },
+    time::{self, error::Elapsed},
 };
+use tokio_stream::{Stream, wrappers::BroadcastStream};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Action(ActionValue);
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionValue {
     Exit,
     Fold,
     CheckOrCall,
-    BetOrRaise(u32),
+    BetOrRaise(Chips),
     AllIn,
 }
 
@@ -33,8 +63,9 @@ impl Action {
         Self(ActionValue::CheckOrCall)
     }
 
-    pub fn bet_or_raise(amount: u32) -> Option<Self> {
-        if amount == 0 || amount % 25 != 0 {
+    // Any amount but none; the table's bet unit is checked by `BetBound::alter_eq`.
+    pub fn bet_or_raise(amount: Chips) -> Option<Self> {
+        if amount.is_zero() {
             None // Invalid bet or raise amount
         } else {
             Some(Self(ActionValue::BetOrRaise(amount)))
@@ -67,30 +98,226 @@ impl Action {
 }
 
 impl FromStr for Action {
-    type Err = ();
+    type Err = ActionParseError;
+
+    // The letters, or the words `ActionDisplay` writes with amounts in chips, commas allowed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let unknown = || ActionParseError::Unknown(lower.clone());
+        match *lower.split_whitespace().collect::<Vec<_>>() {
+            ["e" | "x" | "exit"] => Ok(Self::exit()),
+            ["f" | "fold"] => Ok(Self::fold()),
+            ["c" | "check" | "call"] | ["call", _] => Ok(Self::check_or_call()),
+            ["a" | "allin"] | ["all", "in"] => Ok(Self::all_in()),
+            [amount] | ["bet" | "raise", amount] | ["raise", "to", amount] => {
+                let amount = amount
+                    .replace(',', "")
+                    .parse::<u32>()
+                    .map_err(|_| unknown())?;
+                let amount = Chips::new(amount);
+                Self::bet_or_raise(amount).ok_or(ActionParseError::Amount(amount))
+            }
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl Action {
+    // Like `FromStr`, and sizes relative to `context` too: big blinds as "2.5bb", or a share of
+    // the pot as "pot", "half" or "2/3", all as what to bet or raise to.
+    pub fn parse_in(s: &str, context: &BetContext) -> Result<Self, ActionParseError> {
+        let lower = s.trim().to_ascii_lowercase();
+        let size = match *lower.split_whitespace().collect::<Vec<_>>() {
+            [size] | ["bet" | "raise", size] | ["raise", "to", size] => context.amount(size),
+            _ => None,
+        };
+        match size {
+            Some(amount) => Self::bet_or_raise(amount).ok_or(ActionParseError::Amount(amount)),
+            None => s.parse(),
+        }
+    }
+
+    // In words, e.g. "raise to 1,500", which `FromStr` reads back.
+    pub fn display(self, context: BetContext) -> ActionDisplay {
+        ActionDisplay {
+            action: self,
+            context,
+        }
+    }
+}
+
+// What sizes are relative to: the pot with this round's bets in it, the big blind and the bets
+// this round as `[hero, villain]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct BetContext {
+    pot: Chips,
+    big_blind: Chips,
+    bets: [Chips; 2],
+    unit: Chips,
+}
+
+impl BetContext {
+    // Sizes round to the default unit of blinds of half the big blind, or to single chips for a
+    // big blind too large for any blinds.
+    pub fn new(pot: Chips, big_blind: Chips, bets: [Chips; 2]) -> Self {
+        let unit =
+            u16::try_from(big_blind.get()).map_or(1, |big| Blinds::default_unit(big / 2, big));
+        Self {
+            pot,
+            big_blind,
+            bets,
+            unit: Chips::new(unit as u32),
+        }
+    }
+
+    pub fn with_unit(mut self, unit: Chips) -> Self {
+        self.unit = unit.max(Chips::new(1));
+        self
+    }
+
+    fn to_call(self) -> Chips {
+        self.bets[1].saturating_sub(self.bets[0])
+    }
+
+    // Chips to bet or raise to for a size, to the nearest bet unit. A share of the pot is on top
+    // of the call, like `Decision::sized`.
+    fn amount(&self, size: &str) -> Option<Chips> {
+        let of_pot = |fraction: f64| {
+            self.bets[1].get() as f64 + fraction * (self.pot + self.to_call()).get() as f64
+        };
+        let raw = if let Some(bbs) = size.strip_suffix("bb") {
+            bbs.parse::<f64>().ok()? * self.big_blind.get() as f64
+        } else if let Some((numerator, denominator)) = size.split_once('/') {
+            of_pot(numerator.parse::<f64>().ok()? / denominator.parse::<f64>().ok()?)
+        } else {
+            match size {
+                "pot" => of_pot(1.0),
+                "half" => of_pot(0.5),
+                _ => return None,
+            }
+        };
+        let unit = self.unit.get();
+        (raw.is_finite() && raw > 0.0)
+            .then(|| Chips::new((raw / unit as f64).round() as u32 * unit))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ActionDisplay {
+    action: Action,
+    context: BetContext,
+}
+
+impl Display for ActionDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let context = &self.context;
+        match self.action.0 {
+            ActionValue::Exit => write!(f, "exit"),
+            ActionValue::Fold => write!(f, "fold"),
+            ActionValue::CheckOrCall if context.to_call().is_zero() => write!(f, "check"),
+            ActionValue::CheckOrCall => write!(f, "call {}", context.to_call().grouped()),
+            // the big blind is a bet, so raising over it too
+            ActionValue::BetOrRaise(amount) if context.bets[1].is_zero() => {
+                write!(f, "bet {}", amount.grouped())
+            }
+            ActionValue::BetOrRaise(amount) => write!(f, "raise to {}", amount.grouped()),
+            ActionValue::AllIn => write!(f, "all in"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum ActionParseError {
+    Unknown(String), // none of x, f, c, a or an amount
+    Amount(Chips),   // bets and raises are at least a chip
+}
+
+impl Display for ActionParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(s) => write!(
+                f,
+                "\"{s}\" is no action, use x to exit, f, c, a or an amount to bet or raise to"
+            ),
+            Self::Amount(amount) => write!(f, "{amount} chips is no bet or raise"),
+        }
+    }
+}
+
+impl Error for ActionParseError {}
+
+// The `FromStr` spelling, so actions read back as they were written.
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ActionValue::Exit => write!(f, "x"),
+            ActionValue::Fold => write!(f, "f"),
+            ActionValue::CheckOrCall => write!(f, "c"),
+            ActionValue::BetOrRaise(amount) => write!(f, "{amount}"),
+            ActionValue::AllIn => write!(f, "a"),
+        }
+    }
+}
+
+// Hole cards the winner of an uncontested pot chooses to show, in hole order.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShowCards {
+    #[default]
+    Neither,
+    First,
+    Second,
+    Both,
+}
+
+impl ShowCards {
+    pub fn pick(self, hole: Hole) -> [Option<Card>; 2] {
+        let [first, second] = [hole[0], hole[1]];
+        match self {
+            Self::Neither => [None, None],
+            Self::First => [Some(first), None],
+            Self::Second => [None, Some(second)],
+            Self::Both => [Some(first), Some(second)],
+        }
+    }
+}
+
+impl FromStr for ShowCards {
+    type Err = ShowCardsParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim().to_ascii_lowercase().as_str() {
-            "e" | "x" => Ok(Self::exit()),
-            "f" => Ok(Self::fold()),
-            "c" => Ok(Self::check_or_call()),
-            "a" => Ok(Self::all_in()),
-            s => s
-                .parse::<u32>()
-                .map_err(|_| ())
-                .and_then(|amount| Self::bet_or_raise(amount).ok_or(())),
+            "" | "n" => Ok(Self::Neither),
+            "1" => Ok(Self::First),
+            "2" => Ok(Self::Second),
+            "b" | "12" => Ok(Self::Both),
+            s => Err(ShowCardsParseError(s.to_string())),
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct ShowCardsParseError(pub String);
+
+impl Display for ShowCardsParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" is no choice, use n, 1, 2 or b", self.0)
+    }
+}
+
+impl Error for ShowCardsParseError {}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum ActionSendError {
     NotHeroTurn,
     InvalidAction,
+    TimedOut, // the game went on without the answer, see `TimeBank`
     GameAbort(GameOver),
 }
 
+// Standard buy-ins are in big blinds of a 250/500 table, `Custom` stakes set their own.
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CashBuyin {
     BB15,
     BB30,
@@ -102,9 +329,100 @@ pub enum CashBuyin {
     BB200,
     BB250,
     BB300,
+    Custom(CashStakes),
+}
+
+// Blinds of any ratio with the buy-ins the table allows, in chips. Both players sit with the
+// largest buy-in unless `with_buyin` picks another in the range.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CashStakes {
+    small_blind: u16,
+    big_blind: u16,
+    min_buyin: Chips,
+    max_buyin: Chips,
+    buyin: Chips,
+}
+
+impl CashStakes {
+    pub fn new(
+        small_blind: u16,
+        big_blind: u16,
+        buyins: RangeInclusive<Chips>,
+    ) -> Result<Self, StakesError> {
+        let (min_buyin, max_buyin) = buyins.into_inner();
+        if small_blind == 0 {
+            return Err(StakesError::ZeroBlind);
+        }
+        if small_blind > big_blind {
+            return Err(StakesError::SmallBlindAboveBig);
+        }
+        if min_buyin > max_buyin {
+            return Err(StakesError::EmptyBuyins);
+        }
+        if min_buyin < Chips::new(big_blind as u32) {
+            return Err(StakesError::BuyinBelowBigBlind);
+        }
+        Ok(Self {
+            small_blind,
+            big_blind,
+            min_buyin,
+            max_buyin,
+            buyin: max_buyin,
+        })
+    }
+
+    pub fn with_buyin(mut self, buyin: Chips) -> Result<Self, StakesError> {
+        if !(self.min_buyin..=self.max_buyin).contains(&buyin) {
+            return Err(StakesError::BuyinOutOfRange);
+        }
+        self.buyin = buyin;
+        Ok(self)
+    }
+
+    pub fn small_blind(&self) -> u16 {
+        self.small_blind
+    }
+
+    pub fn big_blind(&self) -> u16 {
+        self.big_blind
+    }
+
+    pub fn buyins(&self) -> RangeInclusive<Chips> {
+        self.min_buyin..=self.max_buyin
+    }
+
+    pub fn buyin(&self) -> Chips {
+        self.buyin
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum StakesError {
+    ZeroBlind,
+    SmallBlindAboveBig,
+    EmptyBuyins,
+    BuyinBelowBigBlind,
+    BuyinOutOfRange,
 }
 
+impl Display for StakesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::ZeroBlind => "the small blind must be at least one chip",
+            Self::SmallBlindAboveBig => "the small blind can't be more than the big blind",
+            Self::EmptyBuyins => "the smallest buy-in can't be more than the largest",
+            Self::BuyinBelowBigBlind => "a buy-in must cover the big blind",
+            Self::BuyinOutOfRange => "the buy-in is outside what the table allows",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl Error for StakesError {}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SNGSpeed {
     Turbo,
     Medium,
@@ -113,14 +431,23 @@ pub enum SNGSpeed {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameType {
-    Cash { buyin: CashBuyin, hands: u16 },
-    SNG(SNGSpeed),
+    Cash {
+        buyin: CashBuyin,
+        hands: u16,
+        #[cfg_attr(feature = "serde", serde(default))]
+        rake: Option<Rake>,
+    },
+    SNG {
+        speed: SNGSpeed,
+        level_hands: u16,
+    }, // hands per blind level, 0 to never raise them
 }
 
 impl Default for GameType {
     fn default() -> Self {
-        Self::SNG(Default::default())
+        Self::sng(Default::default())
     }
 }
 
@@ -129,28 +456,79 @@ impl GameType {
         Self::Cash {
             buyin: CashBuyin::default(),
             hands: 0,
+            rake: None,
+        }
+    }
+
+    // A blind level a hand, the pace sit and gos always had.
+    pub fn sng(speed: SNGSpeed) -> Self {
+        Self::SNG {
+            speed,
+            level_hands: 1,
+        }
+    }
+
+    // By the names bindings take it by: "cash" for the default cash game, or a sit and go of
+    // speed "turbo", "medium" or "slow".
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "cash" => Some(Self::cash_default()),
+            "turbo" => Some(Self::sng(SNGSpeed::Turbo)),
+            "medium" => Some(Self::sng(SNGSpeed::Medium)),
+            "slow" => Some(Self::sng(SNGSpeed::Slow)),
+            _ => None,
+        }
+    }
+
+    // The prize pool's share by finishing place for `equity::icm`: a heads-up sit and go is
+    // winner take all, and cash game chips are money already.
+    pub fn payouts(self) -> &'static [f64] {
+        match self {
+            Self::Cash { .. } => &[],
+            Self::SNG { .. } => &[1.0],
         }
     }
 
     fn is_sng(self) -> bool {
-        matches!(self, Self::SNG(_))
+        matches!(self, Self::SNG { .. })
+    }
+
+    fn rake(self) -> Option<Rake> {
+        match self {
+            Self::Cash { rake, .. } => rake,
+            Self::SNG { .. } => None,
+        }
+    }
+
+    fn level_hands(self) -> u16 {
+        match self {
+            Self::Cash { .. } => 0, // one level all game
+            Self::SNG { level_hands, .. } => level_hands,
+        }
     }
 
-    fn hands_limit(self) -> u16 {
+    fn hands_limit(self) -> u32 {
         match self {
             Self::Cash { hands, .. } => {
                 if hands == 0 {
-                    u16::MAX // no limit
+                    u16::MAX.into() // no limit
                 } else {
-                    hands
+                    hands.into()
                 }
             }
-            Self::SNG(_) => 0, // SNG has no hands limit
+            Self::SNG { .. } => 0, // SNG has no hands limit
         }
     }
 
-    fn init_stack(self) -> u32 {
-        match self {
+    pub(crate) fn init_stack(self) -> Chips {
+        if let Self::Cash {
+            buyin: CashBuyin::Custom(stakes),
+            ..
+        } = self
+        {
+            return stakes.buyin;
+        }
+        Chips::new(match self {
             Self::Cash { buyin, .. } => match buyin {
                 CashBuyin::BB15 => 7500,
                 CashBuyin::BB30 => 15000,
@@ -161,914 +539,5834 @@ impl GameType {
                 CashBuyin::BB200 => 100000,
                 CashBuyin::BB250 => 125000,
                 CashBuyin::BB300 => 150000,
+                CashBuyin::Custom(_) => unreachable!("Custom stakes have their own buy-in"),
             },
-            Self::SNG(speed) => match speed {
+            Self::SNG { speed, .. } => match speed {
                 SNGSpeed::Turbo => 3000,
                 SNGSpeed::Medium => 7500,
                 SNGSpeed::Slow => 15000,
             },
-        }
+        })
     }
 
-    fn blind_levels(self) -> vec::IntoIter<u16> {
+    // None for half the big blind.
+    fn small_blind(self) -> Option<u16> {
         match self {
+            Self::Cash {
+                buyin: CashBuyin::Custom(stakes),
+                ..
+            } => Some(stakes.small_blind),
+            _ => None,
+        }
+    }
+
+    // The blinds the game type plays by, replaced with `Game::with_blind_structure`.
+    pub fn blind_structure(self) -> BlindStructure {
+        let big_blinds = match self {
+            Self::Cash {
+                buyin: CashBuyin::Custom(stakes),
+                ..
+            } => vec![stakes.big_blind],
             Self::Cash { .. } => vec![500],
-            Self::SNG(speed) => match speed {
+            Self::SNG { speed, .. } => match speed {
                 SNGSpeed::Turbo => vec![50, 100, 150, 200],
                 SNGSpeed::Medium => vec![50, 100, 150, 200, 300, 400, 500],
                 SNGSpeed::Slow => vec![50, 100, 150, 200, 300, 400, 500, 600, 800, 1000],
             },
-        }
-        .into_iter()
+        };
+        let levels = big_blinds.into_iter().map(|big_blind| {
+            let small_blind = self.small_blind().unwrap_or(big_blind / 2);
+            Blinds::new(small_blind, big_blind).expect("Game types have valid blinds")
+        });
+        BlindStructure::new(levels)
+            .expect("Game types have blind levels")
+            .with_level_hands(self.level_hands())
     }
 }
 
+// The house's cut of a cash game pot: a percentage of it, at most `cap` when there is one, and
+// with no flop no drop, the default, nothing from pots won before the flop.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub enum Visibility {
-    None,
-    Player(bool), // true for player 0, false for player 1
-    God,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rake {
+    basis_points: u16, // hundredths of a percent
+    cap: Option<Chips>,
+    no_flop_no_drop: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub enum ObservableEvent {
-    DealHoles([Option<Hole>; 2]),
-    ShowdownAll([Hole; 2]),
-    ShowdownAuto([Hole; 2]), // board nuts auto chop
-    PlayerAction(Action),
-    GameOver(GameOver),
-}
+impl Rake {
+    // None outside 0 to 100 percent.
+    pub fn new(percent: f64) -> Option<Self> {
+        (0.0..=100.0).contains(&percent).then(|| Self {
+            basis_points: (percent * 100.0).round() as u16,
+            cap: None,
+            no_flop_no_drop: true,
+        })
+    }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub enum PlayerEvent {
-    Observable(ObservableEvent),
-    HeroTurn(BetBound),
-}
+    pub fn with_cap(mut self, cap: Chips) -> Self {
+        self.cap = Some(cap);
+        self
+    }
 
-impl PlayerEvent {
-    const fn unwrap_observable(self) -> ObservableEvent {
-        match self {
-            Self::Observable(observable) => observable,
-            Self::HeroTurn(_) => unreachable!(),
-        }
+    pub fn with_no_flop_no_drop(mut self, no_flop_no_drop: bool) -> Self {
+        self.no_flop_no_drop = no_flop_no_drop;
+        self
     }
-}
 
-#[derive(Debug)]
-enum InternalEvent {
-    Observable(ObservableEvent),
-    HeroTurn(BetBound, Sender<Action>),
-}
+    pub fn percent(&self) -> f64 {
+        self.basis_points as f64 / 100.0
+    }
 
-impl InternalEvent {
-    fn take_player(self) -> (PlayerEvent, Option<(BetBound, Sender<Action>)>) {
-        match self {
-            Self::Observable(event) => (PlayerEvent::Observable(event), None),
-            Self::HeroTurn(bet_bound, sender) => (
-                PlayerEvent::HeroTurn(bet_bound.clone()),
-                Some((bet_bound, sender)),
-            ),
+    pub fn cap(&self) -> Option<Chips> {
+        self.cap
+    }
+
+    pub fn no_flop_no_drop(&self) -> bool {
+        self.no_flop_no_drop
+    }
+
+    // The cut of `pot` won on `street`, rounded down.
+    pub fn of(&self, pot: Chips, street: Street) -> Chips {
+        if self.no_flop_no_drop && street == Street::Preflop {
+            return Chips::ZERO;
         }
+        let rake = Chips::new((pot.get() as u64 * self.basis_points as u64 / 10000) as u32);
+        self.cap.map_or(rake, |cap| rake.min(cap))
     }
 }
 
-#[derive(Debug)]
-pub struct Player {
-    game_type: GameType,
-    visibility: Visibility,
-    recv: UnboundedReceiver<InternalEvent>,
-    hero_turn: Option<(BetBound, Sender<Action>)>,
-    heads_up: HeadsUp,
-    hands_history: Vec<HandHistory>,
+// Which hands are turned over at a river showdown, and in what order. Hands all in before the
+// river are always shown together. Otherwise the river's last aggressor shows first when called,
+// or the first to act when it checks down, then the other player shows or mucks in turn.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ShowdownRules {
+    pub auto_muck: bool,             // losing hands are mucked instead of shown
+    pub show_only_when_called: bool, // winning hands are shown only when they were called
+    pub position_order: bool,        // the first to act shows first even when the river was bet
 }
 
-impl Player {
-    fn new(
-        game_type: GameType,
-        visibility: Visibility,
-        recv: UnboundedReceiver<InternalEvent>,
-        button: bool,
-    ) -> Self {
-        Self {
-            game_type,
-            visibility,
-            recv,
-            hero_turn: None,
-            heads_up: HeadsUp::new(game_type, button),
-            hands_history: Default::default(),
-        }
+impl ShowdownRules {
+    // `winner` is None for a chop, `called` is the seat that has to show first.
+    pub fn shown(self, holes: [Hole; 2], winner: Option<bool>, called: bool) -> [Option<Hole>; 2] {
+        [(true, holes[0]), (false, holes[1])].map(|(seat, hole)| {
+            let show = seat == called
+                || if winner.is_none_or(|winner| winner == seat) {
+                    !self.show_only_when_called
+                } else {
+                    !self.auto_muck
+                };
+            show.then_some(hole)
+        })
     }
+}
 
-    pub fn is_over(&self) -> bool {
-        self.heads_up.is_over()
+// How long players get to answer: `base` for every decision, then a `bank` of extra time for the
+// whole game that slower answers draw on. A player out of time is folded, or checked when
+// checking is free, and offers to them lapse as if declined.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeBank {
+    base: Duration,
+    bank: Duration,
+}
+
+impl TimeBank {
+    pub fn new(base: Duration, bank: Duration) -> Self {
+        Self { base, bank }
     }
 
-    pub fn game_over(&self) -> Option<GameOver> {
-        self.heads_up.game_over()
+    pub fn base(&self) -> Duration {
+        self.base
     }
 
-    pub fn hands_history(&self) -> &[HandHistory] {
-        &self.hands_history
+    pub fn bank(&self) -> Duration {
+        self.bank
     }
 
-    pub async fn tick_event(&mut self) -> Option<PlayerEvent> {
-        if self.is_over() {
+    // `answer` if it comes within the base time and what is left of `bank`, drawing the time past
+    // the base from the bank, as measured on `clock`. None and an empty bank when it doesn't.
+    async fn limit<T>(
+        &self,
+        clock: &dyn Clock,
+        bank: &mut Duration,
+        answer: impl Future<Output = T>,
+    ) -> Option<T> {
+        let started = clock.now();
+        let Ok(answer) = time::timeout(self.base + *bank, answer).await else {
+            *bank = Duration::ZERO;
             return None;
-        }
-
-        let (event, hero_turn) = self
-            .recv
-            .recv()
-            .await
-            .unwrap_or(InternalEvent::Observable(ObservableEvent::GameOver(
-                self.heads_up.abort(),
-            )))
-            .take_player();
+        };
+        let used = clock.now().saturating_sub(started);
+        *bank = bank.saturating_sub(used.saturating_sub(self.base));
+        Some(answer)
+    }
+}
 
-        self.hero_turn = hero_turn;
-        if let PlayerEvent::Observable(event) = event {
-            if let Some(hand_history) = self.heads_up.event(event) {
-                self.hands_history.push(hand_history);
-            }
-        }
+// All-in insurance: the player ahead once all in may cash out their equity in the pot, less
+// `margin`, instead of running the board. The opponent gets the rest of the pot.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Insurance {
+    margin: f64,
+}
 
-        Some(event)
+impl Insurance {
+    pub fn new(margin: f64) -> Option<Self> {
+        (0.0..1.0).contains(&margin).then_some(Self { margin })
     }
 
-    pub fn send_action(&mut self, action: Action) -> Result<(), ActionSendError> {
-        if self.hero_turn.is_none() {
-            return Err(ActionSendError::NotHeroTurn);
-        }
-
-        let Some(action) = self
-            .hero_turn
-            .as_ref()
-            .expect("hero_turn should to be Some here")
-            .0
-            .alter_eq(action)
-        else {
-            return Err(ActionSendError::InvalidAction);
-        };
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
 
-        if self
-            .hero_turn
-            .take()
-            .expect("hero_turn should to be Some here")
-            .1
-            .send(action)
-            .is_err()
-        {
-            let game_over = self.heads_up.abort();
-            self.heads_up.event(ObservableEvent::GameOver(game_over));
-            return Err(ActionSendError::GameAbort(game_over));
+    // Chips offered for `equity` in `pot`, rounded down to whole bet units. None unless ahead.
+    pub fn payout(&self, pot: Chips, equity: f64, unit: Chips) -> Option<Chips> {
+        if equity <= 0.5 {
+            return None;
         }
 
-        Ok(())
-    }
-
-    pub fn parse_send_action(&mut self, action: &str) -> Result<(), ActionSendError> {
-        self.send_action(action.parse().map_err(|_| ActionSendError::InvalidAction)?)
+        let unit = unit.get().max(1);
+        let payout = (pot.get() as f64 * equity * (1.0 - self.margin) / unit as f64).floor() as u32;
+        let payout = payout * unit;
+        (payout > 0).then_some(Chips::new(payout).min(pot))
     }
 }
 
-#[derive(Debug)]
-pub struct Observer(Player);
+// Cash game bomb pots: every `every`th hand both players ante `big_blinds` big blinds instead of
+// posting blinds, and the hand starts on the flop.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct BombPot {
+    every: u16,
+    big_blinds: u16,
+}
 
-impl Observer {
-    pub fn is_over(&self) -> bool {
-        self.0.is_over()
+impl BombPot {
+    pub fn new(every: u16, big_blinds: u16) -> Option<Self> {
+        (every > 0 && big_blinds > 0).then_some(Self { every, big_blinds })
     }
 
-    pub fn game_over(&self) -> Option<GameOver> {
-        self.0.game_over()
+    pub fn every(&self) -> u16 {
+        self.every
     }
 
-    pub async fn tick_event(&mut self) -> Option<ObservableEvent> {
-        self.0
-            .tick_event()
-            .await
-            .map(PlayerEvent::unwrap_observable)
+    pub fn big_blinds(&self) -> u16 {
+        self.big_blinds
     }
 }
 
+// The forced bets of a blind level. The ante is each player's, or the big blind's alone with a big
+// blind ante, and a straddle has the button post twice the big blind instead of the small blind,
+// acting last before the flop.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub enum GameOver {
-    Defeated(bool),
-    ExitAbandon(bool),
-    ExitCheckout(bool, [u32; 2]),
-    AbortCheckout([u32; 2]),
-    HandsReached([u32; 2]),
-    GameAbort,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Blinds {
+    small_blind: u16,
+    big_blind: u16,
+    ante: u16,
+    big_blind_ante: bool,
+    straddle: bool,
+    // bets and raises go in multiples of it, all ins aside; games before it were all in 25s
+    #[cfg_attr(feature = "serde", serde(default = "Blinds::legacy_unit"))]
+    unit: u16,
 }
 
-#[derive(Debug)]
-struct PlayerSender {
-    visibility: Visibility,
-    send: UnboundedSender<InternalEvent>,
-}
+impl Blinds {
+    pub fn new(small_blind: u16, big_blind: u16) -> Option<Self> {
+        (small_blind > 0 && small_blind <= big_blind).then_some(Self {
+            small_blind,
+            big_blind,
+            ante: 0,
+            big_blind_ante: false,
+            straddle: false,
+            unit: Self::default_unit(small_blind, big_blind),
+        })
+    }
 
-impl PlayerSender {
-    fn send(&self, event: ObservableEvent) -> bool {
-        // todo: transform event (God |-> FirstPerson)
-        self.send.send(InternalEvent::Observable(event)).is_ok()
+    // The largest unit of 25 chips or less both blinds are made of: 25 for the standard tables,
+    // 5 for blinds of 10/20, 1 for 1/2.
+    pub(crate) fn default_unit(small_blind: u16, big_blind: u16) -> u16 {
+        gcd(gcd(25, small_blind), big_blind)
     }
 
-    async fn turn(&self, bet_bound: BetBound) -> Option<Action> {
-        let (send, recv) = channel();
-
-        if self
-            .send
-            .send(InternalEvent::HeroTurn(bet_bound, send))
-            .is_err()
-        {
-            return None; // Player crashed
-        }
-
-        recv.await.ok()
+    #[cfg(feature = "serde")]
+    fn legacy_unit() -> u16 {
+        25
     }
-}
-
-// todo: make private, inside run_hand
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub struct Deck([Card; 52]);
 
-impl Default for Deck {
-    fn default() -> Self {
-        let mut cards = [Default::default(); 52];
-        let values = [
-            Value::Deuce,
-            Value::Trey,
-            Value::Four,
-            Value::Five,
-            Value::Six,
-            Value::Seven,
-            Value::Eight,
-            Value::Nine,
-            Value::Ten,
-            Value::Jack,
-            Value::Queen,
-            Value::King,
-            Value::Ace,
-        ];
-        let suits = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+    // None unless the unit makes up both blinds.
+    pub fn with_unit(mut self, unit: u16) -> Option<Self> {
+        let divides = |chips: u16| chips.is_multiple_of(unit);
+        (unit > 0 && divides(self.small_blind) && divides(self.big_blind)).then(|| {
+            self.unit = unit;
+            self
+        })
+    }
 
-        for (i, &value) in values.iter().enumerate() {
-            for (j, &suit) in suits.iter().enumerate() {
-                cards[i * 4 + j] = Card(value, suit);
-            }
-        }
+    pub fn with_ante(mut self, ante: u16) -> Self {
+        self.ante = ante;
+        self
+    }
 
-        Self(cards)
+    pub fn small_blind(&self) -> Chips {
+        Chips::new(self.small_blind as u32)
     }
-}
 
-impl Deck {
-    pub fn shuffle_and_deal(&mut self) -> Dealer {
-        self.0.shuffle(&mut rand::rng());
-        Dealer(self.0.into_iter())
+    pub fn big_blind(&self) -> Chips {
+        Chips::new(self.big_blind as u32)
     }
-}
 
-// todo: make private, inside run_hand
-#[derive(Debug, Clone)]
-pub struct Dealer(array::IntoIter<Card, 52>);
+    pub fn ante(&self) -> Chips {
+        Chips::new(self.ante as u32)
+    }
 
-impl Dealer {
-    pub fn deal_card(&mut self) -> Card {
-        self.0.next().expect("Dealer should always have cards left")
+    pub fn is_big_blind_ante(&self) -> bool {
+        self.big_blind_ante
     }
 
-    pub fn deal_hole(&mut self) -> Hole {
-        Hole::unchecked([self.deal_card(), self.deal_card()])
+    pub fn has_straddle(&self) -> bool {
+        self.straddle
     }
 
-    pub fn deal_flop(&mut self) -> Flop {
-        Flop::unchecked([self.deal_card(), self.deal_card(), self.deal_card()])
+    pub fn unit(&self) -> Chips {
+        Chips::new(self.unit as u32)
     }
 }
 
+fn gcd(a: u16, b: u16) -> u16 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// How long a blind level lasts: a number of hands, 0 to never raise the blinds, or a time since
+// the first deal. Levels go up between hands.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum LevelDuration {
+    Hands(u16),
+    Time(Duration),
+}
+
+// Blind levels in the order they are played, the last one for the rest of the game.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub enum BetBound {
-    FoldCheckAllIn,
-    FoldCheckBetAllIn(RangeInclusive<u32>),
-    FoldAllIn,
-    FoldCall,
-    FoldCallAllIn,
-    FoldCallRaiseAllIn(RangeInclusive<u32>),
-    FoldBetAllIn(RangeInclusive<u32>), // river nuts button(!opened)
-    FoldRaiseAllIn(RangeInclusive<u32>), // river nuts opened
+pub struct BlindStructure {
+    levels: Vec<Blinds>,
+    duration: LevelDuration,
 }
 
-impl BetBound {
-    pub fn validate_action(&self, action: Action) -> bool {
-        if action.is_exit() || action.is_fold() {
-            return true; // always valid
-        }
+impl BlindStructure {
+    // A level a hand until `with_level_hands` or `with_level_time`; None without levels or with
+    // more than the 255 a level's number goes up to.
+    pub fn new(levels: impl IntoIterator<Item = Blinds>) -> Option<Self> {
+        let levels: Vec<_> = levels.into_iter().collect();
+        (!levels.is_empty() && levels.len() <= u8::MAX as usize).then_some(Self {
+            levels,
+            duration: LevelDuration::Hands(1),
+        })
+    }
 
-        match self {
-            Self::FoldCheckAllIn | Self::FoldCallAllIn => {
-                action.is_check_or_call() || action.is_all_in()
-            }
-            Self::FoldCheckBetAllIn(range) | Self::FoldCallRaiseAllIn(range) => {
-                if let ActionValue::BetOrRaise(amount) = action.value() {
-                    range.contains(&amount)
-                } else {
-                    action.is_check_or_call() || action.is_all_in()
-                }
-            }
-            Self::FoldAllIn => action.is_all_in(),
-            Self::FoldCall => action.is_check_or_call(),
-            Self::FoldBetAllIn(range) | Self::FoldRaiseAllIn(range) => {
-                if let ActionValue::BetOrRaise(amount) = action.value() {
-                    range.contains(&amount)
-                } else {
-                    action.is_all_in()
-                }
-            }
+    pub fn with_level_hands(mut self, hands: u16) -> Self {
+        self.duration = LevelDuration::Hands(hands);
+        self
+    }
+
+    pub fn with_level_time(mut self, time: Duration) -> Self {
+        self.duration = LevelDuration::Time(time);
+        self
+    }
+
+    // Every level's ante is posted by the big blind alone.
+    pub fn with_big_blind_ante(mut self) -> Self {
+        for level in &mut self.levels {
+            level.big_blind_ante = true;
         }
+        self
     }
 
-    pub fn alter_eq(&self, action: Action) -> Option<Action> {
-        if !self.validate_action(action) {
-            return None; // Invalid action
+    // Straddles are a cash game option; SNGs ignore them.
+    pub fn with_straddle(mut self) -> Self {
+        for level in &mut self.levels {
+            level.straddle = true;
         }
+        self
+    }
 
-        if let ActionValue::BetOrRaise(amount) = action.value() {
-            match self {
-                Self::FoldCheckBetAllIn(range)
-                | Self::FoldCallRaiseAllIn(range)
-                | Self::FoldBetAllIn(range)
-                | Self::FoldRaiseAllIn(range) => {
-                    if amount == *range.end() {
-                        return Some(Action::all_in());
-                    }
-                }
-                _ => unreachable!(),
-            }
+    // The bet unit of every level, None unless it makes up all their blinds.
+    pub fn with_unit(mut self, unit: u16) -> Option<Self> {
+        for level in &mut self.levels {
+            *level = level.with_unit(unit)?;
         }
+        Some(self)
+    }
 
-        Some(action)
+    pub fn levels(&self) -> &[Blinds] {
+        &self.levels
+    }
+
+    pub fn duration(&self) -> LevelDuration {
+        self.duration
+    }
+
+    // Levels past the last one stay on it.
+    pub fn level(&self, level: usize) -> Blinds {
+        self.levels[level.min(self.levels.len() - 1)]
     }
 }
 
+// Running the board out `times` over when both players are all in before the river: every run
+// deals the rest of the board from the same deck, and wins a `times`th of the pot.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum ActionOver {
-    TurnOver,
-    RoundOver,
-    ShowdownAll,
-    ShowndownRiver,
-    HandOver,
+pub struct RunItTwice {
+    times: u8,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub struct HandHistory {
-    blind: u16,
-    button: bool,
-    init_stacks: [u32; 2],
-    events: Vec<ObservableEvent>,
-}
+impl RunItTwice {
+    // None for fewer than two runs, or more than the deck has cards for.
+    pub fn new(times: u8) -> Option<Self> {
+        (2..=4).contains(&times).then_some(Self { times })
+    }
 
-impl HandHistory {
-    pub fn replay(&self) -> HandReplay<'_> {
-        HandReplay {
-            events: self.events.iter(),
-            hand_state: HandState::new(self.blind, self.button, self.init_stacks),
-        }
+    pub fn times(&self) -> u8 {
+        self.times
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct HandReplay<'a> {
-    events: Iter<'a, ObservableEvent>,
-    hand_state: HandState,
+// Cash game side bet: winning a pot with seven-deuce, and showing it, collects `big_blinds` big
+// blinds from the opponent.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SevenDeuce {
+    big_blinds: u16,
 }
 
-impl<'a> HandReplay<'a> {
-    pub fn next_event(&mut self) -> Option<ObservableEvent> {
-        let event = self.events.next().copied();
-
-        if let Some(event) = event {
-            self.hand_state.event(event);
-        }
+impl SevenDeuce {
+    pub fn new(big_blinds: u16) -> Option<Self> {
+        (big_blinds > 0).then_some(Self { big_blinds })
+    }
 
-        event
+    pub fn big_blinds(&self) -> u16 {
+        self.big_blinds
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct HandState {
-    blind: u16,
-    button: bool,
-    init_stacks: [u32; 2],
-    pot: u32,
-    cur_turn: bool,
-    cur_round: [u32; 2],
-    behinds: [u32; 2],
-    last_bet: u32,
-    last_aggressor: bool,
-    opened: bool,
-    holes: [Option<Hole>; 2],
-    board: Board,
+// When a cash session ends early, checked after every hand. Stop-loss and stop-win count buy-ins
+// of the starting stack lost or won by either player, so only fractions below 1 can trigger.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct StopConditions {
+    stop_loss: Option<f64>,
+    stop_win: Option<f64>,
+    max_hands: Option<u32>,
+    max_duration: Option<Duration>,
 }
 
-impl HandState {
-    fn new(blind: u16, button: bool, init_stacks: [u32; 2]) -> Self {
-        Self {
-            blind,
-            button,
-            init_stacks,
-            pot: 0,
-            cur_turn: button,
-            cur_round: [0, 0],
-            behinds: init_stacks,
-            last_bet: 0,
-            last_aggressor: button,
-            opened: false,
-            holes: [None, None],
-            board: Default::default(),
-        }
+impl StopConditions {
+    pub fn with_stop_loss(mut self, buyins: f64) -> Self {
+        self.stop_loss = Some(buyins);
+        self
     }
 
-    fn next(&self, blind: u16) -> Self {
-        Self::new(blind, !self.button, self.behinds)
+    pub fn with_stop_win(mut self, buyins: f64) -> Self {
+        self.stop_win = Some(buyins);
+        self
     }
 
-    fn set_holes(&mut self, holes: [Hole; 2]) {
-        self.holes = [Some(holes[0]), Some(holes[1])];
+    pub fn with_max_hands(mut self, hands: u32) -> Self {
+        self.max_hands = Some(hands);
+        self
     }
 
-    fn big_blind(&self) -> u32 {
-        self.blind as u32
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
     }
 
-    fn can_check(&self) -> bool {
-        self.cur_round[0] == 0 && self.cur_round[1] == 0
+    pub fn stop_loss(&self) -> Option<f64> {
+        self.stop_loss
     }
 
-    // todo: river nuts
-    fn bet_bound(&self) -> BetBound {
-        let hero = if self.cur_turn { 0 } else { 1 };
-        let behind = self.behinds[hero];
+    pub fn stop_win(&self) -> Option<f64> {
+        self.stop_win
+    }
 
-        if self.can_check() {
-            let big_blind = self.big_blind();
+    pub fn max_hands(&self) -> Option<u32> {
+        self.max_hands
+    }
 
-            return if behind <= big_blind {
-                BetBound::FoldCheckAllIn
-            } else {
-                BetBound::FoldCheckBetAllIn(big_blind..=behind)
-            };
-        }
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+    }
 
-        let villain = 1 - hero;
-        let villain_bet = self.cur_round[villain];
+    // The first condition met by `stacks` after `hands` hands and `elapsed` time, checked in
+    // the order stop-loss, stop-win, hands, duration.
+    fn reason(&self, stacks: [Chips; 2], hands: u32, elapsed: Duration) -> Option<StopReason> {
+        let buyin = (stacks[0] + stacks[1]) / 2;
+        let loser = stacks[0] < stacks[1];
+        let lost = (buyin - stacks[0].min(stacks[1])).get() as f64 / buyin.get() as f64;
 
-        // cover
-        if behind <= villain_bet {
-            return BetBound::FoldAllIn;
+        if self
+            .stop_loss
+            .is_some_and(|buyins| lost > 0.0 && lost >= buyins)
+        {
+            Some(StopReason::StopLoss(loser))
+        } else if self
+            .stop_win
+            .is_some_and(|buyins| lost > 0.0 && lost >= buyins)
+        {
+            Some(StopReason::StopWin(!loser))
+        } else if self.max_hands.is_some_and(|max| hands >= max) {
+            Some(StopReason::MaxHands)
+        } else if self.max_duration.is_some_and(|max| elapsed >= max) {
+            Some(StopReason::MaxDuration)
+        } else {
+            None
         }
+    }
+}
 
-        // villain all in
-        if self.behinds[villain] == villain_bet {
-            return BetBound::FoldCall;
-        }
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StopReason {
+    StopLoss(bool), // the player who lost too much
+    StopWin(bool),  // the player who won enough
+    MaxHands,
+    MaxDuration,
+}
 
-        let min_raise = villain_bet + (villain_bet - self.last_bet);
+// House rules layered on the engine. Every callback gets the table as it stands and may move
+// chips between the players or queue events, which are sent after the engine's own.
+pub trait RuleHooks: Debug + Send {
+    fn hand_start(&mut self, _table: &mut RuleTable) {}
 
-        // call or all in
-        if behind <= min_raise {
-            return BetBound::FoldCallAllIn;
-        }
+    fn blinds_posted(&mut self, _table: &mut RuleTable) {}
 
-        BetBound::FoldCallRaiseAllIn(min_raise..=behind)
+    fn showdown(&mut self, _table: &mut RuleTable, _holes: [Hole; 2], _board: &FullBoard) {}
+
+    // `chips` is what each player took from the pot.
+    fn pot_awarded(&mut self, _table: &mut RuleTable, _chips: [Chips; 2]) {}
+}
+
+#[derive(Debug)]
+pub struct RuleTable<'a> {
+    state: &'a mut HandState,
+    hands: u32,
+    between_hands: bool,
+    moved: bool,
+    events: Vec<ObservableEvent>,
+}
+
+impl RuleTable<'_> {
+    // Hands played before this one.
+    pub fn hands(&self) -> u32 {
+        self.hands
     }
 
-    fn effective_behind(&self) -> u32 {
-        self.behinds[0].min(self.behinds[1])
+    pub fn button(&self) -> bool {
+        self.state.button
     }
 
-    fn deal_holes(&mut self, holes: [Hole; 2]) -> Option<(bool, BetBound)> {
-        self.set_holes(holes);
-        self.deal_holes_int()?;
-        Some((self.cur_turn, self.bet_bound()))
+    pub fn big_blind(&self) -> Chips {
+        self.state.big_blind()
     }
 
-    fn deal_holes_int(&mut self) -> Option<()> {
-        let effective_stack = self.effective_behind();
-        let big_blind = self.big_blind();
-        let small_blind = big_blind / 2;
+    // Chips each player has that are not in the pot or bet.
+    pub fn behinds(&self) -> [Chips; 2] {
+        [0, 1].map(|i| self.state.behinds[i] - self.state.cur_round[i])
+    }
 
-        // forced all in
-        if effective_stack <= small_blind {
-            self.pot += effective_stack * 2;
-            self.behinds[0] -= effective_stack;
-            self.behinds[1] -= effective_stack;
-            return None;
+    pub fn pot(&self) -> Chips {
+        self.state.total_pot()
+    }
+
+    // Moves `chips` from `from` to the other player, at most what they have behind. Returns the
+    // chips moved.
+    pub fn transfer(&mut self, from: bool, chips: Chips) -> Chips {
+        let (from, to) = if from { (0, 1) } else { (1, 0) };
+        let chips = chips.min(self.behinds()[from]);
+
+        self.state.behinds[from] -= chips;
+        self.state.behinds[to] += chips;
+        if self.between_hands {
+            self.state.init_stacks[from] -= chips;
+            self.state.init_stacks[to] += chips;
         }
+        self.moved |= !chips.is_zero();
+        chips
+    }
 
-        let sb = if self.button { 0 } else { 1 };
-        let bb = 1 - sb;
+    pub fn emit(&mut self, event: ObservableEvent) {
+        self.events.push(event);
+    }
+}
 
-        // blinds betting
-        self.cur_round[sb] = small_blind;
-        self.cur_round[bb] = big_blind.min(self.behinds[bb]);
+// How an event channel copes with a receiver that falls behind. Capacities count events.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Backpressure {
+    #[default]
+    Unbounded,
+    Await(usize),      // the game waits for room
+    DropOldest(usize), // the oldest events are dropped, for observers only
+    Abort(usize),      // a full channel counts as a crashed receiver
+}
 
-        Some(())
+impl Backpressure {
+    fn is_valid(self) -> bool {
+        match self {
+            Self::Unbounded => true,
+            Self::Await(capacity) | Self::DropOldest(capacity) | Self::Abort(capacity) => {
+                capacity > 0
+            }
+        }
     }
+}
 
-    fn action(&mut self, action: Action) -> ActionOver {
-        let hero = if self.cur_turn { 0 } else { 1 };
-        let villain = 1 - hero;
-
-        match action.value() {
-            ActionValue::Exit | ActionValue::Fold => {
-                let round_lose = self.cur_round[hero];
+// What a cancelled game does with the hand in progress before the abort game over goes out.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Shutdown {
+    #[default]
+    FinishHand,
+    AbortHand, // the hand is void, stacks go back to where it started
+}
 
-                self.behinds[hero] -= round_lose;
-                self.behinds[villain] += round_lose + self.pot;
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Channels {
+    pub players: Backpressure,
+    pub observer: Backpressure,
+}
 
-                ActionOver::HandOver
-            }
-            ActionValue::BetOrRaise(amount) => {
-                self.last_aggressor = self.cur_turn;
-                self.cur_round[hero] = amount;
-                self.last_bet = self.cur_round[villain];
-                self.cur_turn = !self.cur_turn;
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Visibility {
+    None,
+    Player(bool), // true for player 0, false for player 1
+    God,
+}
 
-                if self.board.is_preflop() {
-                    self.opened = true;
-                }
+// The name a house rule gives its events. An alias rather than `&'static str` written out, which
+// serde's derive would try to borrow from the input instead of calling `deserialize_with`.
+pub type RuleName = &'static str;
 
-                ActionOver::TurnOver
-            }
-            ActionValue::AllIn => {
-                let hero_behind = self.behinds[hero];
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ObservableEvent {
+    BombPot(Chips), // each player's ante, sent before the holes of a bomb pot
+    DealHoles([Option<Hole>; 2]),
+    ShowdownAll([Hole; 2]),
+    ShowdownAuto([Hole; 2]),  // board nuts auto chop
+    EarlyAward(Option<bool>), // winner no runout could change, None for a chop
+    TimedOut(bool),           // the player ran out of time, the action taken for them follows
+    ShowdownShow(bool, Hole), // river showdown, one event per player in showdown order
+    ShowdownMuck(bool),
+    ShowCards(bool, [Option<Card>; 2]), // cards the winner of an uncontested pot chose to show
+    DealBoard(Board),                   // the whole board so far, sent as each street is dealt
+    // The blinds going up in a sit and go or a game with a blind structure, before anything else
+    // of the level's first hand: the level counting from 1, its blinds and how many hands it
+    // lasts, None for the rest of the game or a level that lasts a time.
+    BlindLevel {
+        level: u8,
+        blinds: Blinds,
+        hands: Option<u16>,
+    },
+    BoardVariant(u8, Board), // the full board of one run when running it more than once
+    // Each player's share of the pot over every runout, after the holes of an all in from the
+    // flop or turn are shown and before the rest of the board is dealt.
+    AllInEquity([PotShare; 2]),
+    Checksum(u64), // the table's chips and board, sent when consistency checks are on
+    Insured {
+        seat: bool,
+        payout: Chips,
+    },
+    // The pot paid out on the full board, before the stacks: what each player got, uncalled bets
+    // not counted, and the hand that won it, or that both held for a chop.
+    PotAwarded {
+        amounts: [Chips; 2],
+        hand: HandValue,
+    },
+    SevenDeuce {
+        seat: bool,
+        bonus: Chips,
+    },
+    Rake([Chips; 2]), // what each player paid the house of the pot just won, before the stacks
+    // Chips `seat` got from the opponent by a house rule.
+    HouseRule {
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "crate::gameplay::wire::static_name")
+        )]
+        name: RuleName,
+        seat: bool,
+        chips: Chips,
+    },
+    PlayerAction(Action, TurnId), // the turn it answered, by the game or a player
+    // Where the game is between hands: hands played, and the button, big blind and stacks of the
+    // next one. The first event of an observer joining after hands were played.
+    CatchUp {
+        hands: u32,
+        button: bool,
+        big_blind: Chips,
+        stacks: [Chips; 2],
+    },
+    // Chips behind each player, their bets of the running round and the pot of finished rounds;
+    // sent after the blinds, after every action moving chips and after the pot is awarded.
+    Stacks {
+        behinds: [Chips; 2],
+        bets: [Chips; 2],
+        pot: Chips,
+    },
+    GameOver(GameOver),
+}
 
-                if hero_behind > self.cur_round[villain] {
-                    // active all in
-                    self.last_aggressor = self.cur_turn;
-                    self.cur_round[hero] = hero_behind;
-                    self.cur_turn = !self.cur_turn;
+impl ObservableEvent {
+    // The seat the event names, if any; actions name nobody, their actor is whose turn it was.
+    fn seat(&self) -> Option<bool> {
+        match *self {
+            Self::TimedOut(seat)
+            | Self::ShowdownShow(seat, _)
+            | Self::ShowdownMuck(seat)
+            | Self::ShowCards(seat, _)
+            | Self::Insured { seat, .. }
+            | Self::SevenDeuce { seat, .. }
+            | Self::HouseRule { seat, .. } => Some(seat),
+            Self::EarlyAward(winner) => winner,
+            _ => None,
+        }
+    }
+}
+
+// An event with where it happened: the hand counting from 1, the street once it's applied and
+// the seat it's about, the actor of an action. Put together by the game as it sends the event, so
+// observers, stats and logs needn't work it out from the order of events.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandEvent {
+    pub hand: u32,
+    pub seat: Option<bool>,
+    pub street: Street,
+    pub event: ObservableEvent,
+}
+
+// A share of the pot, 0 to 1, as events carry it: compared and hashed by its bits, so events
+// stay `Eq` and `Hash`.
+#[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct PotShare(pub f32);
+
+impl PartialEq for PotShare {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for PotShare {}
+
+impl Hash for PotShare {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlayerEvent {
+    Observable(ObservableEvent),
+    HeroTurn(BetBound, TurnId),
+    ShowOffer,             // the hand was won uncontested, answer with `send_show`
+    InsuranceOffer(Chips), // chips to cash out the all in for, answer with `send_insurance`
+}
+
+impl PlayerEvent {
+    const fn unwrap_observable(self) -> ObservableEvent {
+        match self {
+            Self::Observable(observable) => observable,
+            Self::HeroTurn(..) | Self::ShowOffer | Self::InsuranceOffer(_) => unreachable!(),
+        }
+    }
+}
+
+// Pairs a `HeroTurn` with the `PlayerAction` answering it, the player's own or the one the game
+// took when their time ran out, so answers that crossed a timeout or a reconnection can be told
+// apart: the game's count of turns before it.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct TurnId(u32);
+
+impl TurnId {
+    pub const fn new(turn: u32) -> Self {
+        Self(turn)
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    const fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+#[derive(Debug)]
+enum InternalEvent {
+    Observable(HandEvent),
+    HeroTurn(BetBound, TurnId, Sender<Action>),
+    ShowOffer(Sender<ShowCards>),
+    InsuranceOffer(Chips, Sender<bool>),
+}
+
+#[derive(Debug)]
+pub struct Player {
+    game_type: GameType,
+    visibility: Visibility,
+    recv: EventReceiver,
+    hero_turn: Option<(BetBound, Sender<Action>)>,
+    show_offer: Option<Sender<ShowCards>>,
+    insurance_offer: Option<Sender<bool>>,
+    heads_up: HeadsUp,
+    hands_history: Vec<HandHistory>,
+    last_event_at: Option<Duration>,
+    hand_event: Option<HandEvent>,
+    identities: SharedIdentities,
+}
+
+impl Player {
+    fn new(
+        game_type: GameType,
+        visibility: Visibility,
+        recv: EventReceiver,
+        button: bool,
+        identities: SharedIdentities,
+    ) -> Self {
+        Self {
+            game_type,
+            visibility,
+            recv,
+            hero_turn: None,
+            show_offer: None,
+            insurance_offer: None,
+            heads_up: HeadsUp::new(game_type, button),
+            hands_history: Default::default(),
+            last_event_at: None,
+            hand_event: None,
+            identities,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.heads_up.is_over()
+    }
+
+    pub fn game_over(&self) -> Option<GameResult> {
+        self.heads_up.game_over()
+    }
+
+    pub fn hands_history(&self) -> &[HandHistory] {
+        &self.hands_history
+    }
+
+    // Who sits where, as the game has them now.
+    pub fn identities(&self) -> Identities {
+        self.identities.get()
+    }
+
+    // Pots and bets by street of every hand seen so far, the current one last.
+    pub fn street_histories(&self) -> &[StreetHistory] {
+        &self.heads_up.street_histories
+    }
+
+    pub(crate) fn seat(&self) -> Option<bool> {
+        match self.visibility {
+            Visibility::Player(seat) => Some(seat),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn hand_state(&self) -> HandState {
+        self.heads_up.hand_state
+    }
+
+    // What this player may know of the game right now. None for observers.
+    pub fn view(&self) -> Option<GameView> {
+        GameView::new(self)
+    }
+
+    // When the last event was sent, on the game's clock.
+    pub fn last_event_at(&self) -> Option<Duration> {
+        self.last_event_at
+    }
+
+    // The last observable event with its hand, street and seat.
+    pub fn hand_event(&self) -> Option<HandEvent> {
+        self.hand_event
+    }
+
+    // With consistency checks on, the hand where this player's state first stopped matching the
+    // table's checksum, and the checksums. The events keep coming either way.
+    pub fn desync(&self) -> Option<(u32, &StateDiff)> {
+        let (hand, diff) = self.heads_up.desync.as_ref()?;
+        Some((*hand, diff))
+    }
+
+    // Cancel safe: an event is only taken off the channel in the poll that returns it, so the
+    // future can be dropped in a `select!` without losing anything.
+    pub async fn tick_event(&mut self) -> Option<PlayerEvent> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    // `tick_event` with the time the game sent the event at.
+    pub async fn tick_timed_event(&mut self) -> Option<Timed<PlayerEvent>> {
+        poll_fn(|cx| self.poll_timed(cx)).await
+    }
+
+    fn poll_timed(&mut self, cx: &mut Context<'_>) -> Poll<Option<Timed<PlayerEvent>>> {
+        if self.is_over() {
+            return Poll::Ready(None);
+        }
+
+        let event = ready!(self.recv.poll_recv(cx));
+        Poll::Ready(Some(self.receive(event)))
+    }
+
+    // `tick_event` giving up after `timeout`, for loops with heartbeats or redraws to do.
+    pub async fn tick_event_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<PlayerEvent>, Elapsed> {
+        time::timeout(timeout, self.tick_event()).await
+    }
+
+    // A closed channel aborts the game, stamped as of the last event.
+    fn receive(&mut self, event: Option<Timed<InternalEvent>>) -> Timed<PlayerEvent> {
+        let Timed { at, event } = event.unwrap_or_else(|| {
+            let abort = ObservableEvent::GameOver(self.heads_up.abort());
+            Timed::new(
+                self.last_event_at.unwrap_or_default(),
+                InternalEvent::Observable(self.heads_up.hand_event(abort, None)),
+            )
+        });
+        self.last_event_at = Some(at);
+
+        let event = match event {
+            InternalEvent::Observable(hand_event) => {
+                self.hero_turn = None;
+                self.record(Timed::new(at, hand_event));
+                PlayerEvent::Observable(hand_event.event)
+            }
+            InternalEvent::HeroTurn(bet_bound, turn, sender) => {
+                self.hero_turn = Some((bet_bound.clone(), sender));
+                PlayerEvent::HeroTurn(bet_bound, turn)
+            }
+            InternalEvent::ShowOffer(sender) => {
+                self.show_offer = Some(sender);
+                PlayerEvent::ShowOffer
+            }
+            InternalEvent::InsuranceOffer(payout, sender) => {
+                self.insurance_offer = Some(sender);
+                PlayerEvent::InsuranceOffer(payout)
+            }
+        };
+
+        Timed::new(at, event)
+    }
+
+    fn record(&mut self, event: Timed<HandEvent>) {
+        let Timed { at, event } = event;
+        if let Some(hand_history) = self.heads_up.event(Timed::new(at, event.event)) {
+            self.hands_history
+                .push(hand_history.with_identities(self.identities.get()));
+        }
+        self.hand_event = Some(event);
+    }
+
+    fn abort(&mut self) -> GameOver {
+        let game_over = self.heads_up.abort();
+        let at = self.last_event_at.unwrap_or_default();
+        let event = ObservableEvent::GameOver(game_over);
+        self.record(Timed::new(at, self.heads_up.hand_event(event, None)));
+        game_over
+    }
+
+    // Why an answer found nobody waiting: a game still sending events went on without it, else
+    // the game is gone.
+    fn late(&mut self) -> ActionSendError {
+        if self.recv.is_closed() {
+            ActionSendError::GameAbort(self.abort())
+        } else {
+            ActionSendError::TimedOut
+        }
+    }
+
+    pub fn send_action(&mut self, action: Action) -> Result<(), ActionSendError> {
+        if self.hero_turn.is_none() {
+            return Err(ActionSendError::NotHeroTurn);
+        }
+
+        let Some(action) = self
+            .hero_turn
+            .as_ref()
+            .expect("hero_turn should to be Some here")
+            .0
+            .alter_eq(action, self.heads_up.hand_state.bet_unit())
+        else {
+            return Err(ActionSendError::InvalidAction);
+        };
+
+        if self
+            .hero_turn
+            .take()
+            .expect("hero_turn should to be Some here")
+            .1
+            .send(action)
+            .is_err()
+        {
+            return Err(self.late());
+        }
+
+        Ok(())
+    }
+
+    pub fn parse_send_action(&mut self, action: &str) -> Result<(), ActionSendError> {
+        self.send_action(action.parse().map_err(|_| ActionSendError::InvalidAction)?)
+    }
+
+    pub fn send_show(&mut self, show: ShowCards) -> Result<(), ActionSendError> {
+        let Some(sender) = self.show_offer.take() else {
+            return Err(ActionSendError::NotHeroTurn);
+        };
+
+        if sender.send(show).is_err() {
+            return Err(self.late());
+        }
+
+        Ok(())
+    }
+
+    pub fn send_insurance(&mut self, take: bool) -> Result<(), ActionSendError> {
+        let Some(sender) = self.insurance_offer.take() else {
+            return Err(ActionSendError::NotHeroTurn);
+        };
+
+        if sender.send(take).is_err() {
+            return Err(self.late());
+        }
+
+        Ok(())
+    }
+
+    pub fn parse_send_show(&mut self, show: &str) -> Result<(), ActionSendError> {
+        self.send_show(show.parse().map_err(|_| ActionSendError::InvalidAction)?)
+    }
+
+    pub fn game_type(&self) -> GameType {
+        self.game_type
+    }
+
+    #[cfg(feature = "net")]
+    pub(crate) fn button(&self) -> bool {
+        self.heads_up.hand_state.button
+    }
+
+    #[cfg(feature = "net")]
+    // A player of a game running elsewhere, e.g. across a network: the game's events are pushed
+    // in through the `Relay` and the answers come back out of it.
+    pub(crate) fn relay(game_type: GameType, seat: bool, button: bool) -> (Self, Relay) {
+        let (send, recv) = unbounded_channel();
+        let player = Self::new(
+            game_type,
+            Visibility::Player(seat),
+            EventReceiver::Unbounded(recv),
+            button,
+            Default::default(),
+        );
+        (player, Relay(send))
+    }
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug)]
+pub(crate) struct Relay(UnboundedSender<Timed<InternalEvent>>);
+
+#[cfg(feature = "net")]
+impl Relay {
+    // One of the game's events, as the game put it together. Events for a dropped player go
+    // nowhere.
+    pub(crate) fn push_event(&self, event: Timed<HandEvent>) {
+        let _ = self.0.send(event.map(InternalEvent::Observable));
+    }
+
+    // What the player is asked to answer, if anything. The game's events come through
+    // `push_event`, with where they happened.
+    pub(crate) fn push(&self, event: Timed<PlayerEvent>) -> Option<Prompt> {
+        let Timed { at, event } = event;
+        let (event, prompt) = match event {
+            PlayerEvent::Observable(_) => return None,
+            PlayerEvent::HeroTurn(bet_bound, turn) => {
+                let (send, recv) = channel();
+                (
+                    InternalEvent::HeroTurn(bet_bound, turn, send),
+                    Some(Prompt::Action(recv)),
+                )
+            }
+            PlayerEvent::ShowOffer => {
+                let (send, recv) = channel();
+                (InternalEvent::ShowOffer(send), Some(Prompt::Show(recv)))
+            }
+            PlayerEvent::InsuranceOffer(payout) => {
+                let (send, recv) = channel();
+                (
+                    InternalEvent::InsuranceOffer(payout, send),
+                    Some(Prompt::Insurance(recv)),
+                )
+            }
+        };
+        self.0.send(Timed::new(at, event)).ok()?;
+        prompt
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug)]
+pub(crate) enum Prompt {
+    Action(tokio::sync::oneshot::Receiver<Action>),
+    Show(tokio::sync::oneshot::Receiver<ShowCards>),
+    Insurance(tokio::sync::oneshot::Receiver<bool>),
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub(crate) enum Answer {
+    Action(Action),
+    Show(ShowCards),
+    Insurance(bool),
+}
+
+#[cfg(feature = "net")]
+impl Prompt {
+    // Cancel safe. None once the player is dropped or has moved on to a newer prompt.
+    pub(crate) async fn answer(&mut self) -> Option<Answer> {
+        match self {
+            Self::Action(recv) => recv.await.ok().map(Answer::Action),
+            Self::Show(recv) => recv.await.ok().map(Answer::Show),
+            Self::Insurance(recv) => recv.await.ok().map(Answer::Insurance),
+        }
+    }
+}
+
+// A player's snapshot of the game, with nothing of the opponent they haven't been shown. Pairs
+// are [hero, villain].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameView {
+    seat: bool,
+    button: bool,
+    hole: Option<Hole>,
+    shown: [Option<Card>; 2], // opponent cards seen this hand
+    board: Board,
+    street: Street,
+    big_blind: Chips,
+    hands_until_next_level: Option<u16>,
+    pot: Chips,
+    bets: [Chips; 2],            // this round
+    stacks: [Chips; 2],          // behind
+    history: Vec<ActionRecord>,  // this hand so far
+    bet_bound: Option<BetBound>, // Some on hero's turn
+    bet_unit: Chips,
+}
+
+impl GameView {
+    fn new(player: &Player) -> Option<Self> {
+        let seat = player.seat()?;
+        let (hero, villain) = if seat { (0, 1) } else { (1, 0) };
+        let state = player.hand_state();
+        let events = &player.heads_up.events;
+
+        let hand_start = events
+            .iter()
+            .rposition(|event| matches!(event, ObservableEvent::DealHoles(_)))
+            .unwrap_or(0);
+        let hand = &events[hand_start..];
+
+        let mut walker = LineWalker::new(state.button());
+        let history = hand.iter().filter_map(|event| walker.step(event)).collect();
+
+        let shown = match state.holes()[villain] {
+            Some(hole) => [Some(hole[0]), Some(hole[1])],
+            None => hand
+                .iter()
+                .rev()
+                .find_map(|event| match event {
+                    ObservableEvent::ShowCards(shower, cards) if *shower != seat => Some(*cards),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+        };
+
+        let bets = state.cur_round();
+        let behinds = [0, 1].map(|i| state.behinds()[i] - bets[i]);
+
+        Some(Self {
+            seat,
+            button: state.button() == seat,
+            hole: state.holes()[hero],
+            shown,
+            board: state.board(),
+            street: walker.street(),
+            big_blind: state.big_blind(),
+            hands_until_next_level: player.heads_up.hands_until_next_level(),
+            pot: state.total_pot(),
+            bets: [bets[hero], bets[villain]],
+            stacks: [behinds[hero], behinds[villain]],
+            history,
+            bet_bound: player
+                .hero_turn
+                .as_ref()
+                .map(|(bet_bound, _)| bet_bound.clone()),
+            bet_unit: state.bet_unit(),
+        })
+    }
+
+    pub fn seat(&self) -> bool {
+        self.seat
+    }
+
+    pub fn is_button(&self) -> bool {
+        self.button
+    }
+
+    pub fn hole(&self) -> Option<Hole> {
+        self.hole
+    }
+
+    pub fn shown(&self) -> [Option<Card>; 2] {
+        self.shown
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn big_blind(&self) -> Chips {
+        self.big_blind
+    }
+
+    // Hands left at this blind level, the current one included. None when the blinds won't go up.
+    pub fn hands_until_next_level(&self) -> Option<u16> {
+        self.hands_until_next_level
+    }
+
+    pub fn pot(&self) -> Chips {
+        self.pot
+    }
+
+    pub fn bets(&self) -> [Chips; 2] {
+        self.bets
+    }
+
+    pub fn stacks(&self) -> [Chips; 2] {
+        self.stacks
+    }
+
+    pub fn history(&self) -> &[ActionRecord] {
+        &self.history
+    }
+
+    pub fn bet_bound(&self) -> Option<&BetBound> {
+        self.bet_bound.as_ref()
+    }
+
+    pub fn bet_unit(&self) -> Chips {
+        self.bet_unit
+    }
+
+    pub fn to_call(&self) -> Chips {
+        self.bets[1].saturating_sub(self.bets[0])
+    }
+
+    // Every size a unit apart on hero's turn, none otherwise.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let actions = self
+            .bet_bound
+            .iter()
+            .flat_map(|bound| bound.legal_actions(self.bet_unit));
+        actions.collect()
+    }
+
+    // For reading and writing sizes in big blinds or of the pot.
+    pub fn bet_context(&self) -> BetContext {
+        BetContext::new(self.pot, self.big_blind, self.bets).with_unit(self.bet_unit)
+    }
+}
+
+#[derive(Debug)]
+pub struct Observer(Player);
+
+impl Observer {
+    pub fn is_over(&self) -> bool {
+        self.0.is_over()
+    }
+
+    pub fn game_over(&self) -> Option<GameResult> {
+        self.0.game_over()
+    }
+
+    pub fn desync(&self) -> Option<(u32, &StateDiff)> {
+        self.0.desync()
+    }
+
+    // The button of the hand the last event was in, e.g. for `range::SpectatorRanges`.
+    pub fn button(&self) -> bool {
+        self.0.heads_up.hand_state.button
+    }
+
+    pub async fn tick_event(&mut self) -> Option<ObservableEvent> {
+        self.0
+            .tick_event()
+            .await
+            .map(PlayerEvent::unwrap_observable)
+    }
+
+    pub async fn tick_event_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<ObservableEvent>, Elapsed> {
+        time::timeout(timeout, self.tick_event()).await
+    }
+
+    pub async fn tick_timed_event(&mut self) -> Option<Timed<ObservableEvent>> {
+        self.0
+            .tick_timed_event()
+            .await
+            .map(|timed| timed.map(PlayerEvent::unwrap_observable))
+    }
+
+    pub fn last_event_at(&self) -> Option<Duration> {
+        self.0.last_event_at()
+    }
+
+    // `tick_event` with the hand, street and seat of the event.
+    pub async fn tick_hand_event(&mut self) -> Option<HandEvent> {
+        self.tick_event().await?;
+        self.0.hand_event()
+    }
+
+    pub fn hands_history(&self) -> &[HandHistory] {
+        self.0.hands_history()
+    }
+
+    pub fn identities(&self) -> Identities {
+        self.0.identities()
+    }
+}
+
+// Events until the game is over, the same ones `tick_event` gives.
+impl Stream for Player {
+    type Item = PlayerEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .poll_timed(cx)
+            .map(|event| event.map(|timed| timed.event))
+    }
+}
+
+impl Stream for Observer {
+    type Item = ObservableEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|event| event.map(PlayerEvent::unwrap_observable))
+    }
+}
+
+// Cash games check out with the stacks, then the rake each player paid over the session.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameOver {
+    Defeated(bool),
+    ExitAbandon(bool),
+    ExitCheckout(bool, [Chips; 2], [Chips; 2]),
+    AbortCheckout([Chips; 2], [Chips; 2]),
+    HandsReached([Chips; 2], [Chips; 2]),
+    SessionStop(StopReason, [Chips; 2], [Chips; 2]),
+    GameAbort,
+}
+
+// What a game came to: the stacks it ended with and each player's net from the starting stack,
+// the hands dealt, the blind level counting from 1 and the time since the first deal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameResult {
+    pub reason: GameOver,
+    pub stacks: [Chips; 2],
+    pub net: [i64; 2],
+    pub hands: u32,
+    pub level: u8,
+    pub elapsed: Duration,
+}
+
+// Takes over seats of a running game, see `Game::reconnects`.
+#[derive(Debug, Clone)]
+pub struct Reconnects(UnboundedSender<(bool, Sender<Player>)>);
+
+impl Reconnects {
+    // A fresh player for `seat`, caught up to the hand being played, once the game finds the
+    // seat's player gone. None if the game ended or gave the seat up first.
+    pub async fn reconnect(&self, seat: bool) -> Option<Player> {
+        let (send, recv) = channel();
+        self.0.send((seat, send)).ok()?;
+        recv.await.ok()
+    }
+}
+
+// The game's side of `Reconnects`: requests not yet wanted wait by seat until their player is
+// found gone.
+#[derive(Debug)]
+struct SeatRequests {
+    send: UnboundedSender<(bool, Sender<Player>)>,
+    recv: UnboundedReceiver<(bool, Sender<Player>)>,
+    pending: [Option<Sender<Player>>; 2],
+    grace: Duration,
+}
+
+impl SeatRequests {
+    fn new(grace: Duration) -> Self {
+        let (send, recv) = unbounded_channel();
+        Self {
+            send,
+            recv,
+            pending: [None, None],
+            grace,
+        }
+    }
+
+    // Someone taking over `seat` within the grace, None if nobody asks in time.
+    async fn wait(&mut self, seat: bool) -> Option<Sender<Player>> {
+        let wait = async {
+            loop {
+                let pending = self.pending[if seat { 0 } else { 1 }].take();
+                if let Some(reply) = pending.filter(|reply| !reply.is_closed()) {
+                    return Some(reply);
+                }
+                let (requested, reply) = self.recv.recv().await?;
+                self.pending[if requested { 0 } else { 1 }] = Some(reply);
+            }
+        };
+        time::timeout(self.grace, wait).await.ok().flatten()
+    }
+}
+
+fn event_channel(backpressure: Backpressure) -> (EventSender, EventReceiver) {
+    match backpressure {
+        Backpressure::Unbounded => {
+            let (send, recv) = unbounded_channel();
+            (EventSender::Unbounded(send), EventReceiver::Unbounded(recv))
+        }
+        Backpressure::Await(capacity) | Backpressure::Abort(capacity) => {
+            let (send, recv) = mpsc::channel(capacity);
+            let wait = matches!(backpressure, Backpressure::Await(_));
+            (
+                EventSender::Bounded(send, wait),
+                EventReceiver::Bounded(recv),
+            )
+        }
+        Backpressure::DropOldest(capacity) => {
+            let (send, recv) = broadcast::channel(capacity);
+            (
+                EventSender::Lossy(send),
+                EventReceiver::Lossy(BroadcastStream::new(recv)),
+            )
+        }
+    }
+}
+
+#[derive(Debug)]
+enum EventSender {
+    Unbounded(UnboundedSender<Timed<InternalEvent>>),
+    Bounded(mpsc::Sender<Timed<InternalEvent>>, bool), // whether to wait for room instead of failing
+    Lossy(broadcast::Sender<Timed<HandEvent>>),
+}
+
+impl EventSender {
+    async fn send(&self, event: Timed<InternalEvent>) -> bool {
+        match self {
+            Self::Unbounded(send) => send.send(event).is_ok(),
+            Self::Bounded(send, true) => send.send(event).await.is_ok(),
+            _ => self.try_send(event),
+        }
+    }
+
+    // Sends without waiting, failing when a bounded channel is full.
+    fn try_send(&self, event: Timed<InternalEvent>) -> bool {
+        match self {
+            Self::Unbounded(send) => send.send(event).is_ok(),
+            Self::Bounded(send, _) => send.try_send(event).is_ok(),
+            Self::Lossy(send) => match event.event {
+                InternalEvent::Observable(observable) => {
+                    send.send(Timed::new(event.at, observable)).is_ok()
+                }
+                _ => false, // observers are never asked anything
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+enum EventReceiver {
+    Unbounded(UnboundedReceiver<Timed<InternalEvent>>),
+    Bounded(mpsc::Receiver<Timed<InternalEvent>>),
+    Lossy(BroadcastStream<Timed<HandEvent>>),
+}
+
+impl EventReceiver {
+    // Whether the game's end is gone, i.e. the game is over or dropped.
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Unbounded(recv) => recv.is_closed(),
+            Self::Bounded(recv) => recv.is_closed(),
+            Self::Lossy(_) => false, // observers never answer
+        }
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<Timed<InternalEvent>>> {
+        match self {
+            Self::Unbounded(recv) => recv.poll_recv(cx),
+            Self::Bounded(recv) => recv.poll_recv(cx),
+            Self::Lossy(recv) => loop {
+                match ready!(Pin::new(&mut *recv).poll_next(cx)) {
+                    Some(Ok(event)) => {
+                        return Poll::Ready(Some(event.map(InternalEvent::Observable)));
+                    }
+                    Some(Err(_)) => continue, // lagged behind, the oldest events are gone
+                    None => return Poll::Ready(None),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PlayerSender {
+    visibility: Visibility,
+    send: EventSender,
+    clock: Arc<dyn Clock>,
+}
+
+impl PlayerSender {
+    async fn send(&self, event: HandEvent) -> bool {
+        let event = HandEvent {
+            event: self.visible(event.event),
+            ..event
+        };
+        self.stamp_send(InternalEvent::Observable(event)).await
+    }
+
+    async fn stamp_send(&self, event: InternalEvent) -> bool {
+        self.send.send(Timed::new(self.clock.now(), event)).await
+    }
+
+    fn try_send(&self, event: HandEvent) -> bool {
+        let event = InternalEvent::Observable(HandEvent {
+            event: self.visible(event.event),
+            ..event
+        });
+        self.send.try_send(Timed::new(self.clock.now(), event))
+    }
+
+    // Dealt holes are private: players see their own, God sees both.
+    fn visible(&self, event: ObservableEvent) -> ObservableEvent {
+        match (event, self.visibility) {
+            (ObservableEvent::DealHoles(_), Visibility::God) => event,
+            (ObservableEvent::DealHoles(holes), Visibility::Player(seat)) => {
+                ObservableEvent::DealHoles(if seat {
+                    [holes[0], None]
+                } else {
+                    [None, holes[1]]
+                })
+            }
+            (ObservableEvent::DealHoles(_), Visibility::None) => {
+                ObservableEvent::DealHoles([None, None])
+            }
+            _ => event,
+        }
+    }
+
+    async fn turn(&self, bet_bound: BetBound, turn: TurnId) -> Option<Action> {
+        let (send, recv) = channel();
+
+        if !self
+            .stamp_send(InternalEvent::HeroTurn(bet_bound, turn, send))
+            .await
+        {
+            return None; // Player crashed
+        }
+
+        recv.await.ok()
+    }
+
+    async fn insurance_offer(&self, payout: Chips) -> Option<bool> {
+        let (send, recv) = channel();
+
+        if !self
+            .stamp_send(InternalEvent::InsuranceOffer(payout, send))
+            .await
+        {
+            return None; // Player crashed
+        }
+
+        recv.await.ok()
+    }
+
+    async fn show_offer(&self) -> Option<ShowCards> {
+        let (send, recv) = channel();
+
+        if !self.stamp_send(InternalEvent::ShowOffer(send)).await {
+            return None; // Player crashed
+        }
+
+        recv.await.ok()
+    }
+}
+
+// todo: make private, inside run_hand
+// Room for the full deck, of which only the first `len` cards are dealt: a variant without some
+// ranks and removed dead cards keep the rest at the end.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Deck {
+    cards: [Card; 52],
+    len: usize,
+    variant: Variant,
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new(Variant::Holdem)
+    }
+}
+
+impl Deck {
+    // Ranks the variant leaves out go last and are never dealt.
+    pub fn new(variant: Variant) -> Self {
+        let left_out = Value::all().filter(|&value| value < variant.lowest());
+        let values = variant.values().chain(left_out);
+        let mut cards = [Default::default(); 52];
+        for (slot, (value, suit)) in cards.iter_mut().zip(values.cartesian_product(Suit::ALL)) {
+            *slot = Card(value, suit);
+        }
+
+        Self {
+            cards,
+            len: variant.deck_size(),
+            variant,
+        }
+    }
+
+    // A full Hold'em deck in random order.
+    pub fn shuffled() -> Self {
+        let mut deck = Self::default();
+        deck.shuffle();
+        deck
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards[..self.len]
+    }
+
+    // Cards still to be dealt from a new dealer.
+    pub fn remaining(&self) -> usize {
+        self.len
+    }
+
+    // Takes out dead cards, e.g. known holes or a board, so they are never dealt. Cards not in
+    // the deck are ignored; the rest keep their order.
+    pub fn remove(&mut self, dead: &[Card]) {
+        let (mut cards, removed): (Vec<Card>, Vec<Card>) =
+            self.cards().iter().partition(|card| !dead.contains(card));
+        let len = cards.len();
+        cards.extend(removed);
+        cards.extend_from_slice(&self.cards[self.len..]);
+        self.cards = cards.try_into().expect("The deck keeps all 52 cards");
+        self.len = len;
+    }
+
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rand::rng());
+    }
+
+    pub fn shuffle_with(&mut self, rng: &mut impl Rng) {
+        self.cards[..self.len].shuffle(rng);
+    }
+
+    // Deals the cards in their current order, the deck itself is left as it is.
+    pub fn dealer(&self) -> Dealer {
+        Dealer(self.cards.into_iter().take(self.len))
+    }
+
+    // A Hold'em deck dealing `cards` in their order, top card first. None unless they are all 52
+    // cards once each.
+    pub fn stacked(cards: &[Card]) -> Option<Self> {
+        let cards: [Card; 52] = cards.try_into().ok()?;
+        let mut seen = 0u64;
+        for card in cards {
+            let bit = 1 << card.as_u8();
+            if seen & bit != 0 {
+                return None;
+            }
+            seen |= bit;
+        }
+        Some(Self {
+            cards,
+            len: 52,
+            variant: Variant::Holdem,
+        })
+    }
+
+    pub fn shuffle_and_deal(&mut self) -> Dealer {
+        self.shuffle_and_deal_with(&mut rand::rng())
+    }
+
+    pub fn shuffle_and_deal_with(&mut self, rng: &mut impl Rng) -> Dealer {
+        self.shuffle_with(rng);
+        self.dealer()
+    }
+
+    // Hand `hand` of a match seeded with `seed`: the deck in new deck order shuffled by the ChaCha
+    // stream the seed keys and the hand numbers, so any hand of a long match deals again on its
+    // own.
+    pub fn shuffle_and_deal_seeded(&mut self, seed: u64, hand: u64) -> Dealer {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        rng.set_stream(hand);
+        self.cards[..self.len].sort_by_key(|card| card.as_u8());
+        self.shuffle_and_deal_with(&mut rng)
+    }
+
+    // Shuffles `iterations` decks and checks every card is as likely at every position.
+    pub fn audit(iterations: u32) -> ShuffleAudit {
+        Self::audit_with(iterations, &mut rand::rng())
+    }
+
+    pub fn audit_with(iterations: u32, rng: &mut impl Rng) -> ShuffleAudit {
+        let mut counts = [[0u32; 52]; 52]; // [position][card]
+        for _ in 0..iterations {
+            // from a new deck each time, or a fixed permutation applied over and over would pass
+            let dealer = Self::default().shuffle_and_deal_with(rng);
+            for (position, card) in dealer.0.enumerate() {
+                counts[position][card.as_u8() as usize] += 1;
+            }
+        }
+        ShuffleAudit::new(iterations, &counts)
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.cards().contains(&card)
+    }
+
+    // 13 cards per row in dealing order unless configured otherwise.
+    pub fn display(self, mode: DisplayMode) -> DeckDisplay {
+        DeckDisplay {
+            deck: self,
+            mode,
+            per_row: 13,
+            order: DeckOrder::Dealt,
+        }
+    }
+}
+
+// Goodness of fit of card positions to uniform over many shuffles: a chi-square over the whole
+// position by card table, and the worst Kolmogorov-Smirnov distance of one position's cards.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ShuffleAudit {
+    pub iterations: u32,
+    pub chi_square: f64,
+    pub degrees_of_freedom: u32,
+    pub chi_square_z: f64, // Wilson-Hilferty normal approximation, large for a biased shuffle
+    pub ks_distance: f64,
+    pub ks_statistic: f64, // `ks_distance` scaled by the root of the iterations
+}
+
+impl ShuffleAudit {
+    // Loose enough to pass a fair shuffle nearly always: 5 sigma for the chi-square, and a
+    // KS critical value for about 1 in 100000 over all 52 positions.
+    const MAX_Z: f64 = 5.0;
+    const MAX_KS: f64 = 2.8;
+
+    fn new(iterations: u32, counts: &[[u32; 52]; 52]) -> Self {
+        let n = iterations as f64;
+        let expected = n / 52.0;
+        let chi_square = if iterations == 0 {
+            0.0
+        } else {
+            counts
+                .iter()
+                .flatten()
+                .map(|&count| (count as f64 - expected).powi(2) / expected)
+                .sum()
+        };
+
+        let k = 51.0 * 51.0;
+        let t = 2.0 / (9.0 * k);
+        let chi_square_z = ((chi_square / k).cbrt() - (1.0 - t)) / t.sqrt();
+
+        let ks_distance = counts
+            .iter()
+            .map(|cards| {
+                let mut cumulative = 0;
+                cards
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &count)| {
+                        cumulative += count;
+                        (cumulative as f64 / n.max(1.0) - (i + 1) as f64 / 52.0).abs()
+                    })
+                    .fold(0.0, f64::max)
+            })
+            .fold(0.0, f64::max);
+
+        Self {
+            iterations,
+            chi_square,
+            degrees_of_freedom: 51 * 51,
+            chi_square_z,
+            ks_distance,
+            ks_statistic: ks_distance * n.sqrt(),
+        }
+    }
+
+    pub fn is_fair(&self) -> bool {
+        self.iterations > 0 && self.chi_square_z < Self::MAX_Z && self.ks_statistic < Self::MAX_KS
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DeckOrder {
+    #[default]
+    Dealt,
+    BySuit, // spades, hearts, diamonds, clubs, aces first
+    ByRank, // aces first, in suit order
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct DeckDisplay {
+    deck: Deck,
+    mode: DisplayMode,
+    per_row: usize,
+    order: DeckOrder,
+}
+
+impl DeckDisplay {
+    // At least one card per row.
+    pub fn with_per_row(mut self, per_row: usize) -> Self {
+        self.per_row = per_row.max(1);
+        self
+    }
+
+    pub fn with_order(mut self, order: DeckOrder) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+impl Display for DeckDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut cards = self.deck.cards().to_vec();
+        match self.order {
+            DeckOrder::Dealt => {}
+            DeckOrder::BySuit => {
+                cards.sort_by_key(|card| (card.suit().as_u8(), Reverse(card.value())));
+            }
+            DeckOrder::ByRank => {
+                cards.sort_by_key(|card| (Reverse(card.value()), card.suit().as_u8()));
+            }
+        }
+
+        let delimiter = if self.mode == DisplayMode::Ascii {
+            " "
+        } else {
+            "  "
+        };
+        for (i, row) in cards.chunks(self.per_row).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, card) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "{}", delimiter)?;
+                }
+                write!(f, "{}", card.display(self.mode))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// todo: make private, inside run_hand
+#[derive(Debug, Clone)]
+pub struct Dealer(iter::Take<array::IntoIter<Card, 52>>);
+
+impl Dealer {
+    pub fn deal_card(&mut self) -> Card {
+        self.0.next().expect("Dealer should always have cards left")
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn deal_hole(&mut self) -> Hole {
+        Hole::unchecked([self.deal_card(), self.deal_card()])
+    }
+
+    pub fn deal_flop(&mut self) -> Flop {
+        Flop::unchecked([self.deal_card(), self.deal_card(), self.deal_card()])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BetBound {
+    FoldCheckAllIn,
+    FoldCheckBetAllIn(RangeInclusive<Chips>),
+    FoldAllIn,
+    FoldCall,
+    FoldCallAllIn,
+    FoldCallRaiseAllIn(RangeInclusive<Chips>),
+    FoldBetAllIn(RangeInclusive<Chips>), // river nuts button(!opened)
+    FoldRaiseAllIn(RangeInclusive<Chips>), // river nuts opened
+    // Pot limit with more behind than the pot allows: the range ends at a pot sized bet or raise.
+    FoldCheckBet(RangeInclusive<Chips>),
+    FoldCallRaise(RangeInclusive<Chips>),
+}
+
+impl BetBound {
+    // Every legal action but exit: fold, check or call, bets or raises from the minimum up in
+    // steps of `step` chips (only the minimum for 0) and all in. Sizes that aren't valid amounts
+    // are skipped, the maximum is the all in.
+    pub fn legal_actions(&self, step: Chips) -> impl Iterator<Item = Action> + use<> {
+        let (check_or_call, range, all_in) = match self {
+            Self::FoldCheckAllIn | Self::FoldCallAllIn => (true, None, true),
+            Self::FoldCheckBetAllIn(range) | Self::FoldCallRaiseAllIn(range) => {
+                (true, Some(range.clone()), true)
+            }
+            Self::FoldAllIn => (false, None, true),
+            Self::FoldCall => (true, None, false),
+            Self::FoldBetAllIn(range) | Self::FoldRaiseAllIn(range) => {
+                (false, Some(range.clone()), true)
+            }
+            Self::FoldCheckBet(range) | Self::FoldCallRaise(range) => {
+                (true, Some(range.clone()), false)
+            }
+        };
+
+        // without an all in, the maximum is a size of its own
+        let sizes = range.into_iter().flat_map(move |range| {
+            let end = *range.end();
+            std::iter::successors(Some(*range.start()), move |&amount| {
+                amount.checked_add(step).filter(|_| !step.is_zero())
+            })
+            .take_while(move |&amount| amount < end)
+            .chain((!all_in).then_some(end))
+            .filter_map(Action::bet_or_raise)
+        });
+
+        std::iter::once(Action::fold())
+            .chain(check_or_call.then(Action::check_or_call))
+            .chain(sizes)
+            .chain(all_in.then(Action::all_in))
+    }
+
+    pub fn validate_action(&self, action: Action) -> bool {
+        if action.is_exit() || action.is_fold() {
+            return true; // always valid
+        }
+
+        match self {
+            Self::FoldCheckAllIn | Self::FoldCallAllIn => {
+                action.is_check_or_call() || action.is_all_in()
+            }
+            Self::FoldCheckBetAllIn(range) | Self::FoldCallRaiseAllIn(range) => {
+                if let ActionValue::BetOrRaise(amount) = action.value() {
+                    range.contains(&amount)
+                } else {
+                    action.is_check_or_call() || action.is_all_in()
+                }
+            }
+            Self::FoldAllIn => action.is_all_in(),
+            Self::FoldCall => action.is_check_or_call(),
+            Self::FoldBetAllIn(range) | Self::FoldRaiseAllIn(range) => {
+                if let ActionValue::BetOrRaise(amount) = action.value() {
+                    range.contains(&amount)
+                } else {
+                    action.is_all_in()
+                }
+            }
+            Self::FoldCheckBet(range) | Self::FoldCallRaise(range) => {
+                if let ActionValue::BetOrRaise(amount) = action.value() {
+                    range.contains(&amount)
+                } else {
+                    action.is_check_or_call()
+                }
+            }
+        }
+    }
+
+    // The bound under pot limit, where bets and raises go to `max` at most. All in stays only if
+    // it fits; the river nuts rule, a no limit one, is dropped once it doesn't.
+    pub fn pot_limited(self, max: Chips) -> Self {
+        let capped = |range: RangeInclusive<Chips>| *range.start()..=max.max(*range.start());
+        match self {
+            Self::FoldCheckBetAllIn(range)
+            | Self::FoldBetAllIn(range)
+            | Self::FoldCheckBet(range)
+                if *range.end() > max =>
+            {
+                Self::FoldCheckBet(capped(range))
+            }
+            Self::FoldCallRaiseAllIn(range)
+            | Self::FoldRaiseAllIn(range)
+            | Self::FoldCallRaise(range)
+                if *range.end() > max =>
+            {
+                Self::FoldCallRaise(capped(range))
+            }
+            bound => bound,
+        }
+    }
+
+    // The action as the table takes it: bets and raises in whole `unit`s, but for the minimum
+    // and the maximum, which may be off the unit after an odd all in; the all in amount is all in.
+    pub fn alter_eq(&self, action: Action, unit: Chips) -> Option<Action> {
+        if !self.validate_action(action) {
+            return None; // Invalid action
+        }
+
+        if let ActionValue::BetOrRaise(amount) = action.value() {
+            let range = match self {
+                Self::FoldCheckBetAllIn(range)
+                | Self::FoldCallRaiseAllIn(range)
+                | Self::FoldBetAllIn(range)
+                | Self::FoldRaiseAllIn(range)
+                | Self::FoldCheckBet(range)
+                | Self::FoldCallRaise(range) => range,
+                _ => unreachable!(),
+            };
+            let on_unit = unit.is_zero() || amount.is_multiple_of(unit.get());
+            if !on_unit && amount != *range.start() && amount != *range.end() {
+                return None;
+            }
+            if amount == *range.end()
+                && !matches!(self, Self::FoldCheckBet(_) | Self::FoldCallRaise(_))
+            {
+                return Some(Action::all_in());
+            }
+        }
+
+        Some(action)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub(crate) enum ActionOver {
+    TurnOver,
+    RoundOver,
+    ShowdownAll,
+    ShowndownRiver,
+    HandOver,
+}
+
+// Pots and bets of one hand street by street, replayed from the actions on its own copy of the
+// hand state so it stays right wherever the actions come from.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct StreetHistory {
+    state: HandState,
+    streets: Vec<StreetRecord>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreetRecord {
+    street: Street,
+    pot_start: Chips,
+    pot_end: Option<Chips>, // uncalled chips excluded; None while the street is running
+    bets: Vec<StreetBet>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreetBet {
+    actor: bool,
+    action: Action,
+    pot: Chips, // in the middle before the action, bets of the round included
+    to: Chips,  // the actor's chips in the betting round after the action
+}
+
+impl StreetHistory {
+    // Starts after the holes are dealt and the blinds posted.
+    fn new(state: HandState) -> Self {
+        Self {
+            state,
+            streets: vec![],
+        }
+    }
+
+    fn record(&mut self, action: Action) {
+        let before = self.state;
+        let seat = before.cur_turn();
+        let (hero, villain) = if seat { (0, 1) } else { (1, 0) };
+
+        if self.streets.last().is_none_or(|s| s.pot_end.is_some()) {
+            self.streets.push(StreetRecord {
+                street: before.street(),
+                pot_start: before.pot(),
+                pot_end: None,
+                bets: vec![],
+            });
+        }
+
+        let over = self.state.action(action);
+        let to = match action.value() {
+            ActionValue::Exit | ActionValue::Fold => before.cur_round[hero],
+            ActionValue::CheckOrCall if before.can_check() => before.cur_round[hero],
+            ActionValue::CheckOrCall => before.cur_round[villain],
+            ActionValue::BetOrRaise(amount) => amount,
+            ActionValue::AllIn => before.behinds[hero],
+        };
+
+        let record = self.streets.last_mut().expect("A street was just opened");
+        record.bets.push(StreetBet {
+            actor: seat,
+            action,
+            pot: before.total_pot(),
+            to,
+        });
+        record.pot_end = match over {
+            ActionOver::TurnOver => None,
+            ActionOver::RoundOver => Some(self.state.total_pot()),
+            ActionOver::ShowdownAll | ActionOver::ShowndownRiver => Some(self.state.pot()),
+            ActionOver::HandOver => {
+                let [a, b] = before.cur_round;
+                Some(before.pot() + a.min(b) * 2)
+            }
+        };
+
+        if over == ActionOver::RoundOver {
+            self.state.next_street();
+        }
+    }
+
+    pub fn streets(&self) -> &[StreetRecord] {
+        &self.streets
+    }
+
+    pub fn street(&self, street: Street) -> Option<&StreetRecord> {
+        self.streets.iter().find(|record| record.street == street)
+    }
+
+    // The pot after the last finished street.
+    pub fn final_pot(&self) -> Option<Chips> {
+        self.streets.iter().rev().find_map(|record| record.pot_end)
+    }
+}
+
+impl StreetRecord {
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn pot_start(&self) -> Chips {
+        self.pot_start
+    }
+
+    pub fn pot_end(&self) -> Option<Chips> {
+        self.pot_end
+    }
+
+    pub fn bets(&self) -> &[StreetBet] {
+        &self.bets
+    }
+}
+
+impl StreetBet {
+    pub fn actor(&self) -> bool {
+        self.actor
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn pot(&self) -> Chips {
+        self.pot
+    }
+
+    pub fn to(&self) -> Chips {
+        self.to
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub(crate) struct HandState {
+    blind: u16,
+    small_blind: u16,
+    button: bool,
+    init_stacks: [Chips; 2],
+    pot: Chips,
+    cur_turn: bool,
+    cur_round: [Chips; 2],
+    behinds: [Chips; 2],
+    last_bet: Chips,
+    last_aggressor: bool,
+    opened: bool,
+    street: Street,
+    holes: [Option<Hole>; 2],
+    board: Board,
+    ante: Chips, // bomb pot ante, posted instead of the blinds
+    level_ante: u16,
+    big_blind_ante: bool,
+    straddle: bool,
+    unit: u16,
+}
+
+impl HandState {
+    pub(crate) fn new(blind: u16, button: bool, init_stacks: [Chips; 2]) -> Self {
+        Self {
+            blind,
+            small_blind: blind / 2,
+            button,
+            init_stacks,
+            pot: Chips::ZERO,
+            cur_turn: button,
+            cur_round: [Chips::ZERO; 2],
+            behinds: init_stacks,
+            last_bet: Chips::ZERO,
+            last_aggressor: button,
+            opened: false,
+            street: Street::Preflop,
+            holes: [None, None],
+            board: Default::default(),
+            ante: Chips::ZERO,
+            level_ante: 0,
+            big_blind_ante: false,
+            straddle: false,
+            unit: Blinds::default_unit(blind / 2, blind),
+        }
+    }
+
+    // A postflop spot with `pot` already in the middle and nobody to act yet.
+    pub(crate) fn postflop(
+        blind: u16,
+        button: bool,
+        street: Street,
+        pot: Chips,
+        stacks: [Chips; 2],
+    ) -> Self {
+        let mut state = Self::new(blind, button, stacks);
+        state.pot = pot;
+        state.cur_turn = !button;
+        state.opened = true;
+        state.street = street;
+        state
+    }
+
+    // Known cards of an analysis spot; the engine deals them through events.
+    pub(crate) fn with_cards(mut self, holes: [Option<Hole>; 2], board: Board) -> Self {
+        self.holes = holes;
+        self.board = board;
+        self
+    }
+
+    pub(crate) fn street(&self) -> Street {
+        self.street
+    }
+
+    pub(crate) fn button(&self) -> bool {
+        self.button
+    }
+
+    pub(crate) fn holes(&self) -> [Option<Hole>; 2] {
+        self.holes
+    }
+
+    pub(crate) fn board(&self) -> Board {
+        self.board
+    }
+
+    pub(crate) fn cur_turn(&self) -> bool {
+        self.cur_turn
+    }
+
+    pub(crate) fn behinds(&self) -> [Chips; 2] {
+        self.behinds
+    }
+
+    pub(crate) fn cur_round(&self) -> [Chips; 2] {
+        self.cur_round
+    }
+
+    // Chips from finished rounds only.
+    pub(crate) fn pot(&self) -> Chips {
+        self.pot
+    }
+
+    // Chips in the middle, including bets of the current round.
+    pub(crate) fn total_pot(&self) -> Chips {
+        self.pot + self.cur_round[0] + self.cur_round[1]
+    }
+
+    pub(crate) fn next_street(&mut self) {
+        if let Some(street) = self.street.next() {
+            self.street = street;
+        }
+    }
+
+    // Puts `board` on the table, moving to its street.
+    pub(crate) fn deal_board(&mut self, board: Board) {
+        self.board = board;
+        self.street = board.street();
+    }
+
+    // The board with the next street dealt from `dealer`, unchanged once the river is out.
+    fn next_board(&self, dealer: &mut Dealer) -> Board {
+        let board = self.board;
+        match *board {
+            BoardCards::Preflop => Some(Board::flop(dealer.deal_flop())),
+            BoardCards::Flop(_) => board.turn(dealer.deal_card()),
+            BoardCards::Turn { .. } => board.river(dealer.deal_card()),
+            BoardCards::River { .. } => Some(board),
+        }
+        .expect("Dealer should never deal a card twice")
+    }
+
+    // Stakes other than half the big blind, antes and straddles.
+    pub(crate) fn with_blinds(mut self, blinds: Blinds) -> Self {
+        self.blind = blinds.big_blind;
+        self.small_blind = blinds.small_blind;
+        self.level_ante = blinds.ante;
+        self.big_blind_ante = blinds.big_blind_ante;
+        self.straddle = blinds.straddle;
+        self.unit = blinds.unit;
+        self
+    }
+
+    pub(crate) fn blinds(&self) -> Blinds {
+        Blinds {
+            small_blind: self.small_blind,
+            big_blind: self.blind,
+            ante: self.level_ante,
+            big_blind_ante: self.big_blind_ante,
+            straddle: self.straddle,
+            unit: self.unit,
+        }
+    }
+
+    pub(crate) fn bet_unit(&self) -> Chips {
+        Chips::new(self.unit as u32)
+    }
+
+    // The blinds carry over, the table changes them a level at a time.
+    fn next(&self) -> Self {
+        Self::new(self.blind, !self.button, self.behinds).with_blinds(self.blinds())
+    }
+
+    // The stacks once `seat` gives up the hand in play as a fold would, the chips at stake going
+    // to the opponent. A settled hand has none left.
+    fn forfeit(&self, seat: bool) -> [Chips; 2] {
+        let mut state = *self;
+        state.cur_turn = seat;
+        state.action(Action::exit());
+        state.behinds
+    }
+
+    fn set_holes(&mut self, holes: [Hole; 2]) {
+        self.holes = [Some(holes[0]), Some(holes[1])];
+    }
+
+    // Pays the pot out once the hand is decided, `chips` of it to player 0.
+    pub(crate) fn award(&mut self, chips: Chips) {
+        self.behinds[0] += chips;
+        self.behinds[1] += self.pot - chips;
+        self.pot = Chips::ZERO;
+    }
+
+    pub(crate) fn big_blind(&self) -> Chips {
+        Chips::new(self.blind as u32)
+    }
+
+    pub(crate) fn small_blind(&self) -> Chips {
+        Chips::new(self.small_blind as u32)
+    }
+
+    // Each seat's ante of a hand with blinds, bomb pots aside.
+    pub(crate) fn antes(&self) -> [Chips; 2] {
+        let ante = Chips::new(self.level_ante as u32);
+        if !self.big_blind_ante {
+            return [ante; 2];
+        }
+        let mut antes = [Chips::ZERO; 2];
+        antes[if self.button { 1 } else { 0 }] = ante;
+        antes
+    }
+
+    // The river made the board itself the nuts: both players play it and nothing is left to bet.
+    pub(crate) fn is_board_chop(&self) -> bool {
+        self.board
+            .as_full_board()
+            .is_some_and(|board| board.is_nuts())
+    }
+
+    // Player 0's part of the pot for `winner`, None for a chop.
+    pub(crate) fn winner_share(&self, winner: Option<bool>) -> Chips {
+        match winner {
+            Some(true) => self.pot,
+            Some(false) => Chips::ZERO,
+            None => self.chop_share(),
+        }
+    }
+
+    // Player 0's half of a chopped pot, the odd chip going out of position.
+    pub(crate) fn chop_share(&self) -> Chips {
+        self.pot / 2 + (self.pot % 2) * (!self.button as u32)
+    }
+
+    pub(crate) fn can_check(&self) -> bool {
+        self.cur_round[0].is_zero() && self.cur_round[1].is_zero()
+    }
+
+    // Holding the nuts on the river, hero may not check or call when that would end the hand.
+    pub(crate) fn bet_bound(&self) -> BetBound {
+        let bound = self.open_bet_bound();
+        if !self.holds_river_nuts() {
+            return bound;
+        }
+
+        let closes = self.cur_turn == self.button; // a check behind ends the river too
+        match bound {
+            BetBound::FoldCheckAllIn if closes => BetBound::FoldAllIn,
+            BetBound::FoldCheckBetAllIn(range) if closes => BetBound::FoldBetAllIn(range),
+            BetBound::FoldCallAllIn => BetBound::FoldAllIn,
+            BetBound::FoldCallRaiseAllIn(range) => BetBound::FoldRaiseAllIn(range),
+            bound => bound,
+        }
+    }
+
+    fn holds_river_nuts(&self) -> bool {
+        let hero = if self.cur_turn { 0 } else { 1 };
+        self.street == Street::River
+            && self.holes[hero].is_some_and(|hole| self.board.is_nuts(hole))
+    }
+
+    fn open_bet_bound(&self) -> BetBound {
+        let hero = if self.cur_turn { 0 } else { 1 };
+        let behind = self.behinds[hero];
+
+        if self.can_check() {
+            let big_blind = self.big_blind();
+
+            return if behind <= big_blind {
+                BetBound::FoldCheckAllIn
+            } else {
+                BetBound::FoldCheckBetAllIn(big_blind..=behind)
+            };
+        }
+
+        let villain = 1 - hero;
+        let villain_bet = self.cur_round[villain];
+
+        // cover
+        if behind <= villain_bet {
+            return BetBound::FoldAllIn;
+        }
+
+        // villain all in
+        if self.behinds[villain] == villain_bet {
+            return BetBound::FoldCall;
+        }
+
+        let min_raise = villain_bet + (villain_bet - self.last_bet);
+
+        // call or all in
+        if behind <= min_raise {
+            return BetBound::FoldCallAllIn;
+        }
+
+        BetBound::FoldCallRaiseAllIn(min_raise..=behind)
+    }
+
+    // The most a bet or raise can go to under pot limit: calling first, then raising by the pot
+    // that makes.
+    pub(crate) fn pot_limit_max(&self) -> Chips {
+        let hero = if self.cur_turn { 0 } else { 1 };
+        let to_call = self.cur_round[1 - hero].saturating_sub(self.cur_round[hero]);
+        self.cur_round[1 - hero] + self.total_pot() + to_call
+    }
+
+    pub(crate) fn pot_limit_bet_bound(&self) -> BetBound {
+        self.open_bet_bound().pot_limited(self.pot_limit_max())
+    }
+
+    fn effective_behind(&self) -> Chips {
+        self.behinds[0].min(self.behinds[1])
+    }
+
+    fn deal_holes(&mut self, holes: [Hole; 2]) -> Option<(bool, BetBound)> {
+        self.set_holes(holes);
+        self.deal_holes_int()?;
+        Some((self.cur_turn, self.bet_bound()))
+    }
+
+    pub(crate) fn deal_holes_int(&mut self) -> Option<()> {
+        let effective_stack = self.effective_behind();
+
+        // bomb pot antes, straight to the flop
+        if !self.ante.is_zero() {
+            let ante = self.ante.min(effective_stack);
+            self.pot += ante * 2;
+            self.behinds[0] -= ante;
+            self.behinds[1] -= ante;
+            self.street = Street::Flop;
+            self.cur_turn = !self.button;
+            self.opened = true;
+            return (ante < effective_stack).then_some(());
+        }
+
+        // antes are dead money, in the pot before the blinds
+        for (seat, ante) in self.antes().into_iter().enumerate() {
+            let ante = ante.min(self.behinds[seat]);
+            self.pot += ante;
+            self.behinds[seat] -= ante;
+        }
+        let effective_stack = self.effective_behind();
+
+        let big_blind = self.big_blind();
+        let small_blind = self.small_blind();
+
+        // forced all in
+        if effective_stack <= small_blind {
+            self.pot += effective_stack * 2;
+            self.behinds[0] -= effective_stack;
+            self.behinds[1] -= effective_stack;
+            return None;
+        }
+
+        let sb = if self.button { 0 } else { 1 };
+        let bb = 1 - sb;
+
+        // a straddle both players can cover, the big blind acting first
+        let straddle = big_blind * 2;
+        if self.straddle && effective_stack > straddle {
+            self.cur_round[sb] = straddle;
+            self.cur_round[bb] = big_blind;
+            self.cur_turn = !self.button;
+            return Some(());
+        }
+
+        // blinds betting
+        self.cur_round[sb] = small_blind;
+        self.cur_round[bb] = big_blind.min(self.behinds[bb]);
+
+        Some(())
+    }
+
+    pub(crate) fn action(&mut self, action: Action) -> ActionOver {
+        let hero = if self.cur_turn { 0 } else { 1 };
+        let villain = 1 - hero;
+
+        match action.value() {
+            ActionValue::Exit | ActionValue::Fold => {
+                let round_lose = self.cur_round[hero];
+
+                self.behinds[hero] -= round_lose;
+                self.behinds[villain] += round_lose + self.pot;
+                self.pot = Chips::ZERO;
+                self.cur_round = [Chips::ZERO; 2];
+
+                ActionOver::HandOver
+            }
+            ActionValue::BetOrRaise(amount) => {
+                self.last_aggressor = self.cur_turn;
+                self.cur_round[hero] = amount;
+                self.last_bet = self.cur_round[villain];
+                self.cur_turn = !self.cur_turn;
+
+                if self.street == Street::Preflop {
+                    self.opened = true;
+                }
+
+                ActionOver::TurnOver
+            }
+            ActionValue::AllIn => {
+                let hero_behind = self.behinds[hero];
+
+                if hero_behind > self.cur_round[villain] {
+                    // active all in
+                    self.last_aggressor = self.cur_turn;
+                    self.cur_round[hero] = hero_behind;
+                    self.cur_turn = !self.cur_turn;
 
                     ActionOver::TurnOver
                 } else {
-                    // passive all in
-                    self.pot += hero_behind * 2;
-                    self.behinds[0] -= hero_behind;
-                    self.behinds[1] -= hero_behind;
+                    // passive all in
+                    self.pot += hero_behind * 2;
+                    self.behinds[0] -= hero_behind;
+                    self.behinds[1] -= hero_behind;
+                    self.last_bet = Chips::ZERO;
+                    self.cur_round = [Chips::ZERO; 2];
+
+                    if self.street == Street::River {
+                        ActionOver::ShowndownRiver
+                    } else {
+                        ActionOver::ShowdownAll
+                    }
+                }
+            }
+            ActionValue::CheckOrCall => {
+                if self.can_check() {
+                    // check
+                    if self.street == Street::Preflop {
+                        // the big blind's option, or the straddle's
+                        self.cur_turn = !self.button;
+                        ActionOver::RoundOver
+                    } else {
+                        let round_over = self.cur_turn == self.button;
+
+                        if round_over && self.street == Street::River {
+                            ActionOver::ShowndownRiver
+                        } else {
+                            self.cur_turn = !self.cur_turn;
+
+                            if round_over {
+                                ActionOver::RoundOver
+                            } else {
+                                ActionOver::TurnOver
+                            }
+                        }
+                    }
+                } else {
+                    // call
+                    let villain_bet = self.cur_round[villain];
+
+                    self.pot += villain_bet * 2;
+                    self.behinds[0] -= villain_bet;
+                    self.behinds[1] -= villain_bet;
+                    self.last_bet = Chips::ZERO;
+                    self.cur_round = [Chips::ZERO; 2];
+
+                    if self.street == Street::River {
+                        ActionOver::ShowndownRiver
+                    } else if self.behinds[villain].is_zero() {
+                        ActionOver::ShowdownAll
+                    } else if self.street == Street::Preflop && !self.opened {
+                        // the option goes to whoever posted the bigger blind
+                        self.cur_turn = !self.cur_turn;
+                        ActionOver::TurnOver
+                    } else {
+                        self.cur_turn = !self.button;
+                        ActionOver::RoundOver
+                    }
+                }
+            }
+        }
+    }
+
+    fn event(&mut self, event: ObservableEvent) {
+        match event {
+            ObservableEvent::BombPot(ante) => {
+                self.ante = ante;
+            }
+            ObservableEvent::BlindLevel { blinds, .. } => {
+                *self = self.with_blinds(blinds);
+            }
+            ObservableEvent::DealHoles(holes) => {
+                self.holes = holes;
+                self.deal_board(Board::default());
+                self.deal_holes_int();
+            }
+            ObservableEvent::ShowdownAll(holes) | ObservableEvent::ShowdownAuto(holes) => {
+                self.set_holes(holes);
+            }
+            ObservableEvent::ShowdownShow(seat, hole) => {
+                self.holes[if seat { 0 } else { 1 }] = Some(hole);
+            }
+            ObservableEvent::DealBoard(board) => {
+                self.deal_board(board);
+            }
+            ObservableEvent::Stacks { behinds, bets, pot } => {
+                self.behinds = [behinds[0] + bets[0], behinds[1] + bets[1]];
+                self.cur_round = bets;
+                self.pot = pot;
+            }
+            // the turn, the last bet and who opened follow the actions; the chips they move
+            // are set again by the `Stacks` after them, awards and house rules included
+            ObservableEvent::PlayerAction(action, _) => {
+                self.action(action);
+            }
+            ObservableEvent::GameOver(_) => unreachable!(),
+            _ => {}
+        }
+    }
+}
+
+// todo: HeadsUp: core gameplay, rules, logic, and state machine.
+#[derive(Debug, Clone)]
+struct HeadsUp {
+    game_over: Option<GameResult>,
+
+    // game info
+    is_sng: bool,
+    buyins: [Chips; 2], // the stacks the seats sat down with
+    hands_limit: u32,
+    blinds: BlindStructure,
+    announce_levels: bool, // sit and gos and games given a blind structure
+    level: usize,
+    level_opened: u32,        // hands played when the current level opened
+    level_hands: Option<u16>, // how many it lasts
+
+    // current hand state
+    hand_state: HandState,
+    hands: u32,
+    dealt: u32, // the number of the last hand dealt, the hand in progress included
+    events: Vec<ObservableEvent>,
+    street_histories: Vec<StreetHistory>, // one per hand, the current one last
+    hand: Option<HandHistory>,            // until the next hand starts or the game is over
+    bomb_pot: Option<BombPot>,
+    seven_deuce: Option<SevenDeuce>,
+    rake: Option<Rake>,
+    raked: [Chips; 2],     // over the session
+    hand_rake: [Chips; 2], // of the hand just won, until it's told
+    stop_conditions: Option<StopConditions>,
+    clock: Arc<dyn Clock>,
+    started: Option<Duration>, // first deal, for stop conditions and timed blind levels
+    desync: Option<(u32, StateDiff)>, // the hand a checksum first didn't match in
+}
+
+impl HeadsUp {
+    fn new(game_type: GameType, button: bool) -> Self {
+        let init_stack = game_type.init_stack();
+        let init_stacks = [init_stack, init_stack];
+        let blinds = game_type.blind_structure();
+        let hand_state = HandState::new(0, button, init_stacks);
+
+        let mut heads_up = Self {
+            game_over: None,
+            is_sng: game_type.is_sng(),
+            buyins: init_stacks,
+            hands_limit: game_type.hands_limit(),
+            blinds,
+            announce_levels: game_type.is_sng(),
+            level: 0,
+            level_opened: 0,
+            level_hands: None,
+            hand_state,
+            hands: 0,
+            dealt: 0,
+            events: Default::default(),
+            street_histories: vec![],
+            hand: None,
+            bomb_pot: None,
+            seven_deuce: None,
+            rake: game_type.rake(),
+            raked: [Chips::ZERO; 2],
+            hand_rake: [Chips::ZERO; 2],
+            stop_conditions: None,
+            clock: Arc::new(SystemClock::default()),
+            started: None,
+            desync: None,
+        };
+        heads_up.open_level(0);
+        heads_up
+    }
+
+    fn set_blind_structure(&mut self, blinds: BlindStructure) {
+        self.blinds = blinds;
+        self.announce_levels = true;
+        self.open_level(self.level_due());
+    }
+
+    fn is_over(&self) -> bool {
+        self.game_over.is_some()
+    }
+
+    fn game_over(&self) -> Option<GameResult> {
+        self.game_over
+    }
+
+    fn stacks(&self) -> [Chips; 2] {
+        self.hand_state.init_stacks
+    }
+
+    fn hands_reached(&self) -> bool {
+        !(self.is_sng || self.hands < self.hands_limit)
+    }
+
+    // The level counting from 0 of the hand after the `hands` played, by hands or by the time
+    // since the first deal, staying on the last one.
+    fn level_due(&self) -> usize {
+        let level = match self.blinds.duration() {
+            LevelDuration::Hands(0) => 0,
+            LevelDuration::Hands(hands) => (self.hands / u32::from(hands)) as usize,
+            LevelDuration::Time(time) => {
+                let elapsed = self.started.map(|started| self.clock.now() - started);
+                let elapsed = elapsed.unwrap_or_default().as_nanos();
+                elapsed.checked_div(time.as_nanos()).unwrap_or(0) as usize
+            }
+        };
+        level.min(self.blinds.levels().len() - 1)
+    }
+
+    // Puts the coming hand on `level`.
+    fn open_level(&mut self, level: usize) {
+        self.level = level;
+        self.level_opened = self.hands;
+        self.level_hands = match self.blinds.duration() {
+            LevelDuration::Hands(hands) if hands > 0 && level + 1 < self.blinds.levels().len() => {
+                Some(hands)
+            }
+            _ => None,
+        };
+        self.hand_state = self.hand_state.with_blinds(self.blinds.level(level));
+    }
+
+    // Between hands, in case the level's time ran out.
+    fn level_up(&mut self) {
+        let level = self.level_due();
+        if level != self.level {
+            self.open_level(level);
+        }
+    }
+
+    // None on the last level, or when the blinds don't go up by hands.
+    fn hands_until_next_level(&self) -> Option<u16> {
+        let played = u16::try_from(self.hands - self.level_opened).unwrap_or(u16::MAX);
+        self.level_hands.map(|hands| hands.saturating_sub(played))
+    }
+
+    // The level the hand being played opened, None for a hand in the middle of one.
+    fn level_start(&self) -> Option<ObservableEvent> {
+        (self.announce_levels && self.level_opened == self.hands).then(|| {
+            ObservableEvent::BlindLevel {
+                level: self.level_number(),
+                blinds: self.hand_state.blinds(),
+                hands: self.hands_until_next_level(),
+            }
+        })
+    }
+
+    fn abort(&self) -> GameOver {
+        if self.is_sng {
+            GameOver::GameAbort
+        } else {
+            GameOver::AbortCheckout(self.stacks(), self.raked)
+        }
+    }
+
+    fn force_exit(&self, player: bool) -> GameOver {
+        if self.is_sng {
+            GameOver::ExitAbandon(player)
+        } else {
+            GameOver::ExitCheckout(player, self.stacks(), self.raked)
+        }
+    }
+
+    // Takes the rake from what each player won of `pot`, the shares it's taken in.
+    fn take_rake(&mut self, pot: Chips, won: [Chips; 2]) {
+        let Some(rake) = self.rake.map(|rake| rake.of(pot, self.hand_state.street)) else {
+            return;
+        };
+        if rake.is_zero() {
+            return;
+        }
+        let first = Chips::new((rake.get() as u64 * won[0].get() as u64 / pot.get() as u64) as u32);
+        self.hand_rake = [first, rake - first];
+        for seat in 0..2 {
+            self.hand_state.behinds[seat] -= self.hand_rake[seat];
+            self.raked[seat] += self.hand_rake[seat];
+        }
+    }
+
+    // The rake of the hand just won, once.
+    fn hand_rake(&mut self) -> Option<[Chips; 2]> {
+        let rake = std::mem::take(&mut self.hand_rake);
+        (rake != [Chips::ZERO; 2]).then_some(rake)
+    }
+
+    // Ends the game `at` a time on its clock.
+    fn set_game_over(&mut self, game_over: GameOver, at: Duration) {
+        let stacks = match game_over {
+            GameOver::ExitCheckout(_, stacks, _)
+            | GameOver::AbortCheckout(stacks, _)
+            | GameOver::HandsReached(stacks, _)
+            | GameOver::SessionStop(_, stacks, _) => stacks,
+            GameOver::GameAbort => self.stacks(),
+            GameOver::Defeated(_) => self.hand_state.behinds, // the hand was paid out
+            GameOver::ExitAbandon(seat) => self.hand_state.forfeit(seat),
+        };
+        let started = self.started.unwrap_or(at);
+        let net = [0, 1].map(|i| i64::from(stacks[i].get()) - i64::from(self.buyins[i].get()));
+        self.game_over = Some(GameResult {
+            reason: game_over,
+            stacks,
+            net,
+            hands: self.dealt,
+            level: self.level_number(),
+            elapsed: at.saturating_sub(started),
+        });
+    }
+
+    // The level counting from 1, which blind structures keep within a `u8`.
+    fn level_number(&self) -> u8 {
+        u8::try_from(self.level + 1).expect("Blind structures have at most 255 levels")
+    }
+
+    fn bet_bound(&self) -> BetBound {
+        self.hand_state.bet_bound()
+    }
+
+    fn stacks_event(&self) -> ObservableEvent {
+        let state = &self.hand_state;
+        ObservableEvent::Stacks {
+            behinds: [
+                state.behinds[0] - state.cur_round[0],
+                state.behinds[1] - state.cur_round[1],
+            ],
+            bets: state.cur_round,
+            pot: state.pot,
+        }
+    }
+
+    // Where the table is between hands, for those joining late.
+    fn catch_up_event(&self) -> ObservableEvent {
+        let state = &self.hand_state;
+        ObservableEvent::CatchUp {
+            hands: self.hands,
+            button: state.button,
+            big_blind: state.big_blind(),
+            stacks: state.behinds,
+        }
+    }
+
+    // `event` with where it happened, the table having applied it. `actor` is who took a player
+    // action, as the turn has moved on by then.
+    fn hand_event(&self, event: ObservableEvent, actor: Option<bool>) -> HandEvent {
+        HandEvent {
+            hand: self.dealt,
+            seat: actor.or(event.seat()),
+            street: self.hand_state.street,
+            event,
+        }
+    }
+
+    // Each player's ante if the coming hand is a bomb pot.
+    fn bomb_pot_ante(&self) -> Option<Chips> {
+        self.bomb_pot
+            .filter(|bomb_pot| (self.hands + 1).is_multiple_of(bomb_pot.every.into()))
+            .map(|bomb_pot| self.hand_state.big_blind() * bomb_pot.big_blinds as u32)
+    }
+
+    fn deal_holes(&mut self, holes: [Hole; 2]) -> Option<(bool, BetBound)> {
+        if self.started.is_none() {
+            self.started = Some(self.clock.now());
+        }
+        self.dealt = self.hands + 1;
+        if let Some(ante) = self.bomb_pot_ante() {
+            self.hand_state.ante = ante;
+        }
+        let bet_info = self.hand_state.deal_holes(holes);
+        self.street_histories
+            .push(StreetHistory::new(self.hand_state));
+        bet_info
+    }
+
+    fn action(&mut self, action: Action) -> (ActionOver, Option<GameOver>) {
+        if let Some(history) = self.street_histories.last_mut() {
+            history.record(action);
+        }
+        // the pot a fold gives up, uncalled chips aside
+        let folder = if self.hand_state.cur_turn { 0 } else { 1 };
+        let pot = self.hand_state.pot + self.hand_state.cur_round[folder] * 2;
+        let action_over = self.hand_state.action(action);
+        let mut game_over = None;
+
+        if action_over == ActionOver::HandOver {
+            if action.is_exit() {
+                let who_exit = self.hand_state.cur_turn;
+                let stacks_checkout = self.hand_state.behinds;
+                game_over = Some(if self.is_sng {
+                    GameOver::ExitAbandon(who_exit)
+                } else {
+                    GameOver::ExitCheckout(who_exit, stacks_checkout, self.raked)
+                });
+            } else {
+                let mut won = [pot; 2];
+                won[folder] = Chips::ZERO;
+                self.take_rake(pot, won);
+                game_over = self.end_hand();
+            }
+        }
+
+        (action_over, game_over)
+    }
+
+    // Settles a showdown with `chips` of the pot to player 0 and moves on to the next hand.
+    fn settle(&mut self, chips: Chips) -> Option<GameOver> {
+        let pot = self.hand_state.pot;
+        self.hand_state.award(chips);
+        self.take_rake(pot, [chips, pot - chips]);
+        self.end_hand()
+    }
+
+    // Pays `winner` the seven-deuce bonus once the hand is over, capped by the loser's stack.
+    fn pay_seven_deuce(&mut self, winner: bool, hole: Hole) -> Option<Chips> {
+        let rule = self.seven_deuce.filter(|_| hole.is_seven_deuce())?;
+        let state = &mut self.hand_state;
+        let (winner, loser) = if winner { (0, 1) } else { (1, 0) };
+        let bonus = (state.big_blind() * rule.big_blinds as u32).min(state.behinds[loser]);
+
+        for stacks in [&mut state.behinds, &mut state.init_stacks] {
+            stacks[winner] += bonus;
+            stacks[loser] -= bonus;
+        }
+        Some(bonus)
+    }
+
+    // A player with no chips left can't post the next blinds.
+    fn defeated(&self) -> Option<GameOver> {
+        let behinds = self.hand_state.behinds;
+        (behinds[0].is_zero() || behinds[1].is_zero())
+            .then(|| GameOver::Defeated(behinds[0].is_zero()))
+    }
+
+    fn end_hand(&mut self) -> Option<GameOver> {
+        self.hands += 1;
+
+        if let Some(defeated) = self.defeated() {
+            return Some(defeated);
+        }
+        if self.hands_reached() {
+            return Some(GameOver::HandsReached(self.hand_state.behinds, self.raked));
+        }
+        if let Some(stop) = self.stop_conditions {
+            let elapsed = self.started.map(|started| self.clock.now() - started);
+            let stacks = self.hand_state.behinds;
+            if let Some(reason) = stop.reason(stacks, self.hands, elapsed.unwrap_or_default()) {
+                return Some(GameOver::SessionStop(reason, stacks, self.raked));
+            }
+        }
+
+        self.hand_state = self.hand_state.next();
+        self.level_up();
+        None
+    }
+
+    // Where the hand in `other` differs from this one. Holes are compared only where both sides
+    // know them, a player never knows the opponent's before the showdown.
+    fn diff(&self, other: &Self) -> StateDiff {
+        let (left, right) = (&self.hand_state, &other.hand_state);
+        let mut diff = StateDiff::default();
+
+        diff.push("pot", left.total_pot(), right.total_pot());
+        diff.push("stacks", left.behinds, right.behinds);
+        diff.push("bets", left.cur_round, right.cur_round);
+        diff.push("street", left.street, right.street);
+        let board = |board: Board| board.display(DisplayMode::Ascii).to_string();
+        diff.push_text("board", board(left.board), board(right.board));
+        for (field, seat) in [("hole 0", 0), ("hole 1", 1)] {
+            if let (Some(l), Some(r)) = (left.holes[seat], right.holes[seat]) {
+                let hole = |hole: Hole| hole.display(DisplayMode::Ascii).to_string();
+                diff.push_text(field, hole(l), hole(r));
+            }
+        }
+        diff
+    }
+
+    // What both sides of the table must agree on mid-hand: the chips and the board. It goes over
+    // the wire, so it hashes fixed bytes with FNV-1a rather than anything a toolchain may change.
+    fn checksum(&self) -> u64 {
+        let state = &self.hand_state;
+        let chips = [state.pot, state.behinds[0], state.behinds[1]]
+            .into_iter()
+            .chain(state.cur_round)
+            .flat_map(|chips| chips.get().to_le_bytes());
+        let board = state.board.to_vec();
+        let cards = board
+            .iter()
+            .flat_map(|card| [card.value() as u8, card.suit() as u8]);
+        let bytes = chips
+            .chain([state.street as u8, board.len() as u8])
+            .chain(cards);
+
+        bytes.fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    // Joins the game between hands, at the blind level the table is on.
+    fn catch_up(&mut self, hands: u32, button: bool, big_blind: Chips, stacks: [Chips; 2]) {
+        self.hands = hands;
+        // a big blind too large for any blinds can't be the table's, which keeps its own
+        let blind = u16::try_from(big_blind.get()).unwrap_or(self.hand_state.blind);
+        let state = HandState::new(blind, button, stacks);
+        self.hand_state = if state.blind == self.hand_state.blind {
+            state.with_blinds(self.hand_state.blinds())
+        } else {
+            state
+        };
+    }
+
+    // The hand just finished once the next one starts or the game is over.
+    fn event(&mut self, timed: Timed<ObservableEvent>) -> Option<HandHistory> {
+        let event = timed.event;
+        // a new hand starts with its blind level, its bomb pot or, without either, its holes
+        let new_hand = match event {
+            ObservableEvent::BlindLevel { .. } => true,
+            ObservableEvent::BombPot(_) | ObservableEvent::DealHoles(_) => !matches!(
+                self.events.last(),
+                Some(ObservableEvent::BlindLevel { .. } | ObservableEvent::BombPot(_))
+            ),
+            _ => false,
+        };
+        if new_hand && !self.street_histories.is_empty() {
+            self.hands += 1;
+            self.hand_state = self.hand_state.next();
+        }
+        if new_hand {
+            self.dealt = self.hands + 1;
+            self.started.get_or_insert(timed.at);
+        }
+        // the hand's history starts on the level's blinds
+        if let ObservableEvent::BlindLevel {
+            level,
+            blinds,
+            hands,
+        } = event
+        {
+            self.level = level as usize - 1;
+            self.level_opened = self.hands;
+            self.level_hands = hands;
+            self.hand_state = self.hand_state.with_blinds(blinds);
+        }
+        let game_over = matches!(event, ObservableEvent::GameOver(_));
+        let finished = if new_hand || game_over {
+            self.hand.take()
+        } else {
+            None
+        };
+        if new_hand {
+            self.hand = Some(HandHistory::new(self.hands + 1, &self.hand_state));
+        }
+        if let Some(hand) = &mut self.hand {
+            hand.push(timed);
+        }
+        self.events.push(event);
+
+        match event {
+            ObservableEvent::GameOver(game_over) => self.set_game_over(game_over, timed.at),
+            ObservableEvent::CatchUp {
+                hands,
+                button,
+                big_blind,
+                stacks,
+            } => self.catch_up(hands, button, big_blind, stacks),
+            // the first checksum that doesn't match is kept, the events go on as they come
+            ObservableEvent::Checksum(checksum) if self.desync.is_none() => {
+                let mut diff = StateDiff::default();
+                diff.push("checksum", self.checksum(), checksum);
+                if !diff.is_empty() {
+                    self.desync = Some((self.dealt, diff));
+                }
+            }
+            ObservableEvent::Checksum(_) => {}
+            event => self.hand_state.event(event),
+        }
+
+        match event {
+            ObservableEvent::DealHoles(_) => {
+                self.street_histories
+                    .push(StreetHistory::new(self.hand_state));
+            }
+            ObservableEvent::PlayerAction(action, _) => {
+                if let Some(history) = self.street_histories.last_mut() {
+                    history.record(action);
+                }
+            }
+            _ => {}
+        }
+
+        finished
+    }
+}
+
+// Fields of two hand states that don't match, each as (field, left, right).
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct StateDiff(Vec<(&'static str, String, String)>);
+
+impl StateDiff {
+    fn push<T: Debug + PartialEq>(&mut self, field: &'static str, left: T, right: T) {
+        if left != right {
+            self.0
+                .push((field, format!("{left:?}"), format!("{right:?}")));
+        }
+    }
+
+    // Like `push` for values compared by how they are shown.
+    fn push_text(&mut self, field: &'static str, left: String, right: String) {
+        if left != right {
+            self.0.push((field, left, right));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(|(field, _, _)| *field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str, &str)> {
+        self.0
+            .iter()
+            .map(|(field, left, right)| (*field, left.as_str(), right.as_str()))
+    }
+}
+
+impl Display for StateDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no differences");
+        }
+        for (i, (field, left, right)) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{field}: {left} != {right}")?;
+        }
+        Ok(())
+    }
+}
+
+// How a betting round ended, from the engine's side.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+enum RoundEnd {
+    Street,      // deal the next street and bet again
+    AllIn,       // no more betting, run the board out
+    River(bool), // showdown on the river, the seat showing first
+    Folded {
+        winner: bool,
+        pot: Chips, // in the middle when the other player folded
+        game_over: Option<GameOver>,
+    },
+    Exit(GameOver),
+    Crashed(bool),
+}
+
+// Events a player taking over a seat is replayed beyond its channel's capacity, room for any hand.
+const MAX_REPLAY: usize = 1024;
+
+#[derive(Debug)]
+pub struct Game {
+    game_type: GameType,
+    init_button: bool,
+    channels: Channels,
+    players: [PlayerSender; 2],
+    observer: Option<PlayerSender>,
+    deck: Deck,
+    seed: Option<u64>,
+    stacked: VecDeque<Deck>, // dealt as they are before any shuffle
+    heads_up: HeadsUp,
+    showdown_rules: ShowdownRules,
+    insurance: Option<Insurance>,
+    run_it: Option<RunItTwice>,
+    rule_hooks: Vec<Box<dyn RuleHooks>>,
+    checksums: bool,
+    early_award: bool,
+    identities: SharedIdentities,
+    time_bank: Option<TimeBank>,
+    banks: [Duration; 2], // time bank left of each player
+    sink: Option<Box<dyn EventSink>>,
+    events: Vec<HandEvent>, // of the hand being played or last played, for players taking over
+    turns: TurnId,          // the next turn's
+    reconnects: Option<SeatRequests>,
+}
+
+impl Game {
+    pub fn new(game_type: GameType) -> (Self, [Player; 2]) {
+        Self::bounded(game_type, Default::default()).expect("Unbounded channels are valid")
+    }
+
+    // None for a zero capacity, or players dropping events.
+    pub fn bounded(game_type: GameType, channels: Channels) -> Option<(Self, [Player; 2])> {
+        Self::with_button(game_type, channels, rand::random())
+    }
+
+    // A game nothing random goes into but `seed`: the first button comes from it and every hand is
+    // dealt from it and the hand number, see `with_seed`. The same seed plays the same game again.
+    pub fn seeded(game_type: GameType, seed: u64) -> (Self, [Player; 2]) {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        rng.set_stream(0); // hands deal from streams 1 and up
+        let (game, players) = Self::with_button(game_type, Default::default(), rng.random())
+            .expect("Unbounded channels are valid");
+        (game.with_seed(seed), players)
+    }
+
+    // A game dealing its hands from `decks`, player 0 on the first button, for playing out
+    // scripted hands: each deck deals player 0's hole, player 1's and then the board. Hands after
+    // the last deck are shuffled.
+    pub fn stacked(
+        game_type: GameType,
+        decks: impl IntoIterator<Item = Deck>,
+    ) -> (Self, [Player; 2]) {
+        let (game, players) = Self::with_button(game_type, Default::default(), true)
+            .expect("Unbounded channels are valid");
+        (game.with_decks(decks), players)
+    }
+
+    pub(crate) fn with_button(
+        game_type: GameType,
+        channels: Channels,
+        init_button: bool,
+    ) -> Option<(Self, [Player; 2])> {
+        if !(channels.players.is_valid() && channels.observer.is_valid())
+            || matches!(channels.players, Backpressure::DropOldest(_))
+        {
+            return None;
+        }
+
+        let vis = [Visibility::Player(true), Visibility::Player(false)];
+        let [(send0, recv0), (send1, recv1)] = [(); 2].map(|_| event_channel(channels.players));
+        let heads_up = HeadsUp::new(game_type, init_button);
+        let identities = SharedIdentities::default();
+        let game = Self {
+            game_type,
+            init_button,
+            channels,
+            players: [
+                PlayerSender {
+                    visibility: vis[0],
+                    send: send0,
+                    clock: heads_up.clock.clone(),
+                },
+                PlayerSender {
+                    visibility: vis[1],
+                    send: send1,
+                    clock: heads_up.clock.clone(),
+                },
+            ],
+            observer: None,
+            deck: Default::default(),
+            seed: None,
+            stacked: VecDeque::new(),
+            heads_up,
+            showdown_rules: Default::default(),
+            insurance: None,
+            run_it: None,
+            rule_hooks: vec![],
+            checksums: false,
+            early_award: false,
+            identities: identities.clone(),
+            time_bank: None,
+            banks: [Duration::ZERO; 2],
+            sink: None,
+            turns: TurnId::default(),
+            reconnects: None,
+            events: vec![],
+        };
+        let players = [
+            Player::new(game_type, vis[0], recv0, init_button, identities.clone()),
+            Player::new(game_type, vis[1], recv1, init_button, identities),
+        ];
+        Some((game, players))
+    }
+
+    pub fn with_showdown_rules(mut self, showdown_rules: ShowdownRules) -> Self {
+        self.showdown_rules = showdown_rules;
+        self
+    }
+
+    pub fn showdown_rules(&self) -> ShowdownRules {
+        self.showdown_rules
+    }
+
+    // Hooks run in the order they were added.
+    pub fn with_rule_hooks(mut self, hooks: impl RuleHooks + 'static) -> Self {
+        self.rule_hooks.push(Box::new(hooks));
+        self
+    }
+
+    // Replaces the game type's blinds, from the first hand. Straddles are for cash games only.
+    pub fn with_blind_structure(mut self, blinds: BlindStructure) -> Self {
+        let blinds = if self.heads_up.is_sng {
+            let levels = blinds.levels.iter().map(|&level| Blinds {
+                straddle: false,
+                ..level
+            });
+            BlindStructure {
+                levels: levels.collect(),
+                ..blinds
+            }
+        } else {
+            blinds
+        };
+        self.heads_up.set_blind_structure(blinds);
+        self
+    }
+
+    // Bomb pots are a cash game rule; SNGs ignore them.
+    pub fn with_bomb_pot(mut self, bomb_pot: BombPot) -> Self {
+        if !self.heads_up.is_sng {
+            self.heads_up.bomb_pot = Some(bomb_pot);
+        }
+        self
+    }
+
+    // Like bomb pots, the seven-deuce bonus is for cash games only.
+    pub fn with_seven_deuce(mut self, seven_deuce: SevenDeuce) -> Self {
+        if !self.heads_up.is_sng {
+            self.heads_up.seven_deuce = Some(seven_deuce);
+        }
+        self
+    }
+
+    // Stop conditions end cash sessions only; SNGs play to the end.
+    pub fn with_stop_conditions(mut self, stop_conditions: StopConditions) -> Self {
+        if !self.heads_up.is_sng {
+            self.heads_up.stop_conditions = Some(stop_conditions);
+        }
+        self
+    }
+
+    // Sends a checksum of the table's state after every change mid-hand, for players to check
+    // they rebuilt the same state from the events, see `Player::desync`. Meant for tests and
+    // debugging.
+    pub fn with_consistency_checks(mut self) -> Self {
+        self.checksums = true;
+        self
+    }
+
+    // Pays an all in out before the rest of the board when no runout can change who wins. Only
+    // from the flop on: enumerating every preflop runout costs more than dealing them.
+    pub fn with_early_award(mut self) -> Self {
+        self.early_award = true;
+        self
+    }
+
+    // The clock every event is stamped by and session durations are measured on, the system's
+    // by default.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(clock);
+        for sender in self.players.iter_mut().chain(&mut self.observer) {
+            sender.clock = clock.clone();
+        }
+        self.heads_up.clock = clock;
+        self
+    }
+
+    // Deals every hand from `seed` and its hand number alone, see `Deck::shuffle_and_deal_seeded`.
+    // The first button was drawn already; `seeded` draws it from the seed too.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Deals from a new deck of `variant` and ranks showdowns by it. Stacked decks deal as they
+    // are.
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.deck = Deck::new(variant);
+        self
+    }
+
+    // Deals the next hands from `decks` in order, see `Deck::stacked`, and shuffles again once
+    // they run out.
+    pub fn with_decks(mut self, decks: impl IntoIterator<Item = Deck>) -> Self {
+        self.stacked.extend(decks);
+        self
+    }
+
+    pub fn with_identities(self, identities: [Identity; 2]) -> Self {
+        let [first, second] = identities;
+        self.seat(true, first);
+        self.seat(false, second);
+        self
+    }
+
+    // Names `seat` for the players and observer, including ones already handed out; meant for
+    // before the game starts, hands recorded so far keep the names they had.
+    pub fn seat(&self, seat: bool, identity: Identity) {
+        self.identities.set(seat, identity);
+    }
+
+    pub fn identities(&self) -> Identities {
+        self.identities.get()
+    }
+
+    // Without one, a player who never answers holds the game up forever.
+    pub fn with_time_bank(mut self, time_bank: TimeBank) -> Self {
+        self.time_bank = Some(time_bank);
+        self.banks = [time_bank.bank; 2];
+        self
+    }
+
+    pub fn time_bank(&self) -> Option<TimeBank> {
+        self.time_bank
+    }
+
+    // Time bank left of each player.
+    pub fn banks(&self) -> [Duration; 2] {
+        self.banks
+    }
+
+    pub fn with_insurance(mut self, insurance: Insurance) -> Self {
+        self.insurance = Some(insurance);
+        self
+    }
+
+    pub fn insurance(&self) -> Option<Insurance> {
+        self.insurance
+    }
+
+    // Offered after insurance: a hand all in before the river declined it for runs the board
+    // out several times.
+    pub fn with_run_it_twice(mut self, run_it: RunItTwice) -> Self {
+        self.run_it = Some(run_it);
+        self
+    }
+
+    pub fn run_it_twice(&self) -> Option<RunItTwice> {
+        self.run_it
+    }
+
+    // Gets every event the game sends, hole cards and all.
+    pub fn with_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    pub fn observer(&mut self, visibility: Visibility) -> Option<Observer> {
+        if self.observer.is_some() {
+            return None; // Observer already exists
+        }
+
+        let (send, recv) = event_channel(self.channels.observer);
+        let observer = PlayerSender {
+            visibility,
+            send,
+            clock: self.heads_up.clock.clone(),
+        };
+        if self.heads_up.hands > 0 {
+            let catch_up = self.heads_up.catch_up_event();
+            observer.try_send(self.heads_up.hand_event(catch_up, None));
+        }
+        self.observer = Some(observer);
+        Some(Observer(Player::new(
+            self.game_type,
+            visibility,
+            recv,
+            self.init_button,
+            self.identities.clone(),
+        )))
+    }
 
-                    if self.board.is_river() {
-                        ActionOver::ShowndownRiver
-                    } else {
-                        ActionOver::ShowdownAll
-                    }
+    // A fresh player for `seat` whose process crashed or disconnected, told where the table was
+    // when the last hand started and that hand's events. Takes over the seat where the game is not running, between hands; `reconnects`
+    // does it while the game runs. None once the game is over.
+    pub fn resubscribe(&mut self, seat: bool) -> Option<Player> {
+        (!self.is_over()).then(|| self.take_over(seat))
+    }
+
+    // Lets seats be taken over while the game runs, mid-hand too: a seat whose player is gone is
+    // held for `grace` before the player counts as exited, for a `Reconnects::reconnect` to take
+    // it. A turn sent to the gone player is asked again of the new one, offers lapse.
+    pub fn reconnects(&mut self, grace: Duration) -> Reconnects {
+        let requests = self
+            .reconnects
+            .get_or_insert_with(|| SeatRequests::new(grace));
+        requests.grace = grace;
+        Reconnects(requests.send.clone())
+    }
+
+    // A player for `seat` replacing the one there, replayed the hand being played through a
+    // channel with room for it, up to `MAX_REPLAY` more events.
+    fn take_over(&mut self, seat: bool) -> Player {
+        let replay = self.events.len().min(MAX_REPLAY);
+        let backpressure = match self.channels.players {
+            Backpressure::Await(capacity) => Backpressure::Await(capacity + replay),
+            Backpressure::Abort(capacity) => Backpressure::Abort(capacity + replay),
+            backpressure => backpressure,
+        };
+        let visibility = Visibility::Player(seat);
+        let (send, recv) = event_channel(backpressure);
+        let sender = PlayerSender {
+            visibility,
+            send,
+            clock: self.heads_up.clock.clone(),
+        };
+        for &event in &self.events {
+            sender.try_send(event);
+        }
+
+        self.players[if seat { 0 } else { 1 }] = sender;
+        Player::new(
+            self.game_type,
+            visibility,
+            recv,
+            self.init_button,
+            self.identities.clone(),
+        )
+    }
+
+    // Waits out the grace for someone to take over `seat`, whose player is gone. False if nobody
+    // did, for the player to count as exited.
+    async fn reconnect(&mut self, seat: bool) -> bool {
+        while let Some(requests) = &mut self.reconnects
+            && let Some(reply) = requests.wait(seat).await
+        {
+            let player = self.take_over(seat);
+            if reply.send(player).is_ok() {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.heads_up.is_over()
+    }
+
+    pub fn game_over(&self) -> Option<GameResult> {
+        self.heads_up.game_over()
+    }
+
+    // Where the hand `player` rebuilt from its events differs from the table's, for debugging.
+    pub fn diff(&self, player: &Player) -> StateDiff {
+        self.heads_up.diff(&player.heads_up)
+    }
+
+    async fn send_ob(&mut self, event: HandEvent) {
+        if let Some(sink) = &mut self.sink {
+            let record = EventRecord {
+                hand: event.hand,
+                at: self.heads_up.clock.now(),
+                stacks: self.heads_up.hand_state.behinds,
+                event: event.event,
+            };
+            if sink.record(&record).is_err() {
+                self.sink = None;
+            }
+        }
+        if let Some(observer) = &self.observer
+            && !observer.send(event).await
+        {
+            self.observer = None;
+        }
+    }
+
+    async fn dispatch_event(&mut self, event: ObservableEvent) -> Option<bool> {
+        self.dispatch_hand_event(self.heads_up.hand_event(event, None))
+            .await
+    }
+
+    async fn dispatch_hand_event(&mut self, event: HandEvent) -> Option<bool> {
+        self.events.push(event);
+        self.send_ob(event).await;
+
+        // a player taking over is replayed the event with the rest
+        for seat in [true, false] {
+            if !self.players[if seat { 0 } else { 1 }].send(event).await
+                && !self.reconnect(seat).await
+            {
+                return Some(seat);
+            }
+        }
+
+        None
+    }
+
+    // With the rake first when a pot was just won.
+    async fn dispatch_stacks(&mut self) -> Option<bool> {
+        if let Some(rake) = self.heads_up.hand_rake()
+            && let Some(player) = self.dispatch_event(ObservableEvent::Rake(rake)).await
+        {
+            return Some(player);
+        }
+        self.dispatch_event(self.heads_up.stacks_event()).await
+    }
+
+    // Some(player) for crashing; nothing to send unless consistency checks are on.
+    async fn dispatch_checksum(&mut self) -> Option<bool> {
+        if !self.checksums {
+            return None;
+        }
+        self.dispatch_event(ObservableEvent::Checksum(self.heads_up.checksum()))
+            .await
+    }
+
+    // Applies a player's action and tells everyone, with the stacks unless it was a check.
+    async fn dispatch_action(
+        &mut self,
+        action: Action,
+        turn: TurnId,
+    ) -> (ActionOver, Option<GameOver>, Option<bool>) {
+        let state = &self.heads_up.hand_state;
+        let (check, actor) = (
+            action.is_check_or_call() && state.can_check(),
+            state.cur_turn,
+        );
+        let (action_over, game_over) = self.heads_up.action(action);
+        let event = ObservableEvent::PlayerAction(action, turn);
+        let mut crashed = self
+            .dispatch_hand_event(self.heads_up.hand_event(event, Some(actor)))
+            .await;
+
+        if crashed.is_none() && !check {
+            crashed = self.dispatch_stacks().await;
+        }
+        if crashed.is_none() && matches!(action_over, ActionOver::TurnOver | ActionOver::RoundOver)
+        {
+            crashed = self.dispatch_checksum().await;
+        }
+        (action_over, game_over, crashed)
+    }
+
+    // The hands shown once the river is called or checked down, by the table's showdown rules, as
+    // one event per player with `called` first.
+    fn river_showdown(&self, board: &FullBoard, called: bool) -> [ObservableEvent; 2] {
+        let holes = self
+            .heads_up
+            .hand_state
+            .holes
+            .map(|hole| hole.expect("holes should be dealt before the showdown"));
+        let (_, winner) = self.deck.variant().who_wins(board, holes[0], holes[1]);
+        let shown = self.showdown_rules.shown(holes, winner, called);
+        [called, !called].map(|seat| match shown[if seat { 0 } else { 1 }] {
+            Some(hole) => ObservableEvent::ShowdownShow(seat, hole),
+            None => ObservableEvent::ShowdownMuck(seat),
+        })
+    }
+
+    // Runs `hook` of every rule and sends what they queued, with the stacks if chips moved.
+    // Some(player) for crashing.
+    async fn run_hooks(
+        &mut self,
+        between_hands: bool,
+        mut hook: impl FnMut(&mut dyn RuleHooks, &mut RuleTable),
+    ) -> Option<bool> {
+        let mut table = RuleTable {
+            state: &mut self.heads_up.hand_state,
+            hands: self.heads_up.hands,
+            between_hands,
+            moved: false,
+            events: vec![],
+        };
+        for hooks in &mut self.rule_hooks {
+            hook(hooks.as_mut(), &mut table);
+        }
+
+        let (moved, events) = (table.moved, table.events);
+        for event in events {
+            if let Some(player) = self.dispatch_event(event).await {
+                return Some(player);
+            }
+        }
+
+        if moved {
+            self.dispatch_stacks().await
+        } else {
+            None
+        }
+    }
+
+    // None for crashing. A player out of time is folded, or checked when checking is free.
+    async fn player_action(
+        &mut self,
+        cur_turn: bool,
+        bet_bound: BetBound,
+        id: TurnId,
+    ) -> Option<Action> {
+        let i = if cur_turn { 0 } else { 1 };
+        loop {
+            let turn = self.players[i].turn(bet_bound.clone(), id);
+            let action = match self.time_bank {
+                Some(time_bank) => {
+                    let clock = &*self.heads_up.clock;
+                    let Some(action) = time_bank.limit(clock, &mut self.banks[i], turn).await
+                    else {
+                        break;
+                    };
+                    action
                 }
+                None => turn.await,
+            };
+            // the same turn again for whoever took the seat over
+            if action.is_some() || !self.reconnect(cur_turn).await {
+                return action;
             }
-            ActionValue::CheckOrCall => {
-                if self.can_check() {
-                    // check
-                    if self.board.is_preflop() {
-                        ActionOver::RoundOver
-                    } else {
-                        let round_over = self.cur_turn == self.button;
+        }
 
-                        if round_over && self.board.is_river() {
-                            ActionOver::ShowndownRiver
-                        } else {
-                            self.cur_turn = !self.cur_turn;
+        // a crash shows when the action is sent to everyone next
+        self.dispatch_event(ObservableEvent::TimedOut(cur_turn))
+            .await;
+        Some(if self.heads_up.hand_state.can_check() {
+            Action::check_or_call()
+        } else {
+            Action::fold()
+        })
+    }
+
+    // Offers the player ahead in an all in on `board` to cash out before the rest is dealt.
+    // Returns whether the hand was settled that way, the game over it led to and who crashed.
+    async fn offer_insurance(
+        &mut self,
+        holes: [Hole; 2],
+        board: &Board,
+    ) -> (bool, Option<GameOver>, Option<bool>) {
+        let pot = self.heads_up.hand_state.pot;
+        let Some((seat, payout)) =
+            self.insurance
+                .zip(hole_equity(holes, board))
+                .and_then(|(insurance, equity)| {
+                    let seat = equity > 0.5;
+                    let equity = if seat { equity } else { 1.0 - equity };
+                    insurance
+                        .payout(pot, equity, self.heads_up.hand_state.bet_unit())
+                        .map(|payout| (seat, payout))
+                })
+        else {
+            return (false, None, None);
+        };
+
+        let i = if seat { 0 } else { 1 };
+        let offer = self.players[i].insurance_offer(payout);
+        let take = match self.time_bank {
+            Some(time_bank) => {
+                let clock = &*self.heads_up.clock;
+                let take = time_bank.limit(clock, &mut self.banks[i], offer).await;
+                take.unwrap_or(Some(false))
+            }
+            None => offer.await,
+        };
+        match take {
+            // a player taking the seat over comes in after the offer lapsed
+            None if self.reconnect(seat).await => return (false, None, None),
+            None => return (false, None, Some(seat)),
+            Some(false) => return (false, None, None),
+            Some(true) => {}
+        }
+
+        let chips = if seat {
+            [payout, pot - payout]
+        } else {
+            [pot - payout, payout]
+        };
+        let game_over = self.heads_up.settle(chips[0]);
+        let mut crashed = self
+            .dispatch_event(ObservableEvent::Insured { seat, payout })
+            .await;
+        if crashed.is_none() {
+            crashed = self.dispatch_stacks().await;
+        }
+        if crashed.is_none() {
+            crashed = self
+                .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                .await;
+        }
+        (true, game_over, crashed)
+    }
+
+    // Ends the hand in a chop once the river makes the board the nuts, showing both holes.
+    // None if the board can still be beaten, else the game over it led to and who crashed.
+    async fn board_chop(&mut self) -> Option<(Option<GameOver>, Option<bool>)> {
+        let state = &self.heads_up.hand_state;
+        if !state.is_board_chop() {
+            return None;
+        }
+
+        let holes = state
+            .holes
+            .map(|hole| hole.expect("holes should be dealt before the showdown"));
+        let share = state.chop_share();
+        let chips = [share, state.pot - share];
+        let board = state.board.as_full_board();
+        let hand = board
+            .expect("a board chop is on the full board")
+            .hand_value_in(holes[0], self.deck.variant());
+        let game_over = self.heads_up.settle(share);
+
+        let mut crashed = self
+            .dispatch_event(ObservableEvent::ShowdownAuto(holes))
+            .await;
+        if crashed.is_none() {
+            crashed = self.award_pot(chips, hand).await;
+        }
+        if crashed.is_none() {
+            crashed = self
+                .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                .await;
+        }
+        Some((game_over, crashed))
+    }
+
+    // Lets the winner of an uncontested pot show some of `hole`; Some(player) for crashing.
+    async fn offer_show(&mut self, winner: bool, hole: Hole) -> Option<bool> {
+        let i = if winner { 0 } else { 1 };
+        let offer = self.players[i].show_offer();
+        let show = match self.time_bank {
+            Some(time_bank) => {
+                let clock = &*self.heads_up.clock;
+                let show = time_bank.limit(clock, &mut self.banks[i], offer).await;
+                show.unwrap_or(Some(ShowCards::Neither))
+            }
+            None => offer.await,
+        };
+        let Some(show) = show else {
+            // a player taking the seat over comes in after the offer lapsed
+            return (!self.reconnect(winner).await).then_some(winner);
+        };
+
+        let cards = show.pick(hole);
+        if cards == [None, None] {
+            return None;
+        }
+        if let Some(player) = self
+            .dispatch_event(ObservableEvent::ShowCards(winner, cards))
+            .await
+        {
+            return Some(player);
+        }
+
+        if show == ShowCards::Both {
+            self.claim_seven_deuce(winner, hole).await
+        } else {
+            None
+        }
+    }
+
+    // Pays the seven-deuce bonus if `winner` won with it, shown; Some(player) for crashing.
+    async fn claim_seven_deuce(&mut self, winner: bool, hole: Hole) -> Option<bool> {
+        let bonus = self.heads_up.pay_seven_deuce(winner, hole)?;
+        let event = ObservableEvent::SevenDeuce {
+            seat: winner,
+            bonus,
+        };
+        if let Some(player) = self.dispatch_event(event).await {
+            return Some(player);
+        }
+        self.dispatch_stacks().await
+    }
+
+    // infallible game over
+    async fn send_game_over(&mut self, game_over: GameOver) -> Option<GameOver> {
+        self.heads_up
+            .set_game_over(game_over, self.heads_up.clock.now());
+        let event = self
+            .heads_up
+            .hand_event(ObservableEvent::GameOver(game_over), None);
+        self.events.push(event);
+        self.send_ob(event).await;
+        self.players[0].send(event).await;
+        self.players[1].send(event).await;
+        if let Some(sink) = &mut self.sink {
+            let _ = sink.flush();
+        }
+        Some(game_over)
+    }
+
+    // Tells everyone about a new hand and its blinds or bomb pot; Some(player) for crashing.
+    async fn announce_hand(&mut self, ante: Option<Chips>, holes: [Hole; 2]) -> Option<bool> {
+        if let Some(level) = self.heads_up.level_start()
+            && let Some(player) = self.dispatch_event(level).await
+        {
+            return Some(player);
+        }
+        if let Some(ante) = ante
+            && let Some(player) = self.dispatch_event(ObservableEvent::BombPot(ante)).await
+        {
+            return Some(player);
+        }
+
+        let deal = ObservableEvent::DealHoles([Some(holes[0]), Some(holes[1])]);
+        if let Some(player) = self.dispatch_event(deal).await {
+            return Some(player);
+        }
+        if let Some(player) = self.dispatch_stacks().await {
+            return Some(player);
+        }
+        if let Some(player) = self.dispatch_checksum().await {
+            return Some(player);
+        }
+        self.run_hooks(false, |hooks, table| hooks.blinds_posted(table))
+            .await
+    }
+
+    // Takes actions from `cur_turn` on until the betting round is over.
+    async fn run_bet_round(&mut self, mut cur_turn: bool, mut bet_bound: BetBound) -> RoundEnd {
+        loop {
+            let turn = self.turns;
+            self.turns = turn.next();
+            let Some(action) = self.player_action(cur_turn, bet_bound, turn).await else {
+                return RoundEnd::Crashed(cur_turn);
+            };
+
+            let state = &self.heads_up.hand_state;
+            let pot = state.total_pot();
+            let check = action.is_check_or_call() && state.can_check();
+            // whoever bet the river shows first when called, else the first to act
+            let first_shown = if check || self.showdown_rules.position_order {
+                !state.button
+            } else {
+                state.last_aggressor
+            };
+
+            let (action_over, game_over, crashed) = self.dispatch_action(action, turn).await;
+            if action.is_exit() {
+                return RoundEnd::Exit(game_over.expect("Exit should always end the game"));
+            }
+            if let Some(player) = crashed {
+                return RoundEnd::Crashed(player);
+            }
+
+            match action_over {
+                ActionOver::TurnOver => {
+                    cur_turn = self.heads_up.hand_state.cur_turn;
+                    bet_bound = self.heads_up.bet_bound();
+                }
+                ActionOver::RoundOver => return RoundEnd::Street,
+                ActionOver::ShowdownAll => return RoundEnd::AllIn,
+                ActionOver::ShowndownRiver => return RoundEnd::River(first_shown),
+                ActionOver::HandOver => {
+                    return RoundEnd::Folded {
+                        winner: !cur_turn,
+                        pot,
+                        game_over,
+                    };
+                }
+            }
+        }
+    }
+
+    // Deals the next street and tells everyone; Some(player) for crashing.
+    async fn deal_board(&mut self, dealer: &mut Dealer) -> Option<bool> {
+        let state = &mut self.heads_up.hand_state;
+        let board = state.next_board(dealer);
+        state.deal_board(board);
+        if let Some(player) = self.dispatch_event(ObservableEvent::DealBoard(board)).await {
+            return Some(player);
+        }
+        self.dispatch_checksum().await
+    }
+
+    // Shows both holes once nobody can bet any more, then runs the board out unless the player
+    // ahead takes insurance.
+    async fn run_all_in(&mut self, dealer: &mut Dealer) -> Option<GameOver> {
+        let holes = self
+            .heads_up
+            .hand_state
+            .holes
+            .map(|hole| hole.expect("holes should be dealt before the showdown"));
+        if let Some(player) = self
+            .dispatch_event(ObservableEvent::ShowdownAll(holes))
+            .await
+        {
+            return self.finish_hand(None, Some(player)).await;
+        }
+
+        let board = self.heads_up.hand_state.board;
+        if let Some(equity) = equity_exact(holes[0], holes[1], board).filter(|_| !board.is_river())
+        {
+            let shares = [equity.share(), equity.lose + equity.tie / 2.0];
+            let event = ObservableEvent::AllInEquity(shares.map(|share| PotShare(share as f32)));
+            if let Some(player) = self.dispatch_event(event).await {
+                return self.finish_hand(None, Some(player)).await;
+            }
+        }
+
+        if let Some(winner) = self.decided_runout(holes) {
+            return self.early_award(winner, holes).await;
+        }
+
+        if !board.is_river() {
+            let (settled, game_over, crashed) = self.offer_insurance(holes, &board).await;
+            if settled || crashed.is_some() {
+                return self.finish_hand(game_over, crashed).await;
+            }
+        }
+
+        if let Some(run_it) = self.run_it.filter(|_| !board.is_river()) {
+            return self.run_boards(run_it, holes, dealer).await;
+        }
+        while !self.heads_up.hand_state.board.is_river() {
+            if let Some(player) = self.deal_board(dealer).await {
+                return self.finish_hand(None, Some(player)).await;
+            }
+        }
+        self.showdown(None).await
+    }
+
+    // Deals the rest of the board once per run and pays each run's part of the pot on it. The
+    // odd chips of the split go to the first runs, those of a chopped run out of position.
+    async fn run_boards(
+        &mut self,
+        run_it: RunItTwice,
+        holes: [Hole; 2],
+        dealer: &mut Dealer,
+    ) -> Option<GameOver> {
+        let state = &self.heads_up.hand_state;
+        let (pot, button) = (state.pot, state.button);
+        let times = run_it.times() as u32;
+        let mut awards = Vec::new();
+        for run in 0..times {
+            let mut variant = self.heads_up.hand_state;
+            while !variant.board.is_river() {
+                let board = variant.next_board(dealer);
+                variant.deal_board(board);
+            }
+            let full = variant.board.as_full_board().expect("the run is dealt out");
+            let (hand, winner) = self.deck.variant().who_wins(&full, holes[0], holes[1]);
+
+            let part = pot / times + Chips::new((run < (pot % times).get()) as u32);
+            let share = match winner {
+                Some(true) => part,
+                Some(false) => Chips::ZERO,
+                None => part / 2 + (part % 2) * (!button as u32),
+            };
+            awards.push((variant.board, [share, part - share], hand));
+        }
+
+        let mut chips = [Chips::ZERO; 2];
+        for (run, (board, amounts, hand)) in awards.into_iter().enumerate() {
+            let events = [
+                ObservableEvent::BoardVariant(run as u8, board),
+                ObservableEvent::PotAwarded { amounts, hand },
+            ];
+            for event in events {
+                if let Some(player) = self.dispatch_event(event).await {
+                    return self.finish_hand(None, Some(player)).await;
+                }
+            }
+            chips = [chips[0] + amounts[0], chips[1] + amounts[1]];
+        }
+
+        let game_over = self.heads_up.settle(chips[0]);
+        let mut crashed = self.dispatch_stacks().await;
+        if crashed.is_none() {
+            crashed = self
+                .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                .await;
+        }
+        self.finish_hand(game_over, crashed).await
+    }
+
+    // With early awards on, the winner once every runout of the board gives the same one, Some(None)
+    // for a chop on all of them.
+    fn decided_runout(&self, holes: [Hole; 2]) -> Option<Option<bool>> {
+        let board = self.heads_up.hand_state.board;
+        if !self.early_award || board.is_river() {
+            return None;
+        }
+
+        let equity = equity_exact(holes[0], holes[1], board)?;
+        if equity.win == 1.0 {
+            Some(Some(true))
+        } else if equity.lose == 1.0 {
+            Some(Some(false))
+        } else if equity.tie == 1.0 {
+            Some(None)
+        } else {
+            None
+        }
+    }
+
+    async fn early_award(&mut self, winner: Option<bool>, holes: [Hole; 2]) -> Option<GameOver> {
+        let state = &self.heads_up.hand_state;
+        let share = state.winner_share(winner);
+        let chips = [share, state.pot - share];
+        let game_over = self.heads_up.settle(share);
+
+        let mut crashed = self
+            .dispatch_event(ObservableEvent::EarlyAward(winner))
+            .await;
+        if crashed.is_none() {
+            crashed = self.dispatch_stacks().await;
+        }
+        if crashed.is_none() {
+            crashed = self
+                .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                .await;
+        }
+        if crashed.is_none()
+            && let Some(winner) = winner
+        {
+            crashed = self
+                .claim_seven_deuce(winner, holes[if winner { 0 } else { 1 }])
+                .await;
+        }
+        self.finish_hand(game_over, crashed).await
+    }
+
+    // Awards the pot on the full board. `first_shown` shows first at a river showdown, None when
+    // the holes are face up already.
+    async fn showdown(&mut self, first_shown: Option<bool>) -> Option<GameOver> {
+        let state = &self.heads_up.hand_state;
+        let holes = state
+            .holes
+            .map(|hole| hole.expect("holes should be dealt before the showdown"));
+        let board = state
+            .board
+            .as_full_board()
+            .expect("the board should be complete at the showdown");
+
+        let mut shown = holes.map(Some);
+        if let Some(first_shown) = first_shown {
+            for event in self.river_showdown(&board, first_shown) {
+                if let ObservableEvent::ShowdownMuck(seat) = event {
+                    shown[if seat { 0 } else { 1 }] = None;
+                }
+                if let Some(player) = self.dispatch_event(event).await {
+                    return self.finish_hand(None, Some(player)).await;
+                }
+            }
+        }
+        if let Some(player) = self
+            .run_hooks(false, |hooks, table| hooks.showdown(table, holes, &board))
+            .await
+        {
+            return self.finish_hand(None, Some(player)).await;
+        }
+
+        let state = &self.heads_up.hand_state;
+        let (hand, winner) = self.deck.variant().who_wins(&board, holes[0], holes[1]);
+        let share = state.winner_share(winner);
+        let chips = [share, state.pot - share];
+        let game_over = self.heads_up.settle(share);
+
+        let mut crashed = self.award_pot(chips, hand).await;
+        if crashed.is_none() {
+            crashed = self
+                .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                .await;
+        }
+        if crashed.is_none()
+            && let Some(winner) = winner
+            && let Some(hole) = shown[if winner { 0 } else { 1 }]
+        {
+            crashed = self.claim_seven_deuce(winner, hole).await;
+        }
+        self.finish_hand(game_over, crashed).await
+    }
+
+    // Tells who won what at a showdown, then the stacks after it; Some(player) for crashing.
+    async fn award_pot(&mut self, amounts: [Chips; 2], hand: HandValue) -> Option<bool> {
+        let crashed = self
+            .dispatch_event(ObservableEvent::PotAwarded { amounts, hand })
+            .await;
+        match crashed {
+            Some(player) => Some(player),
+            None => self.dispatch_stacks().await,
+        }
+    }
+
+    // Ends the game if the hand led to it, a player crashed or busted by a rule after the pot.
+    async fn finish_hand(
+        &mut self,
+        game_over: Option<GameOver>,
+        crashed: Option<bool>,
+    ) -> Option<GameOver> {
+        let game_over = match crashed {
+            Some(player) => Some(self.heads_up.force_exit(player)),
+            None => game_over.or_else(|| self.heads_up.defeated()),
+        };
+        match game_over {
+            Some(game_over) => self.send_game_over(game_over).await,
+            None => None,
+        }
+    }
+
+    // Starts the replay for players taking over with where the table is between hands, as
+    // observers joining late are told.
+    fn open_replay(&mut self) {
+        self.events.clear();
+        if self.heads_up.hands > 0 {
+            let catch_up = self.heads_up.catch_up_event();
+            self.events.push(self.heads_up.hand_event(catch_up, None));
+        }
+    }
+
+    pub async fn run_hand(&mut self) -> Option<GameOver> {
+        if let Some(result) = self.game_over() {
+            return Some(result.reason);
+        }
+        self.open_replay();
+
+        if let Some(player) = self
+            .run_hooks(true, |hooks, table| hooks.hand_start(table))
+            .await
+        {
+            return self.send_game_over(self.heads_up.force_exit(player)).await;
+        }
+
+        let mut dealer = match (self.stacked.pop_front(), self.seed) {
+            (Some(deck), _) => deck.dealer(),
+            (None, Some(seed)) => {
+                let hand = self.heads_up.hands as u64 + 1;
+                self.deck.shuffle_and_deal_seeded(seed, hand)
+            }
+            (None, None) => self.deck.shuffle_and_deal(),
+        };
+
+        let holes = [dealer.deal_hole(), dealer.deal_hole()];
+        self.heads_up.level_up();
+        let ante = self.heads_up.bomb_pot_ante();
+        let bet_info = self.heads_up.deal_holes(holes);
+
+        if let Some(player) = self.announce_hand(ante, holes).await {
+            return self.send_game_over(self.heads_up.force_exit(player)).await;
+        }
+
+        // a bomb pot goes straight to the flop
+        if ante.is_some()
+            && let Some(player) = self.deal_board(&mut dealer).await
+        {
+            return self.finish_hand(None, Some(player)).await;
+        }
+
+        // all in from the blinds or the ante
+        let mut round_end = match bet_info {
+            Some((cur_turn, bet_bound)) => self.run_bet_round(cur_turn, bet_bound).await,
+            None => RoundEnd::AllIn,
+        };
 
-                            if round_over {
-                                ActionOver::RoundOver
-                            } else {
-                                ActionOver::TurnOver
-                            }
-                        }
+        loop {
+            match round_end {
+                RoundEnd::Street if self.heads_up.hand_state.behinds.contains(&Chips::ZERO) => {
+                    round_end = RoundEnd::AllIn; // a call put the caller all in
+                }
+                RoundEnd::Street => {
+                    if let Some(player) = self.deal_board(&mut dealer).await {
+                        return self.finish_hand(None, Some(player)).await;
+                    }
+                    if let Some((game_over, crashed)) = self.board_chop().await {
+                        return self.finish_hand(game_over, crashed).await;
                     }
-                } else {
-                    // call
-                    let villain_bet = self.cur_round[villain];
-
-                    self.pot += villain_bet * 2;
-                    self.behinds[0] -= villain_bet;
-                    self.behinds[1] -= villain_bet;
 
-                    if self.board.is_river() {
-                        ActionOver::ShowndownRiver
-                    } else if self.behinds[villain] == 0 {
-                        ActionOver::ShowdownAll
+                    let state = &self.heads_up.hand_state;
+                    let (cur_turn, bet_bound) = (state.cur_turn, state.bet_bound());
+                    round_end = self.run_bet_round(cur_turn, bet_bound).await;
+                }
+                RoundEnd::AllIn => return self.run_all_in(&mut dealer).await,
+                RoundEnd::River(first_shown) => return self.showdown(Some(first_shown)).await,
+                RoundEnd::Folded {
+                    winner,
+                    pot,
+                    game_over,
+                } => {
+                    let chips = if winner {
+                        [pot, Chips::ZERO]
                     } else {
-                        self.last_bet = 0;
-                        self.cur_round[0] = 0;
-                        self.cur_round[1] = 0;
-                        self.cur_turn = !self.button;
-
-                        if self.board.is_preflop() && !self.opened {
-                            ActionOver::TurnOver
-                        } else {
-                            ActionOver::RoundOver
-                        }
+                        [Chips::ZERO, pot]
+                    };
+                    let mut crashed = self
+                        .run_hooks(true, |hooks, table| hooks.pot_awarded(table, chips))
+                        .await;
+                    if crashed.is_none() {
+                        let hole = holes[if winner { 0 } else { 1 }];
+                        crashed = self.offer_show(winner, hole).await;
                     }
+                    return self.finish_hand(game_over, crashed).await;
                 }
+                RoundEnd::Exit(game_over) => return self.send_game_over(game_over).await,
+                RoundEnd::Crashed(player) => return self.finish_hand(None, Some(player)).await,
             }
         }
     }
 
-    fn event(&mut self, event: ObservableEvent) {
-        match event {
-            ObservableEvent::DealHoles(holes) => {
-                self.holes = holes;
-                self.deal_holes_int();
-            }
-            ObservableEvent::ShowdownAll(holes) => {
-                self.set_holes(holes);
+    pub async fn run(mut self) -> GameResult {
+        while self.run_hand().await.is_none() {}
+        self.result()
+    }
+
+    // Like `run`, but stops once `token` is cancelled and tells everyone the game was aborted.
+    pub async fn run_until(mut self, token: CancellationToken, shutdown: Shutdown) -> GameResult {
+        loop {
+            if token.is_cancelled() {
+                break;
             }
-            ObservableEvent::GameOver(_) => unreachable!(),
-            _ => {
-                // todo: restore history
+
+            let hand = match shutdown {
+                Shutdown::FinishHand => Some(self.run_hand().await),
+                Shutdown::AbortHand => tokio::select! {
+                    game_over = self.run_hand() => Some(game_over),
+                    _ = token.cancelled() => None,
+                },
+            };
+            if let Some(Some(_)) = hand {
+                return self.result();
             }
         }
+
+        let game_over = self.heads_up.abort();
+        self.send_game_over(game_over).await;
+        self.result()
     }
-}
 
-// todo: HeadsUp: core gameplay, rules, logic, and state machine.
-#[derive(Debug, Clone)]
-struct HeadsUp {
-    game_over: Option<GameOver>,
+    fn result(&self) -> GameResult {
+        self.game_over().expect("The game is over")
+    }
+}
 
-    // game info
-    is_sng: bool,
-    hands_limit: u16,
-    blind_levels: vec::IntoIter<u16>,
+pub mod clock;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+pub mod history;
+pub mod identity;
+pub mod sink;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::testing::{Caller, Folder};
+
+    #[test]
+    fn pots_by_street() {
+        let mut state = HandState::new(100, true, [Chips::new(10000), Chips::new(10000)]);
+        state.deal_holes_int();
+        let mut history = StreetHistory::new(state);
+
+        for action in ["300", "c", "c", "200", "c", "500", "f"] {
+            history.record(action.parse().unwrap());
+        }
 
-    // current hand state
-    hand_state: HandState,
-    hands: u16,
-    events: Vec<ObservableEvent>,
-}
+        let preflop = history.street(Street::Preflop).unwrap();
+        assert_eq!(
+            (preflop.pot_start(), preflop.pot_end()),
+            (Chips::new(0), Some(Chips::new(600)))
+        );
+        assert_eq!(preflop.bets()[0].to(), Chips::new(300));
+
+        let flop = history.street(Street::Flop).unwrap();
+        assert_eq!(
+            (flop.pot_start(), flop.pot_end()),
+            (Chips::new(600), Some(Chips::new(1000)))
+        );
+        assert_eq!(flop.bets()[1].pot(), Chips::new(600));
+
+        let turn = history.street(Street::Turn).unwrap();
+        assert_eq!(turn.pot_end(), Some(Chips::new(1000))); // the bet was not called
+        assert_eq!(history.final_pot(), Some(Chips::new(1000)));
+    }
 
-impl HeadsUp {
-    fn new(game_type: GameType, button: bool) -> Self {
-        let init_stack = game_type.init_stack();
-        let init_stacks = [init_stack, init_stack];
-        let mut blind_levels = game_type.blind_levels();
-        let blind = blind_levels.next().expect("Should always has one blind");
+    #[test]
+    fn stacks_after_blinds_and_calls() {
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        let mut dealer = Deck::default().shuffle_and_deal();
+        heads_up.deal_holes([dealer.deal_hole(), dealer.deal_hole()]);
+
+        let stacks = heads_up.stacks_event();
+        assert_eq!(
+            stacks,
+            ObservableEvent::Stacks {
+                behinds: [Chips::new(49750), Chips::new(49500)],
+                bets: [Chips::new(250), Chips::new(500)],
+                pot: Chips::new(0)
+            }
+        );
 
-        Self {
-            game_over: None,
-            is_sng: game_type.is_sng(),
-            hands_limit: game_type.hands_limit(),
-            blind_levels,
-            hand_state: HandState::new(blind, button, init_stacks),
-            hands: 0,
-            events: Default::default(),
-        }
+        heads_up.action(Action::check_or_call());
+        let ObservableEvent::Stacks { behinds, pot, .. } = heads_up.stacks_event() else {
+            unreachable!()
+        };
+        assert_eq!(
+            (behinds, pot),
+            ([Chips::new(49500), Chips::new(49500)], Chips::new(1000))
+        );
     }
 
-    fn is_over(&self) -> bool {
-        self.game_over.is_some()
+    #[test]
+    fn state_diffs() {
+        let mut table = HeadsUp::new(GameType::cash_default(), true);
+        table.deal_holes(["AsAh".parse().unwrap(), "7c2d".parse().unwrap()]);
+        let mut player = table.clone();
+        assert!(table.diff(&player).is_empty());
+
+        table.action(Action::check_or_call());
+        player.hand_state.holes = [Some("KsKh".parse().unwrap()), None];
+        let diff = table.diff(&player);
+        let fields: Vec<_> = diff.fields().collect();
+        assert_eq!(fields, ["pot", "stacks", "bets", "hole 0"]);
+        assert_eq!(diff.to_string().lines().next(), Some("pot: 1000 != 750"));
     }
 
-    fn game_over(&self) -> Option<GameOver> {
-        self.game_over
+    #[test]
+    fn muck_and_masked_holes() {
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()];
+        let (aces, junk) = (Some(holes[0]), Some(holes[1]));
+
+        assert_eq!(
+            ShowdownRules::default().shown(holes, Some(true), false),
+            [aces, junk]
+        );
+
+        let muck = ShowdownRules {
+            auto_muck: true,
+            ..Default::default()
+        };
+        assert_eq!(muck.shown(holes, Some(true), true), [aces, None]);
+        assert_eq!(muck.shown(holes, Some(true), false), [aces, junk]); // called, so shown
+
+        let quiet = ShowdownRules {
+            auto_muck: true,
+            show_only_when_called: true,
+            ..Default::default()
+        };
+        assert_eq!(quiet.shown(holes, Some(true), false), [None, junk]);
+        assert_eq!(quiet.shown(holes, None, false), [None, junk]);
+
+        let (send, _recv) = event_channel(Backpressure::Unbounded);
+        let sender = PlayerSender {
+            visibility: Visibility::Player(false),
+            send,
+            clock: Arc::new(SystemClock::default()),
+        };
+        let deal = ObservableEvent::DealHoles([aces, junk]);
+        assert_eq!(
+            sender.visible(deal),
+            ObservableEvent::DealHoles([None, junk])
+        );
+        let seen = [Visibility::None, Visibility::God].map(|visibility| {
+            let (send, _recv) = event_channel(Backpressure::Unbounded);
+            let sender = PlayerSender {
+                visibility,
+                send,
+                clock: Arc::new(SystemClock::default()),
+            };
+            sender.visible(deal)
+        });
+        assert_eq!(seen, [ObservableEvent::DealHoles([None, None]), deal]);
+        let river = [
+            ObservableEvent::ShowdownShow(false, holes[1]),
+            ObservableEvent::ShowdownMuck(true),
+        ];
+        assert_eq!(river.map(|event| sender.visible(event)), river); // shown to all
     }
 
-    fn stacks(&self) -> [u32; 2] {
-        self.hand_state.init_stacks
+    #[test]
+    fn show_one_card() {
+        let hole: Hole = "AsKd".parse().unwrap();
+        let (ace, king) = (Some(hole[0]), Some(hole[1]));
+
+        assert_eq!("1".parse::<ShowCards>().unwrap().pick(hole), [ace, None]);
+        assert_eq!("2".parse::<ShowCards>().unwrap().pick(hole), [None, king]);
+        assert_eq!("b".parse::<ShowCards>().unwrap().pick(hole), [ace, king]);
+        assert_eq!(ShowCards::default().pick(hole), [None, None]);
+        assert!("3".parse::<ShowCards>().is_err());
     }
 
-    fn hands_reached(&self) -> bool {
-        !(self.is_sng || self.hands < self.hands_limit)
+    #[test]
+    fn insurance_cashout() {
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "KcKd".parse().unwrap()];
+        let flop: Board = "Ad7c2h".parse().unwrap();
+        let equity = hole_equity(holes, &flop).unwrap();
+        assert!((equity - 989.0 / 990.0).abs() < 1e-12); // only runner-runner kings
+
+        let insurance = Insurance::new(0.05).unwrap();
+        assert_eq!(
+            insurance.payout(Chips::new(10000), equity, Chips::new(25)),
+            Some(Chips::new(9475))
+        );
+        assert_eq!(
+            insurance.payout(Chips::new(10000), 1.0 - equity, Chips::new(25)),
+            None
+        );
+        assert!(Insurance::new(1.0).is_none());
+
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        heads_up.deal_holes(holes);
+        heads_up.action(Action::all_in());
+        assert_eq!(
+            heads_up.action(Action::check_or_call()).0,
+            ActionOver::ShowdownAll
+        );
+        assert_eq!(heads_up.settle(Chips::new(95000)), None);
+        assert_eq!(heads_up.stacks(), [Chips::new(95000), Chips::new(5000)]);
     }
 
-    fn next_blind(&mut self) -> u16 {
-        if let Some(blind) = self.blind_levels.next() {
-            blind
+    #[tokio::test]
+    async fn royal_board_chops() {
+        let holes: [Hole; 2] = ["2c3d".parse().unwrap(), "AhAd".parse().unwrap()];
+        let (mut game, _players) = Game::new(GameType::cash_default());
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let button = game.heads_up.hand_state.button;
+
+        let state = HandState::postflop(
+            100,
+            button,
+            Street::River,
+            Chips::new(1025),
+            [Chips::new(49500), Chips::new(49475)],
+        );
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), "AcKcQc9d8h".parse().unwrap());
+        assert!(game.board_chop().await.is_none());
+
+        let royal = "AsKsQsJsTs".parse().unwrap();
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), royal);
+        assert_eq!(game.board_chop().await, Some((None, None)));
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownAuto(holes))
+        );
+        let stacks = if button {
+            [50012, 49988]
         } else {
-            // no more blinds, continue with the last blind
-            self.hand_state.blind
-        }
+            [50013, 49987]
+        };
+        assert_eq!(game.heads_up.stacks(), stacks.map(Chips::new));
     }
 
-    fn abort(&self) -> GameOver {
-        if self.is_sng {
-            GameOver::GameAbort
-        } else {
-            GameOver::AbortCheckout(self.stacks())
-        }
+    #[tokio::test]
+    async fn river_showdown_order() {
+        let holes: [Hole; 2] = ["2c3d".parse().unwrap(), "AhAd".parse().unwrap()];
+        let (game, _players) = Game::new(GameType::cash_default());
+        let mut game = game.with_showdown_rules(ShowdownRules {
+            auto_muck: true,
+            ..Default::default()
+        });
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let button = game.heads_up.hand_state.button;
+        let state = HandState::postflop(
+            100,
+            button,
+            Street::River,
+            Chips::new(1000),
+            [Chips::new(49500), Chips::new(49500)],
+        );
+
+        // the loser was called and shows first, the winner follows
+        let board = "Kc9d8h4s3h".parse().unwrap();
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), board);
+        assert_eq!(game.showdown(Some(true)).await, None);
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownShow(true, holes[0]))
+        );
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownShow(false, holes[1]))
+        );
+        let hand = board.as_full_board().unwrap().hand_value(holes[1]);
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::PotAwarded {
+                amounts: [Chips::ZERO, Chips::new(1000)],
+                hand
+            })
+        );
+
+        // the winner shows first, the loser mucks in turn
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), board);
+        assert_eq!(game.showdown(Some(false)).await, None);
+        while observer.tick_event().await != Some(ObservableEvent::ShowdownShow(false, holes[1])) {}
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownMuck(true))
+        );
     }
 
-    fn force_exit(&self, player: bool) -> GameOver {
-        if self.is_sng {
-            GameOver::ExitAbandon(player)
-        } else {
-            GameOver::ExitCheckout(player, self.stacks())
+    #[tokio::test]
+    async fn decided_runouts_award_early() {
+        let holes: [Hole; 2] = ["Ts9d".parse().unwrap(), "2c3d".parse().unwrap()];
+        let (game, _players) = Game::new(GameType::cash_default());
+        let mut game = game.with_early_award();
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let button = game.heads_up.hand_state.button;
+        let state = HandState::postflop(
+            500,
+            button,
+            Street::Turn,
+            Chips::new(20000),
+            [Chips::new(40000), Chips::new(40000)],
+        );
+        let mut dealer = Deck::default().shuffle_and_deal();
+
+        // a royal flush on the turn, nothing left to deal changes it
+        let royal = "AsKsQsJs".parse().unwrap();
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), royal);
+        assert_eq!(game.run_all_in(&mut dealer).await, None);
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownAll(holes))
+        );
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::AllInEquity([PotShare(1.0), PotShare(0.0)]))
+        );
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::EarlyAward(Some(true)))
+        );
+        assert_eq!(game.heads_up.stacks(), [60000, 40000].map(Chips::new));
+
+        let open = "AsKsQs2h".parse().unwrap();
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), open);
+        assert_eq!(game.decided_runout(holes), None);
+    }
+
+    #[tokio::test]
+    async fn run_it_twice() {
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "KcKd".parse().unwrap()];
+        let (game, _players) = Game::new(GameType::cash_default());
+        let mut game = game.with_run_it_twice(RunItTwice::new(2).unwrap());
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let button = game.heads_up.hand_state.button;
+        let state = HandState::postflop(
+            500,
+            button,
+            Street::Turn,
+            Chips::new(20001),
+            [Chips::new(40000), Chips::new(39999)],
+        );
+        let turn: Board = "7c2d9h3s".parse().unwrap();
+        game.heads_up.hand_state = state.with_cards(holes.map(Some), turn);
+        let mut dealer = Deck::default().shuffle_and_deal_seeded(3, 0);
+        assert_eq!(game.run_all_in(&mut dealer).await, None);
+
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::ShowdownAll(holes))
+        );
+        // the kings need one of the two kings left of 44 cards
+        let shares = [42.0 / 44.0, 2.0 / 44.0].map(|share: f64| PotShare(share as f32));
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::AllInEquity(shares))
+        );
+        let mut rivers = vec![];
+        let mut won = [Chips::ZERO; 2];
+        for run in 0..2 {
+            let Some(ObservableEvent::BoardVariant(i, board)) = observer.tick_event().await else {
+                panic!("run {run} should deal its board");
+            };
+            assert_eq!(i, run);
+            assert!(board.is_river() && board.to_vec().starts_with(&turn.to_vec()));
+            rivers.push(board.to_vec()[4]);
+            let Some(ObservableEvent::PotAwarded { amounts, .. }) = observer.tick_event().await
+            else {
+                panic!("run {run} should be paid");
+            };
+            assert_eq!(amounts[0] + amounts[1], Chips::new(10001 - run as u32));
+            won = [won[0] + amounts[0], won[1] + amounts[1]];
         }
+        assert_ne!(rivers[0], rivers[1]);
+        let stacks = game.heads_up.stacks();
+        assert_eq!(stacks[0] + stacks[1], Chips::new(100000));
+        assert_eq!(stacks[0], Chips::new(40000) + won[0]);
     }
 
-    fn set_game_over(&mut self, game_over: GameOver) {
-        self.game_over = Some(game_over);
+    #[test]
+    fn session_stops() {
+        let stop = StopConditions::default()
+            .with_stop_loss(0.5)
+            .with_stop_win(0.8)
+            .with_max_hands(3);
+        let second = Duration::from_secs(1);
+        assert_eq!(
+            stop.reason([Chips::new(50000), Chips::new(50000)], 2, second),
+            None
+        );
+        assert_eq!(
+            stop.reason([Chips::new(25000), Chips::new(75000)], 2, second),
+            Some(StopReason::StopLoss(true))
+        );
+        assert_eq!(
+            stop.reason([Chips::new(50000), Chips::new(50000)], 3, second),
+            Some(StopReason::MaxHands)
+        );
+        let stop = stop.with_stop_loss(0.9).with_max_duration(second);
+        assert_eq!(
+            stop.reason([Chips::new(90000), Chips::new(10000)], 0, second),
+            Some(StopReason::StopWin(true))
+        );
+        assert_eq!(
+            stop.reason([Chips::new(50000), Chips::new(50000)], 0, second),
+            Some(StopReason::MaxDuration)
+        );
+
+        let (game, _players) = Game::new(GameType::cash_default());
+        let mut game = game.with_stop_conditions(StopConditions::default().with_max_hands(2));
+        assert_eq!(game.heads_up.end_hand(), None);
+        assert_eq!(
+            game.heads_up.end_hand(),
+            Some(GameOver::SessionStop(
+                StopReason::MaxHands,
+                [Chips::new(50000), Chips::new(50000)],
+                [Chips::ZERO; 2]
+            ))
+        );
+        let (sng, _players) = Game::new(GameType::default());
+        let sng = sng.with_stop_conditions(StopConditions::default().with_max_hands(1));
+        assert!(sng.heads_up.stop_conditions.is_none());
     }
 
-    fn bet_bound(&self) -> BetBound {
-        self.hand_state.bet_bound()
+    #[test]
+    fn shuffle_audit() {
+        let audit = Deck::audit(5000);
+        assert!(audit.is_fair(), "{audit:?}");
+
+        // an RNG stuck at zero always gives the same order
+        struct Stuck;
+        impl RngCore for Stuck {
+            fn next_u32(&mut self) -> u32 {
+                0
+            }
+            fn next_u64(&mut self) -> u64 {
+                0
+            }
+            fn fill_bytes(&mut self, dst: &mut [u8]) {
+                dst.fill(0);
+            }
+        }
+        let audit = Deck::audit_with(5000, &mut Stuck);
+        assert!(!audit.is_fair());
+        assert!(audit.ks_distance > 0.9);
     }
 
-    fn deal_holes(&mut self, holes: [Hole; 2]) -> Option<(bool, BetBound)> {
-        self.hand_state.deal_holes(holes)
+    #[tokio::test]
+    async fn seeded_deals() {
+        let deal = |hand| Deck::default().shuffle_and_deal_seeded(7, hand).deal_hole();
+        let mut deck = Deck::default();
+        deck.shuffle_and_deal_seeded(7, 1);
+        assert_eq!(deck.shuffle_and_deal_seeded(7, 2).deal_hole(), deal(2));
+        assert_ne!(deal(2), deal(3));
+        assert_ne!(
+            deal(2),
+            Deck::default().shuffle_and_deal_seeded(8, 2).deal_hole()
+        );
+
+        let play = async || {
+            let (mut game, players) = Game::seeded(GameType::cash_default(), 7);
+            let mut observer = game.observer(Visibility::God).unwrap();
+            let _bots = players.map(|mut player| {
+                tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+            });
+            for _ in 0..2 {
+                assert_eq!(game.run_hand().await, None);
+            }
+            game.send_game_over(GameOver::GameAbort).await;
+
+            let mut events = vec![];
+            while let Some(event) = observer.tick_event().await {
+                events.push(event);
+            }
+            (game.init_button, events)
+        };
+        let (button, events) = play().await;
+        let holes = events.iter().filter_map(|event| match event {
+            ObservableEvent::DealHoles([hole, _]) => *hole,
+            _ => None,
+        });
+        assert_eq!(holes.collect::<Vec<_>>(), [deal(1), deal(2)]);
+        assert_eq!(play().await, (button, events));
     }
 
-    fn action(&mut self, action: Action) -> (ActionOver, Option<GameOver>) {
-        let action_over = self.hand_state.action(action);
-        let mut game_over = None;
+    #[test]
+    fn deck_display() {
+        let deck = Deck::default();
+        assert!(deck.contains("Ah".parse().unwrap()));
+
+        let by_suit = deck
+            .display(DisplayMode::Ascii)
+            .with_order(DeckOrder::BySuit);
+        let rows = by_suit.to_string();
+        let rows: Vec<_> = rows.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].starts_with("As Ks Qs") && rows[3].ends_with("3c 2c"));
+
+        let by_rank = by_suit.with_order(DeckOrder::ByRank).with_per_row(4);
+        let rows = by_rank.to_string();
+        assert_eq!(rows.lines().count(), 13);
+        assert_eq!(rows.lines().next(), Some("As Ah Ad Ac"));
+
+        let short = Deck::new(Variant::ShortDeck);
+        assert_eq!(short.cards().len(), 36);
+        assert!(!short.contains("5h".parse().unwrap()));
+        let by_suit = short
+            .display(DisplayMode::Ascii)
+            .with_order(DeckOrder::BySuit);
+        assert!(by_suit.to_string().ends_with("7c 6c"));
+
+        // dead cards are never dealt, and stay out across seeded deals
+        let mut deck = Deck::default();
+        let dead: Vec<Card> = ["As", "Ah", "Kd"].map(|c| c.parse().unwrap()).to_vec();
+        deck.remove(&dead);
+        assert_eq!(deck.remaining(), 49);
+        assert!(!deck.contains(dead[0]) && deck.contains("Ad".parse().unwrap()));
+        let mut dealer = deck.shuffle_and_deal_seeded(5, 0);
+        let dealt: Vec<Card> = (0..49).map(|_| dealer.deal_card()).collect();
+        assert_eq!(dealer.remaining(), 0);
+        assert!(dealt.iter().all(|card| !dead.contains(card)));
+        assert_eq!(deck.dealer().deal_card(), dealt[0]); // dealing leaves the deck as it is
+
+        let mut dealer = Deck::new(Variant::ShortDeck).shuffle_and_deal_seeded(5, 0);
+        let dealt: Vec<Card> = (0..36).map(|_| dealer.deal_card()).collect();
+        assert!(dealt.iter().all(|card| card.value() >= Value::Six));
+        assert!(dealt.iter().all_unique());
+    }
 
-        if action_over == ActionOver::HandOver {
-            let stacks_checkout = self.hand_state.behinds;
+    #[tokio::test]
+    async fn player_views() {
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()];
+        let (mut game, [mut first, _second]) = Game::new(GameType::cash_default());
+        let button = game.heads_up.hand_state.button;
+        game.heads_up.deal_holes(holes);
+        game.announce_hand(None, holes).await;
+        game.dispatch_event(ObservableEvent::PlayerAction(
+            Action::check_or_call(),
+            TurnId::new(0),
+        ))
+        .await;
+        for _ in 0..3 {
+            first.tick_event().await;
+        }
 
-            if action.is_exit() {
-                let who_exit = self.hand_state.cur_turn;
-                game_over = Some(if self.is_sng {
-                    GameOver::ExitAbandon(who_exit)
-                } else {
-                    GameOver::ExitCheckout(who_exit, stacks_checkout)
-                });
-            } else {
-                self.hands += 1;
+        let view = first.view().unwrap();
+        assert_eq!(view.hole(), Some(holes[0]));
+        assert_eq!(view.shown(), [None, None]);
+        assert_eq!((view.pot(), view.is_button()), (Chips::new(1000), button));
+        assert_eq!(view.stacks(), [Chips::new(49500); 2]);
+        assert_eq!(view.history().len(), 1);
+        assert_eq!(view.history()[0].actor(), button);
+        assert!(view.bet_bound().is_none() && view.legal_actions().is_empty());
+        assert_eq!(view.bet_unit(), Chips::new(25));
+        // the limp is tracked on the player side, its chips already in the pot
+        assert_eq!(view.bets(), [Chips::ZERO; 2]);
+        assert_eq!(view.to_call(), Chips::ZERO);
+        let sized = Action::parse_in("pot", &view.bet_context()).unwrap();
+        assert_eq!(sized, Action::bet_or_raise(Chips::new(1000)).unwrap());
+
+        let shown = [Some(holes[1][0]), None];
+        game.dispatch_event(ObservableEvent::ShowCards(false, shown))
+            .await;
+        first.tick_event().await;
+        assert_eq!(first.view().unwrap().shown(), shown);
+        assert!(game.observer(Visibility::God).unwrap().0.view().is_none());
+    }
 
-                if self.hands_reached() {
-                    game_over = Some(GameOver::HandsReached(stacks_checkout));
-                } else {
-                    let next_blind = self.next_blind();
-                    self.hand_state = self.hand_state.next(next_blind);
-                }
-            }
+    #[test]
+    fn actions_in_words_and_sizes() {
+        // 200 in facing 700, the pot of 2000 is 2500 with the call
+        let context = BetContext::new(
+            Chips::new(2000),
+            Chips::new(100),
+            [200, 700].map(Chips::new),
+        );
+        let parse = |s| Action::parse_in(s, &context).unwrap();
+        assert_eq!(parse("2.5bb"), "250".parse().unwrap());
+        assert_eq!(parse("pot"), "3200".parse().unwrap());
+        assert_eq!(parse("raise to half"), "1950".parse().unwrap());
+        assert_eq!(parse("2/3"), "2375".parse().unwrap());
+        assert_eq!(parse("c"), Action::check_or_call());
+        assert!(Action::parse_in("0bb", &context).is_err());
+
+        for action in ["x", "f", "c", "1500", "a"] {
+            let action: Action = action.parse().unwrap();
+            let text = action.display(context).to_string();
+            assert_eq!(text.parse(), Ok(action), "{text}");
         }
+        let raise = "raise to 1,500";
+        assert_eq!(parse(raise).display(context).to_string(), raise);
+        assert_eq!(
+            Action::check_or_call().display(context).to_string(),
+            "call 500"
+        );
+
+        let unopened = BetContext::new(Chips::new(1000), Chips::new(100), [Chips::ZERO; 2]);
+        let bet = Action::bet_or_raise(Chips::new(2500)).unwrap();
+        assert_eq!(bet.display(unopened).to_string(), "bet 2,500");
+        assert_eq!(
+            Action::check_or_call().display(unopened).to_string(),
+            "check"
+        );
+    }
 
-        (action_over, game_over)
+    #[test]
+    fn enumerate_legal_actions() {
+        let raise = BetBound::FoldCallRaiseAllIn(Chips::new(200)..=Chips::new(1000));
+        let actions: Vec<Action> = raise.legal_actions(Chips::new(300)).collect();
+        assert_eq!(
+            actions,
+            ["f", "c", "200", "500", "800", "a"].map(|a| a.parse().unwrap())
+        );
+        assert!(actions.iter().all(|&action| raise.validate_action(action)));
+
+        let bet = BetBound::FoldBetAllIn(Chips::new(100)..=Chips::new(10000));
+        assert_eq!(bet.legal_actions(Chips::new(0)).count(), 3);
+        assert_eq!(bet.legal_actions(Chips::new(10)).count(), 2 + 990);
+        assert_eq!(
+            BetBound::FoldCall
+                .legal_actions(Chips::new(100))
+                .collect::<Vec<_>>(),
+            [Action::fold(), Action::check_or_call()]
+        );
     }
 
-    fn event(&mut self, event: ObservableEvent) -> Option<HandHistory> {
-        self.events.push(event);
+    #[test]
+    fn bet_units() {
+        let blinds = Blinds::new(10, 20).unwrap();
+        assert_eq!(blinds.unit(), Chips::new(5));
+        assert_eq!(Blinds::new(250, 500).unwrap().unit(), Chips::new(25));
+        assert!(blinds.with_unit(3).is_none());
+        let structure = BlindStructure::new([blinds])
+            .unwrap()
+            .with_unit(10)
+            .unwrap();
+        assert_eq!(structure.level(0).unit(), Chips::new(10));
+
+        let mut state = HandState::new(20, true, [Chips::new(1003); 2]).with_blinds(blinds);
+        state.deal_holes_int();
+        let bound = state.bet_bound();
+        let unit = state.bet_unit();
+        let raise =
+            |amount| bound.alter_eq(Action::bet_or_raise(Chips::new(amount)).unwrap(), unit);
+        assert_eq!(raise(45), Action::bet_or_raise(Chips::new(45)));
+        assert_eq!(raise(47), None);
+        assert_eq!(raise(1003), Some(Action::all_in())); // the odd stack is all in
+    }
 
-        if let ObservableEvent::GameOver(game_over) = event {
-            self.set_game_over(game_over);
-        } else {
-            self.hand_state.event(event);
-        }
+    #[test]
+    fn river_nuts_bounds() {
+        let board: Board = "AsKsQs7d2c".parse().unwrap();
+        let holes = ["JsTs".parse().unwrap(), "AhAd".parse().unwrap()].map(Some);
+        let river = |button| {
+            HandState::postflop(
+                100,
+                button,
+                Street::River,
+                Chips::new(1000),
+                [Chips::new(10000), Chips::new(10000)],
+            )
+            .with_cards(holes, board)
+        };
 
-        // todo: HandHistory
-        None
+        // out of position may still check, the button may not check behind
+        let mut state = river(false);
+        assert_eq!(
+            state.bet_bound(),
+            BetBound::FoldCheckBetAllIn(Chips::new(100)..=Chips::new(10000))
+        );
+        let mut unopened = river(true);
+        unopened.action(Action::check_or_call());
+        assert_eq!(
+            unopened.bet_bound(),
+            BetBound::FoldBetAllIn(Chips::new(100)..=Chips::new(10000))
+        );
+
+        // facing a bet it must raise
+        state.action(Action::check_or_call());
+        state.action("1000".parse().unwrap());
+        assert_eq!(
+            state.bet_bound(),
+            BetBound::FoldRaiseAllIn(Chips::new(2000)..=Chips::new(10000))
+        );
+        let mut opened = river(true);
+        opened.action("9000".parse().unwrap());
+        assert_eq!(opened.bet_bound(), BetBound::FoldAllIn);
+
+        // the second nuts plays as usual
+        let mut villain = river(false);
+        villain.action("1000".parse().unwrap());
+        assert_eq!(
+            villain.bet_bound(),
+            BetBound::FoldCallRaiseAllIn(Chips::new(2000)..=Chips::new(10000))
+        );
     }
-}
 
-#[derive(Debug)]
-pub struct Game {
-    game_type: GameType,
-    init_button: bool,
-    players: [PlayerSender; 2],
-    observer: Option<PlayerSender>,
-    deck: Deck,
-    heads_up: HeadsUp,
-}
+    #[test]
+    fn pot_limit_bounds() {
+        let flop =
+            |stacks| HandState::postflop(100, false, Street::Flop, Chips::new(1000), [stacks; 2]);
+
+        let mut state = flop(Chips::new(10000));
+        let bound = state.pot_limit_bet_bound();
+        assert_eq!(
+            bound,
+            BetBound::FoldCheckBet(Chips::new(100)..=Chips::new(1000))
+        );
+        assert!(!bound.validate_action(Action::all_in()));
+        assert_eq!(
+            bound.legal_actions(Chips::new(400)).collect::<Vec<_>>(),
+            ["f", "c", "100", "500", "900", "1000"].map(|a| a.parse().unwrap())
+        );
+
+        // call 1000 into 3000, then raise the 3000
+        state.action("1000".parse().unwrap());
+        assert_eq!(
+            state.pot_limit_bet_bound(),
+            BetBound::FoldCallRaise(Chips::new(2000)..=Chips::new(4000))
+        );
+
+        // short enough to be all in within the pot
+        let mut short = flop(Chips::new(3000));
+        short.action("1000".parse().unwrap());
+        assert_eq!(
+            short.pot_limit_bet_bound(),
+            BetBound::FoldCallRaiseAllIn(Chips::new(2000)..=Chips::new(3000))
+        );
+    }
 
-impl Game {
-    pub fn new(game_type: GameType) -> (Self, [Player; 2]) {
-        let vis = [Visibility::Player(true), Visibility::Player(false)];
-        let [(send0, recv0), (send1, recv1)] = [unbounded_channel(), unbounded_channel()];
-        let init_button = rand::random();
-        let game = Self {
-            game_type,
-            init_button,
-            players: [
-                PlayerSender {
-                    visibility: vis[0],
-                    send: send0,
-                },
-                PlayerSender {
-                    visibility: vis[1],
-                    send: send1,
-                },
-            ],
-            observer: None,
-            deck: Default::default(),
-            heads_up: HeadsUp::new(game_type, init_button),
+    #[test]
+    fn bomb_pot_every_other_hand() {
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        heads_up.bomb_pot = BombPot::new(2, 4);
+        let mut dealer = Deck::default().shuffle_and_deal();
+
+        assert_eq!(heads_up.bomb_pot_ante(), None);
+        heads_up.deal_holes([dealer.deal_hole(), dealer.deal_hole()]);
+        heads_up.action(Action::fold());
+
+        assert_eq!(heads_up.bomb_pot_ante(), Some(Chips::new(2000)));
+        let (turn, bet_bound) = heads_up
+            .deal_holes([dealer.deal_hole(), dealer.deal_hole()])
+            .unwrap();
+        assert!(turn); // out of position acts first, the button moved
+        assert_eq!(
+            bet_bound,
+            BetBound::FoldCheckBetAllIn(Chips::new(500)..=Chips::new(47750))
+        );
+        assert_eq!(heads_up.hand_state.street(), Street::Flop);
+        assert_eq!(heads_up.hand_state.pot(), Chips::new(4000));
+    }
+
+    #[test]
+    fn seven_deuce_bonus() {
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        heads_up.seven_deuce = SevenDeuce::new(10);
+        let holes: [Hole; 2] = ["7c2d".parse().unwrap(), "AsAh".parse().unwrap()];
+        heads_up.deal_holes(holes);
+        heads_up.action("1500".parse().unwrap());
+        heads_up.action(Action::fold());
+
+        assert_eq!(heads_up.pay_seven_deuce(false, holes[1]), None);
+        assert_eq!(
+            heads_up.pay_seven_deuce(true, holes[0]),
+            Some(Chips::new(5000))
+        );
+        assert_eq!(heads_up.stacks(), [Chips::new(55500), Chips::new(44500)]);
+    }
+
+    #[test]
+    fn capped_rake_after_the_flop() {
+        let rake = Rake::new(5.0).unwrap().with_cap(Chips::new(300));
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB100,
+            hands: 0,
+            rake: Some(rake),
         };
-        let players = [
-            Player::new(game_type, vis[0], recv0, init_button),
-            Player::new(game_type, vis[1], recv1, !init_button),
-        ];
-        (game, players)
+        let mut heads_up = HeadsUp::new(game_type, true);
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()];
+
+        // no flop, no drop
+        heads_up.deal_holes(holes);
+        heads_up.action("1500".parse().unwrap());
+        heads_up.action(Action::fold());
+        assert_eq!(heads_up.hand_rake(), None);
+
+        // 5% of the 10000 called before the flop, the bet folded to isn't raked
+        heads_up.deal_holes(holes);
+        heads_up.action("5000".parse().unwrap());
+        heads_up.action(Action::check_or_call());
+        heads_up.hand_state.next_street();
+        heads_up.action("3000".parse().unwrap());
+        heads_up.action(Action::fold());
+        assert_eq!(heads_up.hand_rake(), Some([Chips::new(300), Chips::ZERO]));
+        assert_eq!(
+            heads_up.abort(),
+            GameOver::AbortCheckout(
+                [Chips::new(55200), Chips::new(44500)],
+                [Chips::new(300), Chips::ZERO]
+            )
+        );
+        assert_eq!(
+            rake.with_no_flop_no_drop(false)
+                .of(Chips::new(1000), Street::Preflop),
+            Chips::new(50)
+        );
     }
 
-    pub fn observer(&mut self, visibility: Visibility) -> Option<Observer> {
-        if self.observer.is_some() {
-            return None; // Observer already exists
+    #[derive(Debug)]
+    struct ButtonTax(Chips);
+
+    impl RuleHooks for ButtonTax {
+        fn hand_start(&mut self, table: &mut RuleTable) {
+            let button = table.button();
+            let chips = table.transfer(button, self.0);
+            table.emit(ObservableEvent::HouseRule {
+                name: "button tax",
+                seat: !button,
+                chips,
+            });
         }
+    }
 
-        let (send, recv) = unbounded_channel();
-        let button = if visibility == Visibility::Player(false) {
-            !self.init_button
-        } else {
-            self.init_button
+    #[tokio::test]
+    async fn rule_hooks_move_chips() {
+        let (game, _players) = Game::new(GameType::cash_default());
+        let mut game = game.with_rule_hooks(ButtonTax(Chips::new(100)));
+        let button = game.heads_up.hand_state.button;
+
+        let hand_start = game.run_hooks(true, |hooks, table| hooks.hand_start(table));
+        assert_eq!(hand_start.await, None);
+        let mut stacks = [49900, 50100];
+        if !button {
+            stacks.reverse();
+        }
+        assert_eq!(game.heads_up.stacks(), stacks.map(Chips::new));
+    }
+
+    #[tokio::test]
+    async fn backpressure_policies() {
+        let lossy = Channels {
+            players: Backpressure::DropOldest(4),
+            observer: Backpressure::Unbounded,
         };
-        self.observer = Some(PlayerSender { visibility, send });
-        Some(Observer(Player::new(
-            self.game_type,
-            visibility,
-            recv,
-            button,
-        )))
+        assert!(Game::bounded(GameType::cash_default(), lossy).is_none());
+
+        let channels = Channels {
+            players: Backpressure::Abort(2),
+            observer: Backpressure::DropOldest(1),
+        };
+        let (mut game, _players) = Game::bounded(GameType::cash_default(), channels).unwrap();
+        let mut observer = game.observer(Visibility::God).unwrap();
+
+        assert_eq!(game.dispatch_stacks().await, None);
+        assert_eq!(game.dispatch_stacks().await, None);
+        assert_eq!(game.dispatch_stacks().await, Some(true)); // player 0 never read
+
+        let over = ObservableEvent::GameOver(GameOver::GameAbort);
+        game.send_ob(game.heads_up.hand_event(over, None)).await;
+        assert_eq!(observer.tick_event().await, Some(over)); // only the latest is kept
     }
 
-    pub fn is_over(&self) -> bool {
-        self.heads_up.is_over()
+    #[tokio::test]
+    async fn observer_stream() {
+        use tokio_stream::StreamExt;
+
+        let (mut game, _players) = Game::new(GameType::cash_default());
+        let observer = game.observer(Visibility::None).unwrap();
+        game.dispatch_stacks().await;
+        game.send_game_over(GameOver::GameAbort).await;
+
+        let events: Vec<_> = observer.collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            ObservableEvent::Stacks {
+                pot: Chips::ZERO,
+                ..
+            }
+        ));
+        assert_eq!(events[1], ObservableEvent::GameOver(GameOver::GameAbort));
     }
 
-    pub fn game_over(&self) -> Option<GameOver> {
-        self.heads_up.game_over()
+    #[tokio::test]
+    async fn observer_joins_mid_game() {
+        let (game, players) = Game::new(GameType::sng(SNGSpeed::Turbo));
+        let mut game = game.with_consistency_checks();
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+        });
+        for _ in 0..2 {
+            assert_eq!(game.run_hand().await, None);
+        }
+
+        let mut observer = game.observer(Visibility::God).unwrap();
+        let state = game.heads_up.hand_state;
+        assert_eq!(
+            observer.tick_event().await,
+            Some(ObservableEvent::CatchUp {
+                hands: 2,
+                button: state.button,
+                big_blind: Chips::new(150),
+                stacks: state.behinds,
+            })
+        );
+        assert_eq!(game.run_hand().await, None);
+        game.send_game_over(GameOver::GameAbort).await;
+        while observer.tick_event().await.is_some() {}
+        assert_eq!(observer.desync(), None);
+
+        let hands = observer.hands_history();
+        assert_eq!(hands.len(), 1);
+        assert_eq!(
+            (hands[0].number(), hands[0].big_blind()),
+            (3, Chips::new(150))
+        );
+        // the button folds its small blind, the big blind's uncalled half comes back
+        let result = hands[0].result();
+        let button = hands[0].button();
+        assert_eq!((result.net(button), result.net(!button)), (-75, 75));
+        assert_eq!(result.total_invested(!button), Chips::new(75));
     }
 
-    fn send_ob(&mut self, event: ObservableEvent) {
-        if let Some(observer) = &self.observer {
-            if !observer.send(event) {
-                self.observer = None;
+    #[tokio::test]
+    async fn blind_levels_by_hands() {
+        let game_type = GameType::SNG {
+            speed: SNGSpeed::Turbo,
+            level_hands: 2,
+        };
+        let (game, players) = Game::new(game_type);
+        let mut game = game.with_consistency_checks();
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+        });
+        for _ in 0..5 {
+            assert_eq!(game.run_hand().await, None);
+        }
+        assert_eq!(game.heads_up.hands_until_next_level(), Some(1));
+        game.send_game_over(GameOver::GameAbort).await;
+
+        let mut levels = vec![];
+        while let Some(event) = observer.tick_event().await {
+            if let ObservableEvent::BlindLevel {
+                level,
+                blinds,
+                hands,
+            } = event
+            {
+                levels.push((level, blinds.big_blind().get(), hands));
             }
         }
+        assert_eq!(
+            levels,
+            [(1, 50, Some(2)), (2, 100, Some(2)), (3, 150, Some(2))]
+        );
     }
 
-    fn dispatch_event(&mut self, event: ObservableEvent) -> Option<bool> {
-        self.send_ob(event);
-
-        if !self.players[0].send(event) {
-            return Some(true);
+    #[tokio::test]
+    async fn blind_structure_forced_bets() {
+        let levels = [
+            Blinds::new(50, 100).unwrap().with_ante(100),
+            Blinds::new(100, 200).unwrap().with_ante(200),
+        ];
+        let structure = BlindStructure::new(levels)
+            .unwrap()
+            .with_level_time(Duration::from_secs(600))
+            .with_big_blind_ante()
+            .with_straddle();
+        let clock = clock::MockClock::default();
+        let (game, players) = Game::new(GameType::cash_default());
+        let mut game = game
+            .with_clock(clock.clone())
+            .with_blind_structure(structure)
+            .with_consistency_checks();
+        let mut observer = game.observer(Visibility::God).unwrap();
+
+        // the big blind antes and acts first, the straddle has the option
+        let mut state = game.heads_up.hand_state;
+        let bb = if state.button { 1 } else { 0 };
+        state.deal_holes_int().unwrap();
+        assert_eq!(
+            (state.pot, state.behinds[bb]),
+            (Chips::new(100), Chips::new(49900))
+        );
+        assert_eq!(state.cur_round[1 - bb], Chips::new(200));
+        assert_eq!(state.cur_turn, !state.button);
+        assert_eq!(state.action(Action::check_or_call()), ActionOver::TurnOver);
+        assert_eq!(state.cur_turn, state.button);
+        assert_eq!(state.action(Action::check_or_call()), ActionOver::RoundOver);
+        assert_eq!(state.cur_turn, !state.button);
+
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+        });
+        for _ in 0..3 {
+            assert_eq!(game.run_hand().await, None);
+            clock.advance(Duration::from_secs(400));
         }
+        assert_eq!(game.heads_up.hands_until_next_level(), None);
+        game.send_game_over(GameOver::GameAbort).await;
+        while observer.tick_event().await.is_some() {}
+        assert_eq!(observer.desync(), None);
+
+        // the third hand is dealt 800 seconds in
+        let hands = observer.hands_history();
+        let blinds = hands.iter().map(|hand| hand.big_blind().get());
+        assert_eq!(blinds.collect::<Vec<_>>(), [100, 100, 200]);
+        assert_eq!(hands[2].blinds().ante(), Chips::new(200));
+        let text = history::PokerStars::default().export(&hands[0]);
+        assert!(text.contains(": posts the ante 100"), "{text}");
+        assert!(text.contains(": posts straddle 200"), "{text}");
+    }
 
-        if !self.players[1].send(event) {
-            return Some(false);
+    #[tokio::test]
+    async fn game_result() {
+        let clock = clock::MockClock::default();
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB100,
+            hands: 3,
+            rake: None,
+        };
+        let (game, players) = Game::with_button(game_type, Default::default(), true).unwrap();
+        let mut game = game.with_clock(clock.clone());
+        let mut observer = game.observer(Visibility::God).unwrap();
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+        });
+        while game.run_hand().await.is_none() {
+            clock.advance(Duration::from_secs(30));
         }
+        while observer.tick_event().await.is_some() {}
+
+        // the button folds every hand, player 0 twice
+        let result = game.game_over().unwrap();
+        assert!(matches!(result.reason, GameOver::HandsReached(..)));
+        assert_eq!(result.stacks, [Chips::new(49750), Chips::new(50250)]);
+        assert_eq!(result.net, [-250, 250]);
+        assert_eq!((result.hands, result.level), (3, 1));
+        assert_eq!(result.elapsed, Duration::from_secs(60));
+        assert_eq!(observer.game_over(), Some(result));
+    }
 
-        None
+    #[tokio::test]
+    async fn abandoned_hand_goes_to_the_opponent() {
+        let game_type = GameType::sng(Default::default());
+        let (game, [mut first, second]) =
+            Game::with_button(game_type, Default::default(), true).unwrap();
+        drop(second);
+        let bot = tokio::spawn(async move { crate::bot::play(&mut first, &mut Caller).await });
+        let result = game.run().await;
+        bot.await.unwrap();
+
+        // the blinds already in go to the player left, none lost with the hand
+        assert_eq!(result.reason, GameOver::ExitAbandon(false));
+        let stack = game_type.init_stack();
+        assert_eq!(result.stacks[0] + result.stacks[1], stack * 2);
+        assert!(result.stacks[0] > stack);
+        assert_eq!(result.net[0], -result.net[1]);
     }
 
-    // None for crashing
-    async fn player_action(&mut self, cur_turn: bool, bet_bound: BetBound) -> Option<Action> {
-        self.players[if cur_turn { 0 } else { 1 }]
-            .turn(bet_bound)
-            .await
+    #[tokio::test]
+    async fn seated_identities() {
+        let (game, players) = Game::new(GameType::cash_default());
+        let alice = Identity::new("alice").with_id("42");
+        let mut game = game.with_identities([alice, Identity::new("bob")]);
+        let mut observer = game.observer(Visibility::God).unwrap();
+        game.seat(false, Identity::new("carol").with_avatar("🦀"));
+        assert_eq!(players[0].identities().names(), ["alice", "carol"]);
+        assert_eq!(observer.identities().of(true).key(), "42");
+        assert_eq!(observer.identities().of(false).avatar(), Some("🦀"));
+
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Folder).await })
+        });
+        assert_eq!(game.run_hand().await, None);
+        game.send_game_over(GameOver::GameAbort).await;
+        while observer.tick_event().await.is_some() {}
+
+        let hand = &observer.hands_history()[0];
+        assert_eq!(hand.identities().names(), ["alice", "carol"]);
+        let text = history::PokerStars::default().export(hand);
+        assert!(text.contains("Seat 2: carol (50000 in chips)"));
     }
 
-    // infallible game over
-    fn send_game_over(&mut self, game_over: GameOver) -> Option<GameOver> {
-        self.heads_up.set_game_over(game_over);
-        let event = ObservableEvent::GameOver(game_over);
-        self.send_ob(event);
-        self.players[0].send(event);
-        self.players[1].send(event);
-        Some(game_over)
+    #[tokio::test(start_paused = true)]
+    async fn stalled_players_time_out() {
+        let (game, [mut first, _second]) = Game::new(GameType::cash_default());
+        let time_bank = TimeBank::new(Duration::from_secs(1), Duration::from_secs(2));
+        let mut game = game.with_time_bank(time_bank);
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let button = game.heads_up.hand_state.button;
+
+        // the button is folded, then the big blind's offer to show lapses
+        let started = time::Instant::now();
+        assert_eq!(game.run_hand().await, None);
+        assert_eq!(started.elapsed(), Duration::from_secs(6));
+        assert_eq!(game.banks(), [Duration::ZERO; 2]);
+
+        let mut events = vec![];
+        while let Ok(Some(event)) = observer.tick_event_timeout(Duration::ZERO).await {
+            events.push(event);
+        }
+        let timed_out = events
+            .iter()
+            .position(|&event| event == ObservableEvent::TimedOut(button))
+            .unwrap();
+        // the hand's first turn, answered by the game
+        assert_eq!(
+            events[timed_out + 1],
+            ObservableEvent::PlayerAction(Action::fold(), TurnId::new(0))
+        );
+
+        let late = if button {
+            while !matches!(
+                first.tick_event().await,
+                Some(PlayerEvent::HeroTurn(_, TurnId(0)))
+            ) {}
+            first.send_action(Action::all_in())
+        } else {
+            while !matches!(first.tick_event().await, Some(PlayerEvent::ShowOffer)) {}
+            first.send_show(ShowCards::Both)
+        };
+        assert_eq!(late, Err(ActionSendError::TimedOut));
     }
 
-    async fn run_bet_round(&mut self) {
-        todo!() // Implement betting round logic
+    #[tokio::test]
+    async fn shutdown_mid_hand() {
+        use tokio_stream::StreamExt;
+
+        let (game, [mut first, second]) = Game::new(GameType::cash_default());
+        let token = CancellationToken::new();
+        let run = tokio::spawn(game.run_until(token.clone(), Shutdown::AbortHand));
+
+        let deal = first.tick_event().await;
+        assert!(matches!(
+            deal,
+            Some(PlayerEvent::Observable(ObservableEvent::DealHoles(_)))
+        ));
+        token.cancel();
+
+        let game_over =
+            GameOver::AbortCheckout([Chips::new(50000), Chips::new(50000)], [Chips::ZERO; 2]);
+        let result = run.await.unwrap();
+        assert_eq!((result.reason, result.hands), (game_over, 1)); // the aborted hand was dealt
+        let events: Vec<_> = second.collect().await;
+        assert_eq!(
+            events.last(),
+            Some(&PlayerEvent::Observable(ObservableEvent::GameOver(
+                game_over
+            )))
+        );
     }
 
-    pub async fn run_hand(&mut self) -> Option<GameOver> {
-        if self.is_over() {
-            return self.game_over();
+    #[tokio::test]
+    async fn stacked_decks() {
+        let mut cards: Vec<Card> = ["As", "Ah", "Ks", "Kh", "2c", "7d", "9h", "Jc", "3s"]
+            .map(|card| card.parse().unwrap())
+            .to_vec();
+        let mut rest = Deck::default();
+        rest.remove(&cards);
+        cards.extend_from_slice(rest.cards());
+        assert!(Deck::stacked(&cards[1..]).is_none());
+        let mut twice = cards.clone();
+        twice[51] = twice[0];
+        assert!(Deck::stacked(&twice).is_none());
+
+        let deck = Deck::stacked(&cards).unwrap();
+        let (mut game, players) = Game::stacked(GameType::cash_default(), [deck]);
+        let mut observer = game.observer(Visibility::God).unwrap();
+        let _bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Caller).await })
+        });
+        assert_eq!(game.run_hand().await, None);
+        game.send_game_over(GameOver::GameAbort).await;
+
+        let holes: [Hole; 2] = ["AsAh".parse().unwrap(), "KsKh".parse().unwrap()];
+        let board: Board = "2c7d9hJc3s".parse().unwrap();
+        let mut events = vec![];
+        while let Some(event) = observer.tick_event().await {
+            events.push(event);
         }
+        assert!(events.contains(&ObservableEvent::DealHoles(holes.map(Some))));
+        assert!(events.contains(&ObservableEvent::DealBoard(board)));
+        let won = events.iter().find_map(|event| match event {
+            ObservableEvent::PotAwarded { amounts, .. } => Some(*amounts),
+            _ => None,
+        });
+        assert_eq!(won, Some([Chips::new(1000), Chips::ZERO]));
+        let behinds = events.iter().rev().find_map(|event| match event {
+            ObservableEvent::Stacks { behinds, .. } => Some(*behinds),
+            _ => None,
+        });
+        assert_eq!(behinds, Some([Chips::new(50500), Chips::new(49500)]));
+    }
 
-        let mut dealer = self.deck.shuffle_and_deal();
-
-        let holes = [dealer.deal_hole(), dealer.deal_hole()];
-        let bet_info = self.heads_up.deal_holes(holes);
-        let mut _showdown_all = bet_info.is_none();
+    #[tokio::test]
+    async fn resubscribe_between_hands() {
+        let (game, players) = Game::new(GameType::cash_default());
+        let mut game = game.with_consistency_checks();
+        let [first, second] = players.map(|mut player| {
+            tokio::spawn(async move {
+                crate::bot::play(&mut player, &mut Folder).await;
+                player
+            })
+        });
+        assert_eq!(game.run_hand().await, None);
+
+        // the second player's process goes away, and comes back caught up
+        second.abort();
+        assert!(second.await.unwrap_err().is_cancelled());
+        let mut player = game.resubscribe(false).unwrap();
+        let second = tokio::spawn(async move {
+            crate::bot::play(&mut player, &mut Folder).await;
+            player
+        });
+        assert_eq!(game.run_hand().await, None);
+
+        drop(game);
+        for bot in [first, second] {
+            assert_eq!(bot.await.unwrap().desync(), None);
+        }
+    }
 
-        if let Some(player) =
-            self.dispatch_event(ObservableEvent::DealHoles([Some(holes[0]), Some(holes[1])]))
-        {
-            return self.send_game_over(self.heads_up.force_exit(player));
+    #[tokio::test]
+    async fn reconnects_mid_hand() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB100,
+            hands: 3,
+            rake: None,
+        };
+        let channels = Channels {
+            players: Backpressure::Await(4), // less than the replay
+            observer: Backpressure::Unbounded,
+        };
+        let (game, [mut first, mut second]) = Game::with_button(game_type, channels, true).unwrap();
+        let mut game = game.with_consistency_checks();
+        let reconnects = game.reconnects(Duration::from_secs(5));
+        let run = tokio::spawn(game.run());
+        let first = tokio::spawn(async move {
+            crate::bot::play(&mut first, &mut Caller).await;
+            first
+        });
+
+        // the second player goes away on its first turn of the second hand and comes back for it,
+        // answering everything before like a caller
+        let mut dealt = 0;
+        loop {
+            match second.tick_event().await.unwrap() {
+                PlayerEvent::Observable(ObservableEvent::DealHoles(_)) => dealt += 1,
+                PlayerEvent::HeroTurn(..) if dealt == 2 => break,
+                PlayerEvent::HeroTurn(bet_bound, _) => {
+                    let call = Action::check_or_call();
+                    let valid = bet_bound.validate_action(call);
+                    second
+                        .send_action(if valid { call } else { Action::fold() })
+                        .unwrap();
+                }
+                PlayerEvent::ShowOffer => second.send_show(ShowCards::Neither).unwrap(),
+                _ => {}
+            }
+        }
+        drop(second);
+        let mut second = reconnects.reconnect(false).await.unwrap();
+        let second = tokio::spawn(async move {
+            crate::bot::play(&mut second, &mut Caller).await;
+            second
+        });
+
+        let result = run.await.unwrap();
+        assert!(matches!(result.reason, GameOver::HandsReached(..)));
+        // the new player is replayed the second hand, not the first
+        for (bot, hands) in [(first, 3), (second, 2)] {
+            let player = bot.await.unwrap();
+            assert_eq!(player.desync(), None);
+            assert_eq!(player.hands_history().len(), hands);
+            let over = player.game_over().unwrap();
+            assert_eq!(
+                (over.reason, over.stacks, over.hands),
+                (result.reason, result.stacks, 3)
+            );
         }
+        assert!(reconnects.reconnect(false).await.is_none()); // the game is over
+    }
+
+    struct Wild;
 
-        if let Some((cur_turn, bet_bound)) = bet_info {
-            let _action = self.player_action(cur_turn, bet_bound).await;
+    impl crate::bot::Bot for Wild {
+        fn act(&mut self, decision: &crate::bot::Decision) -> Action {
+            let actions: Vec<_> = decision
+                .bet_bound()
+                .legal_actions(Chips::new(2000))
+                .collect();
+            *actions.choose(&mut rand::rng()).unwrap()
         }
+    }
 
-        // let button = self.next_button;
-        let _big_blind = 500;
-        let _stack0 = 150000;
-        let _stack1 = 150000;
-        let _exit_abandon = false;
-        let _deck = 0;
+    #[tokio::test]
+    async fn plays_full_hands() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB30,
+            hands: 30,
+            rake: None,
+        };
+        let (mut game, players) = Game::new(game_type);
+        let mut observer = game.observer(Visibility::God).unwrap();
+        let run = tokio::spawn(game.run());
+        let bots = players.map(|mut player| {
+            tokio::spawn(async move { crate::bot::play(&mut player, &mut Wild).await })
+        });
+
+        let (mut boards, mut turns, mut dealt, mut acted) = (0, 0, 0, false);
+        while let Some(hand_event) = observer.tick_hand_event().await {
+            // a hand's events count as its own from its holes on
+            if let ObservableEvent::DealHoles(_) = hand_event.event {
+                (dealt, acted) = (dealt + 1, false);
+            }
+            assert_eq!(hand_event.hand, dealt);
+            // every turn is answered, in the order they were asked
+            if let ObservableEvent::PlayerAction(_, turn) = hand_event.event {
+                assert_eq!(turn, TurnId::new(turns));
+                turns += 1;
+            }
+            match hand_event.event {
+                ObservableEvent::DealBoard(board) => {
+                    assert_eq!(hand_event.street, board.street());
+                    boards += 1;
+                }
+                // the button opens the hand
+                ObservableEvent::PlayerAction(..) if !acted => {
+                    let button = observer.0.heads_up.hand_state.button;
+                    assert_eq!(hand_event.seat, Some(button));
+                    acted = true;
+                }
+                ObservableEvent::PlayerAction(..) => assert!(hand_event.seat.is_some()),
+                _ => {}
+            }
+        }
+        let game_over = run.await.unwrap().reason;
+        for bot in bots {
+            assert_eq!(bot.await.unwrap(), Some(game_over));
+        }
 
-        // switch button position
-        // self.next_button = !button;
+        assert!(boards > 0);
+        match game_over {
+            GameOver::HandsReached(stacks, _) => {
+                assert_eq!(stacks[0] + stacks[1], Chips::new(30000))
+            }
+            GameOver::Defeated(_) => {}
+            other => panic!("{other:?}"),
+        }
+    }
 
-        None
+    #[test]
+    fn custom_cash_stakes() {
+        let buyins = Chips::new(3000)..=Chips::new(30000);
+        assert_eq!(
+            CashStakes::new(200, 100, buyins.clone()),
+            Err(StakesError::SmallBlindAboveBig)
+        );
+        assert_eq!(
+            CashStakes::new(100, 300, Chips::new(200)..=Chips::new(30000)),
+            Err(StakesError::BuyinBelowBigBlind)
+        );
+        let stakes = CashStakes::new(100, 300, buyins).unwrap();
+        assert_eq!(
+            stakes.with_buyin(Chips::new(40000)),
+            Err(StakesError::BuyinOutOfRange)
+        );
+        let stakes = stakes.with_buyin(Chips::new(9000)).unwrap();
+
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::Custom(stakes),
+            hands: 0,
+            rake: None,
+        };
+        let mut heads_up = HeadsUp::new(game_type, true);
+        assert_eq!(heads_up.stacks(), [Chips::new(9000); 2]);
+        for blinds in [[100, 300], [300, 100]] {
+            let mut state = heads_up.hand_state;
+            state.deal_holes_int().unwrap();
+            assert_eq!(state.cur_round, blinds.map(Chips::new));
+            heads_up.hand_state = heads_up.hand_state.next(); // the small blind carries over
+        }
     }
 
-    pub async fn run(mut self) -> GameOver {
-        loop {
-            if let Some(game_over) = self.run_hand().await {
-                return game_over;
-            }
+    #[tokio::test]
+    async fn consistency_checks() {
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB50,
+            hands: 10,
+            rake: None,
+        };
+        let (game, players) = Game::new(game_type);
+        let mut game = game
+            .with_consistency_checks()
+            .with_bomb_pot(BombPot::new(3, 2).unwrap());
+        let mut observer = game.observer(Visibility::None).unwrap();
+        let run = tokio::spawn(game.run());
+        let bots = players.map(|mut player| {
+            tokio::spawn(async move {
+                crate::bot::play(&mut player, &mut Wild).await;
+                player
+            })
+        });
+
+        let mut checksums = 0;
+        while let Some(event) = observer.tick_event().await {
+            checksums += matches!(event, ObservableEvent::Checksum(_)) as u32;
+        }
+        run.await.unwrap();
+        for bot in bots {
+            assert_eq!(bot.await.unwrap().desync(), None);
         }
+        assert!(checksums > 0);
+
+        // FNV-1a of the stacks, 25000 each, and zeros, the same on every build
+        let mut player = HeadsUp::new(game_type, true);
+        let checksum = player.checksum();
+        assert_eq!(checksum, 16597585498384563869);
+        let checksum = Timed::new(Duration::ZERO, ObservableEvent::Checksum(checksum));
+        player.event(checksum);
+        assert_eq!(player.desync, None);
+
+        // a mismatch is kept, not a panic, and the events go on
+        player.hand_state.pot += Chips::new(100);
+        player.event(checksum);
+        let (hand, diff) = player.desync.as_ref().unwrap();
+        assert_eq!(*hand, 0); // before the first hand was dealt
+        assert_eq!(diff.fields().collect::<Vec<_>>(), ["checksum"]);
+        player.event(Timed::new(
+            Duration::ZERO,
+            ObservableEvent::Rake([Chips::ZERO; 2]),
+        ));
+    }
+
+    #[tokio::test]
+    async fn events_on_the_game_clock() {
+        let clock = clock::MockClock::default();
+        let stop = StopConditions::default().with_max_duration(Duration::from_secs(60));
+        let (game, [mut player, _]) = Game::new(GameType::cash_default());
+        let mut game = game.with_clock(clock.clone()).with_stop_conditions(stop);
+
+        clock.advance(Duration::from_secs(5));
+        game.dispatch_stacks().await;
+        let timed = player.tick_timed_event().await.unwrap();
+        assert_eq!(timed.at, Duration::from_secs(5));
+        assert!(matches!(
+            timed.event,
+            PlayerEvent::Observable(ObservableEvent::Stacks { .. })
+        ));
+        assert_eq!(player.last_event_at(), Some(timed.at));
+
+        game.heads_up
+            .deal_holes(["AsAh".parse().unwrap(), "7c2d".parse().unwrap()]);
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(game.heads_up.end_hand(), None);
+        clock.set(Duration::from_secs(65));
+        assert!(matches!(
+            game.heads_up.end_hand(),
+            Some(GameOver::SessionStop(StopReason::MaxDuration, _, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn tick_with_timeout() {
+        let (mut game, [mut player, _]) = Game::new(GameType::cash_default());
+        let timeout = Duration::from_millis(10);
+        assert!(player.tick_event_timeout(timeout).await.is_err());
+
+        game.dispatch_stacks().await;
+        let event = player.tick_event_timeout(timeout).await.unwrap();
+        assert!(matches!(
+            event,
+            Some(PlayerEvent::Observable(ObservableEvent::Stacks { .. }))
+        ));
     }
 }
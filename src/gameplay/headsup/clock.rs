@@ -0,0 +1,98 @@
+// Where the table's time comes from. Times are durations since the clock started, so stamps of
+// one game compare and subtract (decision times, time banks) but mean nothing across clocks.
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::Instant;
+
+pub trait Clock: Debug + Send + Sync {
+    // Never goes backwards.
+    fn now(&self) -> Duration;
+}
+
+// Real time, following tokio's clock so paused test runtimes stay in step. The browser has no
+// `Instant`, so there it reads the page's wall clock, held from going back before the start.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SystemClock(Instant);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SystemClock(Duration); // since the epoch
+
+#[cfg(target_arch = "wasm32")]
+fn since_epoch() -> Duration {
+    Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self(since_epoch())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        since_epoch().saturating_sub(self.0)
+    }
+}
+
+// Time that only moves when told to. Clones share the time, so a test keeps one to drive the
+// clock it handed to a game.
+#[derive(Debug, Default, Clone)]
+pub struct MockClock(Arc<Mutex<Duration>>);
+
+impl MockClock {
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+
+    // Times in the past are ignored, the clock never goes backwards.
+    pub fn set(&self, to: Duration) {
+        let mut now = self.0.lock().unwrap();
+        *now = (*now).max(to);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        *self.0.lock().unwrap()
+    }
+}
+
+// Something that happened at `at` on the game's clock.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timed<T> {
+    pub at: Duration,
+    pub event: T,
+}
+
+impl<T> Timed<T> {
+    pub fn new(at: Duration, event: T) -> Self {
+        Self { at, event }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timed<U> {
+        Timed::new(self.at, f(self.event))
+    }
+}
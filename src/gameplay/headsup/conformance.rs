@@ -0,0 +1,268 @@
+// Externally authored rule vectors: a hand's starting point, the actions taken and what the
+// engine must make of them, run straight through `HandState`. Vectors live in TOML files so
+// disputed rulings can be contributed as data; `vectors/starter.toml` documents the format and
+// ships with the crate.
+use super::{Action, ActionOver, HandState, StateDiff};
+use crate::gameplay::{Board, FullBoard, Hole, cardset::CardSet, chips::Chips};
+use serde::Deserialize;
+use std::{fs, io, path::Path};
+
+const STARTER: &str = include_str!("../../../vectors/starter.toml");
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Winner {
+    P0,
+    P1,
+    Chop,
+}
+
+impl From<Option<bool>> for Winner {
+    fn from(winner: Option<bool>) -> Self {
+        match winner {
+            Some(true) => Self::P0,
+            Some(false) => Self::P1,
+            None => Self::Chop,
+        }
+    }
+}
+
+// What a vector checks; fields left out aren't. `rejected` is always checked, so an unexpected
+// refusal fails the vector.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expect {
+    pub pot: Option<u32>,
+    pub stacks: Option<[u32; 2]>,
+    pub winner: Option<Winner>,
+    pub rejected: Option<usize>,
+}
+
+// How the engine played a vector out. The winner is None for a hand that didn't finish.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Outcome {
+    pub pot: Chips,
+    pub stacks: [Chips; 2],
+    pub winner: Option<Winner>,
+    pub rejected: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct RuleVector {
+    pub name: String,
+    pub big_blind: u16,
+    pub button: bool,
+    pub stacks: [u32; 2],
+    pub holes: [Hole; 2],
+    pub runout: FullBoard,
+    pub actions: Vec<Action>,
+    pub expect: Expect,
+}
+
+#[derive(PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Seat {
+    P0,
+    P1,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawVector {
+    name: String,
+    big_blind: u16,
+    button: Seat,
+    stacks: [u32; 2],
+    holes: [String; 2],
+    board: String,
+    actions: Vec<String>,
+    expect: Expect,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFile {
+    vector: Vec<RawVector>,
+}
+
+impl RawVector {
+    fn parse(self) -> Option<RuleVector> {
+        let holes = [
+            self.holes[0].parse::<Hole>().ok()?,
+            self.holes[1].parse::<Hole>().ok()?,
+        ];
+        let runout = self.board.parse::<Board>().ok()?.as_full_board()?;
+        let cards: CardSet = holes
+            .iter()
+            .flat_map(|hole| hole.iter())
+            .chain(runout.iter())
+            .copied()
+            .collect();
+        if cards.len() != 9 || self.big_blind < 2 {
+            return None;
+        }
+        let actions = self
+            .actions
+            .iter()
+            .map(|action| action.parse().ok())
+            .collect::<Option<_>>()?;
+
+        Some(RuleVector {
+            name: self.name,
+            big_blind: self.big_blind,
+            button: self.button == Seat::P0,
+            stacks: self.stacks,
+            holes,
+            runout,
+            actions,
+            expect: self.expect,
+        })
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl RuleVector {
+    // Every `[[vector]]` of a TOML document.
+    pub fn parse(toml: &str) -> io::Result<Vec<Self>> {
+        let file: RawFile = toml::from_str(toml).map_err(|err| invalid(err.to_string()))?;
+        file.vector
+            .into_iter()
+            .map(|raw| {
+                let name = raw.name.clone();
+                raw.parse()
+                    .ok_or_else(|| invalid(format!("Bad vector: {name}")))
+            })
+            .collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    // The vectors shipped with the crate: min-raises, all ins and blind edge cases.
+    pub fn starter() -> Vec<Self> {
+        Self::parse(STARTER).expect("Starter vectors should parse")
+    }
+
+    // Plays the actions until the hand ends, the engine refuses one or they run out. Legal
+    // actions are taken the way the table takes them, so a raise to everything is an all in.
+    pub fn run(&self) -> Outcome {
+        let mut state = HandState::new(self.big_blind, self.button, self.stacks.map(Chips::new))
+            .with_cards(self.holes.map(Some), Board::default());
+        let mut over = state
+            .deal_holes_int()
+            .map_or(Some(ActionOver::ShowdownAll), |_| None);
+        let mut pot = state.total_pot();
+        let mut rejected = None;
+
+        for (i, &action) in self.actions.iter().enumerate() {
+            let legal = over
+                .is_none()
+                .then(|| state.bet_bound().alter_eq(action, state.bet_unit()));
+            let Some(Some(action)) = legal else {
+                rejected = Some(i);
+                break;
+            };
+
+            pot = state.total_pot();
+            over = match state.action(action) {
+                ActionOver::TurnOver => None,
+                ActionOver::RoundOver => {
+                    state.next_street();
+                    state.deal_board(self.board(&state));
+                    // a board that is the nuts itself ends the betting
+                    state.is_board_chop().then_some(ActionOver::ShowndownRiver)
+                }
+                over => Some(over),
+            };
+        }
+
+        let winner = match over {
+            None => {
+                let behinds = state.behinds();
+                let cur_round = state.cur_round();
+                return Outcome {
+                    pot: state.total_pot(),
+                    stacks: [behinds[0] - cur_round[0], behinds[1] - cur_round[1]],
+                    winner: None,
+                    rejected,
+                };
+            }
+            Some(ActionOver::HandOver) => Some(!state.cur_turn()), // the folder still has the turn
+            Some(_) => {
+                state.deal_board(
+                    Board::from_slice(&self.runout[..]).expect("Runout cards are distinct"),
+                );
+                pot = state.pot();
+                let (_, winner) = self.runout.who_wins(self.holes[0], self.holes[1]);
+                state.award(match winner {
+                    Some(true) => pot,
+                    Some(false) => Chips::ZERO,
+                    None => state.chop_share(),
+                });
+                winner
+            }
+        };
+
+        Outcome {
+            pot,
+            stacks: state.behinds(),
+            winner: Some(Winner::from(winner)),
+            rejected,
+        }
+    }
+
+    // Where the engine disagrees with the vector, as (field, engine, expected).
+    pub fn check(&self) -> StateDiff {
+        let outcome = self.run();
+        let expect = self.expect;
+        let mut diff = StateDiff::default();
+
+        if let Some(pot) = expect.pot {
+            diff.push("pot", outcome.pot, Chips::new(pot));
+        }
+        if let Some(stacks) = expect.stacks {
+            diff.push("stacks", outcome.stacks, stacks.map(Chips::new));
+        }
+        if let Some(winner) = expect.winner {
+            diff.push("winner", outcome.winner, Some(winner));
+        }
+        diff.push("rejected", outcome.rejected, expect.rejected);
+        diff
+    }
+
+    fn board(&self, state: &HandState) -> Board {
+        Board::from_slice(&self.runout[..state.street().board_len()])
+            .expect("Runout cards are distinct")
+    }
+}
+
+// The vectors the engine fails, each with how.
+pub fn failures(vectors: &[RuleVector]) -> Vec<(&str, StateDiff)> {
+    vectors
+        .iter()
+        .map(|vector| (vector.name.as_str(), vector.check()))
+        .filter(|(_, diff)| !diff.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starter_vectors_pass() {
+        let vectors = RuleVector::starter();
+        assert!(vectors.len() >= 10);
+        let failures = failures(&vectors);
+        assert!(failures.is_empty(), "{failures:#?}");
+
+        let mut wrong = vectors[0].clone();
+        wrong.expect.winner = Some(Winner::P0);
+        assert_eq!(wrong.check().fields().collect::<Vec<_>>(), ["winner"]);
+        assert!(RuleVector::parse("[[vector]]\nname = \"no cards\"").is_err());
+    }
+}
@@ -0,0 +1,921 @@
+// Every hand a player or observer saw, as its events on the game's clock, and those hands written
+// out as PokerStars hand histories so tracking and review tools can read them, or run out again
+// from an all in.
+use super::{
+    Action, ActionOver, ActionValue, Blinds, HandState, ObservableEvent, clock::Timed,
+    identity::Identities,
+};
+use crate::{
+    bot::Decision,
+    equity::{Equity, equity_every_runout},
+    gameplay::{Board, Card, HandValue, Hole, SortedHandValue, Street, Value, chips::Chips},
+};
+use std::{
+    array,
+    cmp::Ordering,
+    fmt::Write,
+    slice::Iter,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Holes are the ones the recording side was shown, both of them for a god view.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandHistory {
+    number: u32, // counting from 1 in its game
+    blind: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    small_blind: Option<u16>, // None in records from before custom stakes, half the big blind
+    #[cfg_attr(feature = "serde", serde(default))]
+    ante: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    big_blind_ante: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    straddle: bool,
+    button: bool,
+    init_stacks: [Chips; 2],
+    events: Vec<Timed<ObservableEvent>>,
+    identities: Identities, // as the hand finished
+}
+
+impl HandHistory {
+    pub(crate) fn new(number: u32, state: &HandState) -> Self {
+        Self {
+            number,
+            blind: state.blind,
+            small_blind: Some(state.small_blind),
+            ante: state.level_ante,
+            big_blind_ante: state.big_blind_ante,
+            straddle: state.straddle,
+            button: state.button,
+            init_stacks: state.init_stacks,
+            events: vec![],
+            identities: Default::default(),
+        }
+    }
+
+    pub(super) fn with_identities(mut self, identities: Identities) -> Self {
+        self.identities = identities;
+        self
+    }
+
+    pub(crate) fn push(&mut self, event: Timed<ObservableEvent>) {
+        self.events.push(event);
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn big_blind(&self) -> Chips {
+        Chips::new(self.blind as u32)
+    }
+
+    pub fn small_blind(&self) -> Chips {
+        Chips::new(self.small_blind.unwrap_or(self.blind / 2) as u32)
+    }
+
+    pub fn blinds(&self) -> Blinds {
+        Blinds {
+            small_blind: self.small_blind().get() as u16,
+            big_blind: self.blind,
+            ante: self.ante,
+            big_blind_ante: self.big_blind_ante,
+            straddle: self.straddle,
+            unit: Blinds::default_unit(self.small_blind().get() as u16, self.blind),
+        }
+    }
+
+    // The hand as it stood before the blinds.
+    fn start_state(&self) -> HandState {
+        HandState::new(self.blind, self.button, self.init_stacks).with_blinds(self.blinds())
+    }
+
+    pub fn button(&self) -> bool {
+        self.button
+    }
+
+    // Before the blinds.
+    pub fn init_stacks(&self) -> [Chips; 2] {
+        self.init_stacks
+    }
+
+    pub fn events(&self) -> &[Timed<ObservableEvent>] {
+        &self.events
+    }
+
+    pub fn identities(&self) -> &Identities {
+        &self.identities
+    }
+
+    // What the house took of the pot, from both players.
+    pub fn rake(&self) -> Chips {
+        self.events
+            .iter()
+            .map(|timed| match timed.event {
+                ObservableEvent::Rake(rake) => rake[0] + rake[1],
+                _ => Chips::ZERO,
+            })
+            .sum()
+    }
+
+    // When the hand was dealt, on the game's clock.
+    pub fn started(&self) -> Duration {
+        self.events.first().map_or(Duration::ZERO, |event| event.at)
+    }
+
+    // What each player put in on every street and won from the pot, read from the hand's stacks
+    // the way the PokerStars export reads them.
+    pub fn result(&self) -> HandResult {
+        let mut result = HandResult::default();
+        let mut street = Street::Preflop as usize;
+        let mut totals = [Chips::ZERO; 2];
+        for timed in &self.events {
+            match timed.event {
+                ObservableEvent::DealBoard(board) => street = board.street() as usize,
+                ObservableEvent::ShowdownAll(_)
+                | ObservableEvent::ShowdownAuto(_)
+                | ObservableEvent::ShowdownShow(..)
+                | ObservableEvent::ShowdownMuck(_) => result.showdown = true,
+                ObservableEvent::Stacks { behinds, bets, pot } => {
+                    let awarded = pot.is_zero() && bets.iter().all(|bet| bet.is_zero());
+                    if awarded && totals.iter().any(|total| !total.is_zero()) {
+                        result.award(totals, behinds, self.init_stacks);
+                        break;
+                    }
+                    for i in 0..2 {
+                        let earlier: Chips = result.invested[..street].iter().map(|s| s[i]).sum();
+                        totals[i] = self.init_stacks[i].saturating_sub(behinds[i]);
+                        result.invested[street][i] = totals[i].saturating_sub(earlier);
+                    }
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+
+    // The board from where the money went in, run out every other way it could have been. None
+    // unless the hand was all in with both holes shown and the pot went to the best hand, e.g. not
+    // after insurance settled it.
+    pub fn all_in_runouts(&self) -> Option<AllInRunouts> {
+        let mut replay = self.replay();
+        let mut all_in = None;
+        let mut early_winner = None;
+        while let Some(event) = replay.next_event() {
+            match event {
+                ObservableEvent::ShowdownAll(holes) => {
+                    all_in = Some((holes, replay.hand_state.board));
+                }
+                ObservableEvent::EarlyAward(winner) => early_winner = Some(winner),
+                _ => {}
+            }
+        }
+
+        let (holes, board) = all_in?;
+        let winner = match early_winner {
+            Some(winner) => winner,
+            None => {
+                let full = replay.hand_state.board.as_full_board()?;
+                match full.hand_value(holes[0]).cmp(&full.hand_value(holes[1])) {
+                    Ordering::Greater => Some(true),
+                    Ordering::Less => Some(false),
+                    Ordering::Equal => None,
+                }
+            }
+        };
+        Some(AllInRunouts {
+            holes,
+            board,
+            winner,
+            equity: equity_every_runout(holes[0], holes[1], board)?,
+        })
+    }
+
+    // Every action of the hand with the decision it answered. Holes shown at any point are
+    // dealt from the start, so a decision has the actor's cards whenever they were ever seen.
+    pub(crate) fn decisions(&self) -> Vec<(Decision, Action)> {
+        let mut holes = [None; 2];
+        for event in self.events.iter().map(|timed| timed.event) {
+            match event {
+                ObservableEvent::DealHoles(dealt) => {
+                    holes = array::from_fn(|seat| holes[seat].or(dealt[seat]));
+                }
+                ObservableEvent::ShowdownAll(shown) | ObservableEvent::ShowdownAuto(shown) => {
+                    holes = shown.map(Some);
+                }
+                ObservableEvent::ShowdownShow(seat, hole) => holes[seat_index(seat)] = Some(hole),
+                _ => {}
+            }
+        }
+
+        let mut state = self.start_state();
+        let mut decisions = vec![];
+        for event in self.events.iter().map(|timed| timed.event) {
+            match event {
+                ObservableEvent::PlayerAction(action, _) => {
+                    let decision = Decision::from_state(state.cur_turn, &state, state.bet_bound());
+                    decisions.push((decision, action));
+                    if state.action(action) == ActionOver::RoundOver {
+                        state.next_street();
+                    }
+                }
+                ObservableEvent::DealHoles(_) => state.event(ObservableEvent::DealHoles(holes)),
+                _ => state.event(event),
+            }
+        }
+        decisions
+    }
+
+    pub fn replay(&self) -> HandReplay<'_> {
+        HandReplay {
+            events: self.events.iter(),
+            hand_state: self.start_state(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HandReplay<'a> {
+    events: Iter<'a, Timed<ObservableEvent>>,
+    hand_state: HandState,
+}
+
+impl<'a> HandReplay<'a> {
+    pub fn next_event(&mut self) -> Option<ObservableEvent> {
+        let event = self.events.next().map(|timed| timed.event);
+
+        if let Some(event) = event {
+            self.hand_state.event(event);
+        }
+
+        event
+    }
+}
+
+// Chips by player, player 0 first. A bet nobody called comes back before the pot is paid out, so
+// it counts neither as invested nor as won, and `won` less `invested` is what the hand made.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandResult {
+    pub invested: [[Chips; 2]; 4], // by street, the blinds and antes preflop
+    pub won: [Chips; 2],           // from the pot, after the rake
+    pub showdown: bool,            // reached one, for winnings with and without it
+}
+
+impl HandResult {
+    // The pot paid out of `totals` put in, `behinds` being the stacks after it.
+    fn award(&mut self, totals: [Chips; 2], behinds: [Chips; 2], init_stacks: [Chips; 2]) {
+        let matched = totals[0].min(totals[1]);
+        for i in 0..2 {
+            let uncalled = totals[i] - matched;
+            let before = init_stacks[i] - totals[i];
+            self.won[i] = behinds[i].saturating_sub(before).saturating_sub(uncalled);
+            // off the last street the player put chips in on
+            if let Some(street) = self.invested.iter_mut().rev().find(|s| !s[i].is_zero()) {
+                street[i] -= uncalled;
+            }
+        }
+    }
+
+    pub fn total_invested(&self, seat: bool) -> Chips {
+        self.invested
+            .iter()
+            .map(|street| street[!seat as usize])
+            .sum()
+    }
+
+    pub fn net(&self, seat: bool) -> i64 {
+        self.won[!seat as usize].get() as i64 - self.total_invested(seat).get() as i64
+    }
+}
+
+// How an all-in hand could have gone: the holes, the board when the money went in, who won on the
+// board actually dealt (None for a chop) and player 0's equity over every runout of it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AllInRunouts {
+    pub holes: [Hole; 2],
+    pub board: Board,
+    pub winner: Option<bool>,
+    pub equity: Equity,
+}
+
+impl AllInRunouts {
+    // How often the winner would have won, chopped or lost; None for a chop.
+    pub fn winner_equity(&self) -> Option<Equity> {
+        let Equity { win, tie, lose } = self.equity;
+        self.winner.map(|winner| {
+            if winner {
+                self.equity
+            } else {
+                Equity {
+                    win: lose,
+                    tie,
+                    lose: win,
+                }
+            }
+        })
+    }
+}
+
+// How hands are written as PokerStars text. Hand times are on the game's clock, dated from the
+// wall clock time that clock read zero at.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct PokerStars {
+    table: String,
+    names: Option<[String; 2]>, // of player 0 and player 1, instead of the hands' identities
+    clock_start: SystemTime,
+    first_hand: u64, // id of hand number 1, trackers drop hands with ids they have seen
+}
+
+impl Default for PokerStars {
+    fn default() -> Self {
+        Self {
+            table: "pokerbot".to_string(),
+            names: None,
+            clock_start: UNIX_EPOCH,
+            first_hand: 1,
+        }
+    }
+}
+
+impl PokerStars {
+    pub fn with_table(mut self, table: &str) -> Self {
+        self.table = table.to_string();
+        self
+    }
+
+    pub fn with_names(mut self, names: [&str; 2]) -> Self {
+        self.names = Some(names.map(str::to_string));
+        self
+    }
+
+    pub fn with_clock_start(mut self, clock_start: SystemTime) -> Self {
+        self.clock_start = clock_start;
+        self
+    }
+
+    pub fn with_first_hand(mut self, first_hand: u64) -> Self {
+        self.first_hand = first_hand;
+        self
+    }
+
+    pub fn export(&self, hand: &HandHistory) -> String {
+        let mut text = HandText::new(self, hand);
+        for event in &hand.events {
+            text.event(event.event);
+        }
+        text.summary()
+    }
+
+    // Hands one after another with blank lines between them, as PokerStars saves a session.
+    pub fn export_all<'a>(&self, hands: impl IntoIterator<Item = &'a HandHistory>) -> String {
+        hands
+            .into_iter()
+            .map(|hand| self.export(hand))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+// One hand being written, replaying its events on a hand state of its own to know who acts and
+// how many chips each action puts in.
+struct HandText<'a> {
+    names: [&'a str; 2],
+    text: String,
+    state: HandState,
+    folded: Option<(usize, Street)>,
+    shown: [Option<Hole>; 2],
+    showdown: Vec<usize>, // seats in the order they showed or mucked
+    collected: [Chips; 2],
+    rake: Chips,
+}
+
+impl<'a> HandText<'a> {
+    fn new(format: &'a PokerStars, hand: &'a HandHistory) -> Self {
+        let names = match &format.names {
+            Some([first, second]) => [first.as_str(), second.as_str()],
+            None => hand.identities.names(),
+        };
+        let started = format.clock_start + hand.started();
+        let big_blind = hand.big_blind();
+        let mut text = String::new();
+        writeln!(
+            text,
+            "PokerStars Hand #{}: Hold'em No Limit ({}/{}) - {} UTC",
+            format.first_hand + hand.number as u64 - 1,
+            hand.small_blind(),
+            big_blind,
+            date_time(started)
+        )
+        .unwrap();
+        writeln!(
+            text,
+            "Table '{}' 2-max Seat #{} is the button",
+            format.table,
+            seat_number(hand.button)
+        )
+        .unwrap();
+        for (i, name) in names.iter().enumerate() {
+            writeln!(
+                text,
+                "Seat {}: {name} ({} in chips)",
+                i + 1,
+                hand.init_stacks[i]
+            )
+            .unwrap();
+        }
+
+        Self {
+            names,
+            text,
+            state: hand.start_state(),
+            folded: None,
+            shown: [None; 2],
+            showdown: Vec::new(),
+            collected: [Chips::ZERO; 2],
+            rake: Chips::ZERO,
+        }
+    }
+
+    fn line(&mut self, seat: usize, line: &str) {
+        writeln!(self.text, "{}{line}", self.names[seat]).unwrap();
+    }
+
+    fn event(&mut self, event: ObservableEvent) {
+        match event {
+            ObservableEvent::DealHoles(holes) => {
+                self.state.event(event);
+                self.blinds();
+                self.text.push_str("*** HOLE CARDS ***\n");
+                for (seat, hole) in holes.into_iter().enumerate() {
+                    if let Some(hole) = hole {
+                        let line = format!("Dealt to {} [{}]", self.names[seat], cards(*hole));
+                        writeln!(self.text, "{line}").unwrap();
+                    }
+                }
+            }
+            ObservableEvent::DealBoard(board) => {
+                self.streets(board, None);
+                self.state.event(event);
+            }
+            ObservableEvent::BoardVariant(run, board) => self.streets(board, Some(run)),
+            ObservableEvent::PlayerAction(action, _) => self.action(action),
+            ObservableEvent::ShowdownAll(holes) | ObservableEvent::ShowdownAuto(holes) => {
+                self.shown = holes.map(Some);
+                self.showdown = vec![0, 1];
+                self.state.event(event);
+            }
+            ObservableEvent::ShowdownShow(seat, hole) => {
+                self.shown[seat_index(seat)] = Some(hole);
+                self.showdown.push(seat_index(seat));
+                self.state.event(event);
+            }
+            ObservableEvent::ShowdownMuck(seat) => self.showdown.push(seat_index(seat)),
+            ObservableEvent::ShowCards(seat, shown) => {
+                let line = format!(": shows [{}]", cards(shown.into_iter().flatten()));
+                self.line(seat_index(seat), &line);
+            }
+            ObservableEvent::Stacks { behinds, bets, pot } => {
+                let awarded = pot.is_zero() && bets.iter().all(|bet| bet.is_zero());
+                if awarded && !self.state.total_pot().is_zero() {
+                    self.award(behinds);
+                }
+                self.state.event(event);
+            }
+            ObservableEvent::TimedOut(seat) => self.line(seat_index(seat), " has timed out"),
+            // house rules move chips outside the pot, and the game's end isn't part of the hand
+            ObservableEvent::Rake(rake) => self.rake = rake[0] + rake[1],
+            ObservableEvent::BombPot(_) | ObservableEvent::BlindLevel { .. } => {
+                self.state.event(event)
+            }
+            ObservableEvent::Checksum(_)
+            | ObservableEvent::PotAwarded { .. }
+            | ObservableEvent::Insured { .. }
+            | ObservableEvent::SevenDeuce { .. }
+            | ObservableEvent::HouseRule { .. }
+            | ObservableEvent::EarlyAward(_)
+            | ObservableEvent::AllInEquity(_)
+            | ObservableEvent::CatchUp { .. }
+            | ObservableEvent::GameOver(_) => {}
+        }
+    }
+
+    // Antes, then the small blind or the straddle first, or both antes of a bomb pot.
+    fn blinds(&mut self) {
+        let sb = seat_index(self.state.button);
+        let bomb_pot = !self.state.ante.is_zero();
+        let straddled = self.state.cur_round[sb] > self.state.cur_round[1 - sb];
+        let mut antes = [Chips::ZERO; 2];
+        if !bomb_pot {
+            for seat in [sb, 1 - sb] {
+                antes[seat] = self.state.antes()[seat].min(self.state.init_stacks[seat]);
+                if !antes[seat].is_zero() {
+                    self.line(seat, &format!(": posts the ante {}", antes[seat]));
+                }
+            }
+        }
+        for (seat, blind) in [(sb, "small blind"), (1 - sb, "big blind")] {
+            let left = self.state.behinds[seat] - self.state.cur_round[seat];
+            let posted = self.state.init_stacks[seat] - left - antes[seat];
+            if posted.is_zero() && !antes[seat].is_zero() {
+                continue; // all in with the ante
+            }
+            let blind = match seat == sb {
+                _ if bomb_pot => "the ante",
+                true if straddled => "straddle",
+                _ => blind,
+            };
+            let all_in = if left.is_zero() { " and is all-in" } else { "" };
+            self.line(seat, &format!(": posts {blind} {posted}{all_in}"));
+        }
+    }
+
+    // Streets up to `board`, a runout after an all in dealing several at once.
+    // The streets `board` deals, named after the run, e.g. "SECOND TURN", for a board run out
+    // more than once.
+    fn streets(&mut self, board: Board, run: Option<u8>) {
+        const RUNS: [&str; 4] = ["FIRST", "SECOND", "THIRD", "FOURTH"];
+        let name = |street: &str| match run {
+            Some(run) => format!("{} {street}", RUNS[run as usize]),
+            None => street.to_string(),
+        };
+        let dealt = self.state.board.to_vec().len();
+        let board = board.to_vec();
+        for len in 3.max(dealt + 1)..=board.len() {
+            let line = match len {
+                3 => format!(
+                    "*** {} *** [{}]",
+                    name("FLOP"),
+                    cards(board[..3].iter().copied())
+                ),
+                _ => format!(
+                    "*** {} *** [{}] [{}]",
+                    name(if len == 4 { "TURN" } else { "RIVER" }),
+                    cards(board[..len - 1].iter().copied()),
+                    cards([board[len - 1]])
+                ),
+            };
+            writeln!(self.text, "{line}").unwrap();
+        }
+    }
+
+    fn action(&mut self, action: Action) {
+        let seat = self.state.cur_turn;
+        let (hero, villain) = (seat_index(seat), 1 - seat_index(seat));
+        let hero_bet = self.state.cur_round[hero];
+        let villain_bet = self.state.cur_round[villain];
+        let behind = self.state.behinds[hero];
+
+        let to = match action.value() {
+            ActionValue::Exit | ActionValue::Fold => {
+                self.line(hero, ": folds");
+                self.folded = Some((hero, self.state.street));
+                // the folder's bet is matched into the pot, the rest goes back
+                self.state.pot += hero_bet * 2;
+                self.state.behinds[hero] -= hero_bet;
+                self.state.behinds[villain] -= hero_bet;
+                self.state.cur_round = [Chips::ZERO; 2];
+                self.uncalled(villain, villain_bet - hero_bet);
+                return;
+            }
+            ActionValue::CheckOrCall if self.state.can_check() => {
+                self.line(hero, ": checks");
+                None
+            }
+            ActionValue::CheckOrCall => Some(villain_bet),
+            ActionValue::BetOrRaise(to) => Some(to),
+            ActionValue::AllIn => Some(behind),
+        };
+
+        if let Some(to) = to {
+            let all_in = if to == behind { " and is all-in" } else { "" };
+            let line = if to <= villain_bet {
+                format!(": calls {}{all_in}", to - hero_bet)
+            } else if villain_bet.is_zero() {
+                format!(": bets {to}{all_in}")
+            } else {
+                format!(": raises {} to {to}{all_in}", to - villain_bet)
+            };
+            self.line(hero, &line);
+            if to < villain_bet {
+                self.uncalled(villain, villain_bet - to);
+            }
+        }
+
+        if self.state.action(action) == ActionOver::RoundOver {
+            self.state.next_street();
+        }
+    }
+
+    fn uncalled(&mut self, seat: usize, chips: Chips) {
+        if !chips.is_zero() {
+            let line = format!("Uncalled bet ({chips}) returned to {}", self.names[seat]);
+            writeln!(self.text, "{line}").unwrap();
+        }
+    }
+
+    // The pot paid out, `behinds` being the stacks after it.
+    fn award(&mut self, behinds: [Chips; 2]) {
+        let state = &self.state;
+        self.collected = array::from_fn(|seat| {
+            let before = state.behinds[seat] - state.cur_round[seat];
+            behinds[seat].saturating_sub(before)
+        });
+
+        if !self.showdown.is_empty() {
+            self.text.push_str("*** SHOW DOWN ***\n");
+            for seat in self.showdown.clone() {
+                let line = match self.shown[seat] {
+                    Some(hole) => match self.hand_value(hole) {
+                        Some(value) => format!(": shows [{}] ({})", cards(*hole), describe(value)),
+                        None => format!(": shows [{}]", cards(*hole)),
+                    },
+                    None => ": mucks hand".to_string(),
+                };
+                self.line(seat, &line);
+            }
+        }
+        for seat in 0..2 {
+            if !self.collected[seat].is_zero() {
+                let line = format!(" collected {} from pot", self.collected[seat]);
+                self.line(seat, &line);
+            }
+        }
+    }
+
+    fn hand_value(&self, hole: Hole) -> Option<HandValue> {
+        let board = self.state.board.as_full_board()?;
+        Some(board.hand_value(hole))
+    }
+
+    fn summary(mut self) -> String {
+        let total = self.collected[0] + self.collected[1] + self.rake;
+        self.text.push_str("*** SUMMARY ***\n");
+        writeln!(self.text, "Total pot {total} | Rake {}", self.rake).unwrap();
+        let board = self.state.board;
+        if !board.is_preflop() {
+            writeln!(self.text, "Board [{}]", cards(board.to_vec())).unwrap();
+        }
+
+        let button = seat_index(self.state.button);
+        for seat in 0..2 {
+            let mut line = format!("Seat {}: {}", seat + 1, self.names[seat]);
+            if seat == button {
+                line.push_str(" (button)");
+            }
+            if self.state.ante.is_zero() {
+                line.push_str(if seat == button {
+                    " (small blind)"
+                } else {
+                    " (big blind)"
+                });
+            }
+
+            let won = self.collected[seat];
+            let showdown = self.showdown.contains(&seat);
+            match (self.folded, showdown, self.shown[seat]) {
+                (Some((folder, street)), ..) if folder == seat => match street {
+                    Street::Preflop => line.push_str(" folded before Flop"),
+                    street => write!(line, " folded on the {}", street_name(street)).unwrap(),
+                },
+                (_, true, Some(hole)) => {
+                    write!(line, " showed [{}] and ", cards(*hole)).unwrap();
+                    if won.is_zero() {
+                        line.push_str("lost");
+                    } else {
+                        write!(line, "won ({won})").unwrap();
+                    }
+                    if let Some(value) = self.hand_value(hole) {
+                        write!(line, " with {}", describe(value)).unwrap();
+                    }
+                }
+                (_, true, None) => line.push_str(" mucked"),
+                _ if !won.is_zero() => write!(line, " collected ({won})").unwrap(),
+                _ => {}
+            }
+            writeln!(self.text, "{line}").unwrap();
+        }
+
+        self.text
+    }
+}
+
+fn seat_index(seat: bool) -> usize {
+    if seat { 0 } else { 1 }
+}
+
+// PokerStars counts seats from 1.
+fn seat_number(seat: bool) -> usize {
+    seat_index(seat) + 1
+}
+
+fn cards(cards: impl IntoIterator<Item = Card>) -> String {
+    cards
+        .into_iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn street_name(street: Street) -> &'static str {
+    match street {
+        Street::Preflop => "Preflop",
+        Street::Flop => "Flop",
+        Street::Turn => "Turn",
+        Street::River => "River",
+    }
+}
+
+// The lowest card of a straight up to `high`, an Ace for the wheel.
+fn straight_low(high: Value) -> &'static str {
+    Value::from_u8_straight(high.as_u8_straight() - 4).name()
+}
+
+// Hands worded the way PokerStars words them, e.g. "two pair, Aces and Kings".
+fn describe(value: HandValue) -> String {
+    match *value {
+        SortedHandValue::RoyalFlush => "a Royal Flush".to_string(),
+        SortedHandValue::StraightFlush(high) => format!(
+            "a straight flush, {} to {}",
+            straight_low(high),
+            high.name()
+        ),
+        SortedHandValue::Quads([quads, _]) => format!("four of a kind, {}", quads.plural()),
+        SortedHandValue::FullHouse([trips, pair]) => {
+            format!("a full house, {} full of {}", trips.plural(), pair.plural())
+        }
+        SortedHandValue::Flush([high, ..]) => format!("a flush, {} high", high.name()),
+        SortedHandValue::Straight(high) => {
+            format!("a straight, {} to {}", straight_low(high), high.name())
+        }
+        SortedHandValue::Trips([trips, ..]) => format!("three of a kind, {}", trips.plural()),
+        SortedHandValue::TwoPair([high, low, _]) => {
+            format!("two pair, {} and {}", high.plural(), low.plural())
+        }
+        SortedHandValue::OnePair([pair, ..]) => format!("a pair of {}", pair.plural()),
+        SortedHandValue::HighCard([high, ..]) => format!("high card {}", high.name()),
+    }
+}
+
+// A UTC date as PokerStars writes it, e.g. "2026/10/16 21:05:09".
+fn date_time(at: SystemTime) -> String {
+    let secs = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, time) = (secs / 86400, secs % 86400);
+
+    // days since 1970 to a civil date, counting years from March so leap days come last
+    let shifted = days + 719468;
+    let era = shifted / 146097;
+    let day_of_era = shifted % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + (month <= 2) as u64;
+
+    format!(
+        "{year}/{month:02}/{day:02} {:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::headsup::{GameOver, GameType, HeadsUp, TurnId};
+
+    #[test]
+    fn pokerstars_export() {
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        let mut hands = vec![];
+        let mut feed = |secs, event| {
+            let at = Duration::from_secs(secs);
+            hands.extend(heads_up.event(Timed::new(at, event)));
+        };
+        let stacks = |behinds: [u32; 2], bets: [u32; 2], pot| ObservableEvent::Stacks {
+            behinds: behinds.map(Chips::new),
+            bets: bets.map(Chips::new),
+            pot: Chips::new(pot),
+        };
+        let action = |action: &str| {
+            ObservableEvent::PlayerAction(action.parse().unwrap(), TurnId::default())
+        };
+
+        feed(65, ObservableEvent::DealHoles(["AsKd".parse().ok(), None]));
+        feed(65, stacks([49750, 49500], [250, 500], 0));
+        feed(70, action("1500"));
+        feed(75, action("c"));
+        feed(75, stacks([48500, 48500], [0, 0], 3000));
+        feed(75, ObservableEvent::DealBoard("Qs8d5c".parse().unwrap()));
+        feed(80, action("c"));
+        feed(85, action("2000"));
+        feed(90, action("f"));
+        feed(90, stacks([51500, 48500], [0, 0], 0));
+
+        let holes = ["QhQd".parse().unwrap(), "JcJs".parse().unwrap()];
+        feed(95, ObservableEvent::DealHoles(holes.map(Some)));
+        feed(100, action("a"));
+        feed(105, action("c"));
+        feed(105, ObservableEvent::ShowdownAll(holes));
+        feed(
+            105,
+            ObservableEvent::DealBoard("2c7d9hKs3s".parse().unwrap()),
+        );
+        feed(105, stacks([100000, 0], [0, 0], 0));
+        feed(105, ObservableEvent::GameOver(GameOver::Defeated(false)));
+        assert_eq!(
+            hands.iter().map(HandHistory::number).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(hands[1].started(), Duration::from_secs(95));
+        let result = hands[0].result();
+        assert_eq!(
+            result.invested[Street::Preflop as usize],
+            [Chips::new(1500); 2]
+        );
+        assert_eq!(result.won, [Chips::new(3000), Chips::ZERO]);
+        assert_eq!((result.net(true), result.net(false)), (1500, -1500));
+        assert!(!result.showdown);
+
+        let format = PokerStars::default()
+            .with_names(["alice", "bob"])
+            .with_clock_start(UNIX_EPOCH + Duration::from_secs(1792108800));
+        let folded = format.export(&hands[0]);
+        assert_eq!(
+            folded,
+            "PokerStars Hand #1: Hold'em No Limit (250/500) - 2026/10/16 00:01:05 UTC
+Table 'pokerbot' 2-max Seat #1 is the button
+Seat 1: alice (50000 in chips)
+Seat 2: bob (50000 in chips)
+alice: posts small blind 250
+bob: posts big blind 500
+*** HOLE CARDS ***
+Dealt to alice [As Kd]
+alice: raises 1000 to 1500
+bob: calls 1000
+*** FLOP *** [Qs 8d 5c]
+bob: checks
+alice: bets 2000
+bob: folds
+Uncalled bet (2000) returned to alice
+alice collected 3000 from pot
+*** SUMMARY ***
+Total pot 3000 | Rake 0
+Board [Qs 8d 5c]
+Seat 1: alice (button) (small blind) collected (3000)
+Seat 2: bob (big blind) folded on the Flop
+"
+        );
+
+        let all_in = format.export(&hands[1]);
+        for line in [
+            "Table 'pokerbot' 2-max Seat #2 is the button",
+            "bob: raises 48000 to 48500 and is all-in",
+            "alice: calls 48000",
+            "*** RIVER *** [2c 7d 9h Ks] [3s]",
+            "alice: shows [Qh Qd] (a pair of Queens)",
+            "Seat 1: alice (big blind) showed [Qh Qd] and won (97000) with a pair of Queens",
+            "Seat 2: bob (button) (small blind) showed [Jc Js] and lost with a pair of Jacks",
+        ] {
+            assert!(all_in.contains(line), "{line} missing from\n{all_in}");
+        }
+        assert_eq!(format.export_all(&hands), format!("{folded}\n\n{all_in}"));
+    }
+
+    #[test]
+    fn all_in_runouts() {
+        let mut heads_up = HeadsUp::new(GameType::cash_default(), true);
+        let mut hands = vec![];
+        let mut feed = |event| hands.extend(heads_up.event(Timed::new(Duration::ZERO, event)));
+        let action = |action: &str| {
+            ObservableEvent::PlayerAction(action.parse().unwrap(), TurnId::default())
+        };
+
+        let holes = ["AsAd".parse().unwrap(), "KsKd".parse().unwrap()];
+        feed(ObservableEvent::DealHoles(holes.map(Some)));
+        feed(action("c"));
+        feed(action("c"));
+        feed(ObservableEvent::DealBoard("9c7h2d".parse().unwrap()));
+        feed(action("a"));
+        feed(action("c"));
+        feed(ObservableEvent::ShowdownAll(holes));
+        feed(ObservableEvent::DealBoard("9c7h2d3c".parse().unwrap()));
+        feed(ObservableEvent::DealBoard("9c7h2d3cKc".parse().unwrap()));
+        feed(ObservableEvent::GameOver(GameOver::Defeated(true)));
+
+        let runouts = hands[0].all_in_runouts().unwrap();
+        assert_eq!(runouts.board, "9c7h2d".parse().unwrap());
+        assert_eq!(runouts.winner, Some(false));
+        let luck = runouts.winner_equity().unwrap();
+        assert!(luck.win > 0.05 && luck.win < 0.1);
+        assert_eq!(luck.win, runouts.equity.lose);
+    }
+}
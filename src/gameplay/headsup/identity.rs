@@ -0,0 +1,93 @@
+// Who sits in each seat. Events keep naming seats by `bool`, the game hands every player and
+// observer the identities too, so frontends and hand histories put names to seats without keeping
+// a seat map of their own.
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identity {
+    name: String,
+    id: Option<String>,     // stable across matches, e.g. an account id
+    avatar: Option<String>, // for the frontend to resolve, e.g. a URL or an emoji
+}
+
+impl Identity {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            id: None,
+            avatar: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_avatar(mut self, avatar: &str) -> Self {
+        self.avatar = Some(avatar.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn avatar(&self) -> Option<&str> {
+        self.avatar.as_deref()
+    }
+
+    // What stats and profiles of the player are kept under: the id, or the name without one.
+    pub fn key(&self) -> &str {
+        self.id().unwrap_or(&self.name)
+    }
+}
+
+// Player 0's identity first; p0 and p1 until someone is seated.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identities([Identity; 2]);
+
+impl Default for Identities {
+    fn default() -> Self {
+        Self([Identity::new("p0"), Identity::new("p1")])
+    }
+}
+
+impl Identities {
+    pub fn new(identities: [Identity; 2]) -> Self {
+        Self(identities)
+    }
+
+    pub fn of(&self, seat: bool) -> &Identity {
+        &self.0[if seat { 0 } else { 1 }]
+    }
+
+    pub fn names(&self) -> [&str; 2] {
+        [self.0[0].name(), self.0[1].name()]
+    }
+
+    fn set(&mut self, seat: bool, identity: Identity) {
+        self.0[if seat { 0 } else { 1 }] = identity;
+    }
+}
+
+// The identities a game and its players share, so seating someone after the players were handed
+// out still reaches them.
+#[derive(Debug, Default, Clone)]
+pub(super) struct SharedIdentities(Arc<Mutex<Identities>>);
+
+impl SharedIdentities {
+    pub(super) fn get(&self) -> Identities {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub(super) fn set(&self, seat: bool, identity: Identity) {
+        self.0.lock().unwrap().set(seat, identity);
+    }
+}
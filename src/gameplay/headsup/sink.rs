@@ -0,0 +1,123 @@
+// Where a game writes down every event as it runs, unfiltered, for logs to train and debug bots
+// on without an observer loop of one's own. A sink that fails to write is dropped, the game goes
+// on without it.
+use super::ObservableEvent;
+use crate::gameplay::chips::Chips;
+use std::{fmt::Debug, io, time::Duration};
+#[cfg(feature = "serde")]
+use std::{
+    fs::File,
+    io::{BufRead, BufWriter, Write},
+    path::Path,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventRecord {
+    pub hand: u32,          // the hand being played or last played, 0 before the first
+    pub at: Duration,       // on the game's clock
+    pub stacks: [Chips; 2], // behind, after the event
+    pub event: ObservableEvent,
+}
+
+pub trait EventSink: Debug + Send {
+    fn record(&mut self, record: &EventRecord) -> io::Result<()>;
+
+    // Called once the game is over.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// One JSON record per line (feature `serde`).
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct JsonlSink<W> {
+    out: W,
+}
+
+#[cfg(feature = "serde")]
+impl JsonlSink<BufWriter<File>> {
+    // Truncates `path` if it exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W: Write> JsonlSink<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W: Write + Debug + Send> EventSink for JsonlSink<W> {
+    fn record(&mut self, record: &EventRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, record)?;
+        self.out.write_all(b"\n")
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+// Reads back what a `JsonlSink` wrote, skipping blank lines.
+#[cfg(feature = "serde")]
+pub fn read_jsonl(read: impl BufRead) -> impl Iterator<Item = io::Result<EventRecord>> {
+    read.lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{self, testing::Caller},
+        gameplay::headsup::{CashBuyin, Game, GameOver, GameType},
+    };
+    use std::io::BufReader;
+
+    #[tokio::test]
+    async fn jsonl_log_of_a_game() {
+        let path = std::env::temp_dir().join(format!("pokerbot-sink-{}.jsonl", std::process::id()));
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB30,
+            hands: 2,
+            rake: None,
+        };
+        let (game, players) = Game::seeded(game_type, 3);
+        let game = game.with_sink(JsonlSink::create(&path).unwrap());
+        for mut player in players {
+            tokio::spawn(async move { bot::play(&mut player, &mut Caller).await });
+        }
+        let result = game.run().await;
+        let game_over = result.reason;
+
+        let records: Vec<EventRecord> = read_jsonl(BufReader::new(File::open(&path).unwrap()))
+            .collect::<io::Result<_>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            records[0].event,
+            ObservableEvent::DealHoles([Some(_), Some(_)])
+        ));
+        assert!(records.iter().all(|record| (1..=2).contains(&record.hand)));
+        assert!(records.windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+        let last = records.last().unwrap();
+        assert_eq!(last.event, ObservableEvent::GameOver(game_over));
+        let GameOver::HandsReached(stacks, _) = game_over else {
+            panic!("{game_over:?}");
+        };
+        assert_eq!((last.hand, last.stacks), (2, stacks));
+        assert_eq!((result.hands, result.stacks), (2, stacks));
+    }
+}
@@ -0,0 +1,369 @@
+use super::{Board, Card, Hole, Street, Suit, Value};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+const RANKS: u8 = 13;
+const SUITS: usize = 4;
+const ROUNDS: [u8; 4] = [2, 3, 1, 1]; // hole, flop, turn, river
+
+type Shape = Vec<u8>; // cards of one suit dealt in each round so far
+
+// One way of spreading the dealt cards over the suits, suits sorted by shape (descending).
+#[derive(Debug, Clone)]
+struct Configuration {
+    shapes: [Shape; SUITS],
+    offset: u64,
+    size: u64,
+}
+
+impl Configuration {
+    // Runs of suits sharing a shape, as (shape, suits in the run).
+    fn groups(&self) -> impl Iterator<Item = (&Shape, usize)> {
+        self.shapes
+            .iter()
+            .chunk_by(|shape| *shape)
+            .into_iter()
+            .map(|(shape, run)| (shape, run.count()))
+            .collect_vec()
+            .into_iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RoundTable {
+    configurations: Vec<Configuration>,
+    lookup: HashMap<[Shape; SUITS], usize>,
+    size: u64,
+}
+
+impl RoundTable {
+    fn new(round: usize) -> Self {
+        let mut shapes: Vec<Shape> = ROUNDS[..=round]
+            .iter()
+            .map(|&cards| 0..=cards)
+            .multi_cartesian_product()
+            .filter(|shape| shape.iter().sum::<u8>() <= RANKS)
+            .collect();
+        shapes.sort_by(|a, b| b.cmp(a));
+
+        let mut found = Vec::new();
+        spread(
+            &shapes,
+            0,
+            ROUNDS[..=round].to_vec(),
+            &mut Vec::new(),
+            &mut found,
+        );
+
+        let mut configurations = Vec::with_capacity(found.len());
+        let mut lookup = HashMap::with_capacity(found.len());
+        let mut offset = 0;
+
+        for shapes in found {
+            let mut config = Configuration {
+                shapes,
+                offset,
+                size: 1,
+            };
+            config.size = config
+                .groups()
+                .map(|(shape, suits)| multisets(shape_size(shape), suits as u64))
+                .product();
+            offset += config.size;
+            lookup.insert(config.shapes.clone(), configurations.len());
+            configurations.push(config);
+        }
+
+        Self {
+            configurations,
+            lookup,
+            size: offset,
+        }
+    }
+}
+
+// Suit-isomorphic perfect indexer for hold'em hands, after Waugh's hand indexer:
+// every (hole, board) maps to a dense index in `0..size(street)` shared by exactly
+// the hands equal up to relabelling suits, and `unindex` gives back a representative.
+#[derive(Debug, Clone)]
+pub struct HandIndexer {
+    rounds: Vec<RoundTable>,
+}
+
+impl Default for HandIndexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandIndexer {
+    pub fn new() -> Self {
+        Self {
+            rounds: (0..ROUNDS.len()).map(RoundTable::new).collect(),
+        }
+    }
+
+    // 169, 1_286_792, 55_190_538, 2_428_287_420
+    pub fn size(&self, street: Street) -> u64 {
+        self.rounds[street as usize].size
+    }
+
+    // None if the hole shares a card with the board.
+    pub fn index(&self, hole: Hole, board: &Board) -> Option<u64> {
+        let round = board.street() as usize;
+        let cards = hole
+            .iter()
+            .chain(board.to_vec().iter())
+            .copied()
+            .collect_vec();
+        if !cards.iter().all_unique() {
+            return None;
+        }
+
+        // ranks of each suit dealt in each round
+        let mut masks = [[0u16; ROUNDS.len()]; SUITS];
+        let mut dealt = 0;
+        for (r, &count) in ROUNDS[..=round].iter().enumerate() {
+            for card in &cards[dealt..dealt + count as usize] {
+                masks[suit_of(card.suit())][r] |= 1 << card.value().as_u8();
+            }
+            dealt += count as usize;
+        }
+
+        let mut suits = masks
+            .iter()
+            .map(|masks| {
+                let masks = &masks[..=round];
+                let shape: Shape = masks.iter().map(|mask| mask.count_ones() as u8).collect();
+                (shape, suit_index(masks))
+            })
+            .collect_vec();
+        suits.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let table = &self.rounds[round];
+        let key = suits.iter().map(|(shape, _)| shape.clone()).collect_vec();
+        let config = &table.configurations[table.lookup[key.as_slice()]];
+
+        let mut index = 0;
+        let mut radix = 1;
+        let mut start = 0;
+        for (shape, run) in config.groups() {
+            let mut indices = suits[start..start + run]
+                .iter()
+                .map(|&(_, index)| index)
+                .collect_vec();
+            indices.sort_unstable();
+            for (i, index) in indices.iter_mut().enumerate() {
+                *index += i as u64; // multiset to set
+            }
+
+            index += colex_rank(&indices) * radix;
+            radix *= multisets(shape_size(shape), run as u64);
+            start += run;
+        }
+
+        Some(config.offset + index)
+    }
+
+    // Canonical representative of `index`: suits are handed out spades first.
+    pub fn unindex(&self, street: Street, index: u64) -> Option<(Hole, Board)> {
+        let round = street as usize;
+        let table = &self.rounds[round];
+        if index >= table.size {
+            return None;
+        }
+
+        let position = table
+            .configurations
+            .partition_point(|config| config.offset + config.size <= index);
+        let config = &table.configurations[position];
+
+        let mut local = index - config.offset;
+        let mut per_round = vec![Vec::new(); round + 1];
+        let mut suit = 0;
+        for (shape, run) in config.groups() {
+            let n = shape_size(shape);
+            let size = multisets(n, run as u64);
+            let mut indices = colex_unrank(local % size, run as u64, n + run as u64 - 1);
+            local /= size;
+            for (i, index) in indices.iter_mut().enumerate() {
+                *index -= i as u64;
+            }
+
+            for index in indices {
+                for (r, mask) in suit_unindex(shape, index).into_iter().enumerate() {
+                    per_round[r].extend(
+                        (0..RANKS)
+                            .filter(|rank| mask & (1 << rank) != 0)
+                            .map(|rank| Card::new(Value::ALL[rank as usize], Suit::ALL[suit])),
+                    );
+                }
+                suit += 1;
+            }
+        }
+
+        let cards = per_round.concat();
+        let hole = Hole::new([cards[0], cards[1]])?;
+        let board = Board::from_slice(&cards[2..])?;
+        Some((hole, board))
+    }
+}
+
+fn suit_of(suit: Suit) -> usize {
+    Suit::ALL
+        .iter()
+        .position(|&s| s == suit)
+        .expect("Suit::ALL should list every suit")
+}
+
+// Picks non-increasing shapes for the remaining suits until every round's cards are placed.
+fn spread(
+    shapes: &[Shape],
+    start: usize,
+    remaining: Vec<u8>,
+    chosen: &mut Vec<usize>,
+    found: &mut Vec<[Shape; SUITS]>,
+) {
+    if chosen.len() == SUITS {
+        if remaining.iter().all(|&cards| cards == 0) {
+            found.push(std::array::from_fn(|i| shapes[chosen[i]].clone()));
+        }
+        return;
+    }
+
+    for (i, shape) in shapes.iter().enumerate().skip(start) {
+        if shape.iter().zip(&remaining).all(|(m, n)| m <= n) {
+            let remaining = remaining.iter().zip(shape).map(|(n, m)| n - m).collect();
+            chosen.push(i);
+            spread(shapes, i, remaining, chosen, found);
+            chosen.pop();
+        }
+    }
+}
+
+// Number of ways one suit can be dealt `shape`.
+fn shape_size(shape: &Shape) -> u64 {
+    let mut available = RANKS as u64;
+    let mut size = 1;
+    for &cards in shape {
+        size *= binomial(available, cards as u64);
+        available -= cards as u64;
+    }
+    size
+}
+
+// Each round's ranks are ranked among the ranks still unused by the earlier rounds.
+fn suit_index(masks: &[u16]) -> u64 {
+    let mut used = 0u16;
+    let mut index = 0;
+    let mut radix = 1;
+
+    for &mask in masks {
+        let positions = (0..RANKS as u16)
+            .filter(|rank| mask & (1 << rank) != 0)
+            .map(|rank| (rank - (used & ((1 << rank) - 1)).count_ones() as u16) as u64)
+            .collect_vec();
+        let available = (RANKS as u32 - used.count_ones()) as u64;
+
+        index += colex_rank(&positions) * radix;
+        radix *= binomial(available, positions.len() as u64);
+        used |= mask;
+    }
+
+    index
+}
+
+fn suit_unindex(shape: &Shape, mut index: u64) -> Vec<u16> {
+    let mut used = 0u16;
+    let mut masks = Vec::with_capacity(shape.len());
+
+    for &cards in shape {
+        let free = (0..RANKS as u16)
+            .filter(|rank| used & (1 << rank) == 0)
+            .collect_vec();
+        let size = binomial(free.len() as u64, cards as u64);
+        let mask = colex_unrank(index % size, cards as u64, free.len() as u64)
+            .into_iter()
+            .fold(0, |mask, position| mask | 1 << free[position as usize]);
+
+        index /= size;
+        used |= mask;
+        masks.push(mask);
+    }
+
+    masks
+}
+
+fn binomial(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k.min(n - k)).fold(1u128, |acc, i| acc * (n - i) as u128 / (i + 1) as u128) as u64
+}
+
+fn multisets(n: u64, k: u64) -> u64 {
+    binomial(n + k - 1, k)
+}
+
+// `set` ascending
+fn colex_rank(set: &[u64]) -> u64 {
+    set.iter()
+        .enumerate()
+        .map(|(i, &element)| binomial(element, i as u64 + 1))
+        .sum()
+}
+
+// The `k`-subset of `0..n` at colex rank `index`, ascending.
+fn colex_unrank(mut index: u64, k: u64, n: u64) -> Vec<u64> {
+    let mut set = vec![0; k as usize];
+    let mut upper = n;
+
+    for i in (1..=k).rev() {
+        // largest element with binomial(element, i) <= index
+        let (mut lo, mut hi) = (i - 1, upper - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if binomial(mid, i) <= index {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        set[i as usize - 1] = lo;
+        index -= binomial(lo, i);
+        upper = lo;
+    }
+
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_and_round_trip() {
+        let indexer = HandIndexer::new();
+        let sizes = [Street::Preflop, Street::Flop, Street::Turn, Street::River]
+            .map(|street| indexer.size(street));
+        assert_eq!(sizes, [169, 1_286_792, 55_190_538, 2_428_287_420]);
+
+        let hole: Hole = "AhKh".parse().unwrap();
+        let board: Board = "Qh7d2c5h".parse().unwrap();
+        let index = indexer.index(hole, &board).unwrap();
+        let (canon_hole, canon_board) = indexer.unindex(Street::Turn, index).unwrap();
+        assert_eq!(indexer.index(canon_hole, &canon_board), Some(index));
+
+        let isomorphic: Hole = "AsKs".parse().unwrap();
+        let board: Board = "Qs7c2d5s".parse().unwrap();
+        assert_eq!(indexer.index(isomorphic, &board), Some(index));
+
+        let pairs = (0..169)
+            .filter(|&i| {
+                let (hole, _) = indexer.unindex(Street::Preflop, i).unwrap();
+                hole.is_pocket_pair()
+            })
+            .count();
+        assert_eq!(pairs, 13);
+    }
+}
@@ -0,0 +1,233 @@
+// What is wrong with text that doesn't parse as cards, boards or hole classes, worded so it can
+// go straight back to whoever typed it.
+use super::Card;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CardParseError {
+    InvalidRank(char),
+    InvalidSuit(char),
+    WrongLength(usize), // characters given for one rank, suit or card
+}
+
+impl Display for CardParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRank(c) => write!(f, "'{c}' is not a rank, use 2-9, T, J, Q, K or A"),
+            Self::InvalidSuit(c) => write!(f, "'{c}' is not a suit, use s, h, d or c"),
+            Self::WrongLength(len) => {
+                write!(f, "expected a card like \"As\", got {len} characters")
+            }
+        }
+    }
+}
+
+impl Error for CardParseError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CardsParseError {
+    NotAscii,
+    Card(CardParseError),
+    DuplicateCard(Card),
+    WrongCount { expected: usize, found: usize },
+}
+
+impl Display for CardsParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAscii => write!(f, "cards are written in plain ASCII"),
+            Self::Card(err) => write!(f, "{err}"),
+            Self::DuplicateCard(card) => {
+                write!(f, "{card} appears twice")
+            }
+            Self::WrongCount { expected, found } => {
+                write!(f, "expected {expected} cards, got {found}")
+            }
+        }
+    }
+}
+
+impl Error for CardsParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Card(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CardParseError> for CardsParseError {
+    fn from(err: CardParseError) -> Self {
+        Self::Card(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum BoardParseError {
+    NotAscii,
+    Card(CardParseError),
+    DuplicateCard(Card),
+    WrongCount(usize), // a board has 3, 4 or 5 cards, or is "x"
+}
+
+impl Display for BoardParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAscii => write!(f, "boards are written in plain ASCII"),
+            Self::Card(err) => write!(f, "{err}"),
+            Self::DuplicateCard(card) => {
+                write!(f, "{card} appears twice")
+            }
+            Self::WrongCount(count) => {
+                write!(
+                    f,
+                    "a board has 3, 4 or 5 cards (or \"x\" for none), got {count}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for BoardParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Card(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CardParseError> for BoardParseError {
+    fn from(err: CardParseError) -> Self {
+        Self::Card(err)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum HoleClassParseError {
+    WrongLength(usize),
+    Rank(CardParseError),
+    Suffix(String), // not "s" or "o" for two ranks, or anything after a pair
+}
+
+impl Display for HoleClassParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength(len) => {
+                write!(
+                    f,
+                    "expected a class like \"AKs\" or \"77\", got {len} characters"
+                )
+            }
+            Self::Rank(err) => write!(f, "{err}"),
+            Self::Suffix(suffix) => {
+                write!(f, "\"{suffix}\" should be \"s\" or \"o\" after two ranks")
+            }
+        }
+    }
+}
+
+impl Error for HoleClassParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Rank(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<CardParseError> for HoleClassParseError {
+    fn from(err: CardParseError) -> Self {
+        Self::Rank(err)
+    }
+}
+
+// Cards back to back with any whitespace between them, e.g. "AsKd" or "As Kd".
+pub(super) fn parse_cards(s: &str) -> Result<Vec<Card>, CardParseError> {
+    let mut cards = vec![];
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let Some(card) = rest.get(0..2) else {
+            return Err(CardParseError::WrongLength(rest.len()));
+        };
+        cards.push(card.parse()?);
+        rest = rest[2..].trim_start();
+    }
+    Ok(cards)
+}
+
+// The first card seen twice.
+pub(super) fn duplicate(cards: &[Card]) -> Option<Card> {
+    cards
+        .iter()
+        .enumerate()
+        .find(|&(i, card)| cards[..i].contains(card))
+        .map(|(_, card)| *card)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gameplay::{Board, Hole, HoleClass, headsup::Action},
+        range::Range,
+    };
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("Xs".parse::<Card>(), Err(CardParseError::InvalidRank('X')));
+        assert_eq!("Ax".parse::<Card>(), Err(CardParseError::InvalidSuit('x')));
+        assert_eq!("Asd".parse::<Card>(), Err(CardParseError::WrongLength(3)));
+
+        let ace = "As".parse().unwrap();
+        assert_eq!(
+            "AsAs".parse::<Hole>(),
+            Err(CardsParseError::DuplicateCard(ace))
+        );
+        assert_eq!(
+            "As Kd Qc".parse::<Hole>(),
+            Err(CardsParseError::WrongCount {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            "AsK".parse::<Hole>(),
+            Err(CardsParseError::Card(CardParseError::WrongLength(1)))
+        );
+
+        assert_eq!(
+            "Ks 9s".parse::<Board>(),
+            Err(BoardParseError::WrongCount(2))
+        );
+        assert_eq!(
+            "Ks9sKs".parse::<Board>(),
+            Err(BoardParseError::DuplicateCard("Ks".parse().unwrap()))
+        );
+        assert_eq!(
+            "Ks9s1d".parse::<Board>().unwrap_err().to_string(),
+            "'1' is not a rank, use 2-9, T, J, Q, K or A"
+        );
+
+        assert_eq!(
+            "AKx".parse::<HoleClass>(),
+            Err(HoleClassParseError::Suffix("x".to_string()))
+        );
+        assert!(
+            "0".parse::<Action>()
+                .unwrap_err()
+                .to_string()
+                .contains("no bet or raise")
+        );
+        assert!(
+            "AKs, 7z"
+                .parse::<Range>()
+                .unwrap_err()
+                .to_string()
+                .starts_with("\"7z\"")
+        );
+    }
+}
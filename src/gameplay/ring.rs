@@ -0,0 +1,555 @@
+// Tables of more than two players. `headsup` keeps its two-seat arrays, a ring table keeps one
+// entry per seat and only does the betting: the caller deals the cards, asks whose turn it is and
+// brings the board to the showdown, where the side pots are paid out.
+use super::{
+    FullBoard, Hole, Street,
+    chips::Chips,
+    headsup::{Action, ActionValue, BetBound},
+};
+
+pub const MAX_SEATS: usize = 10;
+
+// What an action, or the blinds, left the hand at.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HandProgress {
+    Turn(usize),        // the seat to act next
+    StreetOver,         // deal the next street, then `next_street`
+    AllIn,              // nobody left to bet against, deal the rest of the board and show down
+    Showdown,           // the river betting is over
+    Uncontested(usize), // everyone else folded, the pot went to this seat
+}
+
+// Chips that go to the best hand among `eligible`, the main pot first and side pots after.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Pot {
+    amount: Chips,
+    eligible: Vec<usize>,
+}
+
+impl Pot {
+    pub fn amount(&self) -> Chips {
+        self.amount
+    }
+
+    pub fn eligible(&self) -> &[usize] {
+        &self.eligible
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Table {
+    big_blind: Chips,
+    hands: u32,
+    button: usize,
+    street: Street,
+    cur_turn: Option<usize>,
+    stacks: Vec<Chips>,    // behind, not counting what was bet
+    bets: Vec<Chips>,      // this street
+    committed: Vec<Chips>, // this hand, bets included
+    dealt: Vec<bool>,      // in the hand, folded or not
+    folded: Vec<bool>,
+    to_act: Vec<bool>,
+    can_raise: Vec<bool>, // false once acted, until a full raise reopens the betting
+    min_raise: Chips,
+    last_aggressor: Option<usize>,
+}
+
+impl Table {
+    // Seats in order around the table, empty ones with no chips. None for fewer than two or more
+    // than `MAX_SEATS` seats, or a big blind that doesn't halve into a small one.
+    pub fn new(big_blind: Chips, stacks: Vec<Chips>) -> Option<Self> {
+        let seats = stacks.len();
+        if !(2..=MAX_SEATS).contains(&seats) || big_blind.is_zero() || !(big_blind % 2).is_zero() {
+            return None;
+        }
+
+        Some(Self {
+            big_blind,
+            hands: 0,
+            button: seats - 1, // moves to the first seat with chips on the first hand
+            street: Street::Preflop,
+            cur_turn: None,
+            stacks,
+            bets: vec![Chips::ZERO; seats],
+            committed: vec![Chips::ZERO; seats],
+            dealt: vec![false; seats],
+            folded: vec![false; seats],
+            to_act: vec![false; seats],
+            can_raise: vec![false; seats],
+            min_raise: big_blind,
+            last_aggressor: None,
+        })
+    }
+
+    pub fn seats(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn big_blind(&self) -> Chips {
+        self.big_blind
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn cur_turn(&self) -> Option<usize> {
+        self.cur_turn
+    }
+
+    pub fn stacks(&self) -> &[Chips] {
+        &self.stacks
+    }
+
+    pub fn bets(&self) -> &[Chips] {
+        &self.bets
+    }
+
+    // Everything put in this hand, the current street's bets too.
+    pub fn pot(&self) -> Chips {
+        self.committed.iter().copied().sum()
+    }
+
+    pub fn is_in_hand(&self, seat: usize) -> bool {
+        self.dealt[seat] && !self.folded[seat]
+    }
+
+    // Who bet or raised last this street, None when nobody did after the blinds.
+    pub fn last_aggressor(&self) -> Option<usize> {
+        self.last_aggressor
+    }
+
+    // Moves the button to the next seat with chips, posts the blinds and says who acts first.
+    // None when fewer than two seats have chips. Heads up the button posts the small blind.
+    pub fn start_hand(&mut self) -> Option<HandProgress> {
+        let seats = self.seats();
+        self.dealt = self.stacks.iter().map(|stack| !stack.is_zero()).collect();
+        if self.dealt.iter().filter(|&&dealt| dealt).count() < 2 {
+            self.dealt = vec![false; seats];
+            return None;
+        }
+
+        self.hands += 1;
+        self.button = self.next_dealt(self.button);
+        self.street = Street::Preflop;
+        self.bets = vec![Chips::ZERO; seats];
+        self.committed = vec![Chips::ZERO; seats];
+        self.folded = vec![false; seats];
+        self.to_act = self.dealt.clone();
+        self.can_raise = self.dealt.clone();
+        self.min_raise = self.big_blind;
+        self.last_aggressor = None;
+
+        let heads_up = self.dealt.iter().filter(|&&dealt| dealt).count() == 2;
+        let small_blind = if heads_up {
+            self.button
+        } else {
+            self.next_dealt(self.button)
+        };
+        let big_blind = self.next_dealt(small_blind);
+        self.put_in(small_blind, self.big_blind / 2);
+        self.put_in(big_blind, self.big_blind);
+
+        Some(self.advance(big_blind))
+    }
+
+    // Once a street is over: the next one, with the first seat left of the button to act. None
+    // without a street to go to or a hand that is still betting.
+    pub fn next_street(&mut self) -> Option<HandProgress> {
+        if self.cur_turn.is_some() || !self.dealt.iter().any(|&dealt| dealt) {
+            return None;
+        }
+
+        self.street = self.street.next()?;
+        self.bets.fill(Chips::ZERO);
+        self.min_raise = self.big_blind;
+        self.last_aggressor = None;
+        for seat in 0..self.seats() {
+            let open = self.is_in_hand(seat) && !self.stacks[seat].is_zero();
+            self.to_act[seat] = open;
+            self.can_raise[seat] = open;
+        }
+        Some(self.advance(self.button))
+    }
+
+    pub fn bet_bound(&self) -> Option<BetBound> {
+        let hero = self.cur_turn?;
+        let max_bet = self.max_bet();
+        let total = self.bets[hero] + self.stacks[hero];
+
+        if self.bets[hero] == max_bet {
+            let min_bet = max_bet + self.min_raise;
+            return Some(if total <= min_bet {
+                BetBound::FoldCheckAllIn
+            } else {
+                BetBound::FoldCheckBetAllIn(min_bet..=total)
+            });
+        }
+
+        // cover
+        if total <= max_bet {
+            return Some(BetBound::FoldAllIn);
+        }
+
+        // everyone else all in, or a short all in that didn't reopen the betting
+        let opponents = (0..self.seats())
+            .any(|seat| seat != hero && self.is_in_hand(seat) && !self.stacks[seat].is_zero());
+        if !opponents || !self.can_raise[hero] {
+            return Some(BetBound::FoldCall);
+        }
+
+        let min_raise = max_bet + self.min_raise;
+        Some(if total <= min_raise {
+            BetBound::FoldCallAllIn
+        } else {
+            BetBound::FoldCallRaiseAllIn(min_raise..=total)
+        })
+    }
+
+    // The action of the seat to act. None for an illegal action or when nobody is to act.
+    pub fn action(&mut self, action: Action) -> Option<HandProgress> {
+        let hero = self.cur_turn?;
+        if !self.bet_bound()?.validate_action(action) {
+            return None;
+        }
+
+        let max_bet = self.max_bet();
+        self.to_act[hero] = false;
+        self.can_raise[hero] = false;
+        let to = match action.value() {
+            ActionValue::Exit | ActionValue::Fold => {
+                self.folded[hero] = true;
+                let mut left = (0..self.seats()).filter(|&seat| self.is_in_hand(seat));
+                if let (Some(winner), None) = (left.next(), left.next()) {
+                    let pot = self.pot();
+                    self.stacks[winner] += pot;
+                    self.end_hand();
+                    return Some(HandProgress::Uncontested(winner));
+                }
+                return Some(self.advance(hero));
+            }
+            ActionValue::CheckOrCall => max_bet.min(self.bets[hero] + self.stacks[hero]),
+            ActionValue::BetOrRaise(amount) => amount,
+            ActionValue::AllIn => self.bets[hero] + self.stacks[hero],
+        };
+        self.put_in(hero, to - self.bets[hero]);
+
+        if to > max_bet {
+            self.last_aggressor = Some(hero);
+            let raise = to - max_bet;
+            let full = raise >= self.min_raise;
+            if full {
+                self.min_raise = raise;
+            }
+            // a short all in raise has the others call it, only a full one lets them raise again
+            for seat in 0..self.seats() {
+                if seat != hero && self.is_in_hand(seat) && !self.stacks[seat].is_zero() {
+                    self.to_act[seat] = true;
+                    self.can_raise[seat] |= full;
+                }
+            }
+        }
+        Some(self.advance(hero))
+    }
+
+    // The main pot and the side pots, from what everyone put in this hand. Chips nobody matched
+    // make a pot of their own only the bettor is eligible for, and go back to them.
+    pub fn pots(&self) -> Vec<Pot> {
+        let mut levels: Vec<Chips> = (0..self.seats())
+            .filter(|&seat| self.is_in_hand(seat))
+            .map(|seat| self.committed[seat])
+            .collect();
+        levels.sort();
+        levels.dedup();
+
+        let mut pots = Vec::new();
+        let mut below = Chips::ZERO;
+        for level in levels {
+            let amount = self
+                .committed
+                .iter()
+                .map(|&chips| chips.min(level).saturating_sub(below))
+                .sum();
+            let eligible = (0..self.seats())
+                .filter(|&seat| self.is_in_hand(seat) && self.committed[seat] >= level)
+                .collect();
+            pots.push(Pot { amount, eligible });
+            below = level;
+        }
+        // folded chips above every level still in, there when the rest folded to a bet
+        let above: Chips = self
+            .committed
+            .iter()
+            .map(|&c| c.saturating_sub(below))
+            .sum();
+        if let Some(last) = pots.last_mut() {
+            last.amount += above;
+        }
+        pots
+    }
+
+    // Pays every pot to the best hand eligible for it on the full board, split between equal
+    // hands with the odd chips going to the first of them left of the button. `holes` by seat,
+    // None for seats out of the hand. Returns what each seat won.
+    pub fn showdown(&mut self, holes: &[Option<Hole>], board: &FullBoard) -> Vec<Chips> {
+        let values: Vec<_> = (0..self.seats())
+            .map(|seat| {
+                holes
+                    .get(seat)
+                    .copied()
+                    .flatten()
+                    .filter(|_| self.is_in_hand(seat))
+                    .map(|hole| board.hand_value(hole))
+            })
+            .collect();
+
+        let mut won = vec![Chips::ZERO; self.seats()];
+        for pot in self.pots() {
+            let best = pot.eligible.iter().filter_map(|&seat| values[seat]).max();
+            let mut winners: Vec<usize> = pot
+                .eligible
+                .iter()
+                .copied()
+                .filter(|&seat| best.is_some() && values[seat] == best)
+                .collect();
+            if winners.is_empty() {
+                winners = pot.eligible.clone(); // no holes given, a chop
+            }
+            winners.sort_by_key(|&seat| (seat + self.seats() - self.button - 1) % self.seats());
+
+            let share = pot.amount / winners.len() as u32;
+            let odd = (pot.amount % winners.len() as u32).get() as usize;
+            for (i, &seat) in winners.iter().enumerate() {
+                won[seat] += share + Chips::new((i < odd) as u32);
+            }
+        }
+
+        for (stack, &won) in self.stacks.iter_mut().zip(&won) {
+            *stack += won;
+        }
+        self.end_hand();
+        won
+    }
+
+    // Who shows first at a river showdown: the river's last aggressor, else the first still in
+    // left of the button, then the others in turn around the table.
+    pub fn showdown_order(&self) -> Vec<usize> {
+        let first = self
+            .last_aggressor
+            .filter(|&seat| self.is_in_hand(seat))
+            .unwrap_or_else(|| self.next_in_hand(self.button));
+        (0..self.seats())
+            .map(|i| (first + i) % self.seats())
+            .filter(|&seat| self.is_in_hand(seat))
+            .collect()
+    }
+
+    fn max_bet(&self) -> Chips {
+        self.bets.iter().copied().max().unwrap_or_default()
+    }
+
+    // Puts `chips` from `seat`'s stack in front of them, all in when they have no more.
+    fn put_in(&mut self, seat: usize, chips: Chips) {
+        let chips = chips.min(self.stacks[seat]);
+        self.stacks[seat] -= chips;
+        self.bets[seat] += chips;
+        self.committed[seat] += chips;
+    }
+
+    fn next_dealt(&self, seat: usize) -> usize {
+        self.next_seat(seat, |seat| self.dealt[seat])
+    }
+
+    fn next_in_hand(&self, seat: usize) -> usize {
+        self.next_seat(seat, |seat| self.is_in_hand(seat))
+    }
+
+    fn next_seat(&self, seat: usize, take: impl Fn(usize) -> bool) -> usize {
+        (1..=self.seats())
+            .map(|i| (seat + i) % self.seats())
+            .find(|&seat| take(seat))
+            .expect("a hand has players in it")
+    }
+
+    // Whether `seat` still has something to do this street: chips behind and a bet to answer,
+    // or no action yet with someone left to bet against.
+    fn needs_action(&self, seat: usize) -> bool {
+        if !self.is_in_hand(seat) || self.stacks[seat].is_zero() {
+            return false;
+        }
+        if self.bets[seat] < self.max_bet() {
+            return true;
+        }
+        let opponents = (0..self.seats())
+            .any(|other| other != seat && self.is_in_hand(other) && !self.stacks[other].is_zero());
+        self.to_act[seat] && opponents
+    }
+
+    // The next seat after `seat` to act, or how the street ended when nobody is left to.
+    fn advance(&mut self, seat: usize) -> HandProgress {
+        let seats = self.seats();
+        self.cur_turn = (1..=seats)
+            .map(|i| (seat + i) % seats)
+            .find(|&seat| self.needs_action(seat));
+        if let Some(seat) = self.cur_turn {
+            return HandProgress::Turn(seat);
+        }
+
+        let open = (0..seats)
+            .filter(|&seat| self.is_in_hand(seat) && !self.stacks[seat].is_zero())
+            .count();
+        if self.street == Street::River {
+            HandProgress::Showdown
+        } else if open < 2 {
+            HandProgress::AllIn
+        } else {
+            HandProgress::StreetOver
+        }
+    }
+
+    fn end_hand(&mut self) {
+        self.cur_turn = None;
+        self.bets.fill(Chips::ZERO);
+        self.committed.fill(Chips::ZERO);
+        self.dealt.fill(false);
+        self.folded.fill(false);
+        self.to_act.fill(false);
+        self.can_raise.fill(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::Board;
+
+    fn chips(stacks: &[u32]) -> Vec<Chips> {
+        stacks.iter().map(|&chips| Chips::new(chips)).collect()
+    }
+
+    #[test]
+    fn side_pots() {
+        let mut table = Table::new(Chips::new(100), chips(&[1000, 300, 600, 5000])).unwrap();
+        // seat 0 on the button, 1 and 2 in the blinds, 3 first to act
+        assert_eq!(table.start_hand(), Some(HandProgress::Turn(3)));
+        assert_eq!(table.button(), 0);
+        assert_eq!(table.bets(), chips(&[0, 50, 100, 0]));
+
+        let raise = Action::bet_or_raise(Chips::new(300)).unwrap();
+        assert_eq!(table.action(raise), Some(HandProgress::Turn(0)));
+        assert_eq!(table.action(Action::all_in()), Some(HandProgress::Turn(1)));
+        assert_eq!(table.action(Action::all_in()), Some(HandProgress::Turn(2)));
+        assert_eq!(table.action(Action::all_in()), Some(HandProgress::Turn(3)));
+        assert_eq!(
+            table.action(Action::check_or_call()),
+            Some(HandProgress::AllIn)
+        );
+
+        let pots: Vec<_> = table
+            .pots()
+            .iter()
+            .map(|pot| (pot.amount().get(), pot.eligible().to_vec()))
+            .collect();
+        assert_eq!(
+            pots,
+            [
+                (1200, vec![0, 1, 2, 3]),
+                (900, vec![0, 2, 3]),
+                (800, vec![0, 3]),
+            ]
+        );
+        assert_eq!(table.pot(), Chips::new(2900));
+
+        // the shortest stack has the best hand, the biggest the second best
+        let holes = ["QcJc", "AsAh", "3c4d", "KsKh"].map(|hole| hole.parse().ok());
+        let board = "AcKd9h7s2h".parse::<Board>().unwrap();
+        let won = table.showdown(&holes, &board.as_full_board().unwrap());
+        assert_eq!(won, chips(&[0, 1200, 0, 1700]));
+        assert_eq!(table.stacks(), chips(&[0, 1200, 0, 5700]));
+
+        // the button moves past the busted seats, heads up it posts the small blind
+        assert_eq!(table.start_hand(), Some(HandProgress::Turn(1)));
+        assert_eq!(table.button(), 1);
+        assert_eq!(table.bets(), chips(&[0, 50, 0, 100]));
+
+        // a short all in has the others call it, not raise again
+        let mut table = Table::new(Chips::new(100), chips(&[1000, 1000, 150])).unwrap();
+        assert_eq!(table.start_hand(), Some(HandProgress::Turn(0)));
+        assert_eq!(
+            table.action(Action::check_or_call()),
+            Some(HandProgress::Turn(1))
+        );
+        assert_eq!(
+            table.action(Action::check_or_call()),
+            Some(HandProgress::Turn(2))
+        );
+        assert_eq!(table.bet_bound(), Some(BetBound::FoldCheckAllIn));
+        assert_eq!(table.action(Action::all_in()), Some(HandProgress::Turn(0)));
+        assert_eq!(table.bet_bound(), Some(BetBound::FoldCall));
+        assert_eq!(
+            table.action(Action::check_or_call()),
+            Some(HandProgress::Turn(1))
+        );
+        assert_eq!(
+            table.action(Action::check_or_call()),
+            Some(HandProgress::StreetOver)
+        );
+        assert_eq!(table.next_street(), Some(HandProgress::Turn(1)));
+        assert_eq!(table.showdown_order(), [1, 2, 0]); // the all in raised preflop only
+    }
+
+    // Calls or checks `seat`'s turn, returning how the hand went on.
+    fn call(table: &mut Table, seat: usize) -> Option<HandProgress> {
+        assert_eq!(table.cur_turn(), Some(seat));
+        table.action(Action::check_or_call())
+    }
+
+    #[test]
+    fn showdown_order_by_river_bet() {
+        // seat 0 on the button raises preflop, then it's checked down
+        let mut table = Table::new(Chips::new(100), chips(&[1000, 1000, 1000])).unwrap();
+        assert_eq!(table.start_hand(), Some(HandProgress::Turn(0)));
+        let raise = Action::bet_or_raise(Chips::new(300)).unwrap();
+        assert_eq!(table.action(raise), Some(HandProgress::Turn(1)));
+        call(&mut table, 1);
+        assert_eq!(call(&mut table, 2), Some(HandProgress::StreetOver));
+        for street in [Street::Flop, Street::Turn, Street::River] {
+            assert_eq!(table.next_street(), Some(HandProgress::Turn(1)));
+            assert_eq!(table.street(), street);
+            call(&mut table, 1);
+            call(&mut table, 2);
+            call(&mut table, 0);
+        }
+        // nobody bet the river, the first left of the button shows first
+        assert_eq!(table.last_aggressor(), None);
+        assert_eq!(table.showdown_order(), [1, 2, 0]);
+
+        // a river bet has the bettor show first
+        let mut table = Table::new(Chips::new(100), chips(&[1000, 1000, 1000])).unwrap();
+        table.start_hand();
+        call(&mut table, 0);
+        call(&mut table, 1);
+        call(&mut table, 2);
+        for _ in [Street::Flop, Street::Turn] {
+            table.next_street();
+            call(&mut table, 1);
+            call(&mut table, 2);
+            call(&mut table, 0);
+        }
+        table.next_street();
+        call(&mut table, 1);
+        let bet = Action::bet_or_raise(Chips::new(100)).unwrap();
+        assert_eq!(table.action(bet), Some(HandProgress::Turn(0)));
+        call(&mut table, 0);
+        assert_eq!(call(&mut table, 1), Some(HandProgress::Showdown));
+        assert_eq!(table.showdown_order(), [2, 0, 1]);
+    }
+}
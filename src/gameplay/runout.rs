@@ -0,0 +1,171 @@
+use super::{Board, Card, Suit, Value};
+use indexmap::IndexMap;
+use std::fmt::{self, Display, Formatter};
+
+// Strategic class of the next board card, in priority order: a card that both completes a flush
+// and pairs the board counts as flush-completing.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum RunoutBucket {
+    FlushCompleting,
+    BoardPairing,
+    Overcard,
+    Brick,
+}
+
+impl Display for RunoutBucket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::FlushCompleting => "flush-completing",
+            Self::BoardPairing => "board-pairing",
+            Self::Overcard => "overcard",
+            Self::Brick => "brick",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl RunoutBucket {
+    pub const ALL: [Self; 4] = [
+        Self::FlushCompleting,
+        Self::BoardPairing,
+        Self::Overcard,
+        Self::Brick,
+    ];
+
+    // None if `card` can't come next: no flop yet, river already out, or already on board.
+    pub fn classify(board: &Board, card: Card) -> Option<Self> {
+        if board.is_preflop() || board.is_river() {
+            return None;
+        }
+
+        let cards = board.to_vec();
+        if cards.contains(&card) {
+            return None;
+        }
+
+        let suited = cards.iter().filter(|c| c.suit() == card.suit()).count();
+        let top = cards.iter().map(|c| c.value()).max();
+
+        Some(if suited >= 2 {
+            Self::FlushCompleting
+        } else if cards.iter().any(|c| c.value() == card.value()) {
+            Self::BoardPairing
+        } else if Some(card.value()) > top {
+            Self::Overcard
+        } else {
+            Self::Brick
+        })
+    }
+}
+
+// The 45~49 possible next cards of a flop or turn, grouped by bucket.
+// Buckets always appear in `RunoutBucket::ALL` order and cards in deck order, empty ones included.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Runouts {
+    board: Board,
+    buckets: IndexMap<RunoutBucket, Vec<Card>>,
+}
+
+impl Runouts {
+    // `dead` cards (known holes) are left out of the runouts.
+    pub fn new(board: Board, dead: &[Card]) -> Option<Self> {
+        if board.is_preflop() || board.is_river() {
+            return None;
+        }
+
+        let mut buckets: IndexMap<_, _> = RunoutBucket::ALL
+            .into_iter()
+            .map(|bucket| (bucket, Vec::new()))
+            .collect();
+
+        for value in Value::ALL {
+            for suit in Suit::ALL {
+                let card = Card::new(value, suit);
+                if dead.contains(&card) {
+                    continue;
+                }
+                if let Some(bucket) = RunoutBucket::classify(&board, card) {
+                    buckets[&bucket].push(card);
+                }
+            }
+        }
+
+        Some(Self { board, buckets })
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn cards(&self, bucket: RunoutBucket) -> &[Card] {
+        &self.buckets[&bucket]
+    }
+
+    pub fn bucket_of(&self, card: Card) -> Option<RunoutBucket> {
+        self.iter()
+            .find(|(_, cards)| cards.contains(&card))
+            .map(|(bucket, _)| bucket)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (RunoutBucket, &[Card])> {
+        self.buckets
+            .iter()
+            .map(|(&bucket, cards)| (bucket, cards.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Mean of `f` over each bucket's cards (e.g. equity on the next street) and the bucket's
+    // share of all runouts. Empty buckets are skipped.
+    pub fn summarize(&self, mut f: impl FnMut(Board) -> f64) -> Vec<(RunoutBucket, f64, f64)> {
+        let total = self.len() as f64;
+
+        self.iter()
+            .filter(|(_, cards)| !cards.is_empty())
+            .map(|(bucket, cards)| {
+                let sum: f64 = cards.iter().map(|&card| f(self.next_board(card))).sum();
+                let len = cards.len() as f64;
+                (bucket, sum / len, len / total)
+            })
+            .collect()
+    }
+
+    fn next_board(&self, card: Card) -> Board {
+        self.board
+            .turn(card)
+            .or_else(|| self.board.river(card))
+            .expect("Runout cards should be off the board")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_tone_flop_runouts() {
+        let board: Board = "Ks7s2d".parse().unwrap();
+        let hole = ["Ah".parse().unwrap(), "Qh".parse().unwrap()];
+        let runouts = Runouts::new(board, &hole).unwrap();
+
+        assert_eq!(runouts.len(), 47);
+        assert_eq!(runouts.cards(RunoutBucket::FlushCompleting).len(), 11);
+        assert_eq!(runouts.cards(RunoutBucket::BoardPairing).len(), 8); // 3 K, 3 7, 2 2 off spades
+        assert_eq!(runouts.cards(RunoutBucket::Overcard).len(), 2); // Ad, Ac
+        assert_eq!(
+            runouts.bucket_of("Ks".parse().unwrap()),
+            None,
+            "on the board already"
+        );
+        assert_eq!(
+            runouts.bucket_of("2s".parse().unwrap()),
+            Some(RunoutBucket::FlushCompleting)
+        );
+    }
+}
@@ -0,0 +1,262 @@
+// Serde support (feature `serde`). Cards, boards, hole classes and actions travel in the same
+// compact strings they parse from, e.g. "As", "AsKc", "x" for no board or "c"; everything else
+// derives its impls where it is defined. Errors travel as a `WireError`.
+use super::{
+    Board, Card, CardsCombined, DisplayMode, HoleClass, Suit, Value,
+    headsup::{Action, ActionParseError, ActionSendError, ShowCardsParseError},
+    parse::{BoardParseError, CardParseError, CardsParseError, HoleClassParseError},
+};
+use crate::range::RangeParseError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display, Formatter},
+    sync::Mutex,
+};
+
+fn compact<'a>(cards: impl IntoIterator<Item = &'a Card>) -> String {
+    cards.into_iter().map(|card| card.to_string()).collect()
+}
+
+macro_rules! string_form {
+    ($($ty:ty => |$this:ident| $show:expr;)*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let $this = self;
+                serializer.collect_str(&$show)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+    )*};
+}
+
+string_form! {
+    Value => |value| value;
+    Suit => |suit| suit.display(DisplayMode::Ascii);
+    Card => |card| card.display(DisplayMode::Ascii);
+    HoleClass => |class| class;
+    Board => |board| if board.is_preflop() {
+        "x".to_string()
+    } else {
+        compact(&board.to_vec())
+    };
+    Action => |action| action;
+}
+
+impl<const N: usize> Serialize for CardsCombined<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&compact(self.iter()))
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for CardsCombined<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+// House rule names are `&'static str`s picked by the rules' authors, so a name read back is
+// leaked once and shared by every later event naming it. Names come from peers too: past a few
+// short ones, the rest read back as "unknown" rather than growing the process without limit.
+const MAX_RULE_NAMES: usize = 64;
+const MAX_RULE_NAME_LEN: usize = 64;
+
+pub(crate) fn static_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<&'static str, D::Error> {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let name = String::deserialize(deserializer)?;
+    let mut names = NAMES.lock().unwrap();
+    if let Some(name) = names.get(name.as_str()) {
+        return Ok(name);
+    }
+    if names.len() >= MAX_RULE_NAMES || name.len() > MAX_RULE_NAME_LEN {
+        return Ok("unknown");
+    }
+    let name = name.leak();
+    names.insert(name);
+    Ok(name)
+}
+
+// What went wrong, for clients to branch on instead of the wording of the message. The codes are
+// stable: new ones may be added, existing ones keep their spelling.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    IllegalAction, // not allowed by the bet bound
+    NotYourTurn,   // or no seat to act for
+    TimedOut,      // the game went on without the answer
+    GameOver,      // nothing more to act on
+    MalformedAction,
+    MalformedCards, // cards, boards and hole classes
+    MalformedRange,
+    MalformedMessage, // not a message the protocol knows
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Self::IllegalAction => "illegal_action",
+            Self::NotYourTurn => "not_your_turn",
+            Self::TimedOut => "timed_out",
+            Self::GameOver => "game_over",
+            Self::MalformedAction => "malformed_action",
+            Self::MalformedCards => "malformed_cards",
+            Self::MalformedRange => "malformed_range",
+            Self::MalformedMessage => "malformed_message",
+        };
+        write!(f, "{code}")
+    }
+}
+
+// An error as it goes out, e.g. {"code": "malformed_cards", "message": "'X' is not a suit, ..."}.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct WireError {
+    pub code: ErrorCode,
+    pub message: String, // for people, may be reworded
+}
+
+impl WireError {
+    pub fn new(code: ErrorCode, message: impl Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<ActionSendError> for WireError {
+    fn from(err: ActionSendError) -> Self {
+        match err {
+            ActionSendError::NotHeroTurn => {
+                Self::new(ErrorCode::NotYourTurn, "it is not your turn")
+            }
+            ActionSendError::InvalidAction => {
+                Self::new(ErrorCode::IllegalAction, "that action is not allowed now")
+            }
+            ActionSendError::TimedOut => Self::new(ErrorCode::TimedOut, "you ran out of time"),
+            ActionSendError::GameAbort(game_over) => Self::new(
+                ErrorCode::GameOver,
+                format!("the game is over: {game_over:?}"),
+            ),
+        }
+    }
+}
+
+macro_rules! wire_error {
+    ($($err:ty => $code:ident;)*) => {$(
+        impl From<$err> for WireError {
+            fn from(err: $err) -> Self {
+                Self::new(ErrorCode::$code, err)
+            }
+        }
+    )*};
+}
+
+wire_error! {
+    ActionParseError => MalformedAction;
+    ShowCardsParseError => MalformedAction;
+    CardParseError => MalformedCards;
+    CardsParseError => MalformedCards;
+    BoardParseError => MalformedCards;
+    HoleClassParseError => MalformedCards;
+    RangeParseError => MalformedRange;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        FullBoard, HandValue, Hole,
+        chips::Chips,
+        headsup::{GameOver, GameType, ObservableEvent, StopReason, TurnId},
+    };
+    use serde_json::{from_str, json, to_value};
+
+    #[test]
+    fn json_round_trips() {
+        let hole: Hole = "AsKc".parse().unwrap();
+        let board: Board = "Qs8d5c".parse().unwrap();
+        let runout: FullBoard = "Qs8d5c3h2s".parse().unwrap();
+        assert_eq!(to_value(hole).unwrap(), json!("AsKc"));
+        assert_eq!(to_value(Board::default()).unwrap(), json!("x"));
+        assert_eq!(to_value(Action::all_in()).unwrap(), json!("a"));
+        assert_eq!(from_str::<Board>("\"Qs8d5c\"").unwrap(), board);
+        assert!(from_str::<Hole>("\"AsAs\"").is_err());
+
+        let value = runout.hand_value(hole);
+        let events = [
+            ObservableEvent::DealHoles([Some(hole), None]),
+            ObservableEvent::DealBoard(board),
+            ObservableEvent::PlayerAction(
+                Action::bet_or_raise(Chips::new(400)).unwrap(),
+                TurnId::new(3),
+            ),
+            ObservableEvent::HouseRule {
+                name: "button tax",
+                seat: true,
+                chips: Chips::new(100),
+            },
+            ObservableEvent::GameOver(GameOver::SessionStop(
+                StopReason::MaxHands,
+                [Chips::new(9000), Chips::new(11000)],
+                [Chips::new(50), Chips::ZERO],
+            )),
+        ];
+        let json = serde_json::to_string(&events).unwrap();
+        assert!(json.contains(r#"{"DealBoard":"Qs8d5c"}"#));
+        assert_eq!(from_str::<[ObservableEvent; 5]>(&json).unwrap(), events);
+        assert_eq!(to_value(HoleClass::from(hole)).unwrap(), json!("AKo"));
+
+        let game_type = GameType::cash_default();
+        let json = serde_json::to_string(&game_type).unwrap();
+        assert_eq!(from_str::<GameType>(&json).unwrap(), game_type);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(from_str::<HandValue>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn rule_names_read_back_bounded() {
+        let rule = |name: &str| {
+            let json = json!({"HouseRule": {"name": name, "seat": true, "chips": 100}});
+            match serde_json::from_value(json).unwrap() {
+                ObservableEvent::HouseRule { name, .. } => name,
+                event => panic!("{event:?}"),
+            }
+        };
+        assert_eq!(rule("button tax"), "button tax");
+        assert_eq!(rule(&"x".repeat(MAX_RULE_NAME_LEN + 1)), "unknown");
+        for i in 0..MAX_RULE_NAMES {
+            rule(&format!("rule {i}"));
+        }
+        assert_eq!(rule("one too many"), "unknown");
+        assert_eq!(rule("button tax"), "button tax"); // still known
+    }
+
+    #[test]
+    fn error_codes() {
+        let err = WireError::from(ActionSendError::NotHeroTurn);
+        assert_eq!(
+            to_value(&err).unwrap(),
+            json!({"code": "not_your_turn", "message": "it is not your turn"})
+        );
+        let err = WireError::from("AsKx".parse::<Hole>().unwrap_err());
+        assert_eq!(err.code, ErrorCode::MalformedCards);
+        assert_eq!(to_value(err.code).unwrap(), json!(err.code.to_string()));
+        let err = WireError::from("r100".parse::<Action>().unwrap_err());
+        assert_eq!(
+            from_str::<WireError>(&serde_json::to_string(&err).unwrap()).unwrap(),
+            err
+        );
+    }
+}
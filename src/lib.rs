@@ -1,6 +1,25 @@
 #![cfg_attr(nightly, feature(doc_auto_cfg))]
 
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod bot;
+pub mod chart;
+pub mod combinatorics;
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod equity;
 pub mod gameplay;
+pub mod parallel;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod range;
+pub mod server;
+pub mod solver;
+pub mod stats;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
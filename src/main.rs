@@ -1,46 +1,251 @@
-use pokerbot::gameplay::{
-    Board, DisplayMode,
-    headsup::{Dealer, Deck},
+// Heads-up against the bot in the terminal:
+//
+//     pokerbot [--game cash|turbo|medium|slow] [--seed N] [--seat 0|1] [--mode ascii|unicode|colored|emoji]
+//
+// Actions are read from stdin as `Action` parses them, sizes relative to the pot and blinds too:
+// "c", "f", "a", "1500", "2.5bb", "pot", "half", or "x" to leave the table.
+use pokerbot::{
+    bot::{self, ExploitBot},
+    gameplay::{
+        DisplayMode,
+        chips::Chips,
+        headsup::{
+            Action, ActionSendError, ActionValue, BetContext, Game, GameType, GameView,
+            ObservableEvent, PlayerEvent, ShowCards,
+        },
+    },
 };
+use std::{env, io, process};
 
-fn display(mut dealer: Dealer, mode: DisplayMode) {
-    println!("{}", dealer.deal_hole().display(mode));
-    println!("{}", dealer.deal_hole().display(mode));
-
-    let mut board = Board::flop(dealer.deal_flop());
-    println!("{}", board.display(mode));
-
-    board = board.turn(dealer.deal_card()).unwrap();
-    println!("{}", board.display(mode));
-
-    board = board.river(dealer.deal_card()).unwrap();
-    println!("{}", board.display(mode));
+struct Args {
+    game_type: GameType,
+    seed: Option<u64>,
+    seat: bool,
+    mode: DisplayMode,
 }
 
-fn main() {
+fn parse_args() -> Result<Args, String> {
     // todo: DisplayConfig
     // default:
     //   - windows: ColoredUnicode (https://github.com/microsoft/terminal/issues/19100)
     //   - other: ColoredEmoji && !no_white (white canvas)
+    let mut args = Args {
+        game_type: GameType::cash_default(),
+        seed: None,
+        seat: true,
+        mode: if cfg!(windows) {
+            DisplayMode::ColoredUnicode
+        } else {
+            DisplayMode::ColoredEmoji
+        },
+    };
 
-    // init:
-    // todo: windows enable ANSI when `ColoredUnicode || (ColoredEmoji && !no_white)`
-    // Only when `ColoredEmoji && !no_white`:
-    // print!("\x1b[107m\x1b[0J\x1b[30m");
+    let mut words = env::args().skip(1);
+    while let Some(flag) = words.next() {
+        let value = words.next().ok_or(format!("{flag} needs a value"))?;
+        match flag.as_str() {
+            "--game" => {
+                args.game_type = GameType::named(&value).ok_or(format!(
+                    "no game {value:?}, try cash, turbo, medium or slow"
+                ))?;
+            }
+            "--seed" => args.seed = Some(value.parse().map_err(|_| "the seed is a number")?),
+            "--seat" => {
+                args.seat = match value.as_str() {
+                    "0" => true,
+                    "1" => false,
+                    _ => return Err("the seat is 0 or 1".to_string()),
+                };
+            }
+            "--mode" => {
+                args.mode = match value.as_str() {
+                    "ascii" => DisplayMode::Ascii,
+                    "unicode" => DisplayMode::Unicode,
+                    "colored" => DisplayMode::ColoredUnicode,
+                    "emoji" => DisplayMode::ColoredEmoji,
+                    _ => return Err("the mode is ascii, unicode, colored or emoji".to_string()),
+                };
+            }
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+    Ok(args)
+}
+
+// A line of stdin without blocking the game, None at its end.
+async fn read_line() -> Option<String> {
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+fn name(view: &GameView, seat: bool) -> &'static str {
+    if seat == view.seat() { "You" } else { "Bot" }
+}
 
-    let mut deck = Deck::default();
-    display(deck.shuffle_and_deal(), DisplayMode::ColoredEmoji);
+fn table(view: &GameView, mode: DisplayMode) {
+    let board = view.board();
+    if !board.is_preflop() {
+        println!("{:?}: {}", view.street(), board.display(mode));
+    }
+    let [hero, villain] = view.stacks();
+    println!("  pot {} · you {hero} · bot {villain}", view.pot());
+}
+
+// What an action did, sized against the bets before it from the actor's side.
+fn said(action: Action, before: &GameView, actor: bool) -> String {
+    let [hero, villain] = before.bets();
+    let bets = if actor == before.seat() {
+        [hero, villain]
+    } else {
+        [villain, hero]
+    };
+    let context = BetContext::new(before.pot(), before.big_blind(), bets);
+    match action.value() {
+        ActionValue::Exit => "leave the table".to_string(),
+        _ => action.display(context).to_string(),
+    }
+}
+
+fn options(view: &GameView) -> String {
+    let Some(bound) = view.bet_bound() else {
+        return String::new();
+    };
+    // only the smallest size, any other up to the largest goes too
+    let context = view.bet_context();
+    let actions = bound.legal_actions(Chips::ZERO);
+    let actions: Vec<String> = actions
+        .map(|action| action.display(context).to_string())
+        .collect();
+    actions.join(" / ")
+}
+
+#[tokio::main]
+async fn main() {
+    let args = parse_args().unwrap_or_else(|error| {
+        eprintln!("{error}");
+        process::exit(2);
+    });
+    let mode = args.mode;
+
+    // todo: windows enable ANSI when `ColoredUnicode || (ColoredEmoji && !no_white)`
+    // a white canvas for the emoji cards
+    if mode == DisplayMode::ColoredEmoji {
+        print!("\x1b[107m\x1b[0J\x1b[30m");
+    }
 
-    println!();
-    display(deck.shuffle_and_deal(), DisplayMode::ColoredUnicode);
+    let (game, [first, second]) = match args.seed {
+        Some(seed) => Game::seeded(args.game_type, seed),
+        None => Game::new(args.game_type),
+    };
+    let (mut human, mut bot) = if args.seat {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let game = tokio::spawn(game.run());
+    tokio::spawn(async move {
+        bot::play(&mut bot, &mut ExploitBot::default()).await;
+    });
 
-    println!();
-    display(deck.shuffle_and_deal(), DisplayMode::Unicode);
+    let mut before = None;
+    while let Some(event) = human.tick_event().await {
+        let Some(view) = human.view() else {
+            continue;
+        };
+        match event {
+            PlayerEvent::Observable(ObservableEvent::DealHoles(_)) => {
+                println!();
+                if let Some(hole) = view.hole() {
+                    let button = if view.is_button() {
+                        ", on the button"
+                    } else {
+                        ""
+                    };
+                    println!("Your cards: {}{button}", hole.display(mode));
+                }
+                table(&view, mode);
+            }
+            PlayerEvent::Observable(ObservableEvent::DealBoard(_)) => table(&view, mode),
+            PlayerEvent::Observable(ObservableEvent::PlayerAction(action, _)) => {
+                if let (Some(before), Some(record)) = (&before, view.history().last()) {
+                    let actor = record.actor();
+                    println!("{}: {}", name(&view, actor), said(action, before, actor));
+                }
+            }
+            PlayerEvent::Observable(
+                ObservableEvent::ShowdownAll(holes) | ObservableEvent::ShowdownAuto(holes),
+            ) => {
+                let hero = !view.seat() as usize;
+                println!(
+                    "Showdown: you {} · bot {}",
+                    holes[hero].display(mode),
+                    holes[1 - hero].display(mode)
+                );
+            }
+            PlayerEvent::Observable(ObservableEvent::BlindLevel { blinds, .. }) => {
+                println!("Blinds {}/{}", blinds.small_blind(), blinds.big_blind())
+            }
+            PlayerEvent::Observable(ObservableEvent::PotAwarded { amounts, hand }) => {
+                let hero = !view.seat() as usize;
+                let hand = hand.display(mode);
+                match (amounts[hero].is_zero(), amounts[1 - hero].is_zero()) {
+                    (false, true) => println!("You win {} with {hand}.", amounts[hero]),
+                    (true, false) => println!("Bot wins {} with {hand}.", amounts[1 - hero]),
+                    _ => println!("Chopped with {hand}."),
+                }
+            }
+            PlayerEvent::Observable(ObservableEvent::TimedOut(seat)) => {
+                println!("{} ran out of time.", name(&view, seat));
+            }
+            PlayerEvent::HeroTurn(..) => loop {
+                println!("Your turn: {}", options(&view));
+                let Some(line) = read_line().await else {
+                    let _ = human.send_action(Action::exit());
+                    break;
+                };
+                let action = match Action::parse_in(&line, &view.bet_context()) {
+                    Ok(action) => action,
+                    Err(error) => {
+                        println!("{error}");
+                        continue;
+                    }
+                };
+                match human.send_action(action) {
+                    Ok(()) => break,
+                    Err(ActionSendError::InvalidAction) => {
+                        println!("That action is not allowed now.");
+                    }
+                    // the game went on without it
+                    Err(_) => break,
+                }
+            },
+            // showing is the same to a bot, and the all in is played out
+            PlayerEvent::ShowOffer => {
+                let _ = human.send_show(ShowCards::Neither);
+            }
+            PlayerEvent::InsuranceOffer(_) => {
+                let _ = human.send_insurance(false);
+            }
+            _ => {}
+        }
+        before = human.view();
+    }
 
-    println!();
-    display(deck.shuffle_and_deal(), DisplayMode::Ascii);
+    if let Ok(game_over) = game.await {
+        println!();
+        println!("{game_over:?}");
+    }
 
-    // drop:
-    // Only when `ColoredEmoji && !no_white`:
-    // print!("\x1b[0m\x1b[0J");
+    // back from the white canvas
+    if mode == DisplayMode::ColoredEmoji {
+        print!("\x1b[0m\x1b[0J");
+    }
 }
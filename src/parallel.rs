@@ -0,0 +1,191 @@
+// Where the parallel APIs run. By default that's rayon's global pool; a process with other
+// parallel workloads can hand the engine a pool of its own instead, so the two never compete for
+// the same threads. Everything run through `install` stays on the chosen pool, nested parallel
+// iterators included. wasm32 has no threads to spread over, so there rayon is left out and the
+// same calls run one after another on the calling thread.
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::{ThreadPool, ThreadPoolBuilder};
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use rayon::{current_num_threads, join};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
+
+#[derive(Debug, Default, Clone)]
+pub enum Threads {
+    #[default]
+    Global,
+    #[cfg(not(target_arch = "wasm32"))]
+    Pool(Arc<ThreadPool>),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Threads {
+    // A dedicated pool of `threads` threads. None for zero threads or if the pool can't start.
+    pub fn with_threads(threads: usize) -> Option<Self> {
+        if threads == 0 {
+            return None;
+        }
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("pokerbot-{i}"))
+            .build()
+            .ok()?;
+        Some(Self::Pool(Arc::new(pool)))
+    }
+
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match self {
+            Self::Global => op(),
+            Self::Pool(pool) => pool.install(op),
+        }
+    }
+
+    pub fn num_threads(&self) -> usize {
+        match self {
+            Self::Global => rayon::current_num_threads(),
+            Self::Pool(pool) => pool.current_num_threads(),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Threads {
+    pub fn with_threads(_threads: usize) -> Option<Self> {
+        None
+    }
+
+    pub fn install<R>(&self, op: impl FnOnce() -> R) -> R {
+        op()
+    }
+
+    pub fn num_threads(&self) -> usize {
+        1
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<ThreadPool> for Threads {
+    fn from(pool: ThreadPool) -> Self {
+        Self::Pool(Arc::new(pool))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<Arc<ThreadPool>> for Threads {
+    fn from(pool: Arc<ThreadPool>) -> Self {
+        Self::Pool(pool)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn current_num_threads() -> usize {
+    1
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn join<A, B>(a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) {
+    (a(), b())
+}
+
+// The parallel iterator methods the engine calls. Natively they are rayon's; on wasm32 `Seq` wraps
+// a plain iterator and takes the same calls, `fold` and `reduce` with rayon's identity closures.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod prelude {
+    pub(crate) use rayon::prelude::*;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) mod prelude {
+    pub(crate) struct Seq<I>(I);
+
+    impl<I: Iterator> Seq<I> {
+        pub(crate) fn map<R>(self, f: impl FnMut(I::Item) -> R) -> Seq<impl Iterator<Item = R>> {
+            Seq(self.0.map(f))
+        }
+
+        pub(crate) fn filter_map<R>(
+            self,
+            f: impl FnMut(I::Item) -> Option<R>,
+        ) -> Seq<impl Iterator<Item = R>> {
+            Seq(self.0.filter_map(f))
+        }
+
+        pub(crate) fn fold<T>(
+            self,
+            identity: impl Fn() -> T,
+            op: impl FnMut(T, I::Item) -> T,
+        ) -> Seq<impl Iterator<Item = T>> {
+            Seq(std::iter::once(self.0.fold(identity(), op)))
+        }
+
+        pub(crate) fn reduce(
+            self,
+            identity: impl Fn() -> I::Item,
+            op: impl FnMut(I::Item, I::Item) -> I::Item,
+        ) -> I::Item {
+            self.0.fold(identity(), op)
+        }
+
+        pub(crate) fn collect<C: FromIterator<I::Item>>(self) -> C {
+            self.0.collect()
+        }
+    }
+
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Seq<Self::IntoIter> {
+            Seq(self.into_iter())
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    pub(crate) trait ParallelSlice<T> {
+        fn par_iter(&self) -> Seq<std::slice::Iter<'_, T>>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_iter(&self) -> Seq<std::slice::Iter<'_, T>> {
+            Seq(self.iter())
+        }
+    }
+
+    pub(crate) trait ParallelBridge: Iterator + Sized {
+        fn par_bridge(self) -> Seq<Self> {
+            Seq(self)
+        }
+    }
+
+    impl<I: Iterator> ParallelBridge for I {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        equity::{equity_exact, equity_exact_in, equity_in},
+        gameplay::{Board, Hole},
+    };
+
+    #[test]
+    fn runs_on_the_given_pool() {
+        let threads = Threads::with_threads(2).unwrap();
+        assert_eq!(threads.num_threads(), 2);
+        assert_eq!(threads.install(rayon::current_num_threads), 2);
+        assert!(threads.install(|| rayon::current_thread_index().is_some()));
+        assert!(Threads::with_threads(0).is_none());
+
+        let (aces, kings): (Hole, Hole) = ("AsAh".parse().unwrap(), "KsKh".parse().unwrap());
+        let flop: Board = "Kd7c2h".parse().unwrap();
+        let exact = equity_exact_in(kings, aces, flop, &threads);
+        assert_eq!(exact, equity_exact(kings, aces, flop));
+        let sampled = equity_in(kings, aces, flop, 2000, &threads).unwrap();
+        assert!((sampled.share() - exact.unwrap().share()).abs() < 0.03);
+
+        let river = flop.turn("3s".parse().unwrap()).unwrap();
+        let river = river.river("9d".parse().unwrap()).unwrap();
+        let full = river.as_full_board().unwrap();
+        let values = full.hand_values(&[aces, kings], &threads);
+        assert_eq!(values, [full.hand_value(aces), full.hand_value(kings)]);
+        assert_eq!(full.who_wins_in(aces, kings, &threads).1, Some(false));
+    }
+}
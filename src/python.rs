@@ -0,0 +1,238 @@
+// Python bindings, built into the `pokerbot` extension module by maturin with the `python`
+// feature (and `pyo3/extension-module` from maturin's side). Cards, holes, boards and ranges go
+// in and out as the strings they parse from, and a whole heads-up game runs blocking with Python
+// callables for bots: each gets its decision as a dict and answers an action in words, e.g.
+// "call", "raise to 1500" or "pot".
+use crate::{
+    bot::{Bot, Decision, Session},
+    equity::{self as equities},
+    gameplay::{
+        Board, Card, DisplayMode, Hole,
+        headsup::{Action, GameType},
+    },
+    range,
+};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use std::{fmt::Display, str::FromStr};
+
+fn parse<T: FromStr>(s: &str) -> PyResult<T>
+where
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|error: T::Err| PyValueError::new_err(error.to_string()))
+}
+
+// The card back as it displays, or a ValueError when it doesn't parse.
+#[pyfunction]
+fn card(card: &str) -> PyResult<String> {
+    Ok(parse::<Card>(card)?.to_string())
+}
+
+// The best hand of the hole and a full board in words, e.g. "Two Pair, Queens and Eights with
+// Ace kicker".
+#[pyfunction]
+fn hand_value(hole: &str, board: &str) -> PyResult<String> {
+    let board = parse::<Board>(board)?
+        .as_full_board()
+        .ok_or_else(|| PyValueError::new_err("the board should have five cards"))?;
+    Ok(board.hand_value(parse(hole)?).describe())
+}
+
+// Whether the first hole is ahead on the board so far, None for a tie.
+#[pyfunction]
+fn who_wins(first: &str, second: &str, board: &str) -> PyResult<Option<bool>> {
+    let board: Board = parse(board)?;
+    let now = board
+        .who_wins_now(parse(first)?, parse(second)?)
+        .ok_or_else(|| PyValueError::new_err("no hands preflop, or the cards collide"))?;
+    Ok(now.winner)
+}
+
+// Win, tie and lose of `hole` against `villain`: exact after the flop without `iterations`,
+// sampled otherwise.
+#[pyfunction]
+#[pyo3(signature = (hole, villain, board = "", iterations = None))]
+fn equity(
+    hole: &str,
+    villain: &str,
+    board: &str,
+    iterations: Option<usize>,
+) -> PyResult<(f64, f64, f64)> {
+    let (hole, villain): (Hole, Hole) = (parse(hole)?, parse(villain)?);
+    let board: Board = if board.is_empty() {
+        Board::default()
+    } else {
+        parse(board)?
+    };
+    let equity = match iterations {
+        None if !board.is_preflop() => equities::equity_exact(hole, villain, board),
+        iterations => equities::equity(hole, villain, board, iterations.unwrap_or(10000)),
+    }
+    .ok_or_else(|| PyValueError::new_err("the cards collide"))?;
+    Ok((equity.win, equity.tie, equity.lose))
+}
+
+// A weighted range from its usual notation, e.g. "77+, A2s+, KQo, AsKs:0.5"; `str` gives the
+// 13 x 13 grid.
+#[pyclass(name = "Range")]
+struct PyRange(range::Range);
+
+#[pymethods]
+impl PyRange {
+    #[new]
+    fn new(range: &str) -> PyResult<Self> {
+        Ok(Self(parse(range)?))
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn weight(&self, hole: &str) -> PyResult<f64> {
+        Ok(self.0.weight(parse(hole)?))
+    }
+
+    fn combos(&self) -> Vec<(String, f64)> {
+        self.0
+            .iter()
+            .map(|(hole, weight)| (hole.display(DisplayMode::Ascii).to_string(), weight))
+            .collect()
+    }
+}
+
+// A Python callable playing a seat. The first error it raises, or action it answers that doesn't
+// parse, exits the game and comes back from `play`.
+struct PyBot {
+    act: Py<PyAny>,
+    error: Option<PyErr>,
+}
+
+impl PyBot {
+    fn decide(&self, py: Python<'_>, decision: &Decision) -> PyResult<Action> {
+        let context = decision.bet_context();
+        let dict = PyDict::new(py);
+        dict.set_item("seat", decision.seat())?;
+        dict.set_item("button", decision.is_button())?;
+        let hole = decision.hole();
+        let hole = hole.map(|hole| hole.display(DisplayMode::Ascii).to_string());
+        dict.set_item("hole", hole)?;
+        let board = decision.board();
+        dict.set_item("board", board.display(DisplayMode::Ascii).to_string())?;
+        dict.set_item("street", format!("{:?}", decision.street()).to_lowercase())?;
+        dict.set_item("big_blind", decision.big_blind().get())?;
+        dict.set_item("pot", decision.pot().get())?;
+        dict.set_item("bets", decision.bets().map(|chips| chips.get()))?;
+        dict.set_item("stacks", decision.stacks().map(|chips| chips.get()))?;
+        dict.set_item("to_call", decision.to_call().get())?;
+        let legal = decision.bet_bound().legal_actions(decision.bet_unit());
+        let legal: Vec<String> = legal
+            .map(|action| action.display(context).to_string())
+            .collect();
+        dict.set_item("legal", legal)?;
+
+        let answer: String = self.act.call1(py, (dict,))?.extract(py)?;
+        Action::parse_in(&answer, &context)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+}
+
+impl Bot for PyBot {
+    fn act(&mut self, decision: &Decision) -> Action {
+        if self.error.is_some() {
+            return Action::exit();
+        }
+        Python::attach(|py| self.decide(py, decision)).unwrap_or_else(|error| {
+            self.error = Some(error);
+            Action::exit()
+        })
+    }
+}
+
+// Plays one game between `first` (player 0) and `second`, blocking until it's over: "cash" for a
+// cash game, else a sit and go of speed "turbo", "medium" or "slow". Returns how it ended, the
+// hands played and what each player cashed out.
+#[pyfunction]
+#[pyo3(signature = (first, second, game = "cash", seed = None))]
+fn play<'py>(
+    py: Python<'py>,
+    first: Py<PyAny>,
+    second: Py<PyAny>,
+    game: &str,
+    seed: Option<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let game_type =
+        GameType::named(game).ok_or_else(|| PyValueError::new_err(format!("no game {game:?}")))?;
+    let mut session = Session::new(game_type, 1);
+    if let Some(seed) = seed {
+        session = session.with_seed(seed);
+    }
+    let mut bots = [first, second].map(|act| PyBot { act, error: None });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()?;
+    let [first, second] = &mut bots;
+    let report = runtime.block_on(session.run(first, second));
+    if let Some(error) = bots.iter_mut().find_map(|bot| bot.error.take()) {
+        return Err(error);
+    }
+
+    let result = report.games[0];
+    let dict = PyDict::new(py);
+    dict.set_item("game_over", format!("{:?}", result.game_over))?;
+    dict.set_item("hands", result.hands)?;
+    dict.set_item("cashout", result.cashout.map(|chips| chips.get()))?;
+    Ok(dict)
+}
+
+#[pymodule]
+fn pokerbot(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(card, module)?)?;
+    module.add_function(wrap_pyfunction!(hand_value, module)?)?;
+    module.add_function(wrap_pyfunction!(who_wins, module)?)?;
+    module.add_function(wrap_pyfunction!(equity, module)?)?;
+    module.add_function(wrap_pyfunction!(play, module)?)?;
+    module.add_class::<PyRange>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn python_api() {
+        Python::initialize();
+        Python::attach(|py| {
+            assert_eq!(card("Ah").unwrap(), "Ah");
+            assert!(card("ah").is_err());
+            assert_eq!(who_wins("AsAh", "KsKh", "Kd7c2h").unwrap(), Some(false));
+            let (win, _, _) = equity("AsAh", "KsKh", "Kd7c2h", None).unwrap();
+            assert!(win < 0.1);
+            assert!(equity("AsAh", "AsKh", "", None).is_err());
+
+            let eval = |code: &str| {
+                let code = CString::new(code).unwrap();
+                py.eval(&code, None, None).unwrap().unbind()
+            };
+            // a player folding every bet against a caller, to the end of a sit and go
+            let folder = eval("lambda d: 'fold' if d['to_call'] else 'check'");
+            let result = play(py, folder, eval("lambda d: 'call'"), "turbo", Some(7)).unwrap();
+            let cashout: [u32; 2] = result
+                .get_item("cashout")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(cashout[0] + cashout[1], 6000);
+
+            let broken = eval("lambda d: 'shove it'");
+            assert!(play(py, broken, eval("lambda d: 'call'"), "turbo", None).is_err());
+        });
+    }
+}
@@ -0,0 +1,891 @@
+use crate::{
+    bot::hand_strength,
+    gameplay::{
+        Board, Card, DisplayMode, Hole, HoleClass, Street, Value,
+        headsup::{ActionValue, ObservableEvent},
+        parse::HoleClassParseError,
+    },
+    stats::{ActionRecord, LineWalker},
+};
+use indexmap::IndexMap;
+use rand::Rng;
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io::{self, Write},
+    str::FromStr,
+};
+
+// Weighted set of concrete holes, weights in `0.0..=1.0` unless normalized.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Range(IndexMap<Hole, f64>);
+
+impl Range {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn full() -> Self {
+        let mut range = Self::new();
+        for class in HoleClass::all() {
+            range.add_class(class, 1.0);
+        }
+        range
+    }
+
+    pub fn from_classes(classes: impl IntoIterator<Item = HoleClass>) -> Self {
+        let mut range = Self::new();
+        for class in classes {
+            range.add_class(class, 1.0);
+        }
+        range
+    }
+
+    pub fn add_class(&mut self, class: HoleClass, weight: f64) {
+        for hole in class.combos() {
+            self.set(hole, weight);
+        }
+    }
+
+    pub fn set(&mut self, hole: Hole, weight: f64) {
+        if weight > 0.0 {
+            self.0.insert(hole, weight);
+        } else {
+            self.0.shift_remove(&hole);
+        }
+    }
+
+    pub fn weight(&self, hole: Hole) -> f64 {
+        self.0.get(&hole).copied().unwrap_or(0.0)
+    }
+
+    pub fn contains(&self, hole: Hole) -> bool {
+        self.0.contains_key(&hole)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Hole, f64)> + '_ {
+        self.0.iter().map(|(hole, weight)| (*hole, *weight))
+    }
+
+    // Number of combos with a positive weight.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.0.values().sum()
+    }
+
+    // A combo drawn in proportion to its weight.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<Hole> {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut pick = rng.random_range(0.0..total);
+        for (hole, weight) in self.iter() {
+            if pick < weight {
+                return Some(hole);
+            }
+            pick -= weight;
+        }
+        self.0.keys().last().copied()
+    }
+
+    pub fn normalized(&self) -> Self {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return Self::new();
+        }
+        Self(self.0.iter().map(|(h, w)| (*h, w / total)).collect())
+    }
+
+    // Removes every combo holding one of the dead cards (board, hero hole, ...).
+    pub fn without(&self, dead: &[Card]) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|(hole, _)| !dead.iter().any(|card| hole.contains_card(*card)))
+                .map(|(h, w)| (*h, *w))
+                .collect(),
+        )
+    }
+
+    pub fn map_weights(&self, mut f: impl FnMut(Hole, f64) -> f64) -> Self {
+        let mut range = Self::new();
+        for (hole, weight) in self.iter() {
+            range.set(hole, f(hole, weight));
+        }
+        range
+    }
+
+    // Runs every action of the line through the policy. Seats in a bare line are positional:
+    // `true` is the button, so a policy speaking for one position returns 1.0 for the other.
+    pub fn narrowed_by(&self, line: &[ObservableEvent], policy: &impl RangePolicy) -> Self {
+        let mut walker = LineWalker::new(true);
+        let mut range = self.clone();
+
+        for event in line {
+            let board = walker.board();
+            if let Some(record) = walker.step(event) {
+                range = range.narrowed_step(policy, &board, &record);
+            }
+        }
+
+        range
+    }
+
+    fn narrowed_step(
+        &self,
+        policy: &impl RangePolicy,
+        board: &Board,
+        record: &ActionRecord,
+    ) -> Self {
+        self.map_weights(|hole, weight| weight * policy.likelihood(hole, board, record))
+    }
+
+    // Combo-weighted share of each class present in the range.
+    pub fn class_weights(&self) -> IndexMap<HoleClass, f64> {
+        let mut classes: IndexMap<HoleClass, f64> = IndexMap::new();
+        for (hole, weight) in self.iter() {
+            *classes.entry(hole.into()).or_default() += weight;
+        }
+        classes
+    }
+
+    // Weighted combos of each class in the range, and how many of them are still live with the
+    // board and hero's hole dead.
+    pub fn combos_with_blockers(&self, board: Board, hero: Hole) -> CombosBreakdown {
+        let dead: Vec<Card> = board
+            .to_vec()
+            .into_iter()
+            .chain(hero.iter().copied())
+            .collect();
+        let live = self.without(&dead).class_weights();
+        let classes = self.class_weights().into_iter();
+        CombosBreakdown(
+            classes
+                .map(|(class, combos)| {
+                    let live = live.get(&class).copied().unwrap_or_default();
+                    (class, ClassCombos { combos, live })
+                })
+                .collect(),
+        )
+    }
+
+    // The range as a chart, each class at the average weight of its combos.
+    pub fn grid(&self) -> RangeGrid {
+        let classes = self.class_weights().into_iter();
+        RangeGrid::from_classes(
+            classes.map(|(class, weight)| (class, weight / class.combos().len() as f64)),
+        )
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct ClassCombos {
+    pub combos: f64, // weighted, dead cards or not
+    pub live: f64,
+}
+
+// Combos by class in the order the range has them, see `Range::combos_with_blockers`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CombosBreakdown(IndexMap<HoleClass, ClassCombos>);
+
+impl CombosBreakdown {
+    pub fn get(&self, class: HoleClass) -> Option<ClassCombos> {
+        self.0.get(&class).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (HoleClass, ClassCombos)> + '_ {
+        self.0.iter().map(|(class, combos)| (*class, *combos))
+    }
+
+    pub fn combos(&self) -> f64 {
+        self.0.values().map(|class| class.combos).sum()
+    }
+
+    pub fn live(&self) -> f64 {
+        self.0.values().map(|class| class.live).sum()
+    }
+}
+
+// How much of a villain range hero's hole takes away, on top of what the board already does.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BlockerEffects {
+    blocked: IndexMap<HoleClass, f64>, // weighted combos by class, classes hero blocks only
+    share: f64,
+    stronger: Option<f64>,
+}
+
+impl BlockerEffects {
+    pub fn blocked(&self) -> f64 {
+        self.blocked.values().sum()
+    }
+
+    pub fn blocked_class(&self, class: HoleClass) -> f64 {
+        self.blocked.get(&class).copied().unwrap_or_default()
+    }
+
+    // Of the range's weight the board leaves, 0 for an empty range.
+    pub fn share(&self) -> f64 {
+        self.share
+    }
+
+    // Of the blocked weight, how much beats hero on the river: blocking the hands that would call
+    // makes a better bluff. None before the river.
+    pub fn stronger(&self) -> Option<f64> {
+        self.stronger
+    }
+}
+
+pub fn blocker_effects(hero: Hole, villain: &Range, board: Board) -> BlockerEffects {
+    let before = villain.without(&board.to_vec());
+    let blocked: Range = Range(
+        before
+            .iter()
+            .filter(|(hole, _)| hero.iter().any(|&card| hole.contains_card(card)))
+            .collect(),
+    );
+
+    let total = before.total_weight();
+    let share = if total > 0.0 {
+        blocked.total_weight() / total
+    } else {
+        0.0
+    };
+    let stronger = board.as_full_board().map(|board| {
+        let value = board.hand_value(hero);
+        let stronger = blocked
+            .iter()
+            .filter(|(hole, _)| board.hand_value(*hole) > value);
+        stronger.map(|(_, weight)| weight).sum()
+    });
+
+    BlockerEffects {
+        blocked: blocked.class_weights(),
+        share,
+        stronger,
+    }
+}
+
+// Numbers by hole class laid out as the 13 x 13 chart, aces first with suited hands above the
+// pairs and offsuit ones below, e.g. range weights or winnings by starting hand. Classes without
+// a number show as a dot.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangeGrid {
+    cells: [[Option<f64>; 13]; 13],
+    precision: usize,
+}
+
+impl RangeGrid {
+    pub fn from_classes(classes: impl IntoIterator<Item = (HoleClass, f64)>) -> Self {
+        let mut cells = [[None; 13]; 13];
+        for (class, number) in classes {
+            let (row, col) = class.grid_cell();
+            cells[row][col] = Some(number);
+        }
+        Self {
+            cells,
+            precision: 2,
+        }
+    }
+
+    // Digits after the decimal point, 2 by default.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn get(&self, class: HoleClass) -> Option<f64> {
+        let (row, col) = class.grid_cell();
+        self.cells[row][col]
+    }
+
+    // Only `ColoredUnicode` colors the cells, see `RangeGridDisplay`; the rest are plain text.
+    pub fn display(&self, mode: DisplayMode) -> RangeGridDisplay<'_> {
+        RangeGridDisplay { grid: self, mode }
+    }
+
+    // A standalone `<table>` for reports, each cell named by its class and shaded like
+    // `ColoredUnicode` does: green for positive numbers, red for negative ones.
+    pub fn write_html(&self, mut writer: impl Write) -> io::Result<()> {
+        let max = self.max_magnitude();
+        let precision = self.precision;
+        writeln!(writer, "<table class=\"range-grid\">")?;
+        for (row, cells) in self.cells.iter().enumerate() {
+            write!(writer, "<tr>")?;
+            for (col, cell) in cells.iter().enumerate() {
+                let class = class_at(row, col);
+                match cell {
+                    Some(number) => {
+                        let (red, green) = if *number < 0.0 { (200, 0) } else { (0, 160) };
+                        let alpha = if max > 0.0 { number.abs() / max } else { 0.0 };
+                        write!(
+                            writer,
+                            "<td style=\"background: rgba({red}, {green}, 0, {alpha:.2})\">\
+                             {class}<br>{number:.precision$}</td>"
+                        )?;
+                    }
+                    None => write!(writer, "<td>{class}<br>.</td>")?,
+                }
+            }
+            writeln!(writer, "</tr>")?;
+        }
+        writeln!(writer, "</table>")
+    }
+
+    fn max_magnitude(&self) -> f64 {
+        let numbers = self.cells.iter().flatten().flatten();
+        numbers.fold(0.0, |max, number| max.max(number.abs()))
+    }
+}
+
+// The class in `row` and `col` of the chart, the other way around from `HoleClass::grid_cell`.
+fn class_at(row: usize, col: usize) -> HoleClass {
+    let value = |i: usize| Value::ALL[12 - i];
+    match row.cmp(&col) {
+        Ordering::Equal => HoleClass::Pair(value(row)),
+        Ordering::Less => HoleClass::Suited(value(row), value(col)),
+        Ordering::Greater => HoleClass::Offsuit(value(col), value(row)),
+    }
+}
+
+impl Display for RangeGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display(DisplayMode::Ascii))
+    }
+}
+
+// In `ColoredUnicode`, cells at half the largest number or more are bright, the rest dim, green
+// when positive and red when negative; empty cells are faint.
+#[derive(Debug, Clone, Copy)]
+pub struct RangeGridDisplay<'a> {
+    grid: &'a RangeGrid,
+    mode: DisplayMode,
+}
+
+impl Display for RangeGridDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let precision = self.grid.precision;
+        let texts = self.grid.cells.map(|row| {
+            row.map(|cell| cell.map_or(".".to_string(), |number| format!("{number:.precision$}")))
+        });
+        let width = texts.iter().flatten().map(String::len).max().unwrap_or(1);
+        let values = Value::ALL.into_iter().rev();
+        let max = self.grid.max_magnitude();
+
+        write!(f, " ")?;
+        for value in values.clone() {
+            write!(f, " {:>width$}", value.to_string())?;
+        }
+        for ((value, row), cells) in values.zip(&texts).zip(&self.grid.cells) {
+            write!(f, "\n{value}")?;
+            for (text, cell) in row.iter().zip(cells) {
+                let color = match cell {
+                    _ if self.mode != DisplayMode::ColoredUnicode => "",
+                    None => "\x1b[2m",
+                    Some(number) if *number == 0.0 => "",
+                    Some(number) => match (*number > 0.0, number.abs() * 2.0 >= max) {
+                        (true, true) => "\x1b[92m",
+                        (true, false) => "\x1b[32m",
+                        (false, true) => "\x1b[91m",
+                        (false, false) => "\x1b[31m",
+                    },
+                };
+                let reset = if color.is_empty() { "" } else { "\x1b[0m" };
+                write!(f, " {color}{text:>width$}{reset}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The range's `grid`.
+impl Display for Range {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.grid())
+    }
+}
+
+// Comma separated classes or concrete holes with optional `+` and `:weight`,
+// e.g. "77+, A2s+, KQo, AsKs:0.5".
+impl FromStr for Range {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Self::new();
+
+        for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let (spec, weight) = match token.split_once(':') {
+                Some((spec, weight)) => {
+                    let weight = weight.trim();
+                    let parsed = weight.parse();
+                    (
+                        spec.trim(),
+                        parsed.map_err(|_| RangeParseError::Weight(weight.to_string()))?,
+                    )
+                }
+                None => (token, 1.0),
+            };
+
+            if let Ok(hole) = Hole::from_str(spec) {
+                range.set(hole, weight);
+                continue;
+            }
+
+            let (spec, plus) = match spec.strip_suffix('+') {
+                Some(spec) => (spec, true),
+                None => (spec, false),
+            };
+            let hand = |err| RangeParseError::Hand(token.to_string(), err);
+            let classes = match HoleClass::from_str(spec) {
+                Ok(class) => vec![class],
+                Err(_) if spec.len() == 2 => {
+                    // "AK" stands for both suited and offsuit
+                    let s = HoleClass::from_str(&format!("{}s", spec)).map_err(hand)?;
+                    let o = HoleClass::from_str(&format!("{}o", spec)).map_err(hand)?;
+                    vec![s, o]
+                }
+                Err(err) => return Err(hand(err)),
+            };
+
+            for class in classes {
+                let expanded = if plus {
+                    expand_plus(class)
+                } else {
+                    vec![class]
+                };
+                for class in expanded {
+                    range.add_class(class, weight);
+                }
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RangeParseError {
+    Weight(String),                    // not a number after ':'
+    Hand(String, HoleClassParseError), // a token that is neither a hole nor a class
+}
+
+impl Display for RangeParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Weight(weight) => write!(f, "\"{weight}\" is not a weight"),
+            Self::Hand(token, err) => write!(f, "\"{token}\": {err}"),
+        }
+    }
+}
+
+impl Error for RangeParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Hand(_, err) => Some(err),
+            Self::Weight(_) => None,
+        }
+    }
+}
+
+// "77+" up to aces, "A9s+" up to one below the high card.
+fn expand_plus(class: HoleClass) -> Vec<HoleClass> {
+    match class {
+        HoleClass::Pair(value) => Value::ALL
+            .into_iter()
+            .filter(|v| *v >= value)
+            .map(HoleClass::Pair)
+            .collect(),
+        HoleClass::Suited(high, low) => Value::ALL
+            .into_iter()
+            .filter(|v| *v >= low && *v < high)
+            .map(|v| HoleClass::Suited(high, v))
+            .collect(),
+        HoleClass::Offsuit(high, low) => Value::ALL
+            .into_iter()
+            .filter(|v| *v >= low && *v < high)
+            .map(|v| HoleClass::Offsuit(high, v))
+            .collect(),
+    }
+}
+
+// Prior strategy: how likely a hole is to take an observed action.
+pub trait RangePolicy {
+    // Probability in `0.0..=1.0`; only relative values matter.
+    fn likelihood(&self, hole: Hole, board: &Board, record: &ActionRecord) -> f64;
+}
+
+// Strong hands bet and raise, medium hands call, weak hands fold, with some bluffs mixed in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StrengthPolicy {
+    pub bluff_freq: f64,
+    pub steepness: f64,
+}
+
+impl Default for StrengthPolicy {
+    fn default() -> Self {
+        Self {
+            bluff_freq: 0.1,
+            steepness: 12.0,
+        }
+    }
+}
+
+impl StrengthPolicy {
+    fn sigmoid(&self, x: f64) -> f64 {
+        1.0 / (1.0 + (-self.steepness * x).exp())
+    }
+}
+
+impl RangePolicy for StrengthPolicy {
+    fn likelihood(&self, hole: Hole, board: &Board, record: &ActionRecord) -> f64 {
+        let strength = hand_strength(hole, board);
+        let aggressive = self.bluff_freq + (1.0 - self.bluff_freq) * self.sigmoid(strength - 0.65);
+
+        match record.action().value() {
+            ActionValue::BetOrRaise(_) | ActionValue::AllIn => aggressive,
+            ActionValue::Fold | ActionValue::Exit => 1.0 - self.sigmoid(strength - 0.35),
+            ActionValue::CheckOrCall if record.facing_bet() => {
+                self.sigmoid(strength - 0.35) * (1.0 - 0.5 * self.sigmoid(strength - 0.8))
+            }
+            ActionValue::CheckOrCall => 1.0 - 0.8 * aggressive,
+        }
+    }
+}
+
+// Plain continue-ranges of one position per street, split by passive and aggressive actions.
+// Streets or action kinds without a configured range leave the weights untouched.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ContinueRanges {
+    button: bool,
+    ranges: IndexMap<(Street, bool), Range>,
+}
+
+impl ContinueRanges {
+    pub fn new(button: bool) -> Self {
+        Self {
+            button,
+            ranges: IndexMap::new(),
+        }
+    }
+
+    pub fn with(mut self, street: Street, aggressive: bool, range: Range) -> Self {
+        self.ranges.insert((street, aggressive), range);
+        self
+    }
+}
+
+impl RangePolicy for ContinueRanges {
+    fn likelihood(&self, hole: Hole, _board: &Board, record: &ActionRecord) -> f64 {
+        if record.actor() != self.button {
+            return 1.0;
+        }
+
+        let aggressive = match record.action().value() {
+            ActionValue::Fold | ActionValue::Exit => return 1.0, // nothing left to narrow
+            ActionValue::CheckOrCall => false,
+            ActionValue::BetOrRaise(_) | ActionValue::AllIn => true,
+        };
+
+        self.ranges
+            .get(&(record.street(), aggressive))
+            .map_or(1.0, |range| range.weight(hole))
+    }
+}
+
+// Posterior range of one seat, updated action by action and snapshotted per street.
+#[derive(Debug, Clone)]
+pub struct RangeEstimator<P> {
+    policy: P,
+    seat: bool,
+    range: Range,
+    board: Board,
+    street: Street,
+    streets: Vec<(Street, Range)>,
+}
+
+impl<P: RangePolicy> RangeEstimator<P> {
+    pub fn new(policy: P, seat: bool, prior: Range) -> Self {
+        Self {
+            policy,
+            seat,
+            range: prior,
+            board: Default::default(),
+            street: Street::Preflop,
+            streets: Vec::new(),
+        }
+    }
+
+    pub fn seat(&self) -> bool {
+        self.seat
+    }
+
+    // Current posterior, normalized to sum to one.
+    pub fn range(&self) -> Range {
+        self.range.normalized()
+    }
+
+    // Posterior at the end of every finished street followed by the current one.
+    pub fn street_ranges(&self) -> Vec<(Street, Range)> {
+        let mut ranges = self.streets.clone();
+        ranges.push((self.street, self.range()));
+        ranges
+    }
+
+    pub fn set_board(&mut self, board: Board) {
+        self.advance(board.street());
+        self.board = board;
+        self.range = self.range.without(&board.to_vec());
+    }
+
+    // Known cards that the seat cannot hold, e.g. the observing hero's hole.
+    pub fn remove_dead(&mut self, dead: &[Card]) {
+        self.range = self.range.without(dead);
+    }
+
+    pub fn observe(&mut self, record: &ActionRecord) {
+        self.advance(record.street());
+        if record.actor() != self.seat {
+            return;
+        }
+
+        self.range = self.range.narrowed_step(&self.policy, &self.board, record);
+    }
+
+    fn advance(&mut self, street: Street) {
+        if street > self.street {
+            self.streets.push((self.street, self.range()));
+            self.street = street;
+        }
+    }
+}
+
+// One-shot estimate for a finished line.
+pub fn estimate(
+    prior: Range,
+    policy: impl RangePolicy,
+    seat: bool,
+    board: Board,
+    line: &[ActionRecord],
+) -> Range {
+    let mut estimator = RangeEstimator::new(policy, seat, prior);
+    estimator.set_board(board);
+    for record in line {
+        estimator.observe(record);
+    }
+    estimator.range()
+}
+
+// Both seats' ranges for a spectator who doesn't see the holes, e.g. an `Observer` of
+// `Visibility::None`: every hand starts both from the prior and narrows them action by action,
+// so a bot match can be followed without spoiling it. Show them with `Range::grid`.
+#[derive(Debug, Clone)]
+pub struct SpectatorRanges<P> {
+    policy: P,
+    prior: Range,
+    walker: LineWalker,
+    seats: [RangeEstimator<P>; 2], // player 0 first
+}
+
+impl<P: RangePolicy + Clone> SpectatorRanges<P> {
+    pub fn new(policy: P, prior: Range) -> Self {
+        let seats =
+            [true, false].map(|seat| RangeEstimator::new(policy.clone(), seat, prior.clone()));
+        Self {
+            policy,
+            prior,
+            walker: LineWalker::new(true),
+            seats,
+        }
+    }
+
+    // Follows an event of the game, `button` being the button of its hand. A hole the spectator
+    // does see is taken out of the other seat's range.
+    pub fn event(&mut self, event: &ObservableEvent, button: bool) {
+        match event {
+            ObservableEvent::DealHoles(holes) => {
+                self.walker = LineWalker::new(button);
+                self.walker.step(event);
+                for (i, seat) in [true, false].into_iter().enumerate() {
+                    let mut estimator =
+                        RangeEstimator::new(self.policy.clone(), seat, self.prior.clone());
+                    if let Some(hole) = holes[1 - i] {
+                        estimator.remove_dead(&[hole[0], hole[1]]);
+                    }
+                    self.seats[i] = estimator;
+                }
+            }
+            ObservableEvent::DealBoard(board) => {
+                for estimator in &mut self.seats {
+                    estimator.set_board(*board);
+                }
+            }
+            ObservableEvent::PlayerAction(..) => {
+                if let Some(record) = self.walker.step(event) {
+                    for estimator in &mut self.seats {
+                        estimator.observe(&record);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The seat's range so far, normalized.
+    pub fn range(&self, seat: bool) -> Range {
+        self.seats[!seat as usize].range()
+    }
+
+    pub fn street_ranges(&self, seat: bool) -> Vec<(Street, Range)> {
+        self.seats[!seat as usize].street_ranges()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        chips::Chips,
+        headsup::{Action, TurnId},
+    };
+
+    #[test]
+    fn parse_ranges() {
+        let range: Range = "QQ+, AKs, KQ:0.5".parse().unwrap();
+        assert_eq!(range.len(), 18 + 4 + 16);
+        assert_eq!(range.weight("KdQc".parse().unwrap()), 0.5);
+        assert_eq!(Range::full().len(), 1326);
+        assert_eq!("A9s+".parse::<Range>().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn blockers() {
+        let range: Range = "AA, KK, AKs".parse().unwrap();
+        let board: Board = "Ks7c2d".parse().unwrap();
+        let hero: Hole = "AhQh".parse().unwrap();
+
+        let breakdown = range.combos_with_blockers(board, hero);
+        let aces = breakdown.get("AA".parse().unwrap()).unwrap();
+        assert_eq!((aces.combos, aces.live), (6.0, 3.0));
+        let kings = breakdown.get("KK".parse().unwrap()).unwrap();
+        assert_eq!((kings.combos, kings.live), (6.0, 3.0));
+        assert_eq!((breakdown.combos(), breakdown.live()), (16.0, 8.0));
+
+        // the board leaves 6 + 3 + 3 combos, hero's ace of hearts takes 3 aces and AhKh
+        let effects = blocker_effects(hero, &range, board);
+        assert_eq!(effects.blocked(), 4.0);
+        assert_eq!(effects.blocked_class("AKs".parse().unwrap()), 1.0);
+        assert_eq!(effects.share(), 4.0 / 12.0);
+        assert_eq!(effects.stronger(), None);
+
+        let river = board.turn("3h".parse().unwrap()).unwrap();
+        let river = river.river("9s".parse().unwrap()).unwrap();
+        assert_eq!(blocker_effects(hero, &range, river).stronger(), Some(4.0));
+    }
+
+    #[test]
+    fn range_grids() {
+        let range: Range = "AA, AKs:0.5".parse().unwrap();
+        let text = range.to_string();
+        assert!(text.starts_with("     A    K"));
+        assert!(text.lines().nth(1).unwrap().starts_with("A 1.00 0.50    ."));
+        assert_eq!(text, range.grid().display(DisplayMode::Unicode).to_string());
+
+        let colored = range
+            .grid()
+            .display(DisplayMode::ColoredUnicode)
+            .to_string();
+        assert!(colored.contains("\x1b[92m1.00\x1b[0m") && colored.contains("\x1b[92m0.50"));
+
+        let mut html = vec![];
+        range.grid().write_html(&mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert_eq!(html.matches("<tr>").count(), 13);
+        assert!(html.contains("rgba(0, 160, 0, 1.00)\">AA<br>1.00</td>"));
+        assert!(html.contains("<td>72o<br>.</td>"));
+    }
+
+    #[test]
+    fn narrow_by_continue_ranges() {
+        let open: Range = "22+, A2s+, KTo+".parse().unwrap();
+        let three_bet: Range = "QQ+, AK".parse().unwrap();
+        let policy = ContinueRanges::new(true)
+            .with(Street::Preflop, true, open)
+            .with(Street::Preflop, false, Range::full());
+        let bb_policy = ContinueRanges::new(false).with(Street::Preflop, true, three_bet);
+
+        let line = [
+            ObservableEvent::DealHoles([None, None]),
+            ObservableEvent::PlayerAction(
+                Action::bet_or_raise(Chips::new(1250)).unwrap(),
+                TurnId::new(0),
+            ),
+            ObservableEvent::PlayerAction(
+                Action::bet_or_raise(Chips::new(4000)).unwrap(),
+                TurnId::new(1),
+            ),
+            ObservableEvent::PlayerAction(Action::check_or_call(), TurnId::new(2)),
+        ];
+
+        let button = Range::full().narrowed_by(&line, &policy);
+        assert_eq!(button.len(), 13 * 6 + 12 * 4 + 3 * 12);
+        let bb = Range::full().narrowed_by(&line, &bb_policy);
+        assert_eq!(bb.len(), 3 * 6 + 16);
+    }
+
+    #[test]
+    fn raise_shifts_weight_to_strong_hands() {
+        let prior: Range = "AA, 72o".parse().unwrap();
+        let raise = ActionRecord::new(
+            true,
+            Street::Preflop,
+            Action::bet_or_raise(Chips::new(1500)).unwrap(),
+            true,
+            0,
+        );
+        let posterior = estimate(
+            prior,
+            StrengthPolicy::default(),
+            true,
+            Board::default(),
+            &[raise],
+        );
+
+        let aces = posterior.weight("AsAh".parse().unwrap());
+        let trash = posterior.weight("7s2h".parse().unwrap());
+        assert!(aces > trash * 3.0);
+    }
+
+    #[test]
+    fn spectator_ranges() {
+        let mut spectator =
+            SpectatorRanges::new(StrengthPolicy::default(), "AA, 72o".parse().unwrap());
+        let hole: Hole = "AsAh".parse().unwrap();
+        let raise = Action::bet_or_raise(Chips::new(1500)).unwrap();
+        // player 1 on the button raises, player 0 calls
+        for event in [
+            ObservableEvent::DealHoles([None, Some(hole)]),
+            ObservableEvent::PlayerAction(raise, TurnId::new(0)),
+            ObservableEvent::PlayerAction(Action::check_or_call(), TurnId::new(1)),
+            ObservableEvent::DealBoard("Ad7c2d".parse().unwrap()),
+        ] {
+            spectator.event(&event, false);
+        }
+
+        let button = spectator.range(false);
+        assert!(button.weight("AcAd".parse().unwrap()) == 0.0);
+        assert!(button.weight("AcAh".parse().unwrap()) > button.weight("7s2h".parse().unwrap()));
+        // the seen aces and the board's leave player 0 no aces, and 7 combos of 72o
+        let caller = spectator.range(true);
+        assert_eq!(caller.len(), 7);
+        assert_eq!(spectator.street_ranges(true).len(), 2);
+    }
+}
@@ -0,0 +1,336 @@
+// Many heads-up games in one process: tables are opened on demand, joining players fill the first
+// waiting table of their game type and a table starts once both seats are taken. Actions and
+// events are routed by table id; a player can also be taken out to be driven elsewhere.
+use crate::{
+    bot::{self, Bot},
+    gameplay::headsup::{
+        Action, ActionSendError, Channels, Game, GameOver, GameResult, GameType, Observer, Player,
+        PlayerEvent, Shutdown, Visibility, identity::Identity,
+    },
+};
+use indexmap::IndexMap;
+use std::fmt::{self, Display, Formatter};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "http")]
+pub mod api;
+pub mod matchmaking;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "redis")]
+pub mod pubsub;
+
+#[cfg(feature = "http")]
+pub use api::router;
+pub use matchmaking::{MatchEvent, Matchmaker};
+#[cfg(feature = "redis")]
+pub use pubsub::RedisSink;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct GameId(u64);
+
+impl Display for GameId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Seat {
+    pub game: GameId,
+    pub seat: bool, // true for player 0
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TableStatus {
+    Waiting,
+    Running,
+    Over(GameOver),
+}
+
+#[derive(Debug)]
+pub struct Table {
+    game_type: GameType,
+    names: [Option<String>; 2],
+    status: TableStatus,
+    game: Option<Game>,           // until it starts
+    players: [Option<Player>; 2], // until taken out or handed to a bot
+    token: CancellationToken,
+    handle: Option<JoinHandle<GameResult>>,
+}
+
+impl Table {
+    pub fn game_type(&self) -> GameType {
+        self.game_type
+    }
+
+    pub fn names(&self) -> [Option<&str>; 2] {
+        [self.names[0].as_deref(), self.names[1].as_deref()]
+    }
+
+    pub fn status(&self) -> TableStatus {
+        self.status
+    }
+
+    fn free_seat(&self) -> Option<bool> {
+        if self.status != TableStatus::Waiting {
+            return None;
+        }
+        [true, false]
+            .into_iter()
+            .find(|&seat| self.names[index(seat)].is_none())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GameServer {
+    channels: Channels,
+    tables: IndexMap<GameId, Table>,
+    next_id: u64,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisSink>,
+}
+
+impl GameServer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // Backpressure of every table opened from now on. None if the channels are invalid.
+    pub fn with_channels(mut self, channels: Channels) -> Option<Self> {
+        Game::bounded(GameType::default(), channels)?;
+        self.channels = channels;
+        Some(self)
+    }
+
+    // Every table opened from now on publishes its events through `sink`.
+    #[cfg(feature = "redis")]
+    pub fn with_redis(mut self, sink: RedisSink) -> Self {
+        self.redis = Some(sink);
+        self
+    }
+
+    pub fn table(&self, game: GameId) -> Option<&Table> {
+        self.tables.get(&game)
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = (GameId, &Table)> {
+        self.tables.iter().map(|(&id, table)| (id, table))
+    }
+
+    pub fn open_table(&mut self, game_type: GameType) -> GameId {
+        let (game, [first, second]) =
+            Game::bounded(game_type, self.channels).expect("Channels are checked when set");
+        let id = GameId(self.next_id);
+        self.next_id += 1;
+        #[cfg(feature = "redis")]
+        let game = self.publish_table(id, game);
+
+        self.tables.insert(
+            id,
+            Table {
+                game_type,
+                names: [None, None],
+                status: TableStatus::Waiting,
+                game: Some(game),
+                players: [Some(first), Some(second)],
+                token: CancellationToken::new(),
+                handle: None,
+            },
+        );
+        id
+    }
+
+    // Seats `name` at the first waiting table of `game_type`, opening one if there is none.
+    // None if the name is empty or already sitting at a table that is not over.
+    pub fn join(&mut self, name: &str, game_type: GameType) -> Option<Seat> {
+        if !self.can_sit(name) {
+            return None;
+        }
+
+        let game = self
+            .tables
+            .iter()
+            .find(|(_, table)| table.game_type == game_type && table.free_seat().is_some())
+            .map(|(&id, _)| id)
+            .unwrap_or_else(|| self.open_table(game_type));
+        self.sit(game, name)
+    }
+
+    // A table of its own for two players who found each other, started right away.
+    pub fn open_match(&mut self, names: [&str; 2], game_type: GameType) -> Option<[Seat; 2]> {
+        if names[0] == names[1] || !names.iter().all(|name| self.can_sit(name)) {
+            return None;
+        }
+
+        let game = self.open_table(game_type);
+        Some([self.sit(game, names[0])?, self.sit(game, names[1])?])
+    }
+
+    // Whether `name` is a valid name not sitting at a table that is not over.
+    pub fn can_sit(&self, name: &str) -> bool {
+        !name.is_empty()
+            && !self.tables.values().any(|table| {
+                !matches!(table.status, TableStatus::Over(_))
+                    && table.names.iter().flatten().any(|seated| seated == name)
+            })
+    }
+
+    fn sit(&mut self, game: GameId, name: &str) -> Option<Seat> {
+        let table = self.tables.get_mut(&game)?;
+        let seat = table.free_seat()?;
+        table.names[index(seat)] = Some(name.to_string());
+        if let Some(game) = &table.game {
+            game.seat(seat, Identity::new(name));
+        }
+
+        if table.free_seat().is_none() {
+            let game = table.game.take()?;
+            table.handle = Some(tokio::spawn(
+                game.run_until(table.token.clone(), Shutdown::AbortHand),
+            ));
+            table.status = TableStatus::Running;
+        }
+        Some(Seat { game, seat })
+    }
+
+    #[cfg(feature = "redis")]
+    fn publish_table(&self, id: GameId, mut game: Game) -> Game {
+        if let Some(sink) = &self.redis {
+            let observer = game
+                .observer(sink.visibility())
+                .expect("A new game has no observer");
+            sink.clone().forward(id, observer);
+        }
+        game
+    }
+
+    // The table's only observer, while it hasn't started. None for a table publishing to Redis.
+    pub fn observer(&mut self, game: GameId, visibility: Visibility) -> Option<Observer> {
+        self.tables
+            .get_mut(&game)?
+            .game
+            .as_mut()?
+            .observer(visibility)
+    }
+
+    // Joins with a bot driving the seat in a task of its own.
+    pub fn join_bot(
+        &mut self,
+        name: &str,
+        game_type: GameType,
+        mut bot: impl Bot + Send + 'static,
+    ) -> Option<Seat> {
+        let seat = self.join(name, game_type)?;
+        let mut player = self.take_player(seat)?;
+        tokio::spawn(async move { bot::play(&mut player, &mut bot).await });
+        Some(seat)
+    }
+
+    // Hands the seat's player out, e.g. to a connection task. Routing to it stops.
+    pub fn take_player(&mut self, seat: Seat) -> Option<Player> {
+        self.tables.get_mut(&seat.game)?.players[index(seat.seat)].take()
+    }
+
+    pub async fn tick_event(&mut self, seat: Seat) -> Option<PlayerEvent> {
+        self.player(seat)?.tick_event().await
+    }
+
+    pub fn send_action(&mut self, seat: Seat, action: Action) -> Result<(), ActionSendError> {
+        self.player(seat)
+            .ok_or(ActionSendError::NotHeroTurn)?
+            .send_action(action)
+    }
+
+    // Stops a table; a hand in progress is void.
+    pub fn close(&mut self, game: GameId) -> bool {
+        let Some(table) = self.tables.get(&game) else {
+            return false;
+        };
+        table.token.cancel();
+        true
+    }
+
+    // Picks up the results of finished games and of tables closed before they started.
+    pub async fn refresh(&mut self) {
+        for table in self.tables.values_mut() {
+            if table.status == TableStatus::Waiting && table.token.is_cancelled() {
+                table.game = None;
+                table.status = TableStatus::Over(GameOver::GameAbort);
+            }
+
+            if let Some(handle) = table.handle.take_if(|handle| handle.is_finished()) {
+                let result = handle.await.map(|result| result.reason);
+                table.status = TableStatus::Over(result.unwrap_or(GameOver::GameAbort));
+            }
+        }
+    }
+
+    // Forgets tables that are over, returning how many.
+    pub fn prune(&mut self) -> usize {
+        let before = self.tables.len();
+        self.tables
+            .retain(|_, table| !matches!(table.status, TableStatus::Over(_)));
+        before - self.tables.len()
+    }
+
+    fn player(&mut self, seat: Seat) -> Option<&mut Player> {
+        self.tables.get_mut(&seat.game)?.players[index(seat.seat)].as_mut()
+    }
+}
+
+fn index(seat: bool) -> usize {
+    if seat { 0 } else { 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{chips::Chips, headsup::ObservableEvent};
+
+    #[tokio::test]
+    async fn tables_fill_and_close() {
+        let mut server = GameServer::new();
+        let cash = GameType::cash_default();
+
+        let alice = server.join("alice", cash).unwrap();
+        assert!(server.join("alice", cash).is_none());
+        assert!(server.observer(alice.game, Visibility::None).is_some());
+        assert!(server.observer(alice.game, Visibility::None).is_none());
+        let sng = server.join("carol", GameType::default()).unwrap();
+        assert_ne!(sng.game, alice.game);
+        assert_eq!(
+            server.table(alice.game).unwrap().status(),
+            TableStatus::Waiting
+        );
+
+        let bob = server.join("bob", cash).unwrap();
+        assert_eq!((bob.game, bob.seat), (alice.game, false));
+        assert_eq!(
+            server.table(bob.game).unwrap().status(),
+            TableStatus::Running
+        );
+        assert!(matches!(
+            server.tick_event(bob).await,
+            Some(PlayerEvent::Observable(ObservableEvent::DealHoles(_)))
+        ));
+
+        assert!(server.close(alice.game));
+        while let Some(event) = server.tick_event(alice).await {
+            if matches!(event, PlayerEvent::Observable(ObservableEvent::GameOver(_))) {
+                break;
+            }
+        }
+        let over = TableStatus::Over(GameOver::AbortCheckout(
+            [Chips::new(50000), Chips::new(50000)],
+            [Chips::ZERO; 2],
+        ));
+        while server.table(alice.game).unwrap().status() != over {
+            tokio::task::yield_now().await;
+            server.refresh().await;
+        }
+        assert_eq!(server.prune(), 1);
+        assert_eq!(server.tables().count(), 1);
+    }
+}
@@ -0,0 +1,323 @@
+// A small JSON API over a shared `GameServer` for operational tooling:
+//   GET    /games                  tables not over yet
+//   POST   /games                  {"kind": "cash", "buyin": 100, "hands": 0} or
+//                                  {"kind": "sng", "speed": "turbo"}, gives {"id": ..}
+//   GET    /games/{id}             summary of one table
+//   DELETE /games/{id}             closes the table
+//   POST   /games/{id}/observer    {"visibility": "none" | "god" | "player0" | "player1"},
+//                                  records the table's events before it starts
+//   GET    /games/{id}/history     events recorded so far, in `Debug` form
+use super::{GameId, GameServer, TableStatus};
+use crate::gameplay::headsup::{CashBuyin, GameType, SNGSpeed, Visibility};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex as SyncMutex};
+use tokio::sync::Mutex;
+
+type Histories = Arc<SyncMutex<IndexMap<GameId, Vec<String>>>>;
+
+#[derive(Debug, Clone)]
+struct ApiState {
+    server: Arc<Mutex<GameServer>>,
+    histories: Histories,
+}
+
+pub fn router(server: Arc<Mutex<GameServer>>) -> Router {
+    Router::new()
+        .route("/games", get(list_games).post(create_game))
+        .route("/games/{id}", get(game_summary).delete(close_game))
+        .route("/games/{id}/observer", post(observe_game))
+        .route("/games/{id}/history", get(game_history))
+        .with_state(ApiState {
+            server,
+            histories: Default::default(),
+        })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum NewGame {
+    Cash {
+        #[serde(default = "default_buyin")]
+        buyin: u16, // in big blinds
+        #[serde(default)]
+        hands: u16, // 0 for no limit
+    },
+    Sng {
+        #[serde(default)]
+        speed: Speed,
+        #[serde(default = "default_level_hands")]
+        level_hands: u16, // 0 to keep the first blinds
+    },
+}
+
+fn default_buyin() -> u16 {
+    100
+}
+
+fn default_level_hands() -> u16 {
+    1
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Speed {
+    Turbo,
+    Medium,
+    #[default]
+    Slow,
+}
+
+impl NewGame {
+    fn game_type(self) -> Option<GameType> {
+        match self {
+            Self::Cash { buyin, hands } => {
+                let buyin = match buyin {
+                    15 => CashBuyin::BB15,
+                    30 => CashBuyin::BB30,
+                    50 => CashBuyin::BB50,
+                    75 => CashBuyin::BB75,
+                    100 => CashBuyin::BB100,
+                    150 => CashBuyin::BB150,
+                    200 => CashBuyin::BB200,
+                    250 => CashBuyin::BB250,
+                    300 => CashBuyin::BB300,
+                    _ => return None,
+                };
+                Some(GameType::Cash {
+                    buyin,
+                    hands,
+                    rake: None,
+                })
+            }
+            Self::Sng { speed, level_hands } => Some(GameType::SNG {
+                speed: match speed {
+                    Speed::Turbo => SNGSpeed::Turbo,
+                    Speed::Medium => SNGSpeed::Medium,
+                    Speed::Slow => SNGSpeed::Slow,
+                },
+                level_hands,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewObserver {
+    visibility: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Created {
+    id: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    id: u64,
+    game_type: String,
+    names: [Option<String>; 2],
+    status: &'static str,
+    result: Option<String>, // the `GameOver` of a table that is over
+    observed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct History {
+    events: Vec<String>,
+}
+
+async fn list_games(State(state): State<ApiState>) -> Json<Vec<Summary>> {
+    let mut server = state.server.lock().await;
+    server.refresh().await;
+
+    let histories = state.histories.lock().unwrap();
+    Json(
+        server
+            .tables()
+            .filter(|(_, table)| !matches!(table.status(), TableStatus::Over(_)))
+            .map(|(id, _)| summary(&server, &histories, id))
+            .collect::<Option<_>>()
+            .unwrap_or_default(),
+    )
+}
+
+async fn create_game(
+    State(state): State<ApiState>,
+    Json(new_game): Json<NewGame>,
+) -> Result<(StatusCode, Json<Created>), StatusCode> {
+    let game_type = new_game
+        .game_type()
+        .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+    let id = state.server.lock().await.open_table(game_type);
+    Ok((StatusCode::CREATED, Json(Created { id: id.0 })))
+}
+
+async fn game_summary(
+    State(state): State<ApiState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Summary>, StatusCode> {
+    let mut server = state.server.lock().await;
+    server.refresh().await;
+
+    let histories = state.histories.lock().unwrap();
+    summary(&server, &histories, GameId(id))
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn close_game(State(state): State<ApiState>, Path(id): Path<u64>) -> StatusCode {
+    if state.server.lock().await.close(GameId(id)) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn observe_game(
+    State(state): State<ApiState>,
+    Path(id): Path<u64>,
+    Json(new_observer): Json<NewObserver>,
+) -> StatusCode {
+    let visibility = match new_observer.visibility.as_str() {
+        "none" => Visibility::None,
+        "god" => Visibility::God,
+        "player0" => Visibility::Player(true),
+        "player1" => Visibility::Player(false),
+        _ => return StatusCode::UNPROCESSABLE_ENTITY,
+    };
+
+    let game = GameId(id);
+    let mut server = state.server.lock().await;
+    if server.table(game).is_none() {
+        return StatusCode::NOT_FOUND;
+    }
+    // taken by another observer or already started
+    let Some(mut observer) = server.observer(game, visibility) else {
+        return StatusCode::CONFLICT;
+    };
+
+    state.histories.lock().unwrap().insert(game, vec![]);
+    let histories = state.histories.clone();
+    tokio::spawn(async move {
+        while let Some(event) = observer.tick_event().await {
+            let mut histories = histories.lock().unwrap();
+            let Some(history) = histories.get_mut(&game) else {
+                return;
+            };
+            history.push(format!("{event:?}"));
+        }
+    });
+    StatusCode::NO_CONTENT
+}
+
+async fn game_history(
+    State(state): State<ApiState>,
+    Path(id): Path<u64>,
+) -> Result<Json<History>, StatusCode> {
+    let histories = state.histories.lock().unwrap();
+    let events = histories.get(&GameId(id)).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(History {
+        events: events.clone(),
+    }))
+}
+
+fn summary(
+    server: &GameServer,
+    histories: &IndexMap<GameId, Vec<String>>,
+    id: GameId,
+) -> Option<Summary> {
+    let table = server.table(id)?;
+    let (status, result) = match table.status() {
+        TableStatus::Waiting => ("waiting", None),
+        TableStatus::Running => ("running", None),
+        TableStatus::Over(game_over) => ("over", Some(format!("{game_over:?}"))),
+    };
+
+    Some(Summary {
+        id: id.0,
+        game_type: format!("{:?}", table.game_type()),
+        names: table.names().map(|name| name.map(str::to_string)),
+        status,
+        result,
+        observed: histories.contains_key(&id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{Body, to_bytes},
+        http::Request,
+    };
+    use tower::ServiceExt;
+
+    async fn call(app: &Router, method: &str, uri: &str, body: &str) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn manage_games() {
+        let server = Arc::new(Mutex::new(GameServer::new()));
+        let app = router(server.clone());
+
+        let cash = r#"{"kind": "cash"}"#;
+        assert_eq!(call(&app, "POST", "/games", cash).await.1, r#"{"id":0}"#);
+        let bad = r#"{"kind": "cash", "buyin": 60}"#;
+        assert_eq!(
+            call(&app, "POST", "/games", bad).await.0,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let god = r#"{"visibility": "god"}"#;
+        let observe = |body| call(&app, "POST", "/games/0/observer", body);
+        assert_eq!(observe(god).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(observe(god).await.0, StatusCode::CONFLICT);
+
+        {
+            let mut server = server.lock().await;
+            server.join("alice", GameType::cash_default()).unwrap();
+            server.join("bob", GameType::cash_default()).unwrap();
+        }
+        let (status, summary) = call(&app, "GET", "/games/0", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(summary.contains(r#""status":"running""#));
+        assert!(summary.contains(r#""names":["alice","bob"]"#));
+        assert_eq!(
+            call(&app, "GET", "/games", "").await.1,
+            format!("[{summary}]")
+        );
+
+        while !call(&app, "GET", "/games/0/history", "")
+            .await
+            .1
+            .contains("DealHoles")
+        {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            call(&app, "DELETE", "/games/0", "").await.0,
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(
+            call(&app, "GET", "/games/1", "").await.0,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,193 @@
+// First-come matchmaking: players queue for a game type (which fixes the stakes) with a rating
+// and how far from it they accept an opponent. Two players match when each is inside the other's
+// band; the server then opens their table and both get their seat through their queue events.
+use super::{GameServer, Seat};
+use crate::gameplay::headsup::{GameType, Player};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+#[derive(Debug)]
+pub enum MatchEvent {
+    Queued(usize), // players waiting for this game type, this one included
+    Matched {
+        seat: Seat,
+        opponent: String,
+        player: Box<Player>,
+    },
+    Left,
+}
+
+#[derive(Debug)]
+struct Ticket {
+    name: String,
+    game_type: GameType,
+    rating: f64,
+    band: f64,
+    send: UnboundedSender<MatchEvent>,
+}
+
+impl Ticket {
+    fn matches(&self, other: &Ticket) -> bool {
+        self.game_type == other.game_type
+            && (self.rating - other.rating).abs() <= self.band.min(other.band)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Matchmaker {
+    queue: Vec<Ticket>, // oldest first
+}
+
+impl Matchmaker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn is_queued(&self, name: &str) -> bool {
+        self.queue.iter().any(|ticket| ticket.name == name)
+    }
+
+    // Queues `name`, matching them at once with the longest waiting fit. None if the name is
+    // already queued or can't sit at `server`, or the band is negative.
+    pub fn enqueue(
+        &mut self,
+        server: &mut GameServer,
+        name: &str,
+        game_type: GameType,
+        rating: f64,
+        band: f64,
+    ) -> Option<UnboundedReceiver<MatchEvent>> {
+        if self.is_queued(name) || !server.can_sit(name) || !(0.0..).contains(&band) {
+            return None;
+        }
+
+        let (send, recv) = unbounded_channel();
+        let ticket = Ticket {
+            name: name.to_string(),
+            game_type,
+            rating,
+            band,
+            send,
+        };
+
+        // a fit whose table can't open, e.g. sitting elsewhere by now, stays queued
+        let fit = self.queue.iter().enumerate().find_map(|(i, queued)| {
+            queued
+                .matches(&ticket)
+                .then(|| server.open_match([&queued.name, &ticket.name], game_type))
+                .flatten()
+                .map(|seats| (i, seats))
+        });
+        let Some((i, seats)) = fit else {
+            let waiting = 1 + self
+                .queue
+                .iter()
+                .filter(|queued| queued.game_type == game_type)
+                .count();
+            let _ = ticket.send.send(MatchEvent::Queued(waiting));
+            self.queue.push(ticket);
+            return Some(recv);
+        };
+
+        let opponent = self.queue.remove(i);
+        for (seat, (ticket, other)) in seats
+            .into_iter()
+            .zip([(&opponent, &ticket.name), (&ticket, &opponent.name)])
+        {
+            if let Some(player) = server.take_player(seat) {
+                let _ = ticket.send.send(MatchEvent::Matched {
+                    seat,
+                    opponent: other.clone(),
+                    player: Box::new(player),
+                });
+            }
+        }
+        Some(recv)
+    }
+
+    pub fn leave(&mut self, name: &str) -> bool {
+        let Some(i) = self.queue.iter().position(|ticket| ticket.name == name) else {
+            return false;
+        };
+
+        let _ = self.queue.remove(i).send.send(MatchEvent::Left);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::TableStatus;
+    use tokio::sync::mpsc::error::TryRecvError;
+
+    #[tokio::test]
+    async fn pairs_within_bands() {
+        let mut server = GameServer::new();
+        let mut matchmaker = Matchmaker::new();
+        let cash = GameType::cash_default();
+
+        let mut alice = matchmaker
+            .enqueue(&mut server, "alice", cash, 1500.0, 100.0)
+            .unwrap();
+        let mut bob = matchmaker
+            .enqueue(&mut server, "bob", cash, 1700.0, 300.0)
+            .unwrap();
+        assert!(matches!(alice.recv().await, Some(MatchEvent::Queued(1))));
+        assert!(matches!(bob.recv().await, Some(MatchEvent::Queued(2))));
+        assert!(
+            matchmaker
+                .enqueue(&mut server, "bob", cash, 1700.0, 300.0)
+                .is_none()
+        );
+
+        let mut carol = matchmaker
+            .enqueue(&mut server, "carol", cash, 1560.0, 100.0)
+            .unwrap();
+        let Some(MatchEvent::Matched { seat, opponent, .. }) = carol.recv().await else {
+            panic!("carol should be matched");
+        };
+        assert_eq!(opponent, "alice");
+        assert!(matches!(
+            alice.recv().await,
+            Some(MatchEvent::Matched { .. })
+        ));
+        assert_eq!(
+            server.table(seat.game).unwrap().status(),
+            TableStatus::Running
+        );
+
+        assert_eq!(matchmaker.len(), 1);
+        assert!(matchmaker.leave("bob"));
+        assert!(matches!(bob.recv().await, Some(MatchEvent::Left)));
+        assert!(matchmaker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn keeps_fits_whose_table_fails() {
+        let mut server = GameServer::new();
+        let mut matchmaker = Matchmaker::new();
+        let cash = GameType::cash_default();
+
+        let mut alice = matchmaker
+            .enqueue(&mut server, "alice", cash, 1500.0, 100.0)
+            .unwrap();
+        assert!(matches!(alice.recv().await, Some(MatchEvent::Queued(1))));
+
+        // alice sits elsewhere, so no table opens with her
+        server.open_match(["alice", "dave"], cash).unwrap();
+        let mut carol = matchmaker
+            .enqueue(&mut server, "carol", cash, 1520.0, 100.0)
+            .unwrap();
+        assert!(matches!(carol.recv().await, Some(MatchEvent::Queued(2))));
+        assert_eq!(alice.try_recv().unwrap_err(), TryRecvError::Empty); // still waiting
+        assert!(matchmaker.is_queued("alice") && matchmaker.is_queued("carol"));
+    }
+}
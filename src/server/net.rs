@@ -0,0 +1,274 @@
+// A game's seats played over TCP (feature `net`), one JSON message per line. The server says
+// `Welcome` first, then sends the seat's events as they come, the game's as `Hand` with where they
+// happened, and an `Error` for every message it can't take; the client answers prompts with `Action`, `Show` and `Insurance` messages. A client
+// leaving early is a player gone, which the game handles like any other.
+use crate::gameplay::{
+    headsup::{
+        Action, Answer, Game, GameResult, GameType, HandEvent, Player, PlayerEvent, Prompt, Relay,
+        ShowCards, clock::Timed,
+    },
+    wire::{ErrorCode, WireError},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::pending,
+    io,
+    ops::{Deref, DerefMut},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines},
+    net::{
+        TcpListener, TcpStream, ToSocketAddrs,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+    task::JoinHandle,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub enum ServerMessage {
+    Welcome {
+        game_type: GameType,
+        seat: bool, // true for player 0
+        button: bool,
+    },
+    Event(Timed<PlayerEvent>), // what the seat is asked
+    Hand(Timed<HandEvent>),
+    Error(WireError),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub enum ClientMessage {
+    Action(Action),
+    Show(ShowCards),
+    Insurance(bool),
+}
+
+async fn send(write: &mut (impl AsyncWrite + Unpin), message: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    write.write_all(&line).await
+}
+
+// Plays `player`'s seat for the client on `stream` until the game is over, then returns how it
+// ended; None if the client left first. Start it before the game runs so the welcome's button
+// is the first hand's.
+pub async fn serve(stream: TcpStream, mut player: Player) -> io::Result<Option<GameResult>> {
+    let seat = player.seat().expect("Only players have a seat to serve");
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+
+    let welcome = ServerMessage::Welcome {
+        game_type: player.game_type(),
+        seat,
+        button: player.button(),
+    };
+    send(&mut write, &welcome).await?;
+
+    loop {
+        tokio::select! {
+            event = player.tick_timed_event() => {
+                let Some(event) = event else {
+                    return Ok(player.game_over());
+                };
+                let message = match (&event.event, player.hand_event()) {
+                    (PlayerEvent::Observable(_), Some(hand_event)) => {
+                        ServerMessage::Hand(Timed::new(event.at, hand_event))
+                    }
+                    _ => ServerMessage::Event(event),
+                };
+                send(&mut write, &message).await?;
+            }
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(None);
+                };
+                let sent = match serde_json::from_str(&line) {
+                    Ok(ClientMessage::Action(action)) => player.send_action(action),
+                    Ok(ClientMessage::Show(show)) => player.send_show(show),
+                    Ok(ClientMessage::Insurance(take)) => player.send_insurance(take),
+                    Err(err) => {
+                        let err = WireError::new(ErrorCode::MalformedMessage, err);
+                        send(&mut write, &ServerMessage::Error(err)).await?;
+                        continue;
+                    }
+                };
+                if let Err(err) = sent {
+                    send(&mut write, &ServerMessage::Error(err.into())).await?;
+                }
+            }
+        }
+    }
+}
+
+// Seats the first two clients to connect, player 0 first, and runs `game` to the end.
+pub async fn host(
+    listener: &TcpListener,
+    game: Game,
+    players: [Player; 2],
+) -> io::Result<GameResult> {
+    let mut served = Vec::with_capacity(2);
+    for player in players {
+        let (stream, _) = listener.accept().await?;
+        served.push(tokio::spawn(serve(stream, player)));
+    }
+
+    let result = game.run().await;
+    for serving in served {
+        let _ = serving.await;
+    }
+    Ok(result)
+}
+
+// A `Player` whose game is on a server: it's told the game's events like any other player, and
+// its answers are sent back. Dropping it hangs up.
+#[derive(Debug)]
+pub struct RemotePlayer {
+    player: Player,
+    relay: JoinHandle<io::Result<()>>,
+}
+
+impl RemotePlayer {
+    pub async fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let (read, write) = TcpStream::connect(addr).await?.into_split();
+        let mut lines = BufReader::new(read).lines();
+
+        let Some(line) = lines.next_line().await? else {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        };
+        let ServerMessage::Welcome {
+            game_type,
+            seat,
+            button,
+        } = serde_json::from_str(&line)?
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the server did not welcome us",
+            ));
+        };
+
+        let (player, relay) = Player::relay(game_type, seat, button);
+        Ok(Self {
+            player,
+            relay: tokio::spawn(run_relay(lines, write, relay)),
+        })
+    }
+}
+
+impl Deref for RemotePlayer {
+    type Target = Player;
+
+    fn deref(&self) -> &Self::Target {
+        &self.player
+    }
+}
+
+impl DerefMut for RemotePlayer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.player
+    }
+}
+
+impl Drop for RemotePlayer {
+    fn drop(&mut self) {
+        self.relay.abort();
+    }
+}
+
+// Ends with the connection, which leaves the player to abort the game if it wasn't over.
+async fn run_relay(
+    mut lines: Lines<BufReader<OwnedReadHalf>>,
+    mut write: OwnedWriteHalf,
+    relay: Relay,
+) -> io::Result<()> {
+    let mut prompt: Option<Prompt> = None;
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                match serde_json::from_str(&line)? {
+                    ServerMessage::Event(event) => {
+                        if let Some(next) = relay.push(event) {
+                            prompt = Some(next);
+                        }
+                    }
+                    ServerMessage::Hand(event) => relay.push_event(event),
+                    // the player checks its answers before they're sent, so all that's left is
+                    // being too late, which the game's events tell as well
+                    ServerMessage::Welcome { .. } | ServerMessage::Error(_) => {}
+                }
+            }
+            answer = async {
+                match prompt.as_mut() {
+                    Some(prompt) => prompt.answer().await,
+                    None => pending().await,
+                }
+            } => {
+                prompt = None;
+                let message = match answer {
+                    Some(Answer::Action(action)) => ClientMessage::Action(action),
+                    Some(Answer::Show(show)) => ClientMessage::Show(show),
+                    Some(Answer::Insurance(take)) => ClientMessage::Insurance(take),
+                    None => continue,
+                };
+                send(&mut write, &message).await?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{self, testing::Caller},
+        gameplay::headsup::{CashBuyin, GameOver},
+    };
+
+    #[tokio::test]
+    async fn bots_play_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let game_type = GameType::Cash {
+            buyin: CashBuyin::BB30,
+            hands: 3,
+            rake: None,
+        };
+        let (game, players) = Game::new(game_type);
+        let hosted = tokio::spawn(async move { host(&listener, game, players).await });
+
+        let mut clients = vec![];
+        for seat in [true, false] {
+            let mut remote = RemotePlayer::connect(addr).await.unwrap();
+            assert_eq!(remote.seat(), Some(seat));
+            clients.push(tokio::spawn(async move {
+                bot::play(&mut remote, &mut Caller).await;
+                remote
+            }));
+        }
+
+        let result = hosted.await.unwrap().unwrap();
+        assert!(matches!(result.reason, GameOver::HandsReached(..)));
+        for client in clients {
+            let remote = client.await.unwrap();
+            // the time is as each side saw it
+            let remote_result = remote.game_over().unwrap();
+            assert_eq!(
+                (
+                    remote_result.reason,
+                    remote_result.stacks,
+                    remote_result.hands
+                ),
+                (result.reason, result.stacks, result.hands)
+            );
+            assert_eq!(remote.hands_history().len(), 3);
+            assert_eq!(remote.hand_event().map(|event| event.hand), Some(3));
+        }
+
+        let action = ClientMessage::Action(Action::check_or_call());
+        assert_eq!(serde_json::to_string(&action).unwrap(), r#"{"Action":"c"}"#);
+    }
+}
@@ -0,0 +1,70 @@
+// Publishes the events of server tables to Redis, one channel per table, so dashboards and
+// overlays can subscribe from outside the process. The sink takes the table's observer slot.
+use super::GameId;
+use crate::gameplay::headsup::{ObservableEvent, Observer, Visibility};
+use redis::{AsyncCommands, Client, RedisResult, aio::MultiplexedConnection};
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone)]
+pub struct RedisSink {
+    conn: MultiplexedConnection,
+    prefix: String,
+    visibility: Visibility,
+    format: fn(&ObservableEvent) -> String,
+}
+
+impl RedisSink {
+    // Tables publish to `pokerbot:game:<id>` with no hole cards unless configured otherwise.
+    pub async fn connect(url: &str) -> RedisResult<Self> {
+        let conn = Client::open(url)?
+            .get_multiplexed_async_connection()
+            .await?;
+        Ok(Self {
+            conn,
+            prefix: "pokerbot:game:".to_string(),
+            visibility: Visibility::None,
+            format: |event| format!("{event:?}"),
+        })
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    // How an event is written to the channel, `Debug` by default.
+    pub fn with_format(mut self, format: fn(&ObservableEvent) -> String) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn channel(&self, game: GameId) -> String {
+        format!("{}{}", self.prefix, game.0)
+    }
+
+    // Gives how many subscribers got the event.
+    pub async fn publish(&mut self, game: GameId, event: &ObservableEvent) -> RedisResult<usize> {
+        let channel = self.channel(game);
+        let message = (self.format)(event);
+        self.conn.publish(channel, message).await
+    }
+
+    // Publishes everything `observer` sees until the game is over or publishing fails.
+    pub fn forward(mut self, game: GameId, mut observer: Observer) -> JoinHandle<RedisResult<()>> {
+        tokio::spawn(async move {
+            while let Some(event) = observer.tick_event().await {
+                self.publish(game, &event).await?;
+            }
+            Ok(())
+        })
+    }
+}
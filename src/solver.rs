@@ -0,0 +1,21 @@
+pub mod abstraction;
+pub mod cfr;
+pub mod game;
+pub mod history;
+pub mod line_ev;
+pub mod scenario;
+pub mod sequence;
+pub mod subgame;
+pub mod toy;
+pub mod tree;
+
+pub use abstraction::ActionAbstraction;
+pub use cfr::Cfr;
+pub use game::{ExtensiveGame, Turn, best_response_value, expected_value, exploitability};
+pub use history::{ActionHistory, HistoryAction, HistoryError};
+pub use line_ev::{LineBranch, LineNode, LineValue, line_ev};
+pub use scenario::{ScenarioResult, simulate_scenario};
+pub use sequence::{BettingSequence, SequenceCounts, SequenceEnd, count_street, enumerate_street};
+pub use subgame::{Bucketing, Subgame, SubgameAction, SubgameState};
+pub use toy::{Kuhn, Leduc, ToyAction};
+pub use tree::{GameTree, InfosetKey, Node, NodeId, NodeKind, Terminal, TreeConfig};
@@ -0,0 +1,304 @@
+use crate::gameplay::{
+    Street,
+    chips::Chips,
+    headsup::{Action, BetBound, HandState},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ActionAbstraction {
+    bet_sizes: Vec<f64>,   // fractions of the pot
+    raise_sizes: Vec<f64>, // fractions of the pot after calling
+    all_in: bool,
+    geometric: bool,        // adds the size getting the stacks in by the river
+    max_raises: Option<u8>, // per street, blinds excluded
+    step: u32,
+}
+
+impl Default for ActionAbstraction {
+    fn default() -> Self {
+        Self::new(vec![0.5, 1.0], vec![1.0])
+    }
+}
+
+impl ActionAbstraction {
+    pub fn new(bet_sizes: Vec<f64>, raise_sizes: Vec<f64>) -> Self {
+        Self {
+            bet_sizes,
+            raise_sizes,
+            all_in: true,
+            geometric: false,
+            max_raises: Some(3),
+            step: 25,
+        }
+    }
+
+    pub fn with_all_in(mut self, all_in: bool) -> Self {
+        self.all_in = all_in;
+        self
+    }
+
+    // A size on top of the ladders, the same pot fraction bet on each street left that puts the
+    // shorter stack all in by the river. It buckets after the ladder's sizes.
+    pub fn with_geometric(mut self, geometric: bool) -> Self {
+        self.geometric = geometric;
+        self
+    }
+
+    pub fn with_max_raises(mut self, max_raises: Option<u8>) -> Self {
+        self.max_raises = max_raises;
+        self
+    }
+
+    pub fn with_step(mut self, step: u32) -> Option<Self> {
+        if step == 0 {
+            None // Sizes round to multiples of the step, which has to be some chips
+        } else {
+            self.step = step;
+            Some(self)
+        }
+    }
+
+    pub fn bet_sizes(&self) -> &[f64] {
+        &self.bet_sizes
+    }
+
+    pub fn raise_sizes(&self) -> &[f64] {
+        &self.raise_sizes
+    }
+
+    pub fn step(&self) -> u32 {
+        self.step
+    }
+
+    // The pot fraction that, bet and called on each of `streets` streets, puts `behind` in by the
+    // last: each street grows the pot by 1 + 2 times the fraction.
+    pub fn geometric_fraction(pot_after_call: Chips, behind: Chips, streets: u32) -> f64 {
+        let pot = pot_after_call.get().max(1) as f64;
+        let growth = (1.0 + 2.0 * behind.get() as f64 / pot).powf(1.0 / streets.max(1) as f64);
+        (growth - 1.0) / 2.0
+    }
+
+    // Maps pot fractions onto the legal range and deduplicates the resulting amounts, sizes
+    // beyond it going all in. `bets` are the chips committed this round as `[hero, villain]`,
+    // already included in `pot`. The ladders only: the geometric size needs the stacks and street
+    // `actions_at` has.
+    pub fn actions(
+        &self,
+        bet_bound: &BetBound,
+        pot: Chips,
+        bets: [Chips; 2],
+        raises: u8,
+    ) -> Vec<Action> {
+        self.sized_actions(bet_bound, pot, bets, raises, None)
+    }
+
+    fn sized_actions(
+        &self,
+        bet_bound: &BetBound,
+        pot: Chips,
+        bets: [Chips; 2],
+        raises: u8,
+        geometric: Option<f64>,
+    ) -> Vec<Action> {
+        let capped = self.max_raises.is_some_and(|max| raises >= max);
+        let mut actions = Vec::new();
+
+        let (sizes, range, can_fold, passive) = match bet_bound {
+            BetBound::FoldCheckAllIn => (&[][..], None, false, true),
+            BetBound::FoldCheckBetAllIn(range) | BetBound::FoldCheckBet(range) => {
+                (&self.bet_sizes[..], Some(range), false, true)
+            }
+            BetBound::FoldAllIn => (&[][..], None, true, false),
+            BetBound::FoldCall | BetBound::FoldCallAllIn => (&[][..], None, true, true),
+            BetBound::FoldCallRaiseAllIn(range) | BetBound::FoldCallRaise(range) => {
+                (&self.raise_sizes[..], Some(range), true, true)
+            }
+            BetBound::FoldBetAllIn(range) => (&self.bet_sizes[..], Some(range), true, false),
+            BetBound::FoldRaiseAllIn(range) => (&self.raise_sizes[..], Some(range), true, false),
+        };
+
+        if can_fold {
+            actions.push(Action::fold());
+        }
+        if passive {
+            actions.push(Action::check_or_call());
+        }
+
+        let forced_all_in = matches!(bet_bound, BetBound::FoldAllIn);
+        if capped && !forced_all_in {
+            return actions;
+        }
+
+        // Under pot limit the pot sized bet takes the place of all in
+        let pot_limited = matches!(
+            bet_bound,
+            BetBound::FoldCheckBet(_) | BetBound::FoldCallRaise(_)
+        );
+        let passive_len = actions.len();
+        if let Some(range) = range {
+            let facing = bets[1].saturating_sub(bets[0]);
+            let pot_after_call = pot + facing;
+            for size in sizes.iter().copied().chain(geometric) {
+                let raw = bets[1].get() as f64 + size * pot_after_call.get() as f64;
+                let mut amount = Chips::new((raw / self.step as f64).round() as u32 * self.step)
+                    .max(*range.start());
+
+                if amount >= *range.end() {
+                    if !pot_limited {
+                        // Overbets past the stack go all in
+                        if !actions.contains(&Action::all_in()) {
+                            actions.push(Action::all_in());
+                        }
+                        continue;
+                    }
+                    amount = *range.end();
+                }
+                if let Some(action) = Action::bet_or_raise(amount)
+                    && !actions.contains(&action)
+                {
+                    actions.push(action);
+                }
+            }
+        }
+
+        if pot_limited {
+            if let Some(max) = range.and_then(|range| Action::bet_or_raise(*range.end()))
+                && (self.all_in || actions.len() == passive_len)
+                && !actions.contains(&max)
+            {
+                actions.push(max);
+            }
+            return actions;
+        }
+
+        let all_in_allowed = !matches!(bet_bound, BetBound::FoldCall);
+        if (self.all_in || forced_all_in || actions.is_empty())
+            && all_in_allowed
+            && !actions.contains(&Action::all_in())
+        {
+            actions.push(Action::all_in());
+        }
+
+        actions
+    }
+
+    // Index of the abstraction size closest to `amount`, the total a bet or raise goes to.
+    // Sizes are bet sizes when no one has bet yet this round, raise sizes otherwise.
+    pub fn bucket(
+        &self,
+        bet_bound: &BetBound,
+        pot: Chips,
+        bets: [Chips; 2],
+        amount: Chips,
+    ) -> Option<u8> {
+        self.sized_bucket(bet_bound, pot, bets, amount, None)
+    }
+
+    fn sized_bucket(
+        &self,
+        bet_bound: &BetBound,
+        pot: Chips,
+        bets: [Chips; 2],
+        amount: Chips,
+        geometric: Option<f64>,
+    ) -> Option<u8> {
+        let sizes = match bet_bound {
+            BetBound::FoldCheckBetAllIn(_)
+            | BetBound::FoldBetAllIn(_)
+            | BetBound::FoldCheckBet(_) => &self.bet_sizes,
+            BetBound::FoldCallRaiseAllIn(_)
+            | BetBound::FoldRaiseAllIn(_)
+            | BetBound::FoldCallRaise(_) => &self.raise_sizes,
+            _ => return None,
+        };
+        let facing = bets[1].saturating_sub(bets[0]);
+        let pot_after_call = (pot + facing).get().max(1) as f64;
+        let fraction = amount.saturating_sub(bets[1]).get() as f64 / pot_after_call;
+
+        sizes
+            .iter()
+            .copied()
+            .chain(geometric)
+            .map(|size| (size - fraction).abs())
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index as u8)
+    }
+
+    pub(crate) fn actions_at(&self, state: &HandState, raises: u8) -> Vec<Action> {
+        let bets = Self::bets_at(state);
+        let geometric = self.geometric_at(state);
+        self.sized_actions(
+            &state.bet_bound(),
+            state.total_pot(),
+            bets,
+            raises,
+            geometric,
+        )
+    }
+
+    pub(crate) fn bucket_at(&self, state: &HandState, amount: Chips) -> Option<u8> {
+        let bets = Self::bets_at(state);
+        let geometric = self.geometric_at(state);
+        self.sized_bucket(
+            &state.bet_bound(),
+            state.total_pot(),
+            bets,
+            amount,
+            geometric,
+        )
+    }
+
+    fn bets_at(state: &HandState) -> [Chips; 2] {
+        let hero = if state.cur_turn() { 0 } else { 1 };
+        let cur_round = state.cur_round();
+        [cur_round[hero], cur_round[1 - hero]]
+    }
+
+    fn geometric_at(&self, state: &HandState) -> Option<f64> {
+        if !self.geometric {
+            return None;
+        }
+        let hero = if state.cur_turn() { 0 } else { 1 };
+        let (behinds, cur_round) = (state.behinds(), state.cur_round());
+        let [bet, villain_bet] = [cur_round[hero], cur_round[1 - hero]];
+        // what the shorter stack has left once the bet is called
+        let behind = behinds[hero]
+            .min(behinds[1 - hero])
+            .saturating_sub(villain_bet);
+        let pot_after_call = state.total_pot() + villain_bet.saturating_sub(bet);
+        let streets = Street::River as u32 - state.street() as u32 + 1;
+        Some(Self::geometric_fraction(pot_after_call, behind, streets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::TreeConfig;
+
+    #[test]
+    fn overbets_and_geometric() {
+        let abstraction = ActionAbstraction::new(vec![2.0], vec![])
+            .with_all_in(false)
+            .with_geometric(true);
+        let bet = |amount| Action::bet_or_raise(Chips::new(amount)).unwrap();
+
+        // the geometric size on the flop grows 1000 to 21000 over three streets
+        let stacks = [Chips::new(10000), Chips::new(10000)];
+        let flop = TreeConfig::postflop(50, Street::Flop, Chips::new(1000), stacks).unwrap();
+        let (state, _) = flop.initial_state();
+        let actions = abstraction.actions_at(&state, 0);
+        assert_eq!(actions, [Action::check_or_call(), bet(2000), bet(875)]);
+        assert_eq!(abstraction.bucket_at(&state, Chips::new(900)), Some(1));
+
+        // on the river both go past the stacks, into all in
+        let stacks = [Chips::new(1500), Chips::new(1500)];
+        let river = TreeConfig::postflop(50, Street::River, Chips::new(1000), stacks).unwrap();
+        let (state, _) = river.initial_state();
+        let actions = abstraction.actions_at(&state, 0);
+        assert_eq!(actions, [Action::check_or_call(), Action::all_in()]);
+        let fraction = ActionAbstraction::geometric_fraction(Chips::new(1000), stacks[0], 1);
+        assert_eq!(fraction, 1.5);
+    }
+}
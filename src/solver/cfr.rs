@@ -0,0 +1,279 @@
+// Counterfactual regret minimization over any `ExtensiveGame`: regrets and strategy sums are kept
+// per infoset, the average strategy converging to an equilibrium. `iterate` walks the whole tree
+// (CFR+), `iterate_sampled` samples chance and the opponent (external sampling MCCFR), which is
+// what big subgames can afford.
+use super::{ExtensiveGame, InfosetKey, Turn};
+use rand::Rng;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, PartialEq, Clone)]
+struct Regrets {
+    regrets: Vec<f64>,
+    strategy_sum: Vec<f64>,
+    pending: Vec<f64>, // regrets of a full traversal, added once it's done
+}
+
+impl Regrets {
+    fn new(actions: usize) -> Self {
+        Self {
+            regrets: vec![0.0; actions],
+            strategy_sum: vec![0.0; actions],
+            pending: vec![0.0; actions],
+        }
+    }
+
+    // Regret matching: in proportion to the positive regrets, uniform without any.
+    fn current(&self) -> Vec<f64> {
+        normalized(self.regrets.iter().map(|regret| regret.max(0.0)).collect())
+    }
+}
+
+fn normalized(weights: Vec<f64>) -> Vec<f64> {
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        weights.into_iter().map(|weight| weight / total).collect()
+    } else {
+        vec![1.0 / weights.len() as f64; weights.len()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cfr<G: ExtensiveGame> {
+    game: G,
+    infosets: HashMap<InfosetKey, Regrets>,
+    iterations: u64,
+}
+
+impl<G: ExtensiveGame> Cfr<G> {
+    pub fn new(game: G) -> Self {
+        Self {
+            game,
+            infosets: HashMap::new(),
+            iterations: 0,
+        }
+    }
+
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    // Infosets reached so far.
+    pub fn len(&self) -> usize {
+        self.infosets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.infosets.is_empty()
+    }
+
+    // Full traversals, each updating one player then the other. Regrets are floored at 0 and
+    // later iterations weigh more in the average.
+    pub fn iterate(&mut self, iterations: u64) {
+        for _ in 0..iterations {
+            self.iterations += 1;
+            for player in [true, false] {
+                let mut walk = Walk {
+                    game: &self.game,
+                    infosets: &mut self.infosets,
+                    player,
+                    weight: self.iterations as f64,
+                };
+                walk.full(&self.game.root(), [1.0, 1.0]);
+
+                for regrets in self.infosets.values_mut() {
+                    for (regret, pending) in regrets.regrets.iter_mut().zip(&mut regrets.pending) {
+                        *regret = (*regret + *pending).max(0.0);
+                        *pending = 0.0;
+                    }
+                }
+            }
+        }
+    }
+
+    // Traversals that take one chance outcome and one action of the opponent wherever they come
+    // up, and every action of the player updated.
+    pub fn iterate_sampled(&mut self, iterations: u64, rng: &mut impl Rng) {
+        for _ in 0..iterations {
+            self.iterations += 1;
+            for player in [true, false] {
+                let mut walk = Walk {
+                    game: &self.game,
+                    infosets: &mut self.infosets,
+                    player,
+                    weight: 1.0,
+                };
+                walk.sampled(&self.game.root(), rng);
+            }
+        }
+    }
+
+    // Average strategy at an infoset with `actions` actions, uniform where it was never reached.
+    pub fn average_strategy(&self, infoset: &InfosetKey, actions: usize) -> Vec<f64> {
+        match self.infosets.get(infoset) {
+            Some(regrets) if regrets.strategy_sum.len() == actions => {
+                normalized(regrets.strategy_sum.clone())
+            }
+            _ => vec![1.0 / actions as f64; actions],
+        }
+    }
+
+    // The average strategy in the shape `expected_value` and `exploitability` take.
+    pub fn strategy(&self) -> impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64> + '_ {
+        |infoset, actions| self.average_strategy(infoset, actions.len())
+    }
+
+    pub fn expected_value(&self) -> f64 {
+        super::expected_value(&self.game, &self.strategy())
+    }
+
+    pub fn exploitability(&self) -> f64 {
+        super::exploitability(&self.game, &self.strategy())
+    }
+}
+
+struct Walk<'a, G: ExtensiveGame> {
+    game: &'a G,
+    infosets: &'a mut HashMap<InfosetKey, Regrets>,
+    player: bool, // whose regrets are updated
+    weight: f64,  // of this iteration in the average strategy
+}
+
+impl<G: ExtensiveGame> Walk<'_, G> {
+    fn utility(&self, state: &G::State) -> f64 {
+        let payoff = self.game.payoff(state);
+        if self.player { payoff } else { -payoff }
+    }
+
+    fn regrets(&mut self, state: &G::State, actions: usize) -> (InfosetKey, Vec<f64>) {
+        let key = self.game.infoset(state);
+        let current = self
+            .infosets
+            .entry(key.clone())
+            .or_insert_with(|| Regrets::new(actions))
+            .current();
+        (key, current)
+    }
+
+    // `reach` is [the player's, everyone else's including chance].
+    fn full(&mut self, state: &G::State, reach: [f64; 2]) -> f64 {
+        let game = self.game;
+        match game.turn(state) {
+            Turn::Terminal => self.utility(state),
+            Turn::Chance => game
+                .chance_outcomes(state)
+                .into_iter()
+                .map(|(action, prob)| {
+                    prob * self.full(&game.next(state, action), [reach[0], reach[1] * prob])
+                })
+                .sum(),
+            Turn::Player(actor) => {
+                let actions = game.actions(state);
+                let (key, strategy) = self.regrets(state, actions.len());
+
+                if actor != self.player {
+                    return actions
+                        .into_iter()
+                        .zip(strategy)
+                        .map(|(action, prob)| {
+                            prob * self.full(&game.next(state, action), [reach[0], reach[1] * prob])
+                        })
+                        .sum();
+                }
+
+                let values: Vec<f64> = actions
+                    .into_iter()
+                    .zip(&strategy)
+                    .map(|(action, prob)| {
+                        self.full(&game.next(state, action), [reach[0] * prob, reach[1]])
+                    })
+                    .collect();
+                let value: f64 = values.iter().zip(&strategy).map(|(v, p)| v * p).sum();
+
+                let regrets = self.infosets.get_mut(&key).expect("Inserted above");
+                for (i, action_value) in values.into_iter().enumerate() {
+                    regrets.pending[i] += reach[1] * (action_value - value);
+                    regrets.strategy_sum[i] += self.weight * reach[0] * strategy[i];
+                }
+                value
+            }
+        }
+    }
+
+    fn sampled(&mut self, state: &G::State, rng: &mut impl Rng) -> f64 {
+        let game = self.game;
+        match game.turn(state) {
+            Turn::Terminal => self.utility(state),
+            Turn::Chance => {
+                let action = game.sample_chance(state, rng);
+                self.sampled(&game.next(state, action), rng)
+            }
+            Turn::Player(actor) => {
+                let actions = game.actions(state);
+                let (key, strategy) = self.regrets(state, actions.len());
+
+                if actor != self.player {
+                    let regrets = self.infosets.get_mut(&key).expect("Inserted above");
+                    for (sum, prob) in regrets.strategy_sum.iter_mut().zip(&strategy) {
+                        *sum += prob;
+                    }
+                    let action = actions[sample(&strategy, rng)];
+                    return self.sampled(&game.next(state, action), rng);
+                }
+
+                let values: Vec<f64> = actions
+                    .into_iter()
+                    .map(|action| self.sampled(&game.next(state, action), rng))
+                    .collect();
+                let value: f64 = values.iter().zip(&strategy).map(|(v, p)| v * p).sum();
+
+                let regrets = self.infosets.get_mut(&key).expect("Inserted above");
+                for (regret, action_value) in regrets.regrets.iter_mut().zip(values) {
+                    *regret += action_value - value;
+                }
+                value
+            }
+        }
+    }
+}
+
+// Index drawn in proportion to `probs`.
+pub(super) fn sample(probs: &[f64], rng: &mut impl Rng) -> usize {
+    let mut pick = rng.random_range(0.0..probs.iter().sum::<f64>().max(f64::MIN_POSITIVE));
+    for (i, &prob) in probs.iter().enumerate() {
+        if pick < prob {
+            return i;
+        }
+        pick -= prob;
+    }
+    probs.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Kuhn, Leduc};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn kuhn_converges() {
+        let mut cfr = Cfr::new(Kuhn);
+        cfr.iterate(1000);
+        assert!((cfr.expected_value() + 1.0 / 18.0).abs() < 1e-3);
+        assert!(cfr.exploitability() < 1e-3, "{}", cfr.exploitability());
+        assert_eq!(cfr.len(), 12);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(3);
+        let mut sampled = Cfr::new(Kuhn);
+        sampled.iterate_sampled(20_000, &mut rng);
+        assert!(sampled.exploitability() < 0.02);
+
+        let mut leduc = Cfr::new(Leduc);
+        leduc.iterate(50);
+        assert!(leduc.exploitability() < 0.1);
+    }
+}
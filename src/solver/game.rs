@@ -0,0 +1,204 @@
+use super::InfosetKey;
+use rand::Rng;
+use std::{cell::RefCell, collections::HashMap, fmt::Debug};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Turn {
+    Player(bool),
+    Chance,
+    Terminal,
+}
+
+// Two-player zero-sum game in extensive form, the interface solver algorithms run on.
+// Players are `bool` like the seats of the engine, `true` moving first.
+pub trait ExtensiveGame {
+    type State: Clone;
+    type Action: Copy + PartialEq + Debug;
+
+    fn root(&self) -> Self::State;
+
+    fn turn(&self, state: &Self::State) -> Turn;
+
+    // Legal actions of the player to act.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    // Outcomes of a chance node with their probabilities.
+    fn chance_outcomes(&self, state: &Self::State) -> Vec<(Self::Action, f64)>;
+
+    // One chance outcome drawn by its probability, for sampling solvers. Games with too many
+    // outcomes to list cheaply can draw one directly.
+    fn sample_chance(&self, state: &Self::State, rng: &mut impl Rng) -> Self::Action {
+        let (actions, probs): (Vec<_>, Vec<_>) = self.chance_outcomes(state).into_iter().unzip();
+        actions[super::cfr::sample(&probs, rng)]
+    }
+
+    fn next(&self, state: &Self::State, action: Self::Action) -> Self::State;
+
+    // Utility of player `true` at a terminal state; player `false` gets the negation.
+    fn payoff(&self, state: &Self::State) -> f64;
+
+    // What the player to act knows: own private cards and the public history.
+    fn infoset(&self, state: &Self::State) -> InfosetKey;
+}
+
+// Value of a strategy profile for player `true`. The strategy maps an infoset and its actions
+// to action probabilities.
+pub fn expected_value<G: ExtensiveGame>(
+    game: &G,
+    strategy: &impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+) -> f64 {
+    fn walk<G: ExtensiveGame>(
+        game: &G,
+        state: &G::State,
+        strategy: &impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+    ) -> f64 {
+        match game.turn(state) {
+            Turn::Terminal => game.payoff(state),
+            Turn::Chance => game
+                .chance_outcomes(state)
+                .into_iter()
+                .map(|(action, prob)| prob * walk(game, &game.next(state, action), strategy))
+                .sum(),
+            Turn::Player(_) => {
+                let actions = game.actions(state);
+                let probs = strategy(&game.infoset(state), &actions);
+                actions
+                    .into_iter()
+                    .zip(probs)
+                    .map(|(action, prob)| prob * walk(game, &game.next(state, action), strategy))
+                    .sum()
+            }
+        }
+    }
+
+    walk(game, &game.root(), strategy)
+}
+
+// Utility `player` gets by best-responding to the other player's part of `strategy`.
+pub fn best_response_value<G: ExtensiveGame>(
+    game: &G,
+    strategy: &impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+    player: bool,
+) -> f64 {
+    let mut infosets = HashMap::new();
+    collect(game, &game.root(), strategy, player, 1.0, &mut infosets);
+
+    let response = BestResponse {
+        game,
+        strategy,
+        player,
+        infosets,
+        choices: RefCell::new(HashMap::new()),
+    };
+    response.value(&game.root())
+}
+
+// How much a best responder gains on average over the two seats; zero at an equilibrium.
+pub fn exploitability<G: ExtensiveGame>(
+    game: &G,
+    strategy: &impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+) -> f64 {
+    (best_response_value(game, strategy, true) + best_response_value(game, strategy, false)) / 2.0
+}
+
+// States of every infoset of `player`, with the reach probability of chance and the opponent.
+fn collect<G: ExtensiveGame>(
+    game: &G,
+    state: &G::State,
+    strategy: &impl Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+    player: bool,
+    reach: f64,
+    infosets: &mut HashMap<InfosetKey, Vec<(G::State, f64)>>,
+) {
+    match game.turn(state) {
+        Turn::Terminal => {}
+        Turn::Chance => {
+            for (action, prob) in game.chance_outcomes(state) {
+                let next = game.next(state, action);
+                collect(game, &next, strategy, player, reach * prob, infosets);
+            }
+        }
+        Turn::Player(actor) => {
+            let actions = game.actions(state);
+            let probs = if actor == player {
+                infosets
+                    .entry(game.infoset(state))
+                    .or_default()
+                    .push((state.clone(), reach));
+                vec![1.0; actions.len()]
+            } else {
+                strategy(&game.infoset(state), &actions)
+            };
+
+            for (action, prob) in actions.into_iter().zip(probs) {
+                let next = game.next(state, action);
+                collect(game, &next, strategy, player, reach * prob, infosets);
+            }
+        }
+    }
+}
+
+struct BestResponse<'a, G: ExtensiveGame, S> {
+    game: &'a G,
+    strategy: &'a S,
+    player: bool,
+    infosets: HashMap<InfosetKey, Vec<(G::State, f64)>>,
+    choices: RefCell<HashMap<InfosetKey, usize>>,
+}
+
+impl<G, S> BestResponse<'_, G, S>
+where
+    G: ExtensiveGame,
+    S: Fn(&InfosetKey, &[G::Action]) -> Vec<f64>,
+{
+    fn value(&self, state: &G::State) -> f64 {
+        let game = self.game;
+        let utility = match game.turn(state) {
+            Turn::Terminal => game.payoff(state),
+            Turn::Chance => {
+                return game
+                    .chance_outcomes(state)
+                    .into_iter()
+                    .map(|(action, prob)| prob * self.value(&game.next(state, action)))
+                    .sum();
+            }
+            Turn::Player(actor) if actor == self.player => {
+                let actions = game.actions(state);
+                let choice = self.choice(&game.infoset(state), &actions);
+                return self.value(&game.next(state, actions[choice]));
+            }
+            Turn::Player(_) => {
+                let actions = game.actions(state);
+                let probs = (self.strategy)(&game.infoset(state), &actions);
+                return actions
+                    .into_iter()
+                    .zip(probs)
+                    .map(|(action, prob)| prob * self.value(&game.next(state, action)))
+                    .sum();
+            }
+        };
+
+        if self.player { utility } else { -utility }
+    }
+
+    fn choice(&self, infoset: &InfosetKey, actions: &[G::Action]) -> usize {
+        if let Some(&choice) = self.choices.borrow().get(infoset) {
+            return choice;
+        }
+
+        let states = &self.infosets[infoset];
+        let choice = (0..actions.len())
+            .map(|i| {
+                let value: f64 = states
+                    .iter()
+                    .map(|(state, reach)| reach * self.value(&self.game.next(state, actions[i])))
+                    .sum();
+                (i, value)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(i, _)| i);
+
+        self.choices.borrow_mut().insert(infoset.clone(), choice);
+        choice
+    }
+}
@@ -0,0 +1,243 @@
+use super::{ActionAbstraction, InfosetKey, TreeConfig};
+use crate::gameplay::headsup::{Action, ActionOver, ActionValue};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+// Bumped whenever the encoding changes so stored keys are never silently misread.
+pub const HISTORY_VERSION: u8 = 1;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum HistoryAction {
+    Fold,
+    Check,
+    Call,
+    Bet(u8), // index into the abstraction's bet or raise sizes
+    AllIn,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HistoryError {
+    MissingVersion,
+    UnknownVersion(u8),
+    UnexpectedChar(char),
+}
+
+impl Display for HistoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingVersion => write!(f, "Action history has no version prefix"),
+            Self::UnknownVersion(version) => {
+                write!(f, "Unknown action history version {}", version)
+            }
+            Self::UnexpectedChar(c) => write!(f, "Unexpected character `{}` in action history", c),
+        }
+    }
+}
+
+impl Error for HistoryError {}
+
+// Compact public action history, one entry per street starting from the tree's first street.
+// Sizes are bucketed by the action abstraction, so lines that only differ by off-tree amounts
+// share a key. Encoded as e.g. `v1:b0c/xb1a`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct ActionHistory {
+    streets: Vec<Vec<HistoryAction>>,
+}
+
+impl ActionHistory {
+    // Replays `line` from the start of `config`, `None` if an action is illegal or
+    // a bet can't be bucketed because the abstraction has no sizes for it.
+    pub fn from_line(
+        config: TreeConfig,
+        abstraction: &ActionAbstraction,
+        line: &[Action],
+    ) -> Option<Self> {
+        let (mut state, betting) = config.initial_state();
+        let mut history = Self {
+            streets: vec![Vec::new()],
+        };
+        let mut over = !betting;
+
+        for &action in line {
+            let bet_bound = state.bet_bound();
+            if over || !bet_bound.validate_action(action) {
+                return None;
+            }
+
+            let entry = match action.value() {
+                ActionValue::Exit | ActionValue::Fold => HistoryAction::Fold,
+                ActionValue::CheckOrCall if state.can_check() => HistoryAction::Check,
+                ActionValue::CheckOrCall => HistoryAction::Call,
+                ActionValue::BetOrRaise(amount) => {
+                    HistoryAction::Bet(abstraction.bucket_at(&state, amount)?)
+                }
+                ActionValue::AllIn => HistoryAction::AllIn,
+            };
+            history.streets.last_mut()?.push(entry);
+
+            match state.action(action) {
+                ActionOver::TurnOver => {}
+                ActionOver::RoundOver => {
+                    state.next_street();
+                    history.streets.push(Vec::new());
+                }
+                _ => over = true,
+            }
+        }
+
+        Some(history)
+    }
+
+    pub fn streets(&self) -> &[Vec<HistoryAction>] {
+        &self.streets
+    }
+
+    pub fn encode(&self) -> String {
+        format!("v{}:{}", HISTORY_VERSION, self.actions())
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, HistoryError> {
+        let rest = encoded
+            .strip_prefix('v')
+            .ok_or(HistoryError::MissingVersion)?;
+        let (version, body) = rest.split_once(':').ok_or(HistoryError::MissingVersion)?;
+        let version = version.parse().map_err(|_| HistoryError::MissingVersion)?;
+        if version != HISTORY_VERSION {
+            return Err(HistoryError::UnknownVersion(version));
+        }
+
+        let mut streets = vec![Vec::new()];
+        let mut chars = body.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let street = streets.last_mut().expect("Always at least one street");
+            match c {
+                'f' => street.push(HistoryAction::Fold),
+                'x' => street.push(HistoryAction::Check),
+                'c' => street.push(HistoryAction::Call),
+                'a' => street.push(HistoryAction::AllIn),
+                'b' => {
+                    let mut digits = String::new();
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    let bucket = digits
+                        .parse()
+                        .map_err(|_| HistoryError::UnexpectedChar(c))?;
+                    street.push(HistoryAction::Bet(bucket));
+                }
+                '/' => streets.push(Vec::new()),
+                c => return Err(HistoryError::UnexpectedChar(c)),
+            }
+        }
+
+        Ok(Self { streets })
+    }
+
+    fn actions(&self) -> String {
+        let streets: Vec<String> = self
+            .streets
+            .iter()
+            .map(|street| street.iter().map(|action| action.to_string()).collect())
+            .collect();
+        streets.join("/")
+    }
+}
+
+impl Display for HistoryAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fold => write!(f, "f"),
+            Self::Check => write!(f, "x"),
+            Self::Call => write!(f, "c"),
+            Self::Bet(bucket) => write!(f, "b{}", bucket),
+            Self::AllIn => write!(f, "a"),
+        }
+    }
+}
+
+impl Display for ActionHistory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl FromStr for ActionHistory {
+    type Err = HistoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+impl From<&ActionHistory> for InfosetKey {
+    fn from(history: &ActionHistory) -> Self {
+        InfosetKey::new(history.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{Street, chips::Chips};
+
+    #[test]
+    fn bucketed_round_trip() {
+        let config = TreeConfig::postflop(
+            500,
+            Street::Turn,
+            Chips::new(2000),
+            [Chips::new(9000), Chips::new(9000)],
+        )
+        .unwrap();
+        let abstraction = ActionAbstraction::default();
+        let bet = |amount| Action::bet_or_raise(Chips::new(amount)).unwrap();
+
+        // 900 and 1100 are both closest to half pot, 1900 to a full pot
+        let small = [Action::check_or_call(), bet(900), Action::check_or_call()];
+        let other = [Action::check_or_call(), bet(1100), Action::check_or_call()];
+        let history = ActionHistory::from_line(config, &abstraction, &small).unwrap();
+        assert_eq!(history.encode(), "v1:xb0c/");
+        assert_eq!(
+            ActionHistory::from_line(config, &abstraction, &other),
+            Some(history.clone())
+        );
+
+        let line = [
+            Action::check_or_call(),
+            Action::check_or_call(),
+            bet(1900),
+            bet(6000),
+            Action::all_in(),
+            Action::fold(),
+        ];
+        let history = ActionHistory::from_line(config, &abstraction, &line).unwrap();
+        assert_eq!(history.to_string(), "v1:xx/b1b0af");
+        assert_eq!("v1:xx/b1b0af".parse(), Ok(history.clone()));
+        assert_eq!(InfosetKey::from(&history).as_str(), "v1:xx/b1b0af");
+
+        assert_eq!(
+            ActionHistory::decode("xx/b1"),
+            Err(HistoryError::MissingVersion)
+        );
+        assert_eq!(
+            ActionHistory::decode("v2:xx"),
+            Err(HistoryError::UnknownVersion(2))
+        );
+        assert_eq!(
+            ActionHistory::decode("v1:xq"),
+            Err(HistoryError::UnexpectedChar('q'))
+        );
+        assert_eq!(
+            ActionHistory::decode("v1:b"),
+            Err(HistoryError::UnexpectedChar('b'))
+        );
+
+        // Folding after the hand is over isn't a line
+        let line = [Action::fold(), Action::fold()];
+        assert_eq!(ActionHistory::from_line(config, &abstraction, &line), None);
+    }
+}
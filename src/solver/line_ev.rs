@@ -0,0 +1,163 @@
+use crate::{equity::range_equity, gameplay::Board, range::Range};
+
+// A fixed tree of lines for study: who acts, how often each option is taken and what it costs.
+// Frequencies are per node and normalized, so `[40.0, 60.0]` works as well as `[0.4, 0.6]`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LineNode {
+    Act {
+        hero: bool,
+        branches: Vec<LineBranch>,
+    },
+    Fold {
+        hero: bool, // whether hero is the one folding
+    },
+    Showdown,
+}
+
+impl LineNode {
+    pub fn hero(branches: Vec<LineBranch>) -> Self {
+        Self::Act {
+            hero: true,
+            branches,
+        }
+    }
+
+    pub fn villain(branches: Vec<LineBranch>) -> Self {
+        Self::Act {
+            hero: false,
+            branches,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LineBranch {
+    pub(super) frequency: f64,
+    pub(super) chips: u32,           // put in by the actor
+    pub(super) range: Option<Range>, // the actor's range when taking this branch, if narrower
+    pub(super) next: LineNode,
+}
+
+impl LineBranch {
+    pub fn new(frequency: f64, chips: u32, next: LineNode) -> Self {
+        Self {
+            frequency,
+            chips,
+            range: None,
+            next,
+        }
+    }
+
+    pub fn with_range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+// Hero's EV at a node, with the showdown equity at leaves and the values of each branch.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LineValue {
+    pub ev: f64,
+    pub equity: Option<f64>,
+    pub branches: Vec<LineValue>,
+}
+
+// Hero's EV in chips from this point by backward induction; the `pot` already in the middle
+// is dead money. Showdowns use range-vs-range equity over the remaining runouts of `board`.
+// None if a node has no positive frequency or a showdown has no live combos.
+pub fn line_ev(
+    hero: &Range,
+    villain: &Range,
+    board: &Board,
+    pot: u32,
+    tree: &LineNode,
+) -> Option<LineValue> {
+    let spot = Spot { board, pot };
+    spot.value(tree, [hero.clone(), villain.clone()], [0, 0])
+}
+
+struct Spot<'a> {
+    board: &'a Board,
+    pot: u32,
+}
+
+impl Spot<'_> {
+    // `ranges` and `invested` are [hero, villain].
+    fn value(&self, node: &LineNode, ranges: [Range; 2], invested: [u32; 2]) -> Option<LineValue> {
+        let [hero_in, villain_in] = invested.map(f64::from);
+        let pot = self.pot as f64;
+
+        match node {
+            LineNode::Fold { hero: true } => Some(leaf(-hero_in, None)),
+            LineNode::Fold { hero: false } => Some(leaf(pot + villain_in, None)),
+            LineNode::Showdown => {
+                let equity = range_equity(&ranges[0], &ranges[1], self.board)?;
+                let ev = equity * (pot + hero_in + villain_in) - hero_in;
+                Some(leaf(ev, Some(equity)))
+            }
+            LineNode::Act { hero, branches } => {
+                let total: f64 = branches.iter().map(|branch| branch.frequency).sum();
+                if total <= 0.0 {
+                    return None;
+                }
+
+                let actor = if *hero { 0 } else { 1 };
+                let mut ev = 0.0;
+                let mut values = Vec::with_capacity(branches.len());
+
+                for branch in branches {
+                    let mut ranges = ranges.clone();
+                    let mut invested = invested;
+                    if let Some(range) = &branch.range {
+                        ranges[actor] = range.clone();
+                    }
+                    invested[actor] += branch.chips;
+
+                    let value = self.value(&branch.next, ranges, invested)?;
+                    ev += branch.frequency / total * value.ev;
+                    values.push(value);
+                }
+
+                Some(LineValue {
+                    ev,
+                    equity: None,
+                    branches: values,
+                })
+            }
+        }
+    }
+}
+
+fn leaf(ev: f64, equity: Option<f64>) -> LineValue {
+    LineValue {
+        ev,
+        equity,
+        branches: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn river_bluff_against_fold_frequency() {
+        let board: Board = "AsKd8h7c2d".parse().unwrap();
+        let hero: Range = "QJo".parse().unwrap();
+        let villain: Range = "AK".parse().unwrap();
+
+        let facing_bet = LineNode::villain(vec![
+            LineBranch::new(0.4, 0, LineNode::Fold { hero: false }),
+            LineBranch::new(0.6, 100, LineNode::Showdown),
+        ]);
+        let tree = LineNode::hero(vec![
+            LineBranch::new(1.0, 100, facing_bet),
+            LineBranch::new(1.0, 0, LineNode::Showdown),
+        ]);
+
+        let value = line_ev(&hero, &villain, &board, 100, &tree).unwrap();
+        assert!((value.branches[0].ev + 20.0).abs() < 1e-9);
+        assert_eq!(value.branches[1].equity, Some(0.0));
+        assert!((value.ev + 10.0).abs() < 1e-9);
+    }
+}
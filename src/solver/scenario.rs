@@ -0,0 +1,233 @@
+use super::{LineBranch, LineNode};
+use crate::parallel::prelude::*;
+use crate::{
+    equity::runouts,
+    gameplay::{Board, FullBoard, Hole},
+    range::Range,
+};
+use itertools::Itertools;
+use std::cmp::Ordering;
+
+// How a line played out for one fixed hole. The outcome shares sum to one and `results` lists
+// hero's possible results in chips with their probabilities, lowest first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScenarioResult {
+    pub ev: f64,
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+    pub hero_folds: f64,
+    pub villain_folds: f64,
+    pub runouts: usize,
+    pub results: Vec<(f64, f64)>,
+}
+
+// Plays `line` out on every runout of `board` (a sample of them preflop) with hero holding
+// `hole` and the villain drawn from `villain`, narrowed where the line's villain branches give
+// a range. Hero's branch ranges don't apply; hero's branch frequencies are a mixed strategy.
+// None if the hole collides with the board or no runout leaves the villain live combos.
+pub fn simulate_scenario(
+    hole: Hole,
+    villain: &Range,
+    board: &Board,
+    pot: u32,
+    line: &LineNode,
+) -> Option<ScenarioResult> {
+    let board_cards = board.to_vec();
+    if board_cards.iter().any(|card| hole.contains_card(*card)) {
+        return None;
+    }
+
+    let dead = hole.iter().chain(board_cards.iter()).copied().collect_vec();
+    let villain = villain.without(&dead);
+    let outcomes: Vec<Vec<Outcome>> = runouts(&dead, &board_cards)
+        .par_iter()
+        .filter_map(|runout| {
+            let cards = board_cards.iter().chain(runout).copied().collect_vec();
+            let full = FullBoard::new(cards.try_into().ok()?)?;
+            let walk = Walk {
+                hole,
+                board: &full,
+                pot,
+            };
+
+            let mut outcomes = vec![];
+            walk.outcomes(line, &villain, [0, 0], 1.0, &mut outcomes)?;
+            Some(outcomes)
+        })
+        .collect();
+
+    if outcomes.is_empty() {
+        return None;
+    }
+
+    let share = 1.0 / outcomes.len() as f64;
+    let mut result = ScenarioResult {
+        ev: 0.0,
+        win: 0.0,
+        tie: 0.0,
+        lose: 0.0,
+        hero_folds: 0.0,
+        villain_folds: 0.0,
+        runouts: outcomes.len(),
+        results: vec![],
+    };
+
+    for outcome in outcomes.iter().flatten() {
+        let prob = outcome.prob * share;
+        result.ev += prob * outcome.chips;
+        *match outcome.kind {
+            OutcomeKind::Win => &mut result.win,
+            OutcomeKind::Tie => &mut result.tie,
+            OutcomeKind::Lose => &mut result.lose,
+            OutcomeKind::HeroFolds => &mut result.hero_folds,
+            OutcomeKind::VillainFolds => &mut result.villain_folds,
+        } += prob;
+
+        match result
+            .results
+            .iter_mut()
+            .find(|(chips, _)| (chips - outcome.chips).abs() < 1e-9)
+        {
+            Some((_, total)) => *total += prob,
+            None => result.results.push((outcome.chips, prob)),
+        }
+    }
+    result.results.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    Some(result)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OutcomeKind {
+    Win,
+    Tie,
+    Lose,
+    HeroFolds,
+    VillainFolds,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    kind: OutcomeKind,
+    chips: f64,
+    prob: f64, // within the runout
+}
+
+struct Walk<'a> {
+    hole: Hole,
+    board: &'a FullBoard,
+    pot: u32,
+}
+
+impl Walk<'_> {
+    // `invested` is [hero, villain].
+    fn outcomes(
+        &self,
+        node: &LineNode,
+        villain: &Range,
+        invested: [u32; 2],
+        prob: f64,
+        outcomes: &mut Vec<Outcome>,
+    ) -> Option<()> {
+        let [hero_in, villain_in] = invested.map(f64::from);
+        let pot = self.pot as f64 + hero_in + villain_in;
+        let mut push = |kind, chips, prob: f64| {
+            if prob > 0.0 {
+                outcomes.push(Outcome { kind, chips, prob });
+            }
+        };
+
+        match node {
+            LineNode::Fold { hero: true } => push(OutcomeKind::HeroFolds, -hero_in, prob),
+            LineNode::Fold { hero: false } => push(OutcomeKind::VillainFolds, pot - hero_in, prob),
+            LineNode::Showdown => {
+                let hero = self.board.hand_value(self.hole);
+                let mut weights = [0.0; 3]; // win, tie, lose
+                for (combo, weight) in villain.iter() {
+                    if combo.iter().any(|card| self.board.contains_card(*card)) {
+                        continue;
+                    }
+                    let i = match hero.cmp(&self.board.hand_value(combo)) {
+                        Ordering::Greater => 0,
+                        Ordering::Equal => 1,
+                        Ordering::Less => 2,
+                    };
+                    weights[i] += weight;
+                }
+
+                let total: f64 = weights.iter().sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                let [win, tie, lose] = weights.map(|weight| weight / total * prob);
+                push(OutcomeKind::Win, pot - hero_in, win);
+                push(OutcomeKind::Tie, pot / 2.0 - hero_in, tie);
+                push(OutcomeKind::Lose, -hero_in, lose);
+            }
+            LineNode::Act { hero, branches } => {
+                let total: f64 = branches.iter().map(|branch| branch.frequency).sum();
+                if total <= 0.0 {
+                    return None;
+                }
+
+                for LineBranch {
+                    frequency,
+                    chips,
+                    range,
+                    next,
+                } in branches
+                {
+                    let mut invested = invested;
+                    invested[if *hero { 0 } else { 1 }] += chips;
+                    let narrowed;
+                    let villain = match range {
+                        Some(range) if !hero => {
+                            narrowed = range.without(&self.hole[..]);
+                            &narrowed
+                        }
+                        _ => villain,
+                    };
+
+                    let prob = prob * frequency / total;
+                    self.outcomes(next, villain, invested, prob, outcomes)?;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bluff_and_check_down() {
+        let villain: Range = "AK".parse().unwrap();
+        let hole: Hole = "QhJc".parse().unwrap();
+
+        let bluff = LineNode::hero(vec![LineBranch::new(
+            1.0,
+            100,
+            LineNode::villain(vec![
+                LineBranch::new(0.4, 0, LineNode::Fold { hero: false }),
+                LineBranch::new(0.6, 100, LineNode::Showdown),
+            ]),
+        )]);
+        let river: Board = "AsKd8h7c2d".parse().unwrap();
+        let result = simulate_scenario(hole, &villain, &river, 100, &bluff).unwrap();
+        assert_eq!(result.runouts, 1);
+        assert!((result.ev + 20.0).abs() < 1e-9);
+        assert_eq!(result.results.len(), 2);
+        assert!((result.results[0].0 + 100.0).abs() < 1e-9);
+        assert!((result.villain_folds - 0.4).abs() < 1e-9);
+
+        // only a ten gets there
+        let turn: Board = "AsKd8h7c".parse().unwrap();
+        let result = simulate_scenario(hole, &villain, &turn, 100, &LineNode::Showdown).unwrap();
+        assert_eq!(result.runouts, 46);
+        assert!((result.win - 4.0 / 46.0).abs() < 1e-9);
+        assert!((result.ev - 400.0 / 46.0).abs() < 1e-9);
+    }
+}
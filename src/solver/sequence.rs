@@ -0,0 +1,169 @@
+use super::{
+    ActionAbstraction, TreeConfig,
+    tree::{history_token, is_aggressive},
+};
+use crate::gameplay::headsup::{Action, ActionOver, HandState};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SequenceEnd {
+    Fold(bool), // winner
+    NextStreet,
+    Showdown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct BettingSequence {
+    actions: Vec<Action>,
+    encoding: String,
+    end: SequenceEnd,
+}
+
+impl BettingSequence {
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn encoding(&self) -> &str {
+        &self.encoding
+    }
+
+    pub fn end(&self) -> SequenceEnd {
+        self.end
+    }
+}
+
+impl Display for BettingSequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encoding)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SequenceCounts {
+    pub total: usize,
+    pub folds: usize,
+    pub next_street: usize,
+    pub showdowns: usize,
+    pub longest: usize,
+}
+
+// All legal betting sequences of the first street of `config`, in depth-first order.
+pub fn enumerate_street(
+    config: TreeConfig,
+    abstraction: &ActionAbstraction,
+) -> Vec<BettingSequence> {
+    let mut sequences = Vec::new();
+    let (state, betting) = config.initial_state();
+
+    if betting {
+        walk(
+            abstraction,
+            state,
+            0,
+            &mut Vec::new(),
+            &mut String::new(),
+            &mut |seq| sequences.push(seq),
+        );
+    } else {
+        sequences.push(BettingSequence {
+            actions: vec![],
+            encoding: String::new(),
+            end: SequenceEnd::Showdown,
+        });
+    }
+
+    sequences
+}
+
+pub fn count_street(config: TreeConfig, abstraction: &ActionAbstraction) -> SequenceCounts {
+    let mut counts = SequenceCounts::default();
+
+    for seq in enumerate_street(config, abstraction) {
+        counts.total += 1;
+        counts.longest = counts.longest.max(seq.actions.len());
+        match seq.end {
+            SequenceEnd::Fold(_) => counts.folds += 1,
+            SequenceEnd::NextStreet => counts.next_street += 1,
+            SequenceEnd::Showdown => counts.showdowns += 1,
+        }
+    }
+
+    counts
+}
+
+fn walk(
+    abstraction: &ActionAbstraction,
+    state: HandState,
+    raises: u8,
+    actions: &mut Vec<Action>,
+    encoding: &mut String,
+    emit: &mut impl FnMut(BettingSequence),
+) {
+    for action in abstraction.actions_at(&state, raises) {
+        let mut next = state;
+        let player = next.cur_turn();
+        let aggressive = is_aggressive(&next, action);
+        let token = history_token(action, next.can_check());
+        let len = encoding.len();
+
+        actions.push(action);
+        encoding.push_str(&token);
+
+        let end = match next.action(action) {
+            ActionOver::TurnOver => None,
+            ActionOver::RoundOver => Some(SequenceEnd::NextStreet),
+            ActionOver::ShowdownAll | ActionOver::ShowndownRiver => Some(SequenceEnd::Showdown),
+            ActionOver::HandOver => Some(SequenceEnd::Fold(!player)),
+        };
+
+        match end {
+            Some(end) => emit(BettingSequence {
+                actions: actions.clone(),
+                encoding: encoding.clone(),
+                end,
+            }),
+            None => walk(
+                abstraction,
+                next,
+                raises + aggressive as u8,
+                actions,
+                encoding,
+                emit,
+            ),
+        }
+
+        actions.pop();
+        encoding.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{Street, chips::Chips};
+
+    #[test]
+    fn river_check_or_pot() {
+        let config = TreeConfig::postflop(
+            500,
+            Street::River,
+            Chips::new(1000),
+            [Chips::new(10000), Chips::new(10000)],
+        )
+        .unwrap();
+        let abstraction = ActionAbstraction::new(vec![1.0], vec![])
+            .with_all_in(false)
+            .with_max_raises(Some(1));
+        let encodings: Vec<_> = enumerate_street(config, &abstraction)
+            .iter()
+            .map(|seq| seq.encoding().to_string())
+            .collect();
+
+        assert_eq!(encodings, ["xx", "xb1000f", "xb1000c", "b1000f", "b1000c"]);
+        let counts = count_street(config, &abstraction);
+        assert_eq!(counts.folds, 2);
+        assert_eq!(counts.showdowns, 3);
+        assert_eq!(counts.longest, 3);
+    }
+}
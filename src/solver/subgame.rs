@@ -0,0 +1,419 @@
+// Postflop spots for the CFR solver: both players' ranges on a board, betting as a `GameTree`
+// lays it out from the start of a street to the river, the holes and every card still to come
+// dealt by chance. Holes can share infosets by their equity against the other range, which keeps
+// turn subgames with wide ranges small enough to solve.
+use super::{
+    ActionAbstraction, Cfr, ExtensiveGame, GameTree, InfosetKey, NodeId, NodeKind, TreeConfig, Turn,
+};
+use crate::{
+    equity::{hole_equity, range_equity},
+    gameplay::{
+        Board, Card, Hole, HoleClass, Street, cardset::CardSet, chips::Chips, headsup::Action,
+    },
+    range::Range,
+};
+use itertools::Itertools;
+use rand::Rng;
+use std::{cell::RefCell, collections::HashMap};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Bucketing {
+    #[default]
+    Exact, // every hole on its own
+    Equity(u8), // holes in the same of this many even bins of equity share an infoset
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SubgameAction {
+    Deal([Hole; 2]), // holes of [player 0, player 1]
+    Card(Card),
+    Bet(Action),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct SubgameState {
+    node: NodeId,
+    holes: Option<[Hole; 2]>,
+    board: Board,
+}
+
+impl SubgameState {
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+
+    pub fn holes(&self) -> Option<[Hole; 2]> {
+        self.holes
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Subgame {
+    tree: GameTree,
+    board: Board,
+    ranges: [Range; 2], // [player 0, player 1]
+    bucketing: Bucketing,
+    buckets: RefCell<HashMap<(Hole, Board, bool), u8>>,
+}
+
+impl Subgame {
+    // None unless `board` is of the street `config` starts on, postflop, and the ranges have a
+    // pair of holes that fit together on it.
+    pub fn new(
+        config: TreeConfig,
+        abstraction: ActionAbstraction,
+        board: Board,
+        ranges: [Range; 2],
+    ) -> Option<Self> {
+        if config.street() == Street::Preflop || board.street() != config.street() {
+            return None;
+        }
+        let ranges = ranges.map(|range| range.without(&board.to_vec()));
+        let fits = ranges[0]
+            .iter()
+            .any(|(hole, _)| ranges[1].iter().any(|(other, _)| !collide(hole, other)));
+        if !fits {
+            return None;
+        }
+
+        Some(Self {
+            tree: GameTree::build(config, abstraction),
+            board,
+            ranges,
+            bucketing: Default::default(),
+            buckets: Default::default(),
+        })
+    }
+
+    pub fn with_bucketing(mut self, bucketing: Bucketing) -> Self {
+        self.bucketing = bucketing;
+        self.buckets.get_mut().clear();
+        self
+    }
+
+    pub fn tree(&self) -> &GameTree {
+        &self.tree
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn range(&self, seat: bool) -> &Range {
+        &self.ranges[index(seat)]
+    }
+
+    // The decision node `line` leads to, past the cards dealt in between, and who acts there.
+    pub fn locate(&self, line: &[Action]) -> Option<(NodeId, bool)> {
+        let mut id = self.tree.root();
+        for &action in line {
+            id = self.tree.child(id, action)?;
+            if let NodeKind::Chance { child, .. } = self.tree.node(id).kind() {
+                id = *child;
+            }
+        }
+        Some((id, self.tree.node(id).player()?))
+    }
+
+    // Infoset of `hole` acting at `node` with `board` out. Holes colliding with the board share
+    // an infoset no deal reaches.
+    pub fn infoset_at(&self, node: NodeId, hole: Hole, board: &Board) -> InfosetKey {
+        let player = self.tree.node(node).player().unwrap_or(true);
+        let private = match self.bucketing {
+            Bucketing::Exact => hole.iter().join(""),
+            Bucketing::Equity(bins) => format!("e{}", self.bucket(hole, board, player, bins)),
+        };
+        let board = board.to_vec().iter().join("");
+        InfosetKey::new(format!("{private}|{board}|{}", self.tree.infoset_key(node)))
+    }
+
+    fn bucket(&self, hole: Hole, board: &Board, player: bool, bins: u8) -> u8 {
+        let key = (hole, *board, player);
+        if let Some(&bucket) = self.buckets.borrow().get(&key) {
+            return bucket;
+        }
+
+        let mut hero = Range::new();
+        hero.set(hole, 1.0);
+        let equity = range_equity(&hero, &self.ranges[index(!player)], board).unwrap_or(0.0);
+        let bucket = ((equity * bins as f64) as u8).min(bins.saturating_sub(1));
+        self.buckets.borrow_mut().insert(key, bucket);
+        bucket
+    }
+
+    fn start(&self) -> (Chips, Chips) {
+        let root = self.tree.node(self.tree.root());
+        (root.stacks()[0], root.pot())
+    }
+}
+
+fn index(seat: bool) -> usize {
+    if seat { 0 } else { 1 }
+}
+
+fn collide(hole: Hole, other: Hole) -> bool {
+    hole.iter().any(|card| other.contains_card(*card))
+}
+
+impl ExtensiveGame for Subgame {
+    type State = SubgameState;
+    type Action = SubgameAction;
+
+    fn root(&self) -> SubgameState {
+        SubgameState {
+            node: self.tree.root(),
+            holes: None,
+            board: self.board,
+        }
+    }
+
+    fn turn(&self, state: &SubgameState) -> Turn {
+        if state.holes.is_none() {
+            return Turn::Chance;
+        }
+        match self.tree.node(state.node).kind() {
+            NodeKind::Decision { player, .. } => Turn::Player(*player),
+            NodeKind::Chance { .. } => Turn::Chance,
+            NodeKind::Terminal(_) => Turn::Terminal,
+        }
+    }
+
+    fn actions(&self, state: &SubgameState) -> Vec<SubgameAction> {
+        let actions = self.tree.node(state.node).actions();
+        actions.into_iter().map(SubgameAction::Bet).collect()
+    }
+
+    // Every pair of holes by the product of their weights, or every card to come evenly.
+    fn chance_outcomes(&self, state: &SubgameState) -> Vec<(SubgameAction, f64)> {
+        let Some(holes) = state.holes else {
+            let deals = self.ranges[0]
+                .iter()
+                .cartesian_product(self.ranges[1].iter().collect_vec())
+                .filter(|((hole, _), (other, _))| !collide(*hole, *other))
+                .map(|((hole, weight), (other, other_weight))| {
+                    ([hole, other], weight * other_weight)
+                })
+                .collect_vec();
+            let total: f64 = deals.iter().map(|(_, weight)| weight).sum();
+            return deals
+                .into_iter()
+                .map(|(holes, weight)| (SubgameAction::Deal(holes), weight / total))
+                .collect();
+        };
+
+        let dead: CardSet = holes.iter().flat_map(|hole| hole.iter()).copied().collect();
+        let cards = state.board.runouts(&dead).collect_vec();
+        let prob = 1.0 / cards.len() as f64;
+        cards
+            .into_iter()
+            .map(|card| (SubgameAction::Card(card), prob))
+            .collect()
+    }
+
+    // Holes by drawing from each range until they fit together, not listing every pair.
+    fn sample_chance(&self, state: &SubgameState, rng: &mut impl Rng) -> SubgameAction {
+        if state.holes.is_some() {
+            let (cards, _): (Vec<_>, Vec<_>) = self.chance_outcomes(state).into_iter().unzip();
+            return cards[rng.random_range(0..cards.len())];
+        }
+        loop {
+            let hole = self.ranges[0].sample(rng).expect("Ranges fit together");
+            let other = self.ranges[1].sample(rng).expect("Ranges fit together");
+            if !collide(hole, other) {
+                return SubgameAction::Deal([hole, other]);
+            }
+        }
+    }
+
+    fn next(&self, state: &SubgameState, action: SubgameAction) -> SubgameState {
+        let mut next = *state;
+        match action {
+            SubgameAction::Deal(holes) => next.holes = Some(holes),
+            SubgameAction::Card(card) => {
+                let board = if state.board.is_flop() {
+                    state.board.turn(card)
+                } else {
+                    state.board.river(card)
+                };
+                next.board = board.expect("Dealt cards are live");
+                if let NodeKind::Chance { child, .. } = self.tree.node(state.node).kind() {
+                    next.node = *child;
+                }
+            }
+            SubgameAction::Bet(action) => {
+                next.node = self
+                    .tree
+                    .child(state.node, action)
+                    .expect("Actions are from the tree");
+            }
+        }
+        next
+    }
+
+    // Chips player 0 ends up with over their stack and half the pot at the start. A showdown
+    // before the river is worth their equity over the runouts.
+    fn payoff(&self, state: &SubgameState) -> f64 {
+        let holes = state.holes.expect("Terminal states should be dealt");
+        let node = self.tree.node(state.node);
+        let equity = if node.pot().is_zero() {
+            0.0
+        } else {
+            hole_equity(holes, &state.board).expect("Holes and board don't collide")
+        };
+
+        let (stack, pot) = self.start();
+        let end = node.stacks()[0].get() as f64 + node.pot().get() as f64 * equity;
+        end - stack.get() as f64 - pot.get() as f64 / 2.0
+    }
+
+    fn infoset(&self, state: &SubgameState) -> InfosetKey {
+        let holes = state.holes.expect("Players act after the deal");
+        let player = self
+            .tree
+            .node(state.node)
+            .player()
+            .expect("Infosets are at decisions");
+        self.infoset_at(state.node, holes[index(player)], &state.board)
+    }
+}
+
+impl Cfr<Subgame> {
+    // How often `hole` takes each action at the end of `line`, with `board` as dealt by then.
+    // None if the line leaves the tree, ends off a decision or the board doesn't fit it.
+    pub fn hole_strategy(
+        &self,
+        hole: Hole,
+        board: &Board,
+        line: &[Action],
+    ) -> Option<Vec<(Action, f64)>> {
+        let subgame = self.game();
+        let (node, _) = subgame.locate(line)?;
+        let dealt = board.to_vec();
+        if board.street() != subgame.tree().node(node).street()
+            || !dealt.starts_with(&subgame.board().to_vec())
+            || dealt.iter().any(|card| hole.contains_card(*card))
+        {
+            return None;
+        }
+
+        let actions = subgame.tree().node(node).actions();
+        let strategy = self.average_strategy(&subgame.infoset_at(node, hole, board), actions.len());
+        Some(actions.into_iter().zip(strategy).collect())
+    }
+
+    // Like `hole_strategy`, the combos of `class` in the acting player's range averaged by their
+    // weight. None as well if none of them are left on the board.
+    pub fn class_strategy(
+        &self,
+        class: HoleClass,
+        board: &Board,
+        line: &[Action],
+    ) -> Option<Vec<(Action, f64)>> {
+        let (_, player) = self.game().locate(line)?;
+        let range = self.game().range(player).without(&board.to_vec());
+
+        let mut total = 0.0;
+        let mut average: Option<Vec<(Action, f64)>> = None;
+        for (hole, weight) in range.iter().filter(|(hole, _)| class.contains(*hole)) {
+            let strategy = self.hole_strategy(hole, board, line)?;
+            let average = average
+                .get_or_insert_with(|| strategy.iter().map(|(action, _)| (*action, 0.0)).collect());
+            for ((_, sum), (_, prob)) in average.iter_mut().zip(strategy) {
+                *sum += weight * prob;
+            }
+            total += weight;
+        }
+
+        let mut average = average?;
+        for (_, prob) in &mut average {
+            *prob /= total;
+        }
+        Some(average)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn range(holes: &[&str]) -> Range {
+        let mut range = Range::new();
+        for hole in holes {
+            range.set(hole.parse().unwrap(), 1.0);
+        }
+        range
+    }
+
+    #[test]
+    fn polar_range_against_a_bluff_catcher() {
+        let board: Board = "Kh9d4c2s2h".parse().unwrap();
+        let config =
+            TreeConfig::postflop(500, Street::River, Chips::new(2000), [Chips::new(10000); 2])
+                .unwrap();
+        let abstraction = ActionAbstraction::new(vec![1.0], vec![]).with_all_in(false);
+        // the button holds the nuts or air half the time each, the big blind a queen pair
+        let ranges = [range(&["AcAd", "6h5h"]), range(&["QcQd"])];
+        let subgame = Subgame::new(config, abstraction.clone(), board, ranges.clone()).unwrap();
+
+        let mut cfr = Cfr::new(subgame);
+        cfr.iterate(2000);
+        assert!(cfr.exploitability() < 5.0, "{}", cfr.exploitability());
+
+        let check = [Action::check_or_call()];
+        let bet = Action::bet_or_raise(Chips::new(2000)).unwrap();
+        let prob = |strategy: Vec<(Action, f64)>, action| {
+            strategy.into_iter().find(|(a, _)| *a == action).unwrap().1
+        };
+        let aces = cfr
+            .class_strategy("AA".parse().unwrap(), &board, &check)
+            .unwrap();
+        assert!(prob(aces, bet) > 0.95);
+        // bluffs make a third of the pot sized bets, the queens call half of them
+        let air = cfr
+            .hole_strategy("6h5h".parse().unwrap(), &board, &check)
+            .unwrap();
+        assert!((prob(air, bet) - 0.5).abs() < 0.05);
+        let queens = cfr
+            .hole_strategy("QcQd".parse().unwrap(), &board, &[check[0], bet])
+            .unwrap();
+        assert!((prob(queens, Action::check_or_call()) - 0.5).abs() < 0.05);
+        assert_eq!(
+            cfr.hole_strategy("KsKd".parse().unwrap(), &board, &check),
+            Some(vec![(Action::check_or_call(), 0.5), (bet, 0.5)])
+        );
+        assert_eq!(
+            cfr.hole_strategy("9d8d".parse().unwrap(), &board, &check),
+            None
+        );
+
+        // the same spot from the turn, the river dealt by chance and the holes bucketed
+        let turn: Board = "Kh9d4c2s".parse().unwrap();
+        let config =
+            TreeConfig::postflop(500, Street::Turn, Chips::new(2000), [Chips::new(10000); 2])
+                .unwrap();
+        let subgame = Subgame::new(config, abstraction, turn, ranges)
+            .unwrap()
+            .with_bucketing(Bucketing::Equity(4));
+        let mut cfr = Cfr::new(subgame);
+        cfr.iterate_sampled(200, &mut ChaCha20Rng::seed_from_u64(1));
+        let line = [
+            Action::check_or_call(),
+            Action::check_or_call(),
+            Action::check_or_call(),
+        ];
+        let aces = cfr
+            .class_strategy("AA".parse().unwrap(), &board, &line)
+            .unwrap();
+        assert!((aces.iter().map(|(_, prob)| prob).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(
+            cfr.class_strategy("AA".parse().unwrap(), &turn, &line),
+            None
+        );
+    }
+}
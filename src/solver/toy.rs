@@ -0,0 +1,300 @@
+// Small poker games with known equilibria for validating solver algorithms.
+use super::{ExtensiveGame, InfosetKey, Turn};
+use itertools::Itertools;
+
+const RANKS: [char; 3] = ['J', 'Q', 'K'];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ToyAction {
+    Deal(u8), // a private deal is encoded as `first * 8 + second`
+    Fold,
+    CheckOrCall,
+    BetOrRaise,
+}
+
+impl ToyAction {
+    fn token(self) -> char {
+        match self {
+            Self::Deal(_) => unreachable!("Deals are not part of the betting history"),
+            Self::Fold => 'f',
+            Self::CheckOrCall => 'c',
+            Self::BetOrRaise => 'r',
+        }
+    }
+}
+
+// Kuhn poker: three cards J, Q, K, one each, ante 1, a single bet of 1.
+// Player `true` is worth -1/18 at every equilibrium.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Kuhn;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct KuhnState {
+    cards: Option<[u8; 2]>,
+    history: Vec<ToyAction>,
+}
+
+impl Kuhn {
+    fn facing_bet(state: &KuhnState) -> bool {
+        state.history.last() == Some(&ToyAction::BetOrRaise)
+    }
+}
+
+impl ExtensiveGame for Kuhn {
+    type State = KuhnState;
+    type Action = ToyAction;
+
+    fn root(&self) -> KuhnState {
+        KuhnState::default()
+    }
+
+    fn turn(&self, state: &KuhnState) -> Turn {
+        use ToyAction::*;
+
+        if state.cards.is_none() {
+            return Turn::Chance;
+        }
+        match state.history.as_slice() {
+            [CheckOrCall, CheckOrCall] | [.., BetOrRaise, _] => Turn::Terminal,
+            history => Turn::Player(history.len().is_multiple_of(2)),
+        }
+    }
+
+    fn actions(&self, state: &KuhnState) -> Vec<ToyAction> {
+        if Self::facing_bet(state) {
+            vec![ToyAction::Fold, ToyAction::CheckOrCall]
+        } else {
+            vec![ToyAction::CheckOrCall, ToyAction::BetOrRaise]
+        }
+    }
+
+    fn chance_outcomes(&self, _state: &KuhnState) -> Vec<(ToyAction, f64)> {
+        (0..3u8)
+            .permutations(2)
+            .map(|cards| (ToyAction::Deal(cards[0] * 8 + cards[1]), 1.0 / 6.0))
+            .collect()
+    }
+
+    fn next(&self, state: &KuhnState, action: ToyAction) -> KuhnState {
+        let mut next = state.clone();
+        match action {
+            ToyAction::Deal(deal) => next.cards = Some([deal / 8, deal % 8]),
+            action => next.history.push(action),
+        }
+        next
+    }
+
+    fn payoff(&self, state: &KuhnState) -> f64 {
+        let cards = state.cards.expect("Terminal states should be dealt");
+        let called = state.history.contains(&ToyAction::BetOrRaise);
+
+        if let Some(folder) = state.history.iter().position(|&a| a == ToyAction::Fold) {
+            // the folder only lost the ante
+            return if folder.is_multiple_of(2) { -1.0 } else { 1.0 };
+        }
+
+        let stake = if called { 2.0 } else { 1.0 };
+        if cards[0] > cards[1] { stake } else { -stake }
+    }
+
+    fn infoset(&self, state: &KuhnState) -> InfosetKey {
+        let cards = state.cards.expect("Players act after the deal");
+        let player = state.history.len() % 2;
+        let history: String = state.history.iter().map(|a| a.token()).collect();
+        InfosetKey::new(format!("{}{}", RANKS[cards[player] as usize], history))
+    }
+}
+
+// Leduc hold'em: two each of J, Q, K, one private card each, ante 1, two betting rounds with
+// raises of 2 then 4 and at most two raises per round, one public card in between.
+// A pair with the public card wins, otherwise the higher card.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Leduc;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct LeducState {
+    private: Option<[u8; 2]>, // deck indices, rank = index / 2
+    public: Option<u8>,
+    rounds: [Vec<ToyAction>; 2],
+}
+
+impl LeducState {
+    fn round(&self) -> usize {
+        if self.public.is_some() { 1 } else { 0 }
+    }
+
+    fn raises(&self, round: usize) -> usize {
+        self.rounds[round]
+            .iter()
+            .filter(|&&a| a == ToyAction::BetOrRaise)
+            .count()
+    }
+
+    fn round_over(&self, round: usize) -> bool {
+        let actions = &self.rounds[round];
+        actions.len() >= 2 && actions.last() == Some(&ToyAction::CheckOrCall)
+    }
+
+    fn folded(&self) -> Option<bool> {
+        self.rounds
+            .iter()
+            .find_map(|actions| actions.iter().position(|&a| a == ToyAction::Fold))
+            .map(|i| i.is_multiple_of(2))
+    }
+
+    // Chips each player put in, antes included.
+    fn invested(&self) -> [f64; 2] {
+        let mut invested = [1.0; 2];
+        for (round, size) in [(0, 2.0), (1, 4.0)] {
+            let mut to_call = 0.0;
+            for (i, action) in self.rounds[round].iter().enumerate() {
+                let player = i % 2;
+                match action {
+                    ToyAction::CheckOrCall => {
+                        invested[player] += to_call;
+                        to_call = 0.0;
+                    }
+                    ToyAction::BetOrRaise => {
+                        invested[player] += to_call + size;
+                        to_call = size;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        invested
+    }
+}
+
+impl ExtensiveGame for Leduc {
+    type State = LeducState;
+    type Action = ToyAction;
+
+    fn root(&self) -> LeducState {
+        LeducState::default()
+    }
+
+    fn turn(&self, state: &LeducState) -> Turn {
+        if state.private.is_none() {
+            return Turn::Chance;
+        }
+        if state.folded().is_some() || state.round_over(1) {
+            return Turn::Terminal;
+        }
+        if state.round_over(0) && state.public.is_none() {
+            return Turn::Chance;
+        }
+
+        Turn::Player(state.rounds[state.round()].len().is_multiple_of(2))
+    }
+
+    fn actions(&self, state: &LeducState) -> Vec<ToyAction> {
+        let round = state.round();
+        let facing_bet = state.rounds[round].last() == Some(&ToyAction::BetOrRaise);
+        let mut actions = Vec::with_capacity(3);
+
+        if facing_bet {
+            actions.push(ToyAction::Fold);
+        }
+        actions.push(ToyAction::CheckOrCall);
+        if state.raises(round) < 2 {
+            actions.push(ToyAction::BetOrRaise);
+        }
+        actions
+    }
+
+    fn chance_outcomes(&self, state: &LeducState) -> Vec<(ToyAction, f64)> {
+        match state.private {
+            None => (0..6u8)
+                .permutations(2)
+                .map(|cards| (ToyAction::Deal(cards[0] * 8 + cards[1]), 1.0 / 30.0))
+                .collect(),
+            Some(private) => (0..6u8)
+                .filter(|card| !private.contains(card))
+                .map(|card| (ToyAction::Deal(card), 1.0 / 4.0))
+                .collect(),
+        }
+    }
+
+    fn next(&self, state: &LeducState, action: ToyAction) -> LeducState {
+        let mut next = state.clone();
+        match action {
+            ToyAction::Deal(deal) if state.private.is_none() => {
+                next.private = Some([deal / 8, deal % 8]);
+            }
+            ToyAction::Deal(card) => next.public = Some(card),
+            action => next.rounds[state.round()].push(action),
+        }
+        next
+    }
+
+    fn payoff(&self, state: &LeducState) -> f64 {
+        let invested = state.invested();
+
+        if let Some(folder) = state.folded() {
+            return if folder { -invested[0] } else { invested[1] };
+        }
+
+        let private = state.private.expect("Terminal states should be dealt");
+        let public = state.public.expect("Showdowns should have the public card") / 2;
+        let strength = |card: u8| {
+            let rank = card / 2;
+            if rank == public { 10 + rank } else { rank }
+        };
+
+        match strength(private[0]).cmp(&strength(private[1])) {
+            std::cmp::Ordering::Greater => invested[1],
+            std::cmp::Ordering::Less => -invested[0],
+            std::cmp::Ordering::Equal => 0.0,
+        }
+    }
+
+    fn infoset(&self, state: &LeducState) -> InfosetKey {
+        let private = state.private.expect("Players act after the deal");
+        let player = state.rounds[state.round()].len() % 2;
+        let public = state
+            .public
+            .map_or(String::new(), |card| RANKS[card as usize / 2].to_string());
+        let history = state
+            .rounds
+            .iter()
+            .map(|actions| actions.iter().map(|a| a.token()).collect::<String>())
+            .join("/");
+
+        InfosetKey::new(format!(
+            "{}{}:{}",
+            RANKS[private[player] as usize / 2],
+            public,
+            history.trim_end_matches('/')
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{best_response_value, expected_value, exploitability};
+
+    // The alpha = 0 member of Kuhn's equilibrium family: the probability of the second action,
+    // which is betting when checked to and calling when facing a bet.
+    fn kuhn_equilibrium(infoset: &InfosetKey, _actions: &[ToyAction]) -> Vec<f64> {
+        let p = match infoset.as_str() {
+            "Kcr" | "Kc" | "Kr" => 1.0,
+            "Qcr" | "Jc" | "Qr" => 1.0 / 3.0,
+            _ => 0.0,
+        };
+        vec![1.0 - p, p]
+    }
+
+    #[test]
+    fn kuhn_equilibrium_value() {
+        let value = expected_value(&Kuhn, &kuhn_equilibrium);
+        assert!((value + 1.0 / 18.0).abs() < 1e-9);
+        assert!(exploitability(&Kuhn, &kuhn_equilibrium).abs() < 1e-9);
+
+        let uniform =
+            |_: &InfosetKey, actions: &[ToyAction]| vec![1.0 / actions.len() as f64; actions.len()];
+        assert!(exploitability(&Leduc, &uniform) > 0.1);
+        assert!(best_response_value(&Leduc, &uniform, true) > expected_value(&Leduc, &uniform));
+    }
+}
@@ -0,0 +1,533 @@
+use super::ActionAbstraction;
+use crate::gameplay::{
+    Street,
+    chips::Chips,
+    headsup::{Action, ActionOver, ActionValue, BetBound, HandState},
+};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct TreeConfig {
+    blind: u16,
+    button: bool,
+    street: Street,
+    pot: Chips,
+    stacks: [Chips; 2],
+}
+
+impl TreeConfig {
+    // Full hand starting with the blinds posted.
+    pub fn preflop(blind: u16, stacks: [Chips; 2]) -> Self {
+        Self {
+            blind,
+            button: true,
+            street: Street::Preflop,
+            pot: Chips::ZERO,
+            stacks,
+        }
+    }
+
+    // Subgame starting at the beginning of a postflop street.
+    pub fn postflop(blind: u16, street: Street, pot: Chips, stacks: [Chips; 2]) -> Option<Self> {
+        if street == Street::Preflop {
+            None // Use `TreeConfig::preflop` instead
+        } else {
+            Some(Self {
+                blind,
+                button: true,
+                street,
+                pot,
+                stacks,
+            })
+        }
+    }
+
+    pub fn with_button(mut self, button: bool) -> Self {
+        self.button = button;
+        self
+    }
+
+    pub fn blind(&self) -> u16 {
+        self.blind
+    }
+
+    pub fn button(&self) -> bool {
+        self.button
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub(super) fn initial_state(&self) -> (HandState, bool) {
+        if self.street == Street::Preflop {
+            let mut state = HandState::new(self.blind, self.button, self.stacks);
+            let betting = state.deal_holes_int().is_some();
+            (state, betting)
+        } else {
+            let state =
+                HandState::postflop(self.blind, self.button, self.street, self.pot, self.stacks);
+            (state, true)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+// Public action history of a node, street separated by `/`.
+// Combine with the acting player's private cards for a full information set.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct InfosetKey(String);
+
+impl InfosetKey {
+    pub(super) fn new(key: String) -> Self {
+        Self(key)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn push(&self, token: &str) -> Self {
+        Self(format!("{}{}", self.0, token))
+    }
+}
+
+impl Display for InfosetKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Terminal {
+    Fold(bool), // winner
+    Showdown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum NodeKind {
+    Decision {
+        player: bool,
+        bet_bound: BetBound,
+        children: Vec<(Action, NodeId)>,
+    },
+    Chance {
+        street: Street,
+        child: NodeId,
+    },
+    Terminal(Terminal),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Node {
+    parent: Option<NodeId>,
+    street: Street,
+    button: bool,
+    pot: Chips,
+    stacks: [Chips; 2], // chips behind, not yet committed
+    key: InfosetKey,
+    kind: NodeKind,
+}
+
+impl Node {
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn pot(&self) -> Chips {
+        self.pot
+    }
+
+    pub fn stacks(&self) -> [Chips; 2] {
+        self.stacks
+    }
+
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    pub fn player(&self) -> Option<bool> {
+        match self.kind {
+            NodeKind::Decision { player, .. } => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn children(&self) -> Vec<NodeId> {
+        match &self.kind {
+            NodeKind::Decision { children, .. } => children.iter().map(|(_, id)| *id).collect(),
+            NodeKind::Chance { child, .. } => vec![*child],
+            NodeKind::Terminal(_) => vec![],
+        }
+    }
+
+    pub fn actions(&self) -> Vec<Action> {
+        match &self.kind {
+            NodeKind::Decision { children, .. } => children.iter().map(|(a, _)| *a).collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self.kind, NodeKind::Terminal(_))
+    }
+
+    // Final stacks of both players given who wins the showdown (`None` for a chop).
+    pub fn payoff(&self, showdown_winner: Option<bool>) -> Option<[Chips; 2]> {
+        let NodeKind::Terminal(terminal) = self.kind else {
+            return None;
+        };
+        let mut stacks = self.stacks;
+
+        match (terminal, showdown_winner) {
+            (Terminal::Fold(_), _) => {}
+            (Terminal::Showdown, Some(winner)) => stacks[if winner { 0 } else { 1 }] += self.pot,
+            (Terminal::Showdown, None) => {
+                // the odd chip goes out of position, as the table chops it
+                let share = self.pot / 2 + (self.pot % 2) * (!self.button as u32);
+                stacks[0] += share;
+                stacks[1] += self.pot - share;
+            }
+        }
+
+        Some(stacks)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    config: TreeConfig,
+    abstraction: ActionAbstraction,
+    nodes: Vec<Node>,
+}
+
+struct Pending {
+    id: NodeId,
+    state: HandState,
+    raises: u8,
+}
+
+impl GameTree {
+    pub fn build(config: TreeConfig, abstraction: ActionAbstraction) -> Self {
+        let mut tree = Self {
+            config,
+            abstraction,
+            nodes: Vec::new(),
+        };
+        let (state, betting) = config.initial_state();
+
+        if betting {
+            let root = tree.push_decision(None, &state, Default::default());
+            let mut pending = vec![Pending {
+                id: root,
+                state,
+                raises: 0,
+            }];
+
+            while let Some(next) = pending.pop() {
+                tree.expand(next, &mut pending);
+            }
+        } else {
+            tree.push_terminal(None, &state, Terminal::Showdown, Default::default());
+        }
+
+        tree
+    }
+
+    pub fn config(&self) -> TreeConfig {
+        self.config
+    }
+
+    pub fn abstraction(&self) -> &ActionAbstraction {
+        &self.abstraction
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (NodeId(i), node))
+    }
+
+    pub fn decision_nodes(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.iter().filter(|(_, node)| node.player().is_some())
+    }
+
+    pub fn child(&self, id: NodeId, action: Action) -> Option<NodeId> {
+        match &self.node(id).kind {
+            NodeKind::Decision { children, .. } => children
+                .iter()
+                .find(|(a, _)| *a == action)
+                .map(|(_, child)| *child),
+            _ => None,
+        }
+    }
+
+    pub fn infoset_key(&self, id: NodeId) -> &InfosetKey {
+        &self.node(id).key
+    }
+
+    // Actions leading from the root to this node.
+    pub fn line(&self, id: NodeId) -> Vec<Action> {
+        let mut line = Vec::new();
+        let mut cur = id;
+
+        while let Some(parent) = self.node(cur).parent {
+            if let NodeKind::Decision { children, .. } = &self.node(parent).kind
+                && let Some((action, _)) = children.iter().find(|(_, child)| *child == cur)
+            {
+                line.push(*action);
+            }
+            cur = parent;
+        }
+
+        line.reverse();
+        line
+    }
+
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    fn push_decision(
+        &mut self,
+        parent: Option<NodeId>,
+        state: &HandState,
+        key: InfosetKey,
+    ) -> NodeId {
+        let behinds = state.behinds();
+        let cur_round = state.cur_round();
+
+        self.push(Node {
+            parent,
+            street: state.street(),
+            button: state.button(),
+            pot: state.total_pot(),
+            stacks: [behinds[0] - cur_round[0], behinds[1] - cur_round[1]],
+            key,
+            kind: NodeKind::Decision {
+                player: state.cur_turn(),
+                bet_bound: state.bet_bound(),
+                children: Vec::new(),
+            },
+        })
+    }
+
+    fn push_terminal(
+        &mut self,
+        parent: Option<NodeId>,
+        state: &HandState,
+        terminal: Terminal,
+        key: InfosetKey,
+    ) -> NodeId {
+        let pot = match terminal {
+            Terminal::Fold(_) => Chips::ZERO, // already awarded
+            Terminal::Showdown => state.pot(),
+        };
+
+        self.push(Node {
+            parent,
+            street: state.street(),
+            button: state.button(),
+            pot,
+            stacks: state.behinds(),
+            key,
+            kind: NodeKind::Terminal(terminal),
+        })
+    }
+
+    fn expand(&mut self, pending: Pending, queue: &mut Vec<Pending>) {
+        let Pending { id, state, raises } = pending;
+        let key = self.node(id).key.clone();
+        let can_check = state.can_check();
+        let mut children = Vec::new();
+
+        for action in self.abstraction.actions_at(&state, raises) {
+            let mut next = state;
+            let token = history_token(action, can_check);
+            let player = next.cur_turn();
+            let aggressive = is_aggressive(&next, action);
+
+            let child = match next.action(action) {
+                ActionOver::TurnOver => {
+                    let child = self.push_decision(Some(id), &next, key.push(&token));
+                    queue.push(Pending {
+                        id: child,
+                        state: next,
+                        raises: raises + aggressive as u8,
+                    });
+                    child
+                }
+                ActionOver::RoundOver => {
+                    let street = next.street().next().expect("River never ends a round");
+                    let chance = self.push(Node {
+                        parent: Some(id),
+                        street,
+                        button: next.button(),
+                        pot: next.total_pot(),
+                        stacks: next.behinds(),
+                        key: key.push(&token),
+                        kind: NodeKind::Chance {
+                            street,
+                            child: NodeId(usize::MAX),
+                        },
+                    });
+
+                    next.next_street();
+                    let child =
+                        self.push_decision(Some(chance), &next, key.push(&format!("{}/", token)));
+                    self.nodes[chance.0].kind = NodeKind::Chance { street, child };
+                    queue.push(Pending {
+                        id: child,
+                        state: next,
+                        raises: 0,
+                    });
+                    chance
+                }
+                ActionOver::ShowdownAll | ActionOver::ShowndownRiver => {
+                    self.push_terminal(Some(id), &next, Terminal::Showdown, key.push(&token))
+                }
+                ActionOver::HandOver => {
+                    self.push_terminal(Some(id), &next, Terminal::Fold(!player), key.push(&token))
+                }
+            };
+            children.push((action, child));
+        }
+
+        if let NodeKind::Decision { children: slot, .. } = &mut self.nodes[id.0].kind {
+            *slot = children;
+        }
+    }
+}
+
+// Bets, raises and all ins that put the opponent to a decision.
+pub(super) fn is_aggressive(state: &HandState, action: Action) -> bool {
+    match action.value() {
+        ActionValue::BetOrRaise(_) => true,
+        ActionValue::AllIn => state.bet_bound() != BetBound::FoldAllIn,
+        _ => false,
+    }
+}
+
+pub(super) fn history_token(action: Action, can_check: bool) -> String {
+    match action.value() {
+        ActionValue::Exit | ActionValue::Fold => "f".into(),
+        ActionValue::CheckOrCall => if can_check { "x" } else { "c" }.into(),
+        ActionValue::BetOrRaise(amount) => format!("b{}", amount),
+        ActionValue::AllIn => "a".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn river_subgame() {
+        let config = TreeConfig::postflop(
+            500,
+            Street::River,
+            Chips::new(2000),
+            [Chips::new(9000), Chips::new(9000)],
+        )
+        .unwrap();
+        let tree = GameTree::build(config, ActionAbstraction::new(vec![1.0], vec![]));
+        let root = tree.root();
+
+        assert_eq!(tree.node(root).player(), Some(false));
+        let bet = tree
+            .child(root, Action::bet_or_raise(Chips::new(2000)).unwrap())
+            .unwrap();
+        assert_eq!(tree.infoset_key(bet).as_str(), "b2000");
+
+        let fold = tree.child(bet, Action::fold()).unwrap();
+        assert_eq!(
+            tree.node(fold).payoff(None),
+            Some([Chips::new(9000), Chips::new(11000)])
+        );
+
+        let call = tree.child(bet, Action::check_or_call()).unwrap();
+        assert_eq!(
+            tree.node(call).payoff(Some(true)),
+            Some([Chips::new(13000), Chips::new(7000)])
+        );
+        assert_eq!(
+            tree.line(call),
+            [
+                Action::bet_or_raise(Chips::new(2000)).unwrap(),
+                Action::check_or_call()
+            ]
+        );
+    }
+
+    #[test]
+    fn odd_chop_goes_out_of_position() {
+        for button in [true, false] {
+            let config = TreeConfig::postflop(
+                500,
+                Street::River,
+                Chips::new(2001),
+                [Chips::new(9000), Chips::new(9000)],
+            )
+            .unwrap()
+            .with_button(button);
+            let tree = GameTree::build(config, Default::default());
+            let check = tree.child(tree.root(), Action::check_or_call()).unwrap();
+            let showdown = tree.child(check, Action::check_or_call()).unwrap();
+
+            let (out, on) = if button { (1, 0) } else { (0, 1) };
+            let stacks = tree.node(showdown).payoff(None).unwrap();
+            assert_eq!(stacks[out], Chips::new(10001));
+            assert_eq!(stacks[on], Chips::new(10000));
+        }
+    }
+
+    #[test]
+    fn preflop_limp_gives_option() {
+        let tree = GameTree::build(
+            TreeConfig::preflop(500, [Chips::new(5000), Chips::new(5000)]),
+            Default::default(),
+        );
+        let limp = tree.child(tree.root(), Action::check_or_call()).unwrap();
+
+        assert_eq!(tree.node(limp).player(), Some(false));
+        assert_eq!(tree.node(limp).pot(), Chips::new(1000));
+        let check = tree.child(limp, Action::check_or_call()).unwrap();
+        assert!(matches!(
+            tree.node(check).kind(),
+            NodeKind::Chance {
+                street: Street::Flop,
+                ..
+            }
+        ));
+    }
+}
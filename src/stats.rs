@@ -0,0 +1,285 @@
+use crate::gameplay::{
+    Board, Street,
+    chips::Chips,
+    headsup::{Action, ActionOver, ActionValue, HandState, ObservableEvent},
+};
+use std::io::{self, Write};
+
+pub mod heatmap;
+pub mod profiles;
+pub mod review;
+pub mod tracker;
+
+pub use heatmap::Heatmap;
+pub use profiles::Profiles;
+pub use review::{DecisionEv, EvBackend, MistakesReview, ShowdownEv};
+pub use tracker::{StatsTracker, StreetStats, TrackedStats};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionRecord {
+    actor: bool,
+    street: Street,
+    action: Action,
+    facing_bet: bool,
+    raises: u8, // voluntary bets and raises earlier on this street
+}
+
+impl ActionRecord {
+    pub fn new(actor: bool, street: Street, action: Action, facing_bet: bool, raises: u8) -> Self {
+        Self {
+            actor,
+            street,
+            action,
+            facing_bet,
+            raises,
+        }
+    }
+
+    pub fn actor(&self) -> bool {
+        self.actor
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    pub fn facing_bet(&self) -> bool {
+        self.facing_bet
+    }
+
+    pub fn raises(&self) -> u8 {
+        self.raises
+    }
+
+    pub fn is_aggressive(&self) -> bool {
+        matches!(
+            self.action.value(),
+            ActionValue::BetOrRaise(_) | ActionValue::AllIn
+        )
+    }
+
+    pub fn is_call(&self) -> bool {
+        self.facing_bet && self.action.value() == ActionValue::CheckOrCall
+    }
+}
+
+// Attributes the actions of a bare event line to seats by replaying the betting rules.
+// Chip amounts don't matter for who acts next, so stacks are treated as unlimited.
+#[derive(Debug, Clone)]
+pub struct LineWalker {
+    button: bool,
+    state: HandState,
+    raises: u8,
+}
+
+impl LineWalker {
+    const DEEP: Chips = Chips::new(u32::MAX / 4);
+
+    pub fn new(button: bool) -> Self {
+        Self {
+            button,
+            state: HandState::new(2, button, [Self::DEEP; 2]),
+            raises: 0,
+        }
+    }
+
+    pub fn street(&self) -> Street {
+        self.state.street()
+    }
+
+    pub fn board(&self) -> Board {
+        self.state.board()
+    }
+
+    pub fn step(&mut self, event: &ObservableEvent) -> Option<ActionRecord> {
+        match event {
+            ObservableEvent::DealHoles(_) => {
+                self.state = HandState::new(2, self.button, [Self::DEEP; 2]);
+                self.state.deal_holes_int();
+                self.raises = 0;
+                None
+            }
+            ObservableEvent::PlayerAction(action, _) => {
+                let record = ActionRecord::new(
+                    self.state.cur_turn(),
+                    self.state.street(),
+                    *action,
+                    !self.state.can_check(),
+                    self.raises,
+                );
+
+                if record.is_aggressive() {
+                    self.raises += 1;
+                }
+                if self.state.action(*action) == ActionOver::RoundOver {
+                    self.state.next_street();
+                    self.raises = 0;
+                }
+                Some(record)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn records(button: bool, line: &[ObservableEvent]) -> Vec<ActionRecord> {
+        let mut walker = Self::new(button);
+        line.iter().filter_map(|event| walker.step(event)).collect()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PlayerStats {
+    hands: u32,
+    vpip: u32,
+    pfr: u32,
+    limps: u32,
+    faced_bets: u32,
+    folds_to_bet: u32,
+    bets_raises: u32,
+    calls: u32,
+
+    // current hand flags
+    cur_vpip: bool,
+    cur_pfr: bool,
+}
+
+impl PlayerStats {
+    pub fn new_hand(&mut self) {
+        self.hands += 1;
+        self.cur_vpip = false;
+        self.cur_pfr = false;
+    }
+
+    pub fn record(&mut self, record: &ActionRecord) {
+        if record.facing_bet {
+            self.faced_bets += 1;
+            if matches!(record.action.value(), ActionValue::Fold | ActionValue::Exit) {
+                self.folds_to_bet += 1;
+            }
+        }
+
+        if record.is_aggressive() {
+            self.bets_raises += 1;
+        } else if record.is_call() {
+            self.calls += 1;
+        }
+
+        if record.street == Street::Preflop {
+            let voluntary = record.is_aggressive() || record.is_call();
+
+            if record.raises == 0 && record.is_call() {
+                self.limps += 1; // just completing the blind
+            }
+            if voluntary && !self.cur_vpip {
+                self.cur_vpip = true;
+                self.vpip += 1;
+            }
+            if record.is_aggressive() && !self.cur_pfr {
+                self.cur_pfr = true;
+                self.pfr += 1;
+            }
+        }
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn vpip(&self) -> f64 {
+        ratio(self.vpip, self.hands)
+    }
+
+    pub fn pfr(&self) -> f64 {
+        ratio(self.pfr, self.hands)
+    }
+
+    pub fn limp(&self) -> f64 {
+        ratio(self.limps, self.hands)
+    }
+
+    pub fn fold_to_bet(&self) -> f64 {
+        ratio(self.folds_to_bet, self.faced_bets)
+    }
+
+    // (bets + raises) / calls
+    pub fn aggression_factor(&self) -> f64 {
+        if self.calls == 0 {
+            self.bets_raises as f64
+        } else {
+            self.bets_raises as f64 / self.calls as f64
+        }
+    }
+}
+
+// The counters of `stats` after `key`, tab separated; the flags of a hand in progress are dropped.
+pub(crate) fn write_stats(
+    mut writer: impl Write,
+    key: &str,
+    stats: &PlayerStats,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        key,
+        stats.hands,
+        stats.vpip,
+        stats.pfr,
+        stats.limps,
+        stats.faced_bets,
+        stats.folds_to_bet,
+        stats.bets_raises,
+        stats.calls
+    )
+}
+
+pub(crate) fn parse_stats(line: &str) -> Option<(&str, PlayerStats)> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [key, counters @ ..] = &fields[..] else {
+        return None;
+    };
+    let counters: Vec<u32> = counters
+        .iter()
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+    let [
+        hands,
+        vpip,
+        pfr,
+        limps,
+        faced_bets,
+        folds_to_bet,
+        bets_raises,
+        calls,
+    ] = counters[..]
+    else {
+        return None;
+    };
+
+    Some((
+        key,
+        PlayerStats {
+            hands,
+            vpip,
+            pfr,
+            limps,
+            faced_bets,
+            folds_to_bet,
+            bets_raises,
+            calls,
+            ..Default::default()
+        },
+    ))
+}
+
+fn ratio(num: u32, den: u32) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
@@ -0,0 +1,118 @@
+// Where a player makes and loses money over a session: the big blinds one seat won by starting
+// hand, split by whether they were on the button or in the big blind.
+use crate::{bot::HandRecord, gameplay::HoleClass, range::RangeGrid};
+use indexmap::IndexMap;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+struct Cell {
+    hands: u32,
+    won: f64, // in big blinds
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Heatmap {
+    cells: [IndexMap<HoleClass, Cell>; 2], // [button, big blind]
+}
+
+impl Heatmap {
+    // The results of `seat` over `hands`.
+    pub fn from_hands<'a>(seat: bool, hands: impl IntoIterator<Item = &'a HandRecord>) -> Self {
+        let mut heatmap = Self::default();
+        for hand in hands {
+            heatmap.add(seat, hand);
+        }
+        heatmap
+    }
+
+    pub fn add(&mut self, seat: bool, hand: &HandRecord) {
+        let deal = hand.deal();
+        let index = if seat { 0 } else { 1 };
+        let class = HoleClass::from(deal.holes()[index]);
+        let position = if deal.button() == seat { 0 } else { 1 };
+
+        let cell = self.cells[position].entry(class).or_default();
+        cell.hands += 1;
+        cell.won += hand.results()[index];
+    }
+
+    pub fn hands(&self, button: bool, class: HoleClass) -> u32 {
+        self.cell(button, class).hands
+    }
+
+    // Big blinds won with `class` in the position, negative for a loss.
+    pub fn won(&self, button: bool, class: HoleClass) -> f64 {
+        self.cell(button, class).won
+    }
+
+    // Big blinds won in the position over every class.
+    pub fn total(&self, button: bool) -> f64 {
+        self.position(button).values().map(|cell| cell.won).sum()
+    }
+
+    // Winnings by class in the position as the 13 x 13 chart, classes never dealt left empty.
+    pub fn grid(&self, button: bool) -> RangeGrid {
+        let cells = self.position(button).iter();
+        RangeGrid::from_classes(cells.map(|(&class, cell)| (class, cell.won))).with_precision(1)
+    }
+
+    fn position(&self, button: bool) -> &IndexMap<HoleClass, Cell> {
+        &self.cells[if button { 0 } else { 1 }]
+    }
+
+    fn cell(&self, button: bool, class: HoleClass) -> Cell {
+        self.position(button)
+            .get(&class)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Display for Heatmap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (button, name) in [(true, "Button"), (false, "Big blind")] {
+            let hands: u32 = self.position(button).values().map(|cell| cell.hands).sum();
+            let total = self.total(button);
+            writeln!(f, "{name}: {total:+.1} bb in {hands} hands")?;
+            writeln!(f, "{}", self.grid(button))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Deal,
+        gameplay::{Board, Value},
+    };
+
+    #[test]
+    fn winnings_by_position_and_class() {
+        let runout = "2c3d7h8sJc".parse::<Board>().unwrap();
+        let runout = *runout.as_full_board().unwrap();
+        let hand = |holes: [&str; 2], button, results| {
+            let deal = Deal::new(holes.map(|hole| hole.parse().unwrap()), runout, button).unwrap();
+            HandRecord::new(deal, Vec::new(), results)
+        };
+        let hands = [
+            hand(["AsAh", "KcQd"], true, [3.0, -3.0]),
+            hand(["AdAc", "KdQc"], true, [12.5, -12.5]),
+            hand(["7c2d", "AsKs"], false, [-1.0, 1.0]),
+        ];
+
+        let heatmap = Heatmap::from_hands(true, &hands);
+        let aces = HoleClass::Pair(Value::Ace);
+        assert_eq!(heatmap.hands(true, aces), 2);
+        assert_eq!(heatmap.won(true, aces), 15.5);
+        assert_eq!(heatmap.total(false), -1.0);
+        assert_eq!(heatmap.hands(false, aces), 0);
+
+        let grid = heatmap.grid(false).to_string();
+        let rows: Vec<_> = grid.lines().collect();
+        assert_eq!(rows.len(), 14);
+        assert!(rows[0].starts_with("     A    K"));
+        assert_eq!(rows[13].split_whitespace().nth(8), Some("-1.0")); // 72o, below the pairs
+    }
+}
@@ -0,0 +1,127 @@
+// What bots learned about each opponent, kept between sessions and keyed by player id, so
+// exploitation picks up where the last match left off.
+use super::{PlayerStats, parse_stats, write_stats};
+use crate::bot::Bot;
+use indexmap::IndexMap;
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter},
+    path::PathBuf,
+};
+
+#[derive(Debug)]
+pub struct Profiles {
+    path: PathBuf,
+    profiles: IndexMap<String, PlayerStats>,
+}
+
+impl Profiles {
+    // A missing file has no profiles yet.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut profiles = IndexMap::new();
+
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    let (id, stats) = parse_stats(&line).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("Bad profile: {line}"))
+                    })?;
+                    profiles.insert(id.to_string(), stats);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        Ok(Self { path, profiles })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&PlayerStats> {
+        self.profiles.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PlayerStats)> {
+        self.profiles.iter().map(|(id, stats)| (id.as_str(), stats))
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    // Replaces the profile of `id` and rewrites the file.
+    pub fn save(&mut self, id: &str, stats: PlayerStats) -> io::Result<()> {
+        self.profiles.insert(id.to_string(), stats);
+        self.flush()
+    }
+
+    // Gives `bot` the profile of its opponent `id` before a match, if there is one.
+    pub fn prepare(&self, id: &str, bot: &mut impl Bot) -> bool {
+        self.get(id).map(|stats| bot.load_opponent(stats)).is_some()
+    }
+
+    // Saves what `bot` learned about `id` over a match; nothing for bots that don't track it.
+    pub fn remember(&mut self, id: &str, bot: &impl Bot) -> io::Result<()> {
+        match bot.opponent_stats() {
+            Some(stats) => self.save(id, stats),
+            None => Ok(()),
+        }
+    }
+
+    // Same write-and-rename as the ratings file, so a crash never leaves half a file behind.
+    fn flush(&self) -> io::Result<()> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for (id, stats) in &self.profiles {
+            write_stats(&mut writer, id, stats)?;
+        }
+        writer.into_inner()?.sync_all()?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::ExploitBot,
+        gameplay::{Street, headsup::Action},
+        stats::ActionRecord,
+    };
+
+    #[test]
+    fn profiles_carry_over() {
+        let path = std::env::temp_dir().join(format!("profiles-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut profiles = Profiles::open(&path).unwrap();
+        let mut bot = ExploitBot::new(5);
+        assert!(!profiles.prepare("villain", &mut bot));
+
+        let fold = ActionRecord::new(false, Street::Flop, Action::fold(), true, 1);
+        for _ in 0..10 {
+            bot.new_hand();
+            bot.observe(true, &fold);
+        }
+        profiles.remember("villain", &bot).unwrap();
+
+        let profiles = Profiles::open(&path).unwrap();
+        assert_eq!(profiles.len(), 1);
+        let mut next = ExploitBot::new(5);
+        assert!(profiles.prepare("villain", &mut next));
+        assert_eq!(next.opponent(), bot.opponent());
+        assert_eq!(next.adjustments(), bot.adjustments());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+// Played hands gone over decision by decision: what each action was worth next to the best
+// alternative a backend knows, and how much EV every player gave away over the session.
+use crate::{
+    bot::Decision,
+    equity::{hole_equity, range_equity},
+    gameplay::{
+        Street,
+        headsup::{Action, ActionValue, history::HandHistory},
+    },
+    range::Range,
+    solver::ActionAbstraction,
+};
+use std::fmt::{self, Display, Formatter};
+
+// Values the options at a decision, from a solved strategy, an equity model or anything else.
+pub trait EvBackend {
+    // The acting player's EV in chips of each of `actions`, folding being worth 0; None if the
+    // spot can't be valued, e.g. without the actor's hole.
+    fn action_evs(&self, decision: &Decision, actions: &[Action]) -> Option<Vec<f64>>;
+}
+
+// Every bet gets called and the hand checked down: equity against the opponent's hole when it
+// was seen, against `villain` otherwise.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowdownEv {
+    villain: Range,
+}
+
+impl Default for ShowdownEv {
+    fn default() -> Self {
+        Self::new(Range::full())
+    }
+}
+
+impl ShowdownEv {
+    pub fn new(villain: Range) -> Self {
+        Self { villain }
+    }
+
+    fn equity(&self, decision: &Decision) -> Option<f64> {
+        let hole = decision.hole()?;
+        let holes = decision.state().holes();
+        let villain = holes[if decision.seat() { 1 } else { 0 }];
+
+        match villain {
+            Some(villain) => hole_equity([hole, villain], &decision.board()),
+            None => {
+                let mut hero = Range::new();
+                hero.set(hole, 1.0);
+                range_equity(&hero, &self.villain, &decision.board())
+            }
+        }
+    }
+}
+
+impl EvBackend for ShowdownEv {
+    fn action_evs(&self, decision: &Decision, actions: &[Action]) -> Option<Vec<f64>> {
+        let equity = self.equity(decision)?;
+        let [hero_bet, villain_bet] = decision.bets().map(|bet| bet.get() as f64);
+        let [hero_stack, villain_stack] = decision.stacks().map(|stack| stack.get() as f64);
+        let pot = decision.pot().get() as f64;
+
+        let evs = actions.iter().map(|action| {
+            let to = match action.value() {
+                ActionValue::Exit | ActionValue::Fold => return 0.0,
+                ActionValue::CheckOrCall => villain_bet.min(hero_bet + hero_stack),
+                ActionValue::BetOrRaise(to) => to.get() as f64,
+                ActionValue::AllIn => hero_bet + hero_stack,
+            };
+            // what the opponent can't match goes back
+            let to = to.min(villain_bet + villain_stack);
+            equity * (pot - hero_bet - villain_bet + 2.0 * to) - (to - hero_bet)
+        });
+        Some(evs.collect())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DecisionEv {
+    pub hand: u32,
+    pub seat: bool,
+    pub street: Street,
+    pub action: Action,
+    pub ev: f64,
+    pub best: Action,
+    pub best_ev: f64,
+}
+
+impl DecisionEv {
+    pub fn lost(&self) -> f64 {
+        (self.best_ev - self.ev).max(0.0)
+    }
+}
+
+// Alternatives are the abstraction's actions at each decision, raise caps aside.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MistakesReview {
+    abstraction: ActionAbstraction,
+    decisions: Vec<DecisionEv>,
+    lost: [f64; 2],
+}
+
+impl MistakesReview {
+    pub fn new(abstraction: ActionAbstraction) -> Self {
+        Self {
+            abstraction,
+            decisions: vec![],
+            lost: [0.0; 2],
+        }
+    }
+
+    // Decisions the backend can't value are left out.
+    pub fn add(&mut self, hand: &HandHistory, backend: &impl EvBackend) {
+        for (decision, action) in hand.decisions() {
+            let mut actions =
+                self.abstraction
+                    .actions(decision.bet_bound(), decision.pot(), decision.bets(), 0);
+            if !actions.contains(&action) {
+                actions.insert(0, action);
+            }
+            let Some(evs) = backend.action_evs(&decision, &actions) else {
+                continue;
+            };
+
+            let ev = evs[actions.iter().position(|&a| a == action).unwrap()];
+            let (best, best_ev) = actions
+                .into_iter()
+                .zip(evs)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .unwrap();
+            let reviewed = DecisionEv {
+                hand: hand.number(),
+                seat: decision.seat(),
+                street: decision.street(),
+                action,
+                ev,
+                best,
+                best_ev,
+            };
+            self.lost[if reviewed.seat { 0 } else { 1 }] += reviewed.lost();
+            self.decisions.push(reviewed);
+        }
+    }
+
+    pub fn decisions(&self) -> &[DecisionEv] {
+        &self.decisions
+    }
+
+    // Chips `seat` gave away over every hand added.
+    pub fn lost(&self, seat: bool) -> f64 {
+        self.lost[if seat { 0 } else { 1 }]
+    }
+
+    // Decisions that lost EV, costliest first.
+    pub fn mistakes(&self) -> Vec<DecisionEv> {
+        let mut mistakes: Vec<_> = self
+            .decisions
+            .iter()
+            .filter(|decision| decision.lost() > 0.0)
+            .copied()
+            .collect();
+        mistakes.sort_by(|a, b| b.lost().total_cmp(&a.lost()));
+        mistakes
+    }
+}
+
+impl Display for MistakesReview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for mistake in self.mistakes() {
+            let DecisionEv {
+                hand,
+                seat,
+                street,
+                action,
+                ev,
+                best,
+                best_ev,
+            } = mistake;
+            writeln!(
+                f,
+                "Hand #{hand} {street:?}, player {}: {action} ({ev:.1}), best {best} ({best_ev:.1}), lost {:.1}",
+                if seat { 0 } else { 1 },
+                mistake.lost(),
+            )?;
+        }
+        write!(
+            f,
+            "EV lost: player 0 {:.1}, player 1 {:.1}",
+            self.lost[0], self.lost[1]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        chips::Chips,
+        headsup::{HandState, ObservableEvent, TurnId, clock::Timed},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn calling_a_shove_with_seven_deuce() {
+        let state = HandState::new(500, true, [Chips::new(50000); 2]);
+        let mut hand = HandHistory::new(1, &state);
+        let holes = ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()];
+        for event in [
+            ObservableEvent::DealHoles(holes.map(Some)),
+            ObservableEvent::PlayerAction(Action::all_in(), TurnId::new(0)),
+            ObservableEvent::PlayerAction(Action::check_or_call(), TurnId::new(1)),
+            ObservableEvent::ShowdownAll(holes),
+            ObservableEvent::DealBoard("Kd9h4s3cJd".parse().unwrap()),
+        ] {
+            hand.push(Timed::new(Duration::ZERO, event));
+        }
+
+        let mut review = MistakesReview::default();
+        review.add(&hand, &ShowdownEv::default());
+        assert_eq!(review.decisions().len(), 2);
+        assert_eq!(review.lost(true), 0.0);
+
+        let call = review.mistakes()[0];
+        assert_eq!((call.seat, call.street), (false, Street::Preflop));
+        assert_eq!(call.best, Action::fold());
+        assert!(call.lost() > 30000.0, "{}", review);
+        assert!(
+            review
+                .to_string()
+                .starts_with("Hand #1 Preflop, player 1: c (")
+        );
+    }
+}
@@ -0,0 +1,272 @@
+// Session statistics of both seats, tallied from the events of finished hands: how loose and how
+// aggressive each player is preflop, whether they follow through on the flop and how often they
+// see a showdown, with the betting split by street.
+use super::{ActionRecord, LineWalker, PlayerStats};
+use crate::gameplay::{
+    Street,
+    headsup::{ActionValue, ObservableEvent, Observer, history::HandHistory},
+};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct StreetStats {
+    pub bets_raises: u32,
+    pub calls: u32,
+    pub checks: u32,
+    pub folds: u32,
+}
+
+impl StreetStats {
+    fn record(&mut self, record: &ActionRecord) {
+        match record.action().value() {
+            _ if record.is_aggressive() => self.bets_raises += 1,
+            _ if record.is_call() => self.calls += 1,
+            ActionValue::CheckOrCall => self.checks += 1,
+            _ => self.folds += 1,
+        }
+    }
+
+    // (bets + raises) / calls
+    pub fn aggression_factor(&self) -> f64 {
+        if self.calls == 0 {
+            self.bets_raises as f64
+        } else {
+            self.bets_raises as f64 / self.calls as f64
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct TrackedStats {
+    player: PlayerStats,
+    three_bet_chances: u32, // facing the first raise preflop
+    three_bets: u32,
+    cbet_chances: u32, // first to bet on the flop after raising last preflop
+    cbets: u32,
+    flops: u32,
+    showdowns: u32, // of the hands that saw a flop
+    streets: [StreetStats; 4],
+}
+
+impl TrackedStats {
+    // VPIP, PFR, limps and folds to bets, as bots and profiles keep them.
+    pub fn player_stats(&self) -> PlayerStats {
+        self.player
+    }
+
+    pub fn hands(&self) -> u32 {
+        self.player.hands()
+    }
+
+    pub fn vpip(&self) -> f64 {
+        self.player.vpip()
+    }
+
+    pub fn pfr(&self) -> f64 {
+        self.player.pfr()
+    }
+
+    pub fn three_bet(&self) -> f64 {
+        super::ratio(self.three_bets, self.three_bet_chances)
+    }
+
+    pub fn cbet(&self) -> f64 {
+        super::ratio(self.cbets, self.cbet_chances)
+    }
+
+    // Went to showdown, of the flops seen.
+    pub fn wtsd(&self) -> f64 {
+        super::ratio(self.showdowns, self.flops)
+    }
+
+    pub fn aggression_factor(&self) -> f64 {
+        self.player.aggression_factor()
+    }
+
+    pub fn street(&self, street: Street) -> StreetStats {
+        self.streets[street as usize]
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct StatsTracker {
+    seats: [TrackedStats; 2], // [player 0, player 1]
+}
+
+impl StatsTracker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn seat(&self, seat: bool) -> &TrackedStats {
+        &self.seats[index(seat)]
+    }
+
+    pub fn add(&mut self, hand: &HandHistory) {
+        let mut walker = LineWalker::new(hand.button());
+        let mut records = vec![];
+        let mut showdown = false;
+        for event in hand.events() {
+            records.extend(walker.step(&event.event));
+            showdown |= matches!(
+                event.event,
+                ObservableEvent::ShowdownAll(_)
+                    | ObservableEvent::ShowdownAuto(_)
+                    | ObservableEvent::EarlyAward(_)
+                    | ObservableEvent::ShowdownShow(..)
+                    | ObservableEvent::ShowdownMuck(_)
+            );
+        }
+
+        let flop = records
+            .iter()
+            .any(|record| record.street() != Street::Preflop);
+        // the preflop raiser is who raised last, they may c-bet unless bet into first
+        let raiser = records
+            .iter()
+            .filter(|record| record.street() == Street::Preflop && record.is_aggressive())
+            .map(ActionRecord::actor)
+            .next_back();
+
+        for seat in [true, false] {
+            let stats = &mut self.seats[index(seat)];
+            stats.player.new_hand();
+            if flop {
+                stats.flops += 1;
+                stats.showdowns += showdown as u32;
+            }
+
+            let mut cbet_chance = raiser == Some(seat);
+            for record in records.iter().filter(|record| record.actor() == seat) {
+                stats.player.record(record);
+                stats.streets[record.street() as usize].record(record);
+
+                if record.street() == Street::Preflop && record.raises() == 1 {
+                    stats.three_bet_chances += 1;
+                    stats.three_bets += record.is_aggressive() as u32;
+                }
+                if record.street() == Street::Flop && cbet_chance {
+                    cbet_chance = false;
+                    if record.raises() == 0 {
+                        stats.cbet_chances += 1;
+                        stats.cbets += record.is_aggressive() as u32;
+                    }
+                }
+            }
+        }
+    }
+
+    // Adds every hand `observer` finishes until the game is over.
+    pub async fn follow(&mut self, observer: &mut Observer) {
+        let mut added = observer.hands_history().len();
+        while observer.tick_event().await.is_some() {
+            for hand in &observer.hands_history()[added..] {
+                self.add(hand);
+            }
+            added = observer.hands_history().len();
+        }
+    }
+}
+
+fn index(seat: bool) -> usize {
+    if seat { 0 } else { 1 }
+}
+
+impl Display for StatsTracker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (seat, stats) in [(0, &self.seats[0]), (1, &self.seats[1])] {
+            writeln!(
+                f,
+                "Player {seat}: {} hands, VPIP {:.1}%, PFR {:.1}%, 3-bet {:.1}%, c-bet {:.1}%, WTSD {:.1}%, AF {:.2}",
+                stats.hands(),
+                stats.vpip() * 100.0,
+                stats.pfr() * 100.0,
+                stats.three_bet() * 100.0,
+                stats.cbet() * 100.0,
+                stats.wtsd() * 100.0,
+                stats.aggression_factor(),
+            )?;
+            for street in [Street::Preflop, Street::Flop, Street::Turn, Street::River] {
+                let StreetStats {
+                    bets_raises,
+                    calls,
+                    checks,
+                    folds,
+                } = stats.street(street);
+                writeln!(
+                    f,
+                    "  {street:?}: {bets_raises} bets/raises, {calls} calls, {checks} checks, {folds} folds, AF {:.2}",
+                    stats.street(street).aggression_factor(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::{
+        chips::Chips,
+        headsup::{Action, HandState, TurnId, clock::Timed},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn open_three_bet_and_cbet() {
+        let raise = |to| Action::bet_or_raise(Chips::new(to)).unwrap();
+        let holes = ["AsAh".parse().unwrap(), "7c2d".parse().unwrap()];
+        let mut tracker = StatsTracker::new();
+        for (showdown, line) in [
+            // player 0 on the button opens and calls a three-bet, which is c-bet and folded to
+            (
+                false,
+                vec![
+                    raise(1500),
+                    raise(4500),
+                    Action::check_or_call(),
+                    raise(3000),
+                    Action::fold(),
+                ],
+            ),
+            // player 0 opens and c-bets, player 1 calls down to a showdown
+            (
+                true,
+                vec![
+                    raise(1500),
+                    Action::check_or_call(),
+                    Action::check_or_call(),
+                    raise(2000),
+                    Action::check_or_call(),
+                    Action::check_or_call(),
+                    Action::check_or_call(),
+                    Action::check_or_call(),
+                    Action::check_or_call(),
+                ],
+            ),
+        ] {
+            let state = HandState::new(500, true, [Chips::new(50000); 2]);
+            let mut hand = HandHistory::new(1, &state);
+            let mut events = vec![ObservableEvent::DealHoles(holes.map(Some))];
+            events.extend(line.into_iter().enumerate().map(|(turn, action)| {
+                ObservableEvent::PlayerAction(action, TurnId::new(turn as u32))
+            }));
+            if showdown {
+                events.push(ObservableEvent::ShowdownAll(holes));
+            }
+            for event in events {
+                hand.push(Timed::new(Duration::ZERO, event));
+            }
+            tracker.add(&hand);
+        }
+
+        let [button, big_blind] = [tracker.seat(true), tracker.seat(false)];
+        assert_eq!((button.hands(), button.vpip(), button.pfr()), (2, 1.0, 1.0));
+        assert_eq!((big_blind.three_bet(), big_blind.cbet()), (0.5, 1.0));
+        assert_eq!((button.three_bet_chances, button.cbet()), (0, 1.0));
+        assert_eq!((button.wtsd(), big_blind.wtsd()), (0.5, 0.5));
+        assert_eq!(big_blind.street(Street::Flop).calls, 1);
+        assert!(tracker.to_string().contains("3-bet 50.0%"), "{tracker}");
+    }
+}
@@ -0,0 +1,265 @@
+// Self-play hands in an SQLite database, so long experiments keep their hands, actions and
+// results on disk and the stats can be rebuilt from any session later. Hands are grouped by a
+// session name of the caller's choosing; cards are stored as ASCII text, e.g. "AsKd".
+use crate::{
+    bot::{Deal, HandRecord, Trajectories},
+    gameplay::{Card, FullBoard, Street},
+    stats::{ActionRecord, Heatmap, PlayerStats},
+};
+use rusqlite::{
+    Connection, Result, Row,
+    types::{FromSqlError, Type},
+};
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS hands (
+        id INTEGER PRIMARY KEY,
+        session TEXT NOT NULL,
+        hole0 TEXT NOT NULL,
+        hole1 TEXT NOT NULL,
+        runout TEXT NOT NULL,
+        button INTEGER NOT NULL,
+        result0 REAL NOT NULL,
+        result1 REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS hands_session ON hands (session);
+    CREATE TABLE IF NOT EXISTS actions (
+        hand INTEGER NOT NULL REFERENCES hands (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        actor INTEGER NOT NULL,
+        street INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        facing_bet INTEGER NOT NULL,
+        raises INTEGER NOT NULL,
+        PRIMARY KEY (hand, seq)
+    );
+";
+
+#[derive(Debug)]
+pub struct HandStore {
+    conn: Connection,
+}
+
+impl HandStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    // Gives the id of the stored hand.
+    pub fn insert_hand(&mut self, session: &str, hand: &HandRecord) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        let id = insert(&tx, session, hand)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    // All hands of a self-play run in one transaction. Gives how many were stored.
+    pub fn insert_trajectories(
+        &mut self,
+        session: &str,
+        trajectories: &Trajectories,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        for hand in trajectories.hands() {
+            insert(&tx, session, hand)?;
+        }
+        tx.commit()?;
+        Ok(trajectories.hands().len())
+    }
+
+    pub fn sessions(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT session FROM hands ORDER BY session")?;
+        stmt.query_map([], |row| row.get(0))?.collect()
+    }
+
+    // Hands of `session` in the order they were stored.
+    pub fn hands(&self, session: &str) -> Result<Vec<HandRecord>> {
+        let mut hands_stmt = self.conn.prepare(
+            "SELECT id, hole0, hole1, runout, button, result0, result1 FROM hands
+             WHERE session = ?1 ORDER BY id",
+        )?;
+        let mut actions_stmt = self.conn.prepare(
+            "SELECT actor, street, action, facing_bet, raises FROM actions
+             WHERE hand = ?1 ORDER BY seq",
+        )?;
+
+        let rows: Vec<(i64, Deal, [f64; 2])> = hands_stmt
+            .query_map([session], |row| {
+                let holes = [parse(row, 1)?, parse(row, 2)?];
+                let runout: FullBoard = parse(row, 3)?;
+                let deal = Deal::new(holes, *runout, row.get(4)?).ok_or_else(|| invalid(1))?;
+                Ok((row.get(0)?, deal, [row.get(5)?, row.get(6)?]))
+            })?
+            .collect::<Result<_>>()?;
+
+        rows.into_iter()
+            .map(|(id, deal, results)| {
+                let actions = actions_stmt
+                    .query_map([id], action_record)?
+                    .collect::<Result<_>>()?;
+                Ok(HandRecord::new(deal, actions, results))
+            })
+            .collect()
+    }
+
+    // Stats of `seat` over all hands of `session`.
+    pub fn player_stats(&self, session: &str, seat: bool) -> Result<PlayerStats> {
+        let mut stats = PlayerStats::default();
+        for hand in self.hands(session)? {
+            stats.new_hand();
+            for record in hand.actions().iter().filter(|r| r.actor() == seat) {
+                stats.record(record);
+            }
+        }
+        Ok(stats)
+    }
+
+    // Winnings of `seat` over `session` by position and starting hand.
+    pub fn heatmap(&self, session: &str, seat: bool) -> Result<Heatmap> {
+        Ok(Heatmap::from_hands(seat, &self.hands(session)?))
+    }
+
+    // Big blinds each seat won over `session`.
+    pub fn results(&self, session: &str) -> Result<[f64; 2]> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(result0), 0), COALESCE(SUM(result1), 0) FROM hands
+             WHERE session = ?1",
+            [session],
+            |row| Ok([row.get(0)?, row.get(1)?]),
+        )
+    }
+
+    // Gives how many hands were deleted.
+    pub fn delete_session(&mut self, session: &str) -> Result<usize> {
+        self.conn
+            .execute("DELETE FROM hands WHERE session = ?1", [session])
+    }
+}
+
+fn insert(conn: &Connection, session: &str, hand: &HandRecord) -> Result<i64> {
+    let deal = hand.deal();
+    let [result0, result1] = hand.results();
+    conn.execute(
+        "INSERT INTO hands (session, hole0, hole1, runout, button, result0, result1)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        (
+            session,
+            cards_text(&deal.holes()[0][..]),
+            cards_text(&deal.holes()[1][..]),
+            cards_text(&deal.runout()),
+            deal.button(),
+            result0,
+            result1,
+        ),
+    )?;
+    let id = conn.last_insert_rowid();
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO actions (hand, seq, actor, street, action, facing_bet, raises)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for (seq, record) in hand.actions().iter().enumerate() {
+        stmt.execute((
+            id,
+            seq as i64,
+            record.actor(),
+            street_number(record.street()),
+            record.action().to_string(),
+            record.facing_bet(),
+            record.raises(),
+        ))?;
+    }
+    Ok(id)
+}
+
+fn action_record(row: &Row) -> Result<ActionRecord> {
+    let street = match row.get::<_, u8>(1)? {
+        0 => Street::Preflop,
+        1 => Street::Flop,
+        2 => Street::Turn,
+        3 => Street::River,
+        _ => return Err(invalid(1)),
+    };
+    Ok(ActionRecord::new(
+        row.get(0)?,
+        street,
+        parse(row, 2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+fn street_number(street: Street) -> u8 {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 1,
+        Street::Turn => 2,
+        Street::River => 3,
+    }
+}
+
+fn cards_text(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect()
+}
+
+fn parse<T: std::str::FromStr>(row: &Row, index: usize) -> Result<T> {
+    row.get::<_, String>(index)?
+        .parse()
+        .map_err(|_| invalid(index))
+}
+
+fn invalid(index: usize) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        index,
+        Type::Text,
+        Box::new(FromSqlError::InvalidType),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{ExploitBot, SelfPlay};
+
+    #[test]
+    fn hands_round_trip() {
+        let trajectories =
+            SelfPlay::default().play(&mut ExploitBot::default(), &mut ExploitBot::default(), 30);
+        let mut store = HandStore::in_memory().unwrap();
+        assert_eq!(store.insert_trajectories("run", &trajectories).unwrap(), 30);
+        store
+            .insert_hand("other", &trajectories.hands()[0])
+            .unwrap();
+
+        assert_eq!(store.sessions().unwrap(), ["other", "run"]);
+        assert_eq!(store.hands("run").unwrap(), trajectories.hands());
+
+        let mut stats = PlayerStats::default();
+        for hand in trajectories.hands() {
+            stats.new_hand();
+            hand.actions()
+                .iter()
+                .filter(|r| !r.actor())
+                .for_each(|r| stats.record(r));
+        }
+        assert_eq!(store.player_stats("run", false).unwrap(), stats);
+
+        let [won, lost] = store.results("run").unwrap();
+        assert!((won + lost).abs() < 1e-9);
+        assert_eq!(store.delete_session("run").unwrap(), 30);
+        assert_eq!(store.results("run").unwrap(), [0.0, 0.0]);
+    }
+}
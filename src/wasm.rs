@@ -0,0 +1,204 @@
+// Browser bindings (feature `wasm`) through wasm-bindgen, for front ends built on the engine. A
+// `Table` is one heads-up game run on the page's event loop: the page takes the seats it plays,
+// hands the others to a built-in bot and starts the game. Events come out as JSON, each the
+// `Timed<PlayerEvent>` a seat receives, and answers go in as the strings actions and shows parse
+// from; an answer that can't be taken is thrown as its `WireError`'s JSON. Without threads in the
+// browser, the engine's parallel work runs sequentially on the calling thread (see `parallel`).
+use crate::{
+    bot::{self, ExploitBot},
+    gameplay::{
+        headsup::{Action, Game, GameType, Player, ShowCards},
+        wire::{ErrorCode, WireError},
+    },
+};
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, js_sys::Promise, spawn_local};
+
+fn throw(error: WireError) -> JsValue {
+    let json = serde_json::to_string(&error).expect("Wire errors serialize");
+    JsValue::from_str(&json)
+}
+
+#[wasm_bindgen]
+pub struct Table {
+    game: Option<Game>, // until started
+    players: [Option<Player>; 2],
+}
+
+#[wasm_bindgen]
+impl Table {
+    // `game` by `GameType::named`, dealt from `seed` when given.
+    #[wasm_bindgen(constructor)]
+    pub fn new(game: &str, seed: Option<u64>) -> Result<Table, JsValue> {
+        let game_type = GameType::named(game).ok_or_else(|| {
+            let message = format!("no game {game:?}, try cash, turbo, medium or slow");
+            throw(WireError::new(ErrorCode::MalformedMessage, message))
+        })?;
+        let (game, players) = match seed {
+            Some(seed) => Game::seeded(game_type, seed),
+            None => Game::new(game_type),
+        };
+        Ok(Self {
+            game: Some(game),
+            players: players.map(Some),
+        })
+    }
+
+    // The seat to play from the page, `true` for player 0. Undefined once it's taken.
+    pub fn seat(&mut self, seat: bool) -> Option<Seat> {
+        let player = self.players[!seat as usize].take()?;
+        Some(Seat(Rc::new(Cell::new(Some(player)))))
+    }
+
+    // Has `ExploitBot` play the seat once the game starts. False if it's taken.
+    pub fn bot(&mut self, seat: bool) -> bool {
+        let Some(mut player) = self.players[!seat as usize].take() else {
+            return false;
+        };
+        spawn_local(async move {
+            bot::play(&mut player, &mut ExploitBot::default()).await;
+        });
+        true
+    }
+
+    // Runs the game. Seats nobody took are players gone from the start. False if it already ran.
+    pub fn start(&mut self) -> bool {
+        let Some(game) = self.game.take() else {
+            return false;
+        };
+        self.players = [None, None];
+        spawn_local(async move {
+            game.run().await;
+        });
+        true
+    }
+}
+
+// A seat played from the page. Its player is out of the cell while an event is awaited, so the
+// seat takes one call at a time.
+#[wasm_bindgen]
+pub struct Seat(Rc<Cell<Option<Player>>>);
+
+impl Seat {
+    fn with_player<T>(
+        &self,
+        f: impl FnOnce(&mut Player) -> Result<T, WireError>,
+    ) -> Result<T, WireError> {
+        let mut player = self.0.take().ok_or_else(|| {
+            WireError::new(ErrorCode::NotYourTurn, "the seat is waiting for an event")
+        })?;
+        let result = f(&mut player);
+        self.0.set(Some(player));
+        result
+    }
+
+    fn answer(
+        &self,
+        answer: &str,
+        send: fn(&mut Player, &str) -> Result<(), WireError>,
+    ) -> Result<(), JsValue> {
+        self.with_player(|player| send(player, answer))
+            .map_err(throw)
+    }
+}
+
+async fn next_event(seat: Rc<Cell<Option<Player>>>) -> Result<Option<String>, WireError> {
+    let mut player = seat.take().ok_or_else(|| {
+        WireError::new(ErrorCode::NotYourTurn, "the seat is waiting for an event")
+    })?;
+    let event = player.tick_timed_event().await;
+    seat.set(Some(player));
+    Ok(event.map(|event| serde_json::to_string(&event).expect("Events serialize")))
+}
+
+fn send_action(player: &mut Player, action: &str) -> Result<(), WireError> {
+    let action: Action = action.parse()?;
+    Ok(player.send_action(action)?)
+}
+
+fn send_show(player: &mut Player, show: &str) -> Result<(), WireError> {
+    let show: ShowCards = show.parse()?;
+    Ok(player.send_show(show)?)
+}
+
+#[wasm_bindgen]
+impl Seat {
+    // A promise of the next event's JSON, null once the game is over.
+    pub fn next_event(&self) -> Promise {
+        let seat = self.0.clone();
+        future_to_promise(async move {
+            match next_event(seat).await {
+                Ok(event) => Ok(event.map_or(JsValue::NULL, |event| JsValue::from_str(&event))),
+                Err(error) => Err(throw(error)),
+            }
+        })
+    }
+
+    // Answers the seat's turn, e.g. "c", "r1500" or "pot".
+    pub fn act(&self, action: &str) -> Result<(), JsValue> {
+        self.answer(action, send_action)
+    }
+
+    // Answers a show offer.
+    pub fn show(&self, show: &str) -> Result<(), JsValue> {
+        self.answer(show, send_show)
+    }
+
+    pub fn insure(&self, take: bool) -> Result<(), JsValue> {
+        self.with_player(|player| Ok(player.send_insurance(take)?))
+            .map_err(throw)
+    }
+
+    // How the game ended and what it came to as JSON, null before that.
+    pub fn game_over(&self) -> Option<String> {
+        let result = self.with_player(|player| Ok(player.game_over())).ok()??;
+        Some(serde_json::to_string(&result).expect("Game results serialize"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // Calls down every bet it can and shows nothing, through what the bindings wrap: JS values only
+    // exist in the browser.
+    async fn call_down(seat: &Seat) -> usize {
+        let mut events = 0;
+        while let Some(event) = next_event(seat.0.clone()).await.unwrap() {
+            events += 1;
+            let event: Value = serde_json::from_str(&event).unwrap();
+            let answered = match &event["event"] {
+                // some river bounds leave no check, only the fold
+                Value::Object(event) if event.contains_key("HeroTurn") => seat
+                    .with_player(|player| send_action(player, "c"))
+                    .or_else(|_| seat.with_player(|player| send_action(player, "f"))),
+                Value::Object(event) if event.contains_key("InsuranceOffer") => {
+                    seat.with_player(|player| Ok(player.send_insurance(false)?))
+                }
+                Value::String(event) if event == "ShowOffer" => {
+                    seat.with_player(|player| send_show(player, "n"))
+                }
+                _ => Ok(()),
+            };
+            answered.unwrap();
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn seats_over_json() {
+        let mut table = Table::new("turbo", Some(7)).unwrap();
+        let seats = [true, false].map(|seat| table.seat(seat).unwrap());
+        assert!(table.seat(true).is_none());
+        tokio::spawn(table.game.take().unwrap().run());
+
+        let (first, second) = tokio::join!(call_down(&seats[0]), call_down(&seats[1]));
+        assert!(first > 0 && second > 0);
+        let over = seats[0].with_player(|player| Ok(player.game_over()));
+        assert!(over.unwrap().is_some());
+        let shove = seats[0].with_player(|player| send_action(player, "shove"));
+        assert_eq!(shove.unwrap_err().code, ErrorCode::MalformedAction);
+    }
+}